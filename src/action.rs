@@ -11,6 +11,7 @@ use crate::{WidgetId, WindowId};
 // TODO - Refactor - See issue #1
 
 // TODO - TextCursor changed, ImeChanged, EnterKey, MouseEnter
+#[derive(Clone)]
 #[non_exhaustive]
 #[allow(missing_docs)]
 /// Events from UI elements.
@@ -21,6 +22,10 @@ pub enum Action {
     TextChanged(String),
     TextEntered(String),
     CheckboxChecked(bool),
+    NumberChanged(f64),
+    SortRequested { column: usize, ascending: bool },
+    RowSelected(usize),
+    RadioSelected(usize),
     // FIXME - This is a huge hack
     Other(Arc<dyn Any>),
 }
@@ -31,6 +36,19 @@ impl PartialEq for Action {
             (Self::TextChanged(l0), Self::TextChanged(r0)) => l0 == r0,
             (Self::TextEntered(l0), Self::TextEntered(r0)) => l0 == r0,
             (Self::CheckboxChecked(l0), Self::CheckboxChecked(r0)) => l0 == r0,
+            (Self::NumberChanged(l0), Self::NumberChanged(r0)) => l0 == r0,
+            (
+                Self::SortRequested {
+                    column: l_column,
+                    ascending: l_ascending,
+                },
+                Self::SortRequested {
+                    column: r_column,
+                    ascending: r_ascending,
+                },
+            ) => l_column == r_column && l_ascending == r_ascending,
+            (Self::RowSelected(l0), Self::RowSelected(r0)) => l0 == r0,
+            (Self::RadioSelected(l0), Self::RadioSelected(r0)) => l0 == r0,
             (Self::Other(val_l), Self::Other(val_r)) => Arc::ptr_eq(val_l, val_r),
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
         }
@@ -46,6 +64,14 @@ impl std::fmt::Debug for Action {
             Self::TextChanged(text) => f.debug_tuple("TextChanged").field(text).finish(),
             Self::TextEntered(text) => f.debug_tuple("TextEntered").field(text).finish(),
             Self::CheckboxChecked(b) => f.debug_tuple("CheckboxChecked").field(b).finish(),
+            Self::NumberChanged(n) => f.debug_tuple("NumberChanged").field(n).finish(),
+            Self::SortRequested { column, ascending } => f
+                .debug_struct("SortRequested")
+                .field("column", column)
+                .field("ascending", ascending)
+                .finish(),
+            Self::RowSelected(row) => f.debug_tuple("RowSelected").field(row).finish(),
+            Self::RadioSelected(index) => f.debug_tuple("RadioSelected").field(index).finish(),
             Self::Other(_) => write!(f, "Other(...)"),
         }
     }
@@ -7,12 +7,14 @@ use std::collections::HashMap;
 
 use tracing::trace;
 
+use druid_shell::Scale;
+
 use crate::action::Action;
 use crate::command::{Command, CommandQueue};
 use crate::ext_event::{ExtEventQueue, ExtEventSink};
 use crate::widget::{StoreInWidgetMut, WidgetMut, WidgetRef};
 use crate::{
-    Env, Event, Handled, SingleUse, Target, Widget, WidgetId, WindowDescription, WindowId,
+    Env, Event, Handled, SingleUse, Size, Target, Widget, WidgetId, WindowDescription, WindowId,
     WindowRoot,
 };
 
@@ -28,10 +30,13 @@ pub struct DelegateCtx<'a, 'b> {
 }
 
 impl<'a, 'b> DelegateCtx<'a, 'b> {
-    #[cfg(FALSE)]
+    /// Submit a [`Command`] to be run after the current event is handled.
+    ///
+    /// [`Target::Auto`] commands are sent to the whole application.
     pub fn submit_command(&mut self, command: impl Into<Command>) {
-        self.command_queue
-            .push_back(command.into().default_to(Target::Global))
+        trace!("submit_command");
+        Box::<dyn Widget>::get_ctx(&mut self.main_root_widget.inner)
+            .submit_command(command.into().default_to(Target::Global));
     }
 
     /// Return an [`ExtEventSink`] that can be moved between threads,
@@ -50,6 +55,16 @@ impl<'a, 'b> DelegateCtx<'a, 'b> {
         );
     }
 
+    /// Quit the application.
+    ///
+    /// This submits a [`QUIT_APP`](crate::command::QUIT_APP) command: once the current
+    /// event is handled, [`AppDelegate::on_app_will_quit`] is called, then all windows are
+    /// closed and the event loop is stopped.
+    pub fn quit(&mut self) {
+        trace!("quit");
+        self.submit_command(crate::command::QUIT_APP.to(Target::Global));
+    }
+
     // TODO - Use static typing to guarantee proper return type - See issue #17
     /// Try to return a [`WidgetMut`] to the root widget.
     ///
@@ -125,6 +140,147 @@ pub trait AppDelegate {
     fn on_window_removed(&mut self, ctx: &mut DelegateCtx, id: WindowId, env: &Env) {
         #![allow(unused)]
     }
+
+    /// The handler called before the app quits.
+    ///
+    /// This is called once, either when a [`QUIT_APP`](crate::command::QUIT_APP) command is
+    /// handled, or when the last window is closed. It can be used to save state, or to cancel
+    /// background work.
+    ///
+    /// When quitting because of a command, returning [`Handled::Yes`] vetoes quitting (eg to
+    /// ask the user to save their work first), and the hook will be called again on the next
+    /// attempt. The veto is ignored when the last window has already been closed.
+    fn on_app_will_quit(&mut self, ctx: &mut DelegateCtx, env: &Env) -> Handled {
+        #![allow(unused)]
+        Handled::No
+    }
+
+    /// The handler for window resizes.
+    ///
+    /// This function is called before the new size is passed down the tree.
+    fn on_window_resized(&mut self, ctx: &mut DelegateCtx, id: WindowId, size: Size, env: &Env) {
+        #![allow(unused)]
+    }
+
+    /// The handler for changes of a window's scale factor.
+    ///
+    /// This is typically called when the window moves to a screen with a different DPI,
+    /// and can be used to reload resolution-dependent assets.
+    fn on_scale_changed(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        id: WindowId,
+        old_scale: Scale,
+        new_scale: Scale,
+        env: &Env,
+    ) {
+        #![allow(unused)]
+    }
+}
+
+/// An [`AppDelegate`] that forwards every hook to several delegates, in order.
+///
+/// [`on_event`](AppDelegate::on_event), [`on_command`](AppDelegate::on_command) and
+/// [`on_app_will_quit`](AppDelegate::on_app_will_quit) stop at the first delegate that
+/// returns [`Handled::Yes`]. The other hooks are passed to every delegate.
+///
+/// This lets libraries provide reusable delegates (eg for logging) that can be combined
+/// with the app's own.
+#[derive(Default)]
+pub struct ChainedDelegate {
+    delegates: Vec<Box<dyn AppDelegate>>,
+}
+
+impl ChainedDelegate {
+    /// Create a chain with no delegates.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder-style method for adding a delegate at the end of the chain.
+    pub fn with(mut self, delegate: impl AppDelegate + 'static) -> Self {
+        self.delegates.push(Box::new(delegate));
+        self
+    }
+}
+
+impl AppDelegate for ChainedDelegate {
+    fn on_event(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        window_id: WindowId,
+        event: &Event,
+        env: &Env,
+    ) -> Handled {
+        for delegate in &mut self.delegates {
+            if delegate.on_event(ctx, window_id, event, env).is_handled() {
+                return Handled::Yes;
+            }
+        }
+        Handled::No
+    }
+
+    fn on_command(&mut self, ctx: &mut DelegateCtx, cmd: &Command, env: &Env) -> Handled {
+        for delegate in &mut self.delegates {
+            if delegate.on_command(ctx, cmd, env).is_handled() {
+                return Handled::Yes;
+            }
+        }
+        Handled::No
+    }
+
+    fn on_action(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        window_id: WindowId,
+        widget_id: WidgetId,
+        action: Action,
+        env: &Env,
+    ) {
+        for delegate in &mut self.delegates {
+            delegate.on_action(ctx, window_id, widget_id, action.clone(), env);
+        }
+    }
+
+    fn on_window_added(&mut self, ctx: &mut DelegateCtx, id: WindowId, env: &Env) {
+        for delegate in &mut self.delegates {
+            delegate.on_window_added(ctx, id, env);
+        }
+    }
+
+    fn on_window_removed(&mut self, ctx: &mut DelegateCtx, id: WindowId, env: &Env) {
+        for delegate in &mut self.delegates {
+            delegate.on_window_removed(ctx, id, env);
+        }
+    }
+
+    fn on_app_will_quit(&mut self, ctx: &mut DelegateCtx, env: &Env) -> Handled {
+        for delegate in &mut self.delegates {
+            if delegate.on_app_will_quit(ctx, env).is_handled() {
+                return Handled::Yes;
+            }
+        }
+        Handled::No
+    }
+
+    fn on_window_resized(&mut self, ctx: &mut DelegateCtx, id: WindowId, size: Size, env: &Env) {
+        for delegate in &mut self.delegates {
+            delegate.on_window_resized(ctx, id, size, env);
+        }
+    }
+
+    fn on_scale_changed(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        id: WindowId,
+        old_scale: Scale,
+        new_scale: Scale,
+        env: &Env,
+    ) {
+        for delegate in &mut self.delegates {
+            delegate.on_scale_changed(ctx, id, old_scale, new_scale, env);
+        }
+    }
 }
 
 // TODO - impl AppDelegate for FnMut
@@ -133,3 +289,214 @@ pub trait AppDelegate {
 pub(crate) struct NullDelegate;
 
 impl AppDelegate for NullDelegate {}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::{Button, Label};
+    use crate::Selector;
+
+    #[derive(Debug, PartialEq)]
+    enum WindowChange {
+        Resized(Size),
+        ScaleChanged(f64, f64),
+    }
+
+    struct RecordingDelegate(Rc<RefCell<Vec<WindowChange>>>);
+
+    impl AppDelegate for RecordingDelegate {
+        fn on_window_resized(&mut self, _: &mut DelegateCtx, _: WindowId, size: Size, _: &Env) {
+            self.0.borrow_mut().push(WindowChange::Resized(size));
+        }
+
+        fn on_scale_changed(
+            &mut self,
+            _: &mut DelegateCtx,
+            _: WindowId,
+            old_scale: Scale,
+            new_scale: Scale,
+            _: &Env,
+        ) {
+            self.0
+                .borrow_mut()
+                .push(WindowChange::ScaleChanged(old_scale.x(), new_scale.x()));
+        }
+    }
+
+    #[test]
+    fn window_change_hooks() {
+        let changes = Rc::new(RefCell::new(Vec::new()));
+
+        let mut harness = TestHarness::create(Label::new("Hello"));
+        harness.set_delegate(RecordingDelegate(changes.clone()));
+
+        harness.resize_window(Size::new(300.0, 200.0));
+        harness.set_scale(Scale::new(2.0, 2.0));
+        // Setting the same scale again isn't a change.
+        harness.set_scale(Scale::new(2.0, 2.0));
+
+        assert_eq!(
+            *changes.borrow(),
+            vec![
+                WindowChange::Resized(Size::new(300.0, 200.0)),
+                WindowChange::ScaleChanged(1.0, 2.0),
+            ]
+        );
+    }
+
+    const HANDLED_BY_FIRST: Selector = Selector::new("masonry-test.handled-by-first");
+    const UNHANDLED: Selector = Selector::new("masonry-test.unhandled");
+
+    struct NamedDelegate(&'static str, Rc<RefCell<Vec<String>>>);
+
+    impl AppDelegate for NamedDelegate {
+        fn on_command(&mut self, _: &mut DelegateCtx, cmd: &Command, _: &Env) -> Handled {
+            self.1.borrow_mut().push(format!("{}: command", self.0));
+            if cmd.is(HANDLED_BY_FIRST) {
+                Handled::Yes
+            } else {
+                Handled::No
+            }
+        }
+
+        fn on_action(&mut self, _: &mut DelegateCtx, _: WindowId, _: WidgetId, a: Action, _: &Env) {
+            self.1.borrow_mut().push(format!("{}: {:?}", self.0, a));
+        }
+    }
+
+    #[test]
+    fn chained_delegates() {
+        let [button_id] = widget_ids();
+        let calls = Rc::new(RefCell::new(Vec::new()));
+
+        let mut harness = TestHarness::create(Button::new("Hello").with_id(button_id));
+        harness.set_delegate(
+            ChainedDelegate::new()
+                .with(NamedDelegate("first", calls.clone()))
+                .with(NamedDelegate("second", calls.clone())),
+        );
+
+        harness.mouse_click_on(button_id);
+        assert_eq!(
+            calls.take(),
+            vec!["first: ButtonPressed", "second: ButtonPressed"]
+        );
+        assert_eq!(harness.pop_action(), None);
+
+        harness.submit_command(HANDLED_BY_FIRST);
+        assert_eq!(calls.take(), vec!["first: command"]);
+
+        harness.submit_command(UNHANDLED);
+        assert_eq!(calls.take(), vec!["first: command", "second: command"]);
+    }
+
+    struct QuitDelegate {
+        vetoes_left: u32,
+        calls: Rc<RefCell<u32>>,
+    }
+
+    impl AppDelegate for QuitDelegate {
+        fn on_app_will_quit(&mut self, _: &mut DelegateCtx, _: &Env) -> Handled {
+            *self.calls.borrow_mut() += 1;
+            if self.vetoes_left > 0 {
+                self.vetoes_left -= 1;
+                Handled::Yes
+            } else {
+                Handled::No
+            }
+        }
+    }
+
+    #[test]
+    fn app_will_quit_hook_runs_once() {
+        let calls = Rc::new(RefCell::new(0));
+
+        let mut harness = TestHarness::create(Label::new("Hello"));
+        harness.set_delegate(QuitDelegate {
+            vetoes_left: 1,
+            calls: calls.clone(),
+        });
+
+        harness.submit_command(crate::command::QUIT_APP);
+        assert_eq!(*calls.borrow(), 1);
+        assert!(!harness.has_quit());
+
+        harness.submit_command(crate::command::QUIT_APP);
+        harness.submit_command(crate::command::QUIT_APP);
+        assert_eq!(*calls.borrow(), 2);
+        assert!(harness.has_quit());
+    }
+
+    #[test]
+    fn quit_removes_all_windows() {
+        #[derive(Default)]
+        struct QuitRecorder {
+            events: Rc<RefCell<Vec<&'static str>>>,
+        }
+
+        impl AppDelegate for QuitRecorder {
+            fn on_command(&mut self, ctx: &mut DelegateCtx, cmd: &Command, _: &Env) -> Handled {
+                if cmd.is(QUIT_SELECTOR) {
+                    ctx.quit();
+                    Handled::Yes
+                } else {
+                    Handled::No
+                }
+            }
+
+            fn on_app_will_quit(&mut self, _: &mut DelegateCtx, _: &Env) -> Handled {
+                self.events.borrow_mut().push("will_quit");
+                Handled::No
+            }
+
+            fn on_window_removed(&mut self, _: &mut DelegateCtx, _: WindowId, _: &Env) {
+                self.events.borrow_mut().push("window_removed");
+            }
+        }
+
+        const QUIT_SELECTOR: Selector = Selector::new("masonry-test.quit");
+
+        let delegate = QuitRecorder::default();
+        let events = delegate.events.clone();
+
+        let mut harness = TestHarness::create(Label::new("Hello"));
+        harness.set_delegate(delegate);
+        harness.submit_command(QUIT_SELECTOR);
+
+        assert!(harness.has_quit());
+        assert!(harness.window().is_closing());
+        assert_eq!(*events.borrow(), vec!["will_quit", "window_removed"]);
+    }
+
+    #[test]
+    fn close_all_windows_without_quitting() {
+        #[derive(Default)]
+        struct RemovedRecorder {
+            removed: Rc<RefCell<Vec<WindowId>>>,
+        }
+
+        impl AppDelegate for RemovedRecorder {
+            fn on_window_removed(&mut self, _: &mut DelegateCtx, id: WindowId, _: &Env) {
+                self.removed.borrow_mut().push(id);
+            }
+        }
+
+        let delegate = RemovedRecorder::default();
+        let removed = delegate.removed.clone();
+
+        let mut harness = TestHarness::create(Label::new("Hello"));
+        harness.set_delegate(delegate);
+        assert!(!harness.window().is_closing());
+
+        harness.submit_command(crate::command::CLOSE_ALL_WINDOWS);
+        harness.submit_command(crate::command::CLOSE_ALL_WINDOWS);
+
+        assert!(harness.window().is_closing());
+        assert!(!harness.has_quit());
+        assert_eq!(*removed.borrow(), vec![harness.window().id]);
+    }
+}
@@ -3,6 +3,7 @@
 // details.
 
 use druid_shell::{Application as AppHandle, Error as PlatformError};
+use tracing_subscriber::{Layer, Registry};
 
 use crate::app_delegate::AppDelegate;
 use crate::app_root::AppRoot;
@@ -10,11 +11,16 @@ use crate::ext_event::{ExtEventQueue, ExtEventSink};
 use crate::platform::{MasonryAppHandler, WindowDescription};
 use crate::Env;
 
+/// The layers passed to [`AppLauncher::with_tracing_layer`].
+type BoxedTracingLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
 /// Handles initial setup of an application, and starts the runloop.
 pub struct AppLauncher {
     windows: Vec<WindowDescription>,
     app_delegate: Option<Box<dyn AppDelegate>>,
     ext_event_queue: ExtEventQueue,
+    log_to_console: bool,
+    tracing_layer: Option<BoxedTracingLayer>,
 }
 
 impl AppLauncher {
@@ -24,36 +30,84 @@ impl AppLauncher {
             windows: vec![window],
             app_delegate: None,
             ext_event_queue: ExtEventQueue::new(),
+            log_to_console: false,
+            tracing_layer: None,
         }
     }
 
     /// Set the [`AppDelegate`].
     ///
+    /// To install several delegates, combine them with a [`ChainedDelegate`].
+    ///
     /// [`AppDelegate`]: trait.AppDelegate.html
+    /// [`ChainedDelegate`]: crate::ChainedDelegate
     pub fn with_delegate(mut self, delegate: impl AppDelegate + 'static) -> Self {
         self.app_delegate = Some(Box::new(delegate));
         self
     }
 
-    /// Initialize a minimal tracing subscriber with DEBUG max level for printing logs out to
-    /// stderr.
+    /// Print logs out to stderr, with DEBUG max level.
     ///
     /// This is meant for quick-and-dirty debugging. If you want more serious trace handling,
-    /// it's probably better to implement it yourself.
+    /// install your own layer with [`with_tracing_layer`](Self::with_tracing_layer).
+    ///
+    /// The tracing subscriber is installed when the app is [launched](Self::launch), along
+    /// with any layer passed to `with_tracing_layer`, so the two can be combined without
+    /// installing two global subscribers. Note that the DEBUG level filter then applies to
+    /// every layer.
+    ///
+    /// On wasm, logs are printed to the browser console, and layers passed to
+    /// `with_tracing_layer` are ignored.
+    pub fn log_to_console(mut self) -> Self {
+        self.log_to_console = true;
+        self
+    }
+
+    /// Add a [`tracing_subscriber`] layer, eg to write traces to a file or to send
+    /// them to a remote collector.
+    ///
+    /// Framework traces have targets starting with `masonry`, so a layer can tell them
+    /// apart from the app's own traces with [`Metadata::target`](tracing::Metadata::target).
+    ///
+    /// This method can be called multiple times to add several layers. They are installed
+    /// in a single global subscriber when the app is [launched](Self::launch); see
+    /// [`log_to_console`](Self::log_to_console) for how they interact with console logging.
+    /// If the app installs its own global subscriber instead, it shouldn't call either method.
+    pub fn with_tracing_layer(
+        mut self,
+        layer: impl Layer<Registry> + Send + Sync + 'static,
+    ) -> Self {
+        self.tracing_layer = Some(match self.tracing_layer.take() {
+            Some(previous) => Box::new(previous.and_then(layer)),
+            None => Box::new(layer),
+        });
+        self
+    }
+
+    /// Install the global tracing subscriber, if any logging was requested.
     ///
     /// # Panics
     ///
-    /// Panics if the subscriber fails to initialize.
-    pub fn log_to_console(self) -> Self {
+    /// Panics if the subscriber fails to initialize, eg because a global subscriber
+    /// was already installed.
+    fn init_tracing(log_to_console: bool, tracing_layer: Option<BoxedTracingLayer>) {
         #[cfg(not(target_arch = "wasm32"))]
         {
             use tracing_subscriber::prelude::*;
-            let filter_layer = tracing_subscriber::filter::LevelFilter::DEBUG;
-            let fmt_layer = tracing_subscriber::fmt::layer()
-                // Display target (eg "my_crate::some_mod::submod") with logs
-                .with_target(true);
+            if !log_to_console && tracing_layer.is_none() {
+                return;
+            }
+
+            let filter_layer =
+                log_to_console.then_some(tracing_subscriber::filter::LevelFilter::DEBUG);
+            let fmt_layer = log_to_console.then(|| {
+                tracing_subscriber::fmt::layer()
+                    // Display target (eg "my_crate::some_mod::submod") with logs
+                    .with_target(true)
+            });
 
             tracing_subscriber::registry()
+                .with(tracing_layer)
                 .with(filter_layer)
                 .with(fmt_layer)
                 .init();
@@ -62,13 +116,19 @@ impl AppLauncher {
         // because this is a GUI framework, so wasm targets will virtually always be browsers.
         #[cfg(target_arch = "wasm32")]
         {
-            console_error_panic_hook::set_once();
-            let config = tracing_wasm::WASMLayerConfigBuilder::new()
-                .set_max_level(tracing::Level::DEBUG)
-                .build();
-            tracing_wasm::set_as_global_default_with_config(config)
+            use tracing_subscriber::prelude::*;
+            // tracing-wasm uses a different version of tracing-subscriber, so its layer
+            // can't be combined with ours.
+            if log_to_console {
+                console_error_panic_hook::set_once();
+                let config = tracing_wasm::WASMLayerConfigBuilder::new()
+                    .set_max_level(tracing::Level::DEBUG)
+                    .build();
+                tracing_wasm::set_as_global_default_with_config(config)
+            } else if let Some(tracing_layer) = tracing_layer {
+                tracing_subscriber::registry().with(tracing_layer).init();
+            }
         }
-        self
     }
 
     /// Returns an [`ExtEventSink`] that can be moved between threads,
@@ -81,7 +141,15 @@ impl AppLauncher {
     ///
     /// Returns an error if a window cannot be instantiated. This is usually
     /// a fatal error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`log_to_console`](Self::log_to_console) or
+    /// [`with_tracing_layer`](Self::with_tracing_layer) were used, and a global
+    /// tracing subscriber was already installed.
     pub fn launch(self) -> Result<(), PlatformError> {
+        Self::init_tracing(self.log_to_console, self.tracing_layer);
+
         let app = AppHandle::new()?;
         let state = AppRoot::create(
             app.clone(),
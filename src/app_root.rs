@@ -12,34 +12,39 @@ use std::cell::{RefCell, RefMut};
 use std::collections::{HashMap, VecDeque};
 use std::ops::DerefMut;
 use std::rc::Rc;
+use std::sync::Arc;
 
 use druid_shell::text::InputHandler;
 // TODO - rename Application to AppHandle in glazier
 // See https://github.com/linebender/glazier/issues/44
 use druid_shell::{Application as AppHandle, WindowHandle};
 use druid_shell::{
-    Cursor, FileDialogToken, FileInfo, Region, TextFieldToken, TimerToken, WindowBuilder,
+    Cursor, FileDialogToken, FileInfo, KeyEvent, Region, Scale, TextFieldToken, TimerToken,
+    WindowBuilder,
 };
 // Automatically defaults to std::time::Instant on non Wasm platforms
 use instant::Instant;
-use tracing::{error, info, info_span};
+use tracing::{error, info, info_span, trace};
 
 use crate::action::ActionQueue;
 use crate::app_delegate::{AppDelegate, DelegateCtx, NullDelegate};
 use crate::command::CommandQueue;
-use crate::contexts::GlobalPassCtx;
+use crate::contexts::{GlobalPassCtx, WidgetTimer};
 use crate::debug_logger::DebugLogger;
+use crate::event_log::{EventKind, EventLog};
 use crate::ext_event::{ExtEventQueue, ExtEventSink, ExtMessage};
-use crate::kurbo::{Point, Size};
-use crate::piet::{Color, Piet, RenderContext};
+use crate::kurbo::{Insets, Line, Point, Rect, Size};
+use crate::piet::{Color, FontFamily, Piet, RenderContext, Text, TextLayout, TextLayoutBuilder};
 use crate::platform::{
     DialogInfo, WindowConfig, WindowSizePolicy, EXT_EVENT_IDLE_TOKEN, RUN_COMMANDS_TOKEN,
 };
+use crate::promise::PromiseResult;
+use crate::rng::Rng;
 use crate::testing::MockTimerQueue;
 use crate::text::TextFieldRegistration;
 use crate::widget::{FocusChange, StoreInWidgetMut, WidgetMut, WidgetRef, WidgetState};
 use crate::{
-    command as sys_cmd, ArcStr, BoxConstraints, Command, Env, Event, EventCtx, Handled,
+    command as sys_cmd, ArcStr, BoxConstraints, Clipboard, Command, Env, Event, EventCtx, Handled,
     InternalEvent, InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx, MasonryWinHandler,
     PaintCtx, PlatformError, Target, Widget, WidgetCtx, WidgetId, WidgetPod, WindowDescription,
     WindowId,
@@ -76,6 +81,8 @@ struct AppRootInner {
     active_windows: HashMap<WindowId, WindowRoot>,
     // FIXME - remove
     main_window_id: WindowId,
+    /// Set once the delegate has agreed to quit, so it's only told once.
+    is_quitting: bool,
     /// The id of the most-recently-focused window that has a menu. On macOS, this
     /// is the window that's currently in charge of the app menu.
     #[allow(unused)]
@@ -90,6 +97,32 @@ struct PendingWindow {
     title: ArcStr,
     transparent: bool,
     size_policy: WindowSizePolicy,
+    initial_focus: Option<WidgetId>,
+}
+
+/// Paint statistics for the most recent frame of a window.
+///
+/// These can be used to catch over-invalidation, eg a small interaction that ends
+/// up repainting the whole window.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameMetrics {
+    /// The area of the window that was repainted, in square pixels.
+    pub damaged_area: f64,
+    /// The area of the whole window, in square pixels.
+    pub window_area: f64,
+    /// The number of widgets whose `paint` method was called.
+    pub painted_widgets: usize,
+}
+
+impl FrameMetrics {
+    /// The fraction of the window that was repainted, from `0.0` to `1.0`.
+    pub fn damage_ratio(&self) -> f64 {
+        if self.window_area > 0.0 {
+            self.damaged_area / self.window_area
+        } else {
+            0.0
+        }
+    }
 }
 
 // TODO - refactor out again
@@ -102,19 +135,110 @@ pub struct WindowRoot {
     pub(crate) title: ArcStr,
     size_policy: WindowSizePolicy,
     size: Size,
+    pub(crate) scale: Scale,
     invalid: Region,
     // Is `Some` if the most recently displayed frame was an animation frame.
     pub(crate) last_anim: Option<Instant>,
     pub(crate) last_mouse_pos: Option<Point>,
     pub(crate) focus: Option<WidgetId>,
+    /// The widget to focus when the window connects.
+    pub(crate) initial_focus: Option<WidgetId>,
     pub(crate) ext_event_sink: ExtEventSink,
     pub(crate) handle: WindowHandle,
-    pub(crate) timers: HashMap<TimerToken, WidgetId>,
+    pub(crate) timers: HashMap<TimerToken, WidgetTimer>,
     // Used in unit tests - see `src/testing/mock_timer_queue.rs`
     pub(crate) mock_timer_queue: Option<MockTimerQueue>,
+    // Replaced with an in-memory clipboard in unit tests.
+    pub(crate) clipboard: Clipboard,
+    pub(crate) rng: Rng,
     pub(crate) transparent: bool,
     pub(crate) ime_handlers: Vec<(TextFieldToken, TextFieldRegistration)>,
     pub(crate) ime_focus_change: Option<Option<TextFieldToken>>,
+    frame_metrics: FrameMetrics,
+    debug_overlay: bool,
+    debug_overlay_outlines: usize,
+    hit_test_overlay: bool,
+    hit_test_target: Option<WidgetId>,
+    event_log: Option<EventLog>,
+    safe_area: Insets,
+    /// The env seen by this window's widgets when it has a safe area, and the version
+    /// of the app env it was built from.
+    safe_area_env: Option<(u64, Env)>,
+    /// Dropped along with the window, so that its sinks can tell it closed.
+    #[allow(unused)]
+    alive: Arc<()>,
+    /// Set once the platform has been asked to close this window.
+    closing: bool,
+}
+
+/// Send a command to the windows it targets.
+fn dispatch_cmd_to_windows(
+    active_windows: &mut HashMap<WindowId, WindowRoot>,
+    cmd: Command,
+    debug_logger: &mut DebugLogger,
+    command_queue: &mut CommandQueue,
+    action_queue: &mut ActionQueue,
+    env: &Env,
+) -> Handled {
+    match cmd.target() {
+        Target::Global => {
+            for w in active_windows.values_mut() {
+                if w.event(
+                    Event::Command(cmd.clone()),
+                    debug_logger,
+                    command_queue,
+                    action_queue,
+                    env,
+                )
+                .is_handled()
+                {
+                    return Handled::Yes;
+                }
+            }
+            return Handled::No;
+        }
+        Target::Window(id) => {
+            if let Some(w) = active_windows.get_mut(&id) {
+                return w.event(
+                    Event::Command(cmd),
+                    debug_logger,
+                    command_queue,
+                    action_queue,
+                    env,
+                );
+            }
+        }
+        // in this case we send it to every window that might contain
+        // this widget, breaking if the event is handled.
+        Target::Widget(id) => {
+            for w in active_windows
+                .values_mut()
+                .filter(|w| w.may_contain_widget(id))
+            {
+                let event = Event::Internal(InternalEvent::TargetedCommand(cmd.clone()));
+                if w.event(event, debug_logger, command_queue, action_queue, env)
+                    .is_handled()
+                {
+                    return Handled::Yes;
+                }
+            }
+        }
+        // The target is resolved by each window, see WindowRoot::event.
+        Target::Named(_) | Target::ByType(_) => {
+            for w in active_windows.values_mut() {
+                let event = Event::Internal(InternalEvent::TargetedCommand(cmd.clone()));
+                if w.event(event, debug_logger, command_queue, action_queue, env)
+                    .is_handled()
+                {
+                    return Handled::Yes;
+                }
+            }
+        }
+        Target::Auto => {
+            tracing::error!("{:?} reached window handler with `Target::Auto`", cmd);
+        }
+    }
+    Handled::No
 }
 
 // ---
@@ -142,6 +266,7 @@ impl AppRoot {
             file_dialogs: HashMap::new(),
             // FIXME - this is awful
             main_window_id: windows.first().unwrap().id,
+            is_quitting: false,
             menu_window: None,
             env,
             window_requests: VecDeque::new(),
@@ -168,7 +293,7 @@ impl AppRoot {
             let inner = inner.deref_mut();
 
             if let Some(pending) = inner.pending_windows.remove(&window_id) {
-                let win = WindowRoot::new(
+                let mut win = WindowRoot::new(
                     window_id,
                     handle,
                     inner.ext_event_queue.make_sink(),
@@ -178,6 +303,7 @@ impl AppRoot {
                     pending.size_policy,
                     None,
                 );
+                win.initial_focus = pending.initial_focus;
                 let existing = inner.active_windows.insert(window_id, win);
                 debug_assert!(existing.is_none(), "duplicate window");
             } else {
@@ -208,13 +334,23 @@ impl AppRoot {
     pub fn window_removed(&mut self, window_id: WindowId) {
         self.with_delegate(|delegate, ctx, env| delegate.on_window_removed(ctx, window_id, env));
 
+        let is_last_window = {
+            let inner = self.inner();
+            inner.pending_windows.is_empty()
+                && inner.active_windows.keys().all(|id| *id == window_id)
+        };
+        // The window is already being destroyed, so the delegate can't veto quitting.
+        if is_last_window && cfg!(any(target_os = "windows", feature = "x11")) {
+            self.app_will_quit();
+        }
+
         let mut inner = self.inner.borrow_mut();
         inner.active_windows.remove(&window_id);
 
         // If there are no active or pending windows, we quit the run loop.
         if inner.active_windows.is_empty() && inner.pending_windows.is_empty() {
             #[cfg(any(target_os = "windows", feature = "x11"))]
-            inner.app.quit();
+            inner.app_handle.quit();
         }
 
         // If we are closing the window that is currently responsible
@@ -235,6 +371,38 @@ impl AppRoot {
         // TODO - menu stuff
     }
 
+    /// Notify the app that a window has been resized.
+    ///
+    /// The delegate is notified before the window receives the [`WindowSize`](Event::WindowSize)
+    /// event.
+    pub fn window_resized(&mut self, window_id: WindowId, size: Size) -> Handled {
+        self.with_delegate(|delegate, ctx, env| {
+            delegate.on_window_resized(ctx, window_id, size, env)
+        });
+        self.handle_event(Event::WindowSize(size), window_id)
+    }
+
+    /// Notify the app that the scale factor of a window has changed, eg because it moved
+    /// to a screen with a different DPI.
+    pub fn window_scale_changed(&mut self, window_id: WindowId, scale: Scale) {
+        let old_scale = match self.inner().active_windows.get_mut(&window_id) {
+            Some(window) => std::mem::replace(&mut window.scale, scale),
+            None => return,
+        };
+        if old_scale == scale {
+            return;
+        }
+
+        self.with_delegate(|delegate, ctx, env| {
+            delegate.on_scale_changed(ctx, window_id, old_scale, scale, env)
+        });
+
+        self.process_commands_and_actions();
+        self.inner().invalidate_paint_regions();
+        self.process_ime_changes();
+        self.process_window_requests();
+    }
+
     /// Send an event to the widget hierarchy.
     ///
     /// Returns [`Handled::Yes`] if the event produced an action.
@@ -336,6 +504,13 @@ impl AppRoot {
         }
     }
 
+    /// Paint statistics for the last frame painted in the given window.
+    pub fn frame_metrics(&self, window_id: WindowId) -> Option<FrameMetrics> {
+        let inner = self.inner.borrow();
+        let window = inner.active_windows.get(&window_id)?;
+        Some(window.frame_metrics().clone())
+    }
+
     /// Run any leftover commands from previous events.
     pub fn run_commands(&mut self) {
         self.process_commands_and_actions();
@@ -430,14 +605,14 @@ impl AppRoot {
                     self.do_cmd(Command::from_ext(selector, payload, target))
                 }
                 Some(ExtMessage::Promise(promise_result, widget_id, window_id)) => {
-                    // TODO
-                    self.do_window_event(
-                        window_id,
-                        Event::Internal(InternalEvent::RoutePromiseResult(
-                            promise_result,
-                            widget_id,
-                        )),
-                    );
+                    let event = self
+                        .inner()
+                        .active_windows
+                        .get(&window_id)
+                        .and_then(|window| window.promise_result_event(promise_result, widget_id));
+                    if let Some(event) = event {
+                        self.do_window_event(window_id, event);
+                    }
                 }
                 None => break,
             }
@@ -475,7 +650,13 @@ impl AppRoot {
         use Target as T;
         match cmd.target() {
             // these are handled the same no matter where they come from
-            _ if cmd.is(sys_cmd::QUIT_APP) => self.inner().app_handle.quit(),
+            _ if cmd.is(sys_cmd::QUIT_APP) => {
+                if self.app_will_quit() == Handled::No {
+                    let mut inner = self.inner();
+                    inner.request_close_all_windows();
+                    inner.app_handle.quit();
+                }
+            }
             #[cfg(target_os = "macos")]
             _ if cmd.is(sys_cmd::HIDE_APPLICATION) => self.inner().hide_app(),
             #[cfg(target_os = "macos")]
@@ -490,18 +671,23 @@ impl AppRoot {
             // TODO - uncomment
             //T::Window(id) if cmd.is(sys_cmd::SHOW_OPEN_PANEL) => self.inner().show_open_panel(cmd, id),
             //T::Window(id) if cmd.is(sys_cmd::SHOW_SAVE_PANEL) => self.inner().show_save_panel(cmd, id),
-            //T::Window(id) if cmd.is(sys_cmd::CONFIGURE_WINDOW) => self.inner().request_configure_window(cmd, id),
+            T::Window(id) if cmd.is(sys_cmd::CONFIGURE_WINDOW) => {
+                let config = cmd.get(sys_cmd::CONFIGURE_WINDOW);
+                self.inner().request_configure_window(config, id);
+            }
             T::Window(id) if cmd.is(sys_cmd::CLOSE_WINDOW) => {
                 self.inner().request_close_window(id);
             }
             T::Window(id) if cmd.is(sys_cmd::SHOW_WINDOW) => self.inner().request_show_window(id),
-            //T::Window(id) if cmd.is(sys_cmd::PASTE) => self.inner().do_paste(id),
             _ if cmd.is(sys_cmd::CLOSE_WINDOW) => {
                 tracing::warn!("CLOSE_WINDOW command must target a window.")
             }
             _ if cmd.is(sys_cmd::SHOW_WINDOW) => {
                 tracing::warn!("SHOW_WINDOW command must target a window.")
             }
+            _ if cmd.is(sys_cmd::CONFIGURE_WINDOW) => {
+                tracing::warn!("CONFIGURE_WINDOW command must target a window.")
+            }
             // TODO - uncomment
             /*
             _ if cmd.is(sys_cmd::SHOW_OPEN_PANEL) => {
@@ -545,6 +731,18 @@ impl AppRoot {
         }
     }
 
+    /// Call the delegate's `on_app_will_quit` hook, unless it already agreed to quit.
+    ///
+    /// Returns `Handled::Yes` if the delegate vetoed quitting.
+    fn app_will_quit(&mut self) -> Handled {
+        let is_quitting = self.inner().is_quitting;
+        let vetoed = confirm_quit(is_quitting, || {
+            self.with_delegate(|delegate, ctx, env| delegate.on_app_will_quit(ctx, env))
+        });
+        self.inner().is_quitting = vetoed == Handled::No;
+        vetoed
+    }
+
     /// A helper fn for setting up the `DelegateCtx`. Takes a closure with
     /// an arbitrary return type `R`, and returns `Some(R)` if an `AppDelegate`
     /// is configured.
@@ -565,9 +763,12 @@ impl AppRoot {
                 &mut inner.action_queue,
                 &mut window.timers,
                 window.mock_timer_queue.as_mut(),
+                &mut window.rng,
                 &window.handle,
                 inner.main_window_id,
                 window.focus,
+                window.scale,
+                window.clipboard.clone(),
             );
             fake_widget_state = window.root.state.clone();
 
@@ -637,6 +838,7 @@ impl AppRoot {
             title,
             transparent: config.transparent.unwrap_or(false),
             size_policy: config.size_policy,
+            initial_focus: config.initial_focus,
         };
 
         let existing = self.inner.borrow_mut().pending_windows.insert(id, pending);
@@ -705,7 +907,7 @@ impl AppRootInner {
                     &mut self.action_queue,
                     &self.env,
                 );
-                window.handle.close();
+                window.close();
             }
         } else {
             tracing::warn!("Failed to close {window_id:?}: no active window with this id");
@@ -715,9 +917,7 @@ impl AppRootInner {
     // TODO - same confirmation code as request_close_window?
     /// Requests the platform to close all windows.
     fn request_close_all_windows(&mut self) {
-        for win in self.active_windows.values_mut() {
-            win.handle.close();
-        }
+        close_all_windows(self.active_windows.values_mut());
     }
 
     fn request_show_window(&mut self, id: WindowId) {
@@ -734,61 +934,14 @@ impl AppRootInner {
 
     fn dispatch_cmd(&mut self, cmd: Command) -> Handled {
         self.invalidate_paint_regions();
-        match cmd.target() {
-            Target::Global => {
-                for w in self.active_windows.values_mut() {
-                    if w.event(
-                        Event::Command(cmd.clone()),
-                        &mut self.debug_logger,
-                        &mut self.command_queue,
-                        &mut self.action_queue,
-                        &self.env,
-                    )
-                    .is_handled()
-                    {
-                        return Handled::Yes;
-                    }
-                }
-                return Handled::No;
-            }
-            Target::Window(id) => {
-                if let Some(w) = self.active_windows.get_mut(&id) {
-                    return w.event(
-                        Event::Command(cmd),
-                        &mut self.debug_logger,
-                        &mut self.command_queue,
-                        &mut self.action_queue,
-                        &self.env,
-                    );
-                }
-            }
-            // in this case we send it to every window that might contain
-            // this widget, breaking if the event is handled.
-            Target::Widget(id) => {
-                for w in self
-                    .active_windows
-                    .values_mut()
-                    .filter(|w| w.may_contain_widget(id))
-                {
-                    let event = Event::Internal(InternalEvent::TargetedCommand(cmd.clone()));
-                    if w.event(
-                        event,
-                        &mut self.debug_logger,
-                        &mut self.command_queue,
-                        &mut self.action_queue,
-                        &self.env,
-                    )
-                    .is_handled()
-                    {
-                        return Handled::Yes;
-                    }
-                }
-            }
-            Target::Auto => {
-                tracing::error!("{:?} reached window handler with `Target::Auto`", cmd);
-            }
-        }
-        Handled::No
+        dispatch_cmd_to_windows(
+            &mut self.active_windows,
+            cmd,
+            &mut self.debug_logger,
+            &mut self.command_queue,
+            &mut self.action_queue,
+            &self.env,
+        )
     }
 
     #[cfg(FALSE)]
@@ -853,6 +1006,30 @@ impl AppRootInner {
     }
 }
 
+/// Ask the delegate whether the app may quit, unless it already agreed to.
+///
+/// Returns `Handled::Yes` if the delegate vetoed quitting. This is shared with
+/// [`TestHarness`](crate::testing::TestHarness), so that tests go through the same checks.
+pub(crate) fn confirm_quit(
+    is_quitting: bool,
+    on_app_will_quit: impl FnOnce() -> Handled,
+) -> Handled {
+    if is_quitting {
+        return Handled::No;
+    }
+    on_app_will_quit()
+}
+
+/// Ask the platform to close each of `windows`.
+///
+/// This is shared with [`TestHarness`](crate::testing::TestHarness), which stands in
+/// for the platform and removes the closed window.
+pub(crate) fn close_all_windows<'a>(windows: impl Iterator<Item = &'a mut WindowRoot>) {
+    for window in windows {
+        window.close();
+    }
+}
+
 // ---
 
 impl WindowRoot {
@@ -866,23 +1043,39 @@ impl WindowRoot {
         size_policy: WindowSizePolicy,
         mock_timer_queue: Option<MockTimerQueue>,
     ) -> WindowRoot {
+        let alive = Arc::new(());
         WindowRoot {
             id,
             root: WidgetPod::new(root),
             size_policy: size_policy,
             size: Size::ZERO,
+            // The handle only has a scale once it's connected to a platform window.
+            scale: handle.get_scale().unwrap_or(Scale::new(1.0, 1.0)),
             invalid: Region::EMPTY,
             title,
             transparent,
             last_anim: None,
             last_mouse_pos: None,
             focus: None,
-            ext_event_sink,
+            initial_focus: None,
+            ext_event_sink: ext_event_sink.for_window(&alive),
             handle,
             timers: HashMap::new(),
             mock_timer_queue,
+            clipboard: Clipboard::platform(),
+            rng: Rng::from_entropy(),
             ime_handlers: Vec::new(),
             ime_focus_change: None,
+            frame_metrics: FrameMetrics::default(),
+            debug_overlay: false,
+            debug_overlay_outlines: 0,
+            hit_test_overlay: false,
+            hit_test_target: None,
+            event_log: None,
+            safe_area: Insets::ZERO,
+            safe_area_env: None,
+            alive,
+            closing: false,
         }
     }
 
@@ -897,6 +1090,43 @@ impl WindowRoot {
         &self.root.state().focus_chain
     }
 
+    /// Find the command of the innermost accelerator on the focused path that matches
+    /// this key event.
+    fn focused_accelerator(&self, key: &KeyEvent) -> Option<Command> {
+        let mut command = None;
+        let mut widget = Some(self.root.as_dyn());
+        while let Some(current) = widget.filter(|widget| widget.state().has_focus) {
+            let accelerators = &current.state().accelerators;
+            if let Some((_, cmd)) = accelerators.iter().find(|(hotkey, _)| hotkey.matches(key)) {
+                command = Some(cmd.clone());
+            }
+            widget = current
+                .children()
+                .into_iter()
+                .find(|child| child.state().has_focus);
+        }
+        command
+    }
+
+    /// Find the command of the first global accelerator, among enabled widgets, that
+    /// matches this key event.
+    fn global_accelerator(&self, key: &KeyEvent) -> Option<Command> {
+        fn find(widget: WidgetRef<'_, dyn Widget>, key: &KeyEvent) -> Option<Command> {
+            if widget.state().is_disabled() {
+                return None;
+            }
+            let accelerators = &widget.state().global_accelerators;
+            if let Some((_, cmd)) = accelerators.iter().find(|(hotkey, _)| hotkey.matches(key)) {
+                return Some(cmd.clone());
+            }
+            widget
+                .children()
+                .into_iter()
+                .find_map(|child| find(child, key))
+        }
+        find(self.root.as_dyn(), key)
+    }
+
     /// Returns `true` if the provided widget may be in this window,
     /// but it may also be a false positive.
     /// However when this returns `false` the widget is definitely not in this window.
@@ -1021,27 +1251,121 @@ impl WindowRoot {
         action_queue: &mut ActionQueue,
         env: &Env,
     ) -> Handled {
+        let log_entry = self
+            .event_log
+            .is_some()
+            .then(|| (Instant::now(), EventKind::of(&event), event.short_name()));
+
+        let handled = self.route_event(event, debug_logger, command_queue, action_queue, env);
+
+        if let (Some(event_log), Some((received, kind, name))) = (&mut self.event_log, log_entry) {
+            event_log.record(received, kind, name, handled);
+        }
+        handled
+    }
+
+    fn route_event(
+        &mut self,
+        event: Event,
+        debug_logger: &mut DebugLogger,
+        command_queue: &mut CommandQueue,
+        action_queue: &mut ActionQueue,
+        env: &Env,
+    ) -> Handled {
+        let env: &Env = &self.window_env(env);
         match &event {
             Event::WindowSize(size) => self.size = *size,
-            Event::MouseDown(e) | Event::MouseUp(e) | Event::MouseMove(e) | Event::Wheel(e) => {
-                self.last_mouse_pos = Some(e.pos)
+            Event::MouseDown(e) | Event::MouseMove(e) => {
+                self.last_mouse_pos = Some(e.pos);
+                // A WindowDragRegion under the mouse will set this again.
+                self.handle.handle_titlebar(false);
             }
-            Event::Internal(InternalEvent::MouseLeave) => self.last_mouse_pos = None,
+            Event::MouseUp(e) | Event::Wheel(e) => self.last_mouse_pos = Some(e.pos),
+            Event::MouseLeave => self.last_mouse_pos = None,
             _ => (),
         }
+        if self.hit_test_overlay && matches!(event, Event::MouseMove(_) | Event::MouseLeave) {
+            self.invalid.add_rect(self.size.to_rect());
+        }
 
+        let mut fired_timer = None;
         let event = match event {
-            Event::Timer(token) => {
-                if let Some(widget_id) = self.timers.get(&token) {
-                    Event::Internal(InternalEvent::RouteTimer(token, *widget_id))
-                } else {
+            Event::Timer(token) => match self.timers.get(&token) {
+                Some(timer) if timer.cancelled => {
+                    trace!("Ignoring cancelled timer {:?}", token);
+                    self.timers.remove(&token);
+                    return Handled::No;
+                }
+                Some(timer) => {
+                    fired_timer = Some(token);
+                    Event::Internal(InternalEvent::RouteTimer(timer.token, timer.widget_id))
+                }
+                None => {
                     error!("No widget found for timer {:?}", token);
                     return Handled::No;
                 }
+            },
+            // Paste commands sent to the window are turned into a Paste event, which
+            // goes to the focused widget.
+            Event::Command(cmd) | Event::Internal(InternalEvent::TargetedCommand(cmd))
+                if cmd.is(sys_cmd::PASTE)
+                    && matches!(cmd.target(), Target::Global | Target::Window(_)) =>
+            {
+                Event::Paste(self.clipboard.clone())
+            }
+            Event::Internal(InternalEvent::TargetedCommand(cmd))
+                if matches!(cmd.target(), Target::Named(_) | Target::ByType(_)) =>
+            {
+                let root = self.root.as_dyn();
+                let target = match cmd.target() {
+                    Target::Named(name) => root.find_widget_by_name(name),
+                    Target::ByType(type_id) => root.find_widget_by_type_id(type_id),
+                    _ => unreachable!(),
+                };
+                match target {
+                    Some(widget) => {
+                        let cmd = cmd.to(widget.id());
+                        Event::Internal(InternalEvent::TargetedCommand(cmd))
+                    }
+                    None => {
+                        trace!("No widget found for command target {:?}", cmd.target());
+                        return Handled::No;
+                    }
+                }
             }
             other => other,
         };
 
+        if let Event::Command(cmd) | Event::Internal(InternalEvent::TargetedCommand(cmd)) = &event {
+            // Global toggles aren't handled, so that they reach every window.
+            if cmd.is(sys_cmd::TOGGLE_DEBUG_OVERLAY)
+                && matches!(cmd.target(), Target::Global | Target::Window(_))
+            {
+                self.debug_overlay = !self.debug_overlay;
+                self.invalid.add_rect(self.size.to_rect());
+                return Handled::from(matches!(cmd.target(), Target::Window(_)));
+            }
+            if cmd.is(sys_cmd::TOGGLE_HIT_TEST_OVERLAY)
+                && matches!(cmd.target(), Target::Global | Target::Window(_))
+            {
+                self.hit_test_overlay = !self.hit_test_overlay;
+                self.invalid.add_rect(self.size.to_rect());
+                return Handled::from(matches!(cmd.target(), Target::Window(_)));
+            }
+        }
+
+        if let Event::KeyDown(key) = &event {
+            // Accelerators of the focused path win over global ones.
+            let command = self
+                .focused_accelerator(key)
+                .or_else(|| self.global_accelerator(key));
+            if let Some(command) = command {
+                trace!("Key event triggered accelerator {:?}", command);
+                command_queue.push_back(command.default_to(self.id.into()));
+                return Handled::Yes;
+            }
+        }
+
         if let Event::WindowConnected = event {
             self.lifecycle(
                 &LifeCycle::Internal(InternalLifeCycle::RouteWidgetAdded),
@@ -1054,6 +1378,11 @@ impl WindowRoot {
         }
 
         let mut widget_state = WidgetState::new(self.root.id(), Some(self.size), "<root>");
+        if let Event::WindowConnected = event {
+            if let Some(id) = self.initial_focus.take() {
+                widget_state.request_focus = Some(FocusChange::Focus(id));
+            }
+        }
         let is_handled = {
             let mut global_state = GlobalPassCtx::new(
                 self.ext_event_sink.clone(),
@@ -1062,9 +1391,12 @@ impl WindowRoot {
                 action_queue,
                 &mut self.timers,
                 self.mock_timer_queue.as_mut(),
+                &mut self.rng,
                 &self.handle,
                 self.id,
                 self.focus,
+                self.scale,
+                self.clipboard.clone(),
             );
             let mut notifications = VecDeque::new();
 
@@ -1098,16 +1430,25 @@ impl WindowRoot {
 
         // Clean up the timer token and do it immediately after the event handling
         // because the token may be reused and re-added in a lifecycle pass below.
-        if let Event::Internal(InternalEvent::RouteTimer(token, _)) = event {
-            self.timers.remove(&token);
+        // Interval timers are re-armed instead, unless they were cancelled while
+        // handling the event.
+        if let Some(token) = fired_timer {
+            if let Some(timer) = self.timers.remove(&token) {
+                if let (Some(interval), false) = (timer.interval, timer.cancelled) {
+                    let token = if self.mock_timer_queue.is_some() {
+                        // The mock queue already re-armed the timer.
+                        token
+                    } else {
+                        self.handle.request_timer(interval)
+                    };
+                    self.timers.insert(token, timer);
+                }
+            }
         }
 
         if let Some(cursor) = &widget_state.cursor {
             self.handle.set_cursor(cursor);
-        } else if matches!(
-            event,
-            Event::MouseMove(..) | Event::Internal(InternalEvent::MouseLeave)
-        ) {
+        } else if matches!(event, Event::MouseMove(..) | Event::MouseLeave) {
             self.handle.set_cursor(&Cursor::Arrow);
         }
 
@@ -1143,6 +1484,7 @@ impl WindowRoot {
         env: &Env,
         process_commands: bool,
     ) {
+        let env: &Env = &self.window_env(env);
         let mut widget_state = WidgetState::new(self.root.id(), Some(self.size), "<root>");
         let mut global_state = GlobalPassCtx::new(
             self.ext_event_sink.clone(),
@@ -1151,9 +1493,12 @@ impl WindowRoot {
             action_queue,
             &mut self.timers,
             self.mock_timer_queue.as_mut(),
+            &mut self.rng,
             &self.handle,
             self.id,
             self.focus,
+            self.scale,
+            self.clipboard.clone(),
         );
         let mut ctx = LifeCycleCtx {
             global_state: &mut global_state,
@@ -1191,6 +1536,94 @@ impl WindowRoot {
         self.invalid.clear();
     }
 
+    /// Ask the platform to close this window.
+    ///
+    /// The platform then destroys the window, which removes it from the app.
+    pub(crate) fn close(&mut self) {
+        self.closing = true;
+        self.handle.close();
+    }
+
+    /// Return `true` if the platform has been asked to close this window.
+    pub fn is_closing(&self) -> bool {
+        self.closing
+    }
+
+    /// Paint statistics for the last frame painted in this window.
+    pub fn frame_metrics(&self) -> &FrameMetrics {
+        &self.frame_metrics
+    }
+
+    /// Start recording the events this window receives in `event_log`, or stop
+    /// recording them if `None` is passed.
+    pub fn set_event_log(&mut self, event_log: Option<EventLog>) {
+        self.event_log = event_log;
+    }
+
+    /// The events recorded since [`set_event_log`](Self::set_event_log) was called.
+    pub fn event_log(&self) -> Option<&EventLog> {
+        self.event_log.as_ref()
+    }
+
+    /// Set the parts of the window which may be obscured, eg by rounded corners or a
+    /// camera notch, and lay the window out again.
+    ///
+    /// druid-shell doesn't report these, so they must come from the app. Widgets read
+    /// them from [`SAFE_AREA_INSETS`](crate::theme::SAFE_AREA_INSETS) in the env, and
+    /// [`SafeArea`](crate::widget::SafeArea) keeps its child inside them.
+    pub fn set_safe_area_insets(&mut self, insets: Insets) {
+        if self.safe_area == insets {
+            return;
+        }
+        self.safe_area = insets;
+        self.safe_area_env = None;
+        self.root.state.needs_layout = true;
+        self.invalid.add_rect(self.size.to_rect());
+    }
+
+    /// The parts of the window which may be obscured.
+    ///
+    /// See [`set_safe_area_insets`](Self::set_safe_area_insets).
+    pub fn safe_area_insets(&self) -> Insets {
+        self.safe_area
+    }
+
+    /// The env seen by the widgets of this window, which holds its safe area.
+    ///
+    /// The env is only copied when the safe area or the app env changes, so that its
+    /// [version](Env::version) stays the same from one pass to the next.
+    fn window_env(&mut self, env: &Env) -> Env {
+        if env.try_get(crate::theme::SAFE_AREA_INSETS).ok() == Some(self.safe_area) {
+            return env.clone();
+        }
+        match &self.safe_area_env {
+            Some((version, window_env)) if *version == env.version() => window_env.clone(),
+            _ => {
+                let window_env = env
+                    .clone()
+                    .adding(crate::theme::SAFE_AREA_INSETS, self.safe_area);
+                self.safe_area_env = Some((env.version(), window_env.clone()));
+                window_env
+            }
+        }
+    }
+
+    /// The number of widgets outlined by the debug overlay in the last frame painted.
+    ///
+    /// See [`TOGGLE_DEBUG_OVERLAY`](crate::command::TOGGLE_DEBUG_OVERLAY).
+    #[allow(dead_code)]
+    pub(crate) fn debug_overlay_outlines(&self) -> usize {
+        self.debug_overlay_outlines
+    }
+
+    /// The widget highlighted by the hit-test overlay in the last frame painted.
+    ///
+    /// See [`TOGGLE_HIT_TEST_OVERLAY`](crate::command::TOGGLE_HIT_TEST_OVERLAY).
+    #[allow(dead_code)]
+    pub(crate) fn hit_test_target(&self) -> Option<WidgetId> {
+        self.hit_test_target
+    }
+
     #[allow(dead_code)]
     pub(crate) fn invalid(&self) -> &Region {
         &self.invalid
@@ -1269,6 +1702,7 @@ impl WindowRoot {
         action_queue: &mut ActionQueue,
         env: &Env,
     ) {
+        let env: &Env = &self.window_env(env);
         let mut widget_state = WidgetState::new(self.root.id(), Some(self.size), "<root>");
         let mut global_state = GlobalPassCtx::new(
             self.ext_event_sink.clone(),
@@ -1277,9 +1711,12 @@ impl WindowRoot {
             action_queue,
             &mut self.timers,
             self.mock_timer_queue.as_mut(),
+            &mut self.rng,
             &self.handle,
             self.id,
             self.focus,
+            self.scale,
+            self.clipboard.clone(),
         );
         let mut layout_ctx = LayoutCtx {
             global_state: &mut global_state,
@@ -1337,6 +1774,7 @@ impl WindowRoot {
         action_queue: &mut ActionQueue,
         env: &Env,
     ) {
+        let env: &Env = &self.window_env(env);
         let widget_state = WidgetState::new(self.root.id(), Some(self.size), "<root>");
         let mut global_state = GlobalPassCtx::new(
             self.ext_event_sink.clone(),
@@ -1345,9 +1783,12 @@ impl WindowRoot {
             action_queue,
             &mut self.timers,
             self.mock_timer_queue.as_mut(),
+            &mut self.rng,
             &self.handle,
             self.id,
             self.focus,
+            self.scale,
+            self.clipboard.clone(),
         );
         let mut ctx = PaintCtx {
             render_ctx: piet,
@@ -1356,6 +1797,7 @@ impl WindowRoot {
             z_ops: Vec::new(),
             region: invalid.clone(),
             depth: 0,
+            crisp_edges: env.get(crate::theme::PREFERS_CRISP_EDGES),
         };
 
         let root = &mut self.root;
@@ -1375,6 +1817,34 @@ impl WindowRoot {
             });
         }
 
+        if self.debug_overlay {
+            let root = self.root.as_dyn();
+            let mut outlines = 0;
+            ctx.with_child_ctx(invalid.clone(), |ctx| {
+                outlines = paint_debug_overlay(ctx, root, env);
+            });
+            self.debug_overlay_outlines = outlines;
+        }
+
+        self.hit_test_target = None;
+        if let (true, Some(pos)) = (self.hit_test_overlay, self.last_mouse_pos) {
+            let root = self.root.as_dyn();
+            if let Some(target) = root.find_widget_at_pos(pos) {
+                let mut chain = Vec::new();
+                find_ancestor_chain(root, target.id(), &mut chain);
+                ctx.with_child_ctx(invalid.clone(), |ctx| {
+                    paint_hit_test_overlay(ctx, &chain);
+                });
+                self.hit_test_target = Some(target.id());
+            }
+        }
+
+        self.frame_metrics = FrameMetrics {
+            damaged_area: region_area(invalid, self.size.to_rect()),
+            window_area: self.size.area(),
+            painted_widgets: ctx.global_state.painted_widgets,
+        };
+
         if self.wants_animation_frame() {
             self.handle.request_anim_frame();
         }
@@ -1397,9 +1867,17 @@ impl WindowRoot {
         mutable: bool,
     ) -> Option<Box<dyn InputHandler>> {
         let focused_widget_id = self.focus?;
+        self.get_widget_ime_handler(focused_widget_id, mutable)
+    }
+
+    pub(crate) fn get_widget_ime_handler(
+        &self,
+        widget_id: WidgetId,
+        mutable: bool,
+    ) -> Option<Box<dyn InputHandler>> {
         self.ime_handlers
             .iter()
-            .find(|(_, reg)| reg.widget_id == focused_widget_id)
+            .find(|(_, reg)| reg.widget_id == widget_id)
             .and_then(|(_, reg)| reg.document.acquire(mutable))
     }
 
@@ -1488,12 +1966,38 @@ impl WindowRoot {
 
     pub(crate) fn release_focused_ime_handler(&mut self) -> Option<WidgetId> {
         let focused_widget_id = self.focus?;
+        self.release_widget_ime_handler(focused_widget_id)
+    }
+
+    pub(crate) fn release_widget_ime_handler(&self, widget_id: WidgetId) -> Option<WidgetId> {
         self.ime_handlers
             .iter()
-            .find(|(_, reg)| reg.widget_id == focused_widget_id)
+            .find(|(_, reg)| reg.widget_id == widget_id)
             .and_then(|(_, reg)| reg.document.release().then(|| reg.widget_id))
     }
 
+    /// Change focus the same way it changes when the focused widget requests it, eg with
+    /// [`EventCtx::focus_next`].
+    pub(crate) fn change_focus(
+        &mut self,
+        focus_change: FocusChange,
+        debug_logger: &mut DebugLogger,
+        command_queue: &mut CommandQueue,
+        action_queue: &mut ActionQueue,
+        env: &Env,
+    ) {
+        let mut widget_state = WidgetState::new(self.root.id(), Some(self.size), "<root>");
+        widget_state.request_focus = Some(focus_change);
+        self.post_event_processing(
+            &mut widget_state,
+            debug_logger,
+            command_queue,
+            action_queue,
+            env,
+            false,
+        );
+    }
+
     fn widget_for_focus_request(&self, focus: FocusChange) -> Option<WidgetId> {
         match focus {
             FocusChange::Resign => None,
@@ -1504,28 +2008,33 @@ impl WindowRoot {
     }
 
     fn widget_from_focus_chain(&self, forward: bool) -> Option<WidgetId> {
+        // If a focus trap is active, focus can't leave it.
+        let focus_chain = match &self.root.state().trapped_focus_chain {
+            Some(trapped_focus_chain) => trapped_focus_chain.as_slice(),
+            None => self.focus_chain(),
+        };
         self.focus.and_then(|focus| {
-            self.focus_chain()
+            focus_chain
                 .iter()
                 // Find where the focused widget is in the focus chain
                 .position(|id| id == &focus)
                 .map(|idx| {
                     // Return the id that's next to it in the focus chain
-                    let len = self.focus_chain().len();
+                    let len = focus_chain.len();
                     let new_idx = if forward {
                         (idx + 1) % len
                     } else {
                         (idx + len - 1) % len
                     };
-                    self.focus_chain()[new_idx]
+                    focus_chain[new_idx]
                 })
                 .or_else(|| {
                     // If the currently focused widget isn't in the focus chain,
                     // then we'll just return the first/last entry of the chain, if any.
                     if forward {
-                        self.focus_chain().first().copied()
+                        focus_chain.first().copied()
                     } else {
-                        self.focus_chain().last().copied()
+                        focus_chain.last().copied()
                     }
                 })
         })
@@ -1541,6 +2050,28 @@ impl WindowRoot {
         self.root.as_dyn().find_widget_by_id(id)
     }
 
+    /// Return the event delivering a promise result to the given widget.
+    ///
+    /// Returns `None` if the widget that started the task was removed since, in
+    /// which case the result is discarded.
+    pub(crate) fn promise_result_event(
+        &self,
+        promise_result: PromiseResult,
+        widget_id: WidgetId,
+    ) -> Option<Event> {
+        if self.find_widget_by_id(widget_id).is_none() {
+            trace!(
+                "Discarding promise result for removed widget {:?}",
+                widget_id
+            );
+            return None;
+        }
+        Some(Event::Internal(InternalEvent::RoutePromiseResult(
+            promise_result,
+            widget_id,
+        )))
+    }
+
     /// Recursively find innermost widget at given position.
     pub fn find_widget_at_pos(&self, pos: Point) -> Option<WidgetRef<'_, dyn Widget>> {
         self.root.as_dyn().find_widget_at_pos(pos)
@@ -1550,4 +2081,234 @@ impl WindowRoot {
     pub fn focused_widget(&self) -> Option<WidgetRef<'_, dyn Widget>> {
         self.find_widget_by_id(self.focus?)
     }
+
+    /// Return a rough estimate of the memory used by the widget tree.
+    ///
+    /// See [`WidgetRef::estimated_memory`].
+    pub fn estimated_memory(&self) -> usize {
+        self.root.as_dyn().estimated_memory()
+    }
+}
+
+const DEBUG_HOT_COLOR: Color = Color::rgb8(0xff, 0xd7, 0x00);
+const DEBUG_ACTIVE_COLOR: Color = Color::rgb8(0xff, 0x45, 0x00);
+const DEBUG_FOCUS_COLOR: Color = Color::rgb8(0x00, 0xbf, 0xff);
+const DEBUG_BASELINE_COLOR: Color = Color::rgb8(0xff, 0x00, 0xff);
+const DEBUG_HIT_TARGET_COLOR: Color = Color::rgb8(0x32, 0xcd, 0x32);
+const DEBUG_HIT_ANCESTOR_COLOR: Color = Color::rgb8(0x90, 0xee, 0x90);
+
+/// Outline the layout rect and baseline of `widget` and its descendants, and return
+/// the number of outlines painted.
+///
+/// Focused, active and hot widgets get a fixed color, in that order of precedence.
+/// Other widgets get their debug color.
+fn paint_debug_overlay(ctx: &mut PaintCtx, widget: WidgetRef<'_, dyn Widget>, env: &Env) -> usize {
+    const BORDER_WIDTH: f64 = 1.0;
+
+    let state = widget.state();
+    if state.is_stashed {
+        return 0;
+    }
+
+    let rect = Rect::from_origin_size(state.window_origin(), state.size);
+    let color = if state.has_focus {
+        DEBUG_FOCUS_COLOR
+    } else if state.is_active {
+        DEBUG_ACTIVE_COLOR
+    } else if state.is_hot {
+        DEBUG_HOT_COLOR
+    } else {
+        env.get_debug_color(state.id.to_raw())
+    };
+    ctx.stroke(rect.inset(BORDER_WIDTH / -2.0), &color, BORDER_WIDTH);
+
+    if state.baseline_offset > 0.0 {
+        let baseline_y = rect.y1 - state.baseline_offset;
+        let baseline = Line::new((rect.x0, baseline_y), (rect.x1, baseline_y));
+        ctx.stroke(baseline, &DEBUG_BASELINE_COLOR, BORDER_WIDTH);
+    }
+
+    let mut outlines = 1;
+    for child in widget.children() {
+        outlines += paint_debug_overlay(ctx, child, env);
+    }
+    outlines
+}
+
+/// Push `widget` and its descendants down to the widget with the given id to `chain`.
+///
+/// Returns `false`, leaving `chain` unchanged, if that widget isn't in the subtree.
+fn find_ancestor_chain<'w>(
+    widget: WidgetRef<'w, dyn Widget>,
+    id: WidgetId,
+    chain: &mut Vec<WidgetRef<'w, dyn Widget>>,
+) -> bool {
+    chain.push(widget);
+    if widget.id() == id {
+        return true;
+    }
+    for child in widget.children() {
+        if find_ancestor_chain(child, id, chain) {
+            return true;
+        }
+    }
+    chain.pop();
+    false
+}
+
+/// Highlight the last widget of `chain`, outline the others, and label the last widget
+/// with its type, id and layout rect.
+fn paint_hit_test_overlay(ctx: &mut PaintCtx, chain: &[WidgetRef<'_, dyn Widget>]) {
+    let (target, ancestors) = match chain.split_last() {
+        Some(split) => split,
+        None => return,
+    };
+
+    for ancestor in ancestors {
+        let rect = ancestor.state().window_layout_rect();
+        ctx.stroke(rect.inset(-0.5), &DEBUG_HIT_ANCESTOR_COLOR, 1.0);
+    }
+
+    let rect = target.state().window_layout_rect();
+    ctx.fill(rect, &DEBUG_HIT_TARGET_COLOR.with_alpha(0.25));
+    ctx.stroke(rect.inset(-1.0), &DEBUG_HIT_TARGET_COLOR, 2.0);
+
+    let label = format!(
+        "{} #{} ({}, {}) {}x{}",
+        target.short_type_name(),
+        target.id().to_raw(),
+        rect.x0,
+        rect.y0,
+        rect.width(),
+        rect.height(),
+    );
+    let layout = ctx
+        .text()
+        .new_text_layout(label)
+        .font(FontFamily::MONOSPACE, 10.0)
+        .text_color(Color::WHITE)
+        .build();
+    if let Ok(layout) = layout {
+        let label_rect = Rect::from_origin_size(rect.origin(), layout.size());
+        ctx.fill(label_rect, &Color::BLACK.with_alpha(0.75));
+        ctx.draw_text(&layout, rect.origin());
+    }
+}
+
+/// The area of the union of the region's rects, clipped to `clip`.
+///
+/// The rects of a region may overlap, so their areas can't just be summed.
+fn region_area(region: &Region, clip: Rect) -> f64 {
+    let rects: Vec<Rect> = region
+        .rects()
+        .iter()
+        .map(|rect| rect.intersect(clip))
+        .filter(|rect| rect.area() > 0.0)
+        .collect();
+
+    let mut xs: Vec<f64> = rects.iter().flat_map(|rect| [rect.x0, rect.x1]).collect();
+    xs.sort_by(f64::total_cmp);
+    xs.dedup();
+
+    // Sweep over vertical strips, merging the covered intervals within each strip.
+    let mut area = 0.0;
+    for strip in xs.windows(2) {
+        let (x0, x1) = (strip[0], strip[1]);
+        let mut spans: Vec<(f64, f64)> = rects
+            .iter()
+            .filter(|rect| rect.x0 <= x0 && rect.x1 >= x1)
+            .map(|rect| (rect.y0, rect.y1))
+            .collect();
+        spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut covered = 0.0;
+        let mut last_y = f64::NEG_INFINITY;
+        for (y0, y1) in spans {
+            let y0 = y0.max(last_y);
+            if y1 > y0 {
+                covered += y1 - y0;
+                last_y = y1;
+            }
+        }
+        area += covered * (x1 - x0);
+    }
+    area
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::Label;
+
+    fn make_windows(count: usize) -> HashMap<WindowId, WindowRoot> {
+        let ext_event_queue = ExtEventQueue::new();
+        (0..count)
+            .map(|_| {
+                let window = WindowRoot::new(
+                    WindowId::next(),
+                    Default::default(),
+                    ext_event_queue.make_sink(),
+                    Box::new(Label::new("Hello")),
+                    "Masonry test window".into(),
+                    false,
+                    WindowSizePolicy::User,
+                    Some(MockTimerQueue::new()),
+                );
+                (window.id, window)
+            })
+            .collect()
+    }
+
+    fn dispatch(windows: &mut HashMap<WindowId, WindowRoot>, cmd: Command) -> Handled {
+        dispatch_cmd_to_windows(
+            windows,
+            cmd,
+            &mut DebugLogger::new(false),
+            &mut VecDeque::new(),
+            &mut VecDeque::new(),
+            &Env::with_theme(),
+        )
+    }
+
+    #[test]
+    fn global_debug_overlay_toggles_every_window() {
+        let mut windows = make_windows(2);
+
+        let cmd = Command::from(sys_cmd::TOGGLE_DEBUG_OVERLAY).to(Target::Global);
+        assert_eq!(dispatch(&mut windows, cmd), Handled::No);
+        assert!(windows.values().all(|w| w.debug_overlay));
+    }
+
+    #[test]
+    fn window_debug_overlay_toggles_one_window() {
+        let mut windows = make_windows(2);
+        let target_id = *windows.keys().next().unwrap();
+
+        let cmd = Command::from(sys_cmd::TOGGLE_DEBUG_OVERLAY).to(target_id);
+        assert_eq!(dispatch(&mut windows, cmd), Handled::Yes);
+        for (id, window) in &windows {
+            assert_eq!(window.debug_overlay, *id == target_id);
+        }
+    }
+
+    #[test]
+    fn global_hit_test_overlay_toggles_every_window() {
+        let mut windows = make_windows(2);
+
+        let cmd = Command::from(sys_cmd::TOGGLE_HIT_TEST_OVERLAY).to(Target::Global);
+        assert_eq!(dispatch(&mut windows, cmd), Handled::No);
+        assert!(windows.values().all(|w| w.hit_test_overlay));
+    }
+
+    #[test]
+    fn window_hit_test_overlay_toggles_one_window() {
+        let mut windows = make_windows(2);
+        let target_id = *windows.keys().next().unwrap();
+
+        let cmd = Command::from(sys_cmd::TOGGLE_HIT_TEST_OVERLAY).to(target_id);
+        assert_eq!(dispatch(&mut windows, cmd), Handled::Yes);
+        for (id, window) in &windows {
+            assert_eq!(window.hit_test_overlay, *id == target_id);
+        }
+    }
 }
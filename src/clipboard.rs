@@ -0,0 +1,57 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! Access to the system clipboard.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A handle to the clipboard, available to widgets through
+/// [`EventCtx::clipboard`](crate::EventCtx::clipboard) and in [`Event::Paste`](crate::Event::Paste).
+///
+/// In a running app this is the system clipboard. In the
+/// [`TestHarness`](crate::testing::TestHarness) it's an in-memory clipboard, which tests
+/// can inspect and pre-fill, so widget code is the same in both environments.
+#[derive(Clone, Debug)]
+pub struct Clipboard(ClipboardInner);
+
+#[derive(Clone, Debug)]
+enum ClipboardInner {
+    Platform,
+    Mock(Rc<RefCell<Option<String>>>),
+}
+
+impl Clipboard {
+    /// The system clipboard.
+    pub(crate) fn platform() -> Self {
+        Clipboard(ClipboardInner::Platform)
+    }
+
+    /// An empty in-memory clipboard, for unit tests.
+    ///
+    /// Clones of the returned handle share the same contents.
+    pub(crate) fn mock() -> Self {
+        Clipboard(ClipboardInner::Mock(Default::default()))
+    }
+
+    /// Put a string onto the clipboard, replacing its previous contents.
+    pub fn put_string(&mut self, s: impl AsRef<str>) {
+        match &self.0 {
+            ClipboardInner::Platform => {
+                druid_shell::Application::global().clipboard().put_string(s)
+            }
+            ClipboardInner::Mock(contents) => {
+                *contents.borrow_mut() = Some(s.as_ref().to_string());
+            }
+        }
+    }
+
+    /// Get the clipboard's contents as a string, if it holds text.
+    pub fn get_string(&self) -> Option<String> {
+        match &self.0 {
+            ClipboardInner::Platform => druid_shell::Application::global().clipboard().get_string(),
+            ClipboardInner::Mock(contents) => contents.borrow().clone(),
+        }
+    }
+}
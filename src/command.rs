@@ -4,12 +4,12 @@
 
 //! Custom commands.
 
-use std::any::Any;
+use std::any::{Any, TypeId};
 use std::collections::VecDeque;
 use std::marker::PhantomData;
 use std::sync::{Arc, Mutex};
 
-use crate::{WidgetId, WindowId};
+use crate::{Widget, WidgetId, WindowId};
 
 /// The identity of a [`Selector`].
 ///
@@ -136,6 +136,16 @@ pub enum Target {
     Window(WindowId),
     /// The target is a specific widget.
     Widget(WidgetId),
+    /// The target is the first widget, in depth-first order, with the given name.
+    ///
+    /// Widgets are named with [`WidgetPod::new_with_name`](crate::WidgetPod::new_with_name).
+    /// The command is dropped if no widget has the name.
+    Named(&'static str),
+    /// The target is the first widget, in depth-first order, of the given type.
+    ///
+    /// Use [`Target::by_type`] to create this target.
+    /// The command is dropped if no widget has the type.
+    ByType(TypeId),
     // FIXME - remove this variant
     /// The target will be determined automatically.
     ///
@@ -160,6 +170,9 @@ mod sys {
     use crate::WidgetId;
 
     /// Quit the running application. This command is handled by the Masonry library.
+    ///
+    /// [`AppDelegate::on_app_will_quit`](crate::AppDelegate::on_app_will_quit) is called
+    /// first, then all windows are closed. See also [`DelegateCtx::quit`](crate::DelegateCtx::quit).
     pub const QUIT_APP: Selector = Selector::new("masonry-builtin.quit-app");
 
     /// Hide the application. (mac only)
@@ -197,6 +210,22 @@ mod sys {
     /// will automatically target the window containing the widget.
     pub const SHOW_WINDOW: Selector = Selector::new("masonry-builtin.show-window");
 
+    /// Show or hide the debug overlay of a window.
+    ///
+    /// The overlay outlines the layout rect of every widget, draws their baseline, and
+    /// shows whether they're hot, active or focused. It's painted on top of everything
+    /// else. If the command targets the whole app, every window toggles its overlay.
+    pub const TOGGLE_DEBUG_OVERLAY: Selector =
+        Selector::new("masonry-builtin.toggle-debug-overlay");
+
+    /// Show or hide the hit-test overlay of a window.
+    ///
+    /// The overlay highlights the widget under the mouse and outlines its ancestors, and
+    /// shows the type, id and layout rect of that widget. It follows the mouse as it moves.
+    /// If the command targets the whole app, every window toggles its overlay.
+    pub const TOGGLE_HIT_TEST_OVERLAY: Selector =
+        Selector::new("masonry-builtin.toggle-hit-test-overlay");
+
     /// Apply the configuration payload to an existing window. The target should be a WindowId.
     pub const CONFIGURE_WINDOW: Selector<WindowConfig> =
         Selector::new("masonry-builtin.configure-window");
@@ -507,6 +536,11 @@ impl<T> Clone for Selector<T> {
 }
 
 impl Target {
+    /// A target for the first widget of type `W`.
+    pub fn by_type<W: Widget + 'static>() -> Target {
+        Target::ByType(TypeId::of::<W>())
+    }
+
     /// If `self` is `Auto` it will be replaced with `target`.
     pub(crate) fn default(&mut self, target: Target) {
         if self == &Target::Auto {
@@ -567,4 +601,43 @@ mod tests {
 
         assert_send_sync::<Selector>();
     }
+
+    #[test]
+    fn command_to_named_widget() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        use crate::testing::{ModularWidget, TestHarness, TestWidgetExt as _};
+        use crate::widget::Flex;
+        use crate::Event;
+
+        const PING: Selector = Selector::new("masonry-test.ping");
+
+        fn ping_counter(count: Rc<Cell<u32>>) -> ModularWidget<Rc<Cell<u32>>> {
+            ModularWidget::new(count).event_fn(|count, _ctx, event, _| {
+                if let Event::Command(cmd) = event {
+                    if cmd.is(PING) {
+                        count.set(count.get() + 1);
+                    }
+                }
+            })
+        }
+
+        let other_pings = Rc::new(Cell::new(0));
+        let search_pings = Rc::new(Cell::new(0));
+        let widget = Flex::column()
+            .with_child(ping_counter(other_pings.clone()).with_name("other"))
+            .with_child(ping_counter(search_pings.clone()).with_name("search"));
+
+        let mut harness = TestHarness::create(widget);
+
+        harness.submit_command(PING.to(Target::Named("search")));
+        assert_eq!((other_pings.get(), search_pings.get()), (0, 1));
+
+        harness.submit_command(PING.to(Target::Named("missing")));
+        assert_eq!((other_pings.get(), search_pings.get()), (0, 1));
+
+        harness.submit_command(PING.to(Target::by_type::<ModularWidget<Rc<Cell<u32>>>>()));
+        assert_eq!((other_pings.get(), search_pings.get()), (1, 1));
+    }
 }
@@ -11,21 +11,23 @@ use std::rc::Rc;
 use std::time::Duration;
 
 use druid_shell::text::Event as ImeInvalidation;
-use druid_shell::{Cursor, Region, TimerToken, WindowHandle};
+use druid_shell::{Cursor, HotKey, Region, Scale, TimerToken, WindowHandle};
 use tracing::{error, trace, warn};
 
 use crate::action::{Action, ActionQueue};
 use crate::command::{Command, CommandQueue, Notification, SingleUse};
 use crate::debug_logger::DebugLogger;
 use crate::ext_event::ExtEventSink;
-use crate::piet::{Piet, PietText, RenderContext};
+use crate::piet::{InterpolationMode, Piet, PietText, RenderContext};
 use crate::platform::WindowDescription;
-use crate::promise::PromiseToken;
+use crate::promise::{spawn_promise_task, PromiseToken};
+use crate::rng::Rng;
 use crate::testing::MockTimerQueue;
 use crate::text::{ImeHandlerRef, TextFieldRegistration};
 use crate::widget::{CursorChange, FocusChange, StoreInWidgetMut, WidgetMut, WidgetState};
 use crate::{
-    Affine, Env, Insets, Point, Rect, Size, Target, Vec2, Widget, WidgetId, WidgetPod, WindowId,
+    Affine, Clipboard, Env, Insets, LifeCycle, Point, Rect, Size, Target, Vec2, Widget, WidgetId,
+    WidgetPod, WindowId,
 };
 
 /// A macro for implementing methods on multiple contexts.
@@ -41,9 +43,24 @@ macro_rules! impl_context_method {
         impl_context_method!($($more),+, { $($method)+ });
     };
 }
+pub(crate) use impl_context_method;
 
 // TODO - remove second lifetime, only keep queues and Rc
 // TODO - rename lifetimes
+// Zero-length intervals would fire endlessly.
+const MIN_TIMER_INTERVAL: Duration = Duration::from_millis(1);
+
+/// A timer requested by a widget, keyed by the token of the underlying platform timer.
+pub(crate) struct WidgetTimer {
+    pub(crate) widget_id: WidgetId,
+    /// The token the widget was given. Interval timers re-arm the platform timer each
+    /// time they fire, but keep the same token.
+    pub(crate) token: TimerToken,
+    pub(crate) interval: Option<Duration>,
+    /// The widget cancelled the timer, but the platform timer is still pending.
+    pub(crate) cancelled: bool,
+}
+
 /// Static state that is shared between most contexts.
 pub(crate) struct GlobalPassCtx<'a> {
     pub(crate) ext_event_sink: ExtEventSink,
@@ -52,14 +69,19 @@ pub(crate) struct GlobalPassCtx<'a> {
     pub(crate) action_queue: &'a mut ActionQueue,
     // TODO - merge queues
     // Associate timers with widgets that requested them.
-    pub(crate) timers: &'a mut HashMap<TimerToken, WidgetId>,
+    pub(crate) timers: &'a mut HashMap<TimerToken, WidgetTimer>,
     // Used in Harness for unit tests - see `src/testing/mock_timer_queue.rs`
     pub(crate) mock_timer_queue: Option<&'a mut MockTimerQueue>,
+    pub(crate) rng: &'a mut Rng,
     pub(crate) window_id: WindowId,
     pub(crate) window: &'a WindowHandle,
     pub(crate) text: PietText,
     /// The id of the widget that currently has focus.
     pub(crate) focus_widget: Option<WidgetId>,
+    /// The number of widgets painted so far in the current paint pass.
+    pub(crate) painted_widgets: usize,
+    pub(crate) scale: Scale,
+    pub(crate) clipboard: Clipboard,
 }
 
 /// A context provided to implementors of [`StoreInWidgetMut`].
@@ -130,6 +152,8 @@ pub struct PaintCtx<'a, 'b, 'c> {
     pub(crate) region: Region,
     /// The approximate depth in the tree at the time of painting.
     pub(crate) depth: u32,
+    /// Whether [`theme::PREFERS_CRISP_EDGES`](crate::theme::PREFERS_CRISP_EDGES) is set.
+    pub(crate) crisp_edges: bool,
 }
 
 impl_context_method!(
@@ -154,11 +178,33 @@ impl_context_method!(
             self.global_state.window_id
         }
 
+        /// The scale factor of the window, ie the number of device pixels per display point.
+        ///
+        /// Use it with [`Dp`](crate::Dp) and [`Px`](crate::Px) to convert lengths.
+        pub fn scale(&self) -> Scale {
+            self.global_state.scale
+        }
+
+        /// A handle to the clipboard.
+        pub fn clipboard(&self) -> Clipboard {
+            self.global_state.clipboard.clone()
+        }
+
         /// Get an object which can create text layouts.
         pub fn text(&mut self) -> &mut PietText {
             &mut self.global_state.text
         }
 
+        /// Get the window's random number generator.
+        ///
+        /// Widgets that need randomness (eg for visual effects) should use this
+        /// generator instead of their own source of entropy. In a running app it is
+        /// seeded from the OS; in [`TestHarness`](crate::testing::TestHarness) it is
+        /// seeded with a fixed value, so that tests and render snapshots are reproducible.
+        pub fn rng(&mut self) -> &mut Rng {
+            &mut *self.global_state.rng
+        }
+
         /// Skip iterating over the given child.
         ///
         /// Normally, container widgets are supposed to iterate over each of their
@@ -456,6 +502,87 @@ impl_context_method!(WidgetCtx<'_, '_>, EventCtx<'_, '_>, LifeCycleCtx<'_, '_>,
         self.request_layout();
     }
 
+    /// Set whether keyboard focus is trapped in this widget's subtree.
+    ///
+    /// While a focus trap is in the tree, [`focus_next`](EventCtx::focus_next) and
+    /// [`focus_prev`](EventCtx::focus_prev) cycle through the focusable widgets inside the
+    /// trap, wrapping around at either end, instead of moving to the rest of the window.
+    /// This is what modal dialogs should use.
+    ///
+    /// If traps are nested, the innermost one wins. If several traps are side by side,
+    /// the last one in the tree wins, so a dialog opened over another one, which is
+    /// added after it, takes the focus trap over.
+    pub fn set_focus_trap(&mut self, is_focus_trap: bool) {
+        trace!("set_focus_trap {}", is_focus_trap);
+        if self.widget_state.is_focus_trap != is_focus_trap {
+            self.widget_state.is_focus_trap = is_focus_trap;
+            self.widget_state.update_focus_chain = true;
+        }
+    }
+
+    /// Register a keyboard accelerator for this widget's subtree.
+    ///
+    /// While this widget or one of its descendants has focus, pressing `hotkey` submits
+    /// `command` instead of sending the key event to the focused widget. If several
+    /// widgets on the focused path register the same hotkey, the innermost one wins.
+    /// These accelerators are checked before
+    /// [global ones](Self::register_global_accelerator).
+    ///
+    /// Registering a hotkey again replaces its command. Accelerators are unregistered
+    /// automatically when the widget is removed, or with
+    /// [`unregister_accelerator`](Self::unregister_accelerator).
+    pub fn register_accelerator(&mut self, hotkey: HotKey, command: Command) {
+        trace!("register_accelerator {:?}", hotkey);
+        set_accelerator(&mut self.widget_state.accelerators, hotkey, command);
+    }
+
+    /// Register a keyboard accelerator for the whole window.
+    ///
+    /// While this widget is enabled, pressing `hotkey` submits `command`, wherever
+    /// focus is, unless an accelerator registered with
+    /// [`register_accelerator`](Self::register_accelerator) on the focused path matches
+    /// it first. If several widgets register the same global hotkey, the first one in
+    /// the tree wins.
+    ///
+    /// Registering a hotkey again replaces its command. Accelerators are unregistered
+    /// automatically when the widget is removed, or with
+    /// [`unregister_accelerator`](Self::unregister_accelerator).
+    pub fn register_global_accelerator(&mut self, hotkey: HotKey, command: Command) {
+        trace!("register_global_accelerator {:?}", hotkey);
+        set_accelerator(&mut self.widget_state.global_accelerators, hotkey, command);
+    }
+
+    /// Unregister a keyboard accelerator of this widget, whether it's global or not.
+    pub fn unregister_accelerator(&mut self, hotkey: &HotKey) {
+        trace!("unregister_accelerator {:?}", hotkey);
+        self.widget_state
+            .accelerators
+            .retain(|(registered, _)| registered != hotkey);
+        self.widget_state
+            .global_accelerators
+            .retain(|(registered, _)| registered != hotkey);
+    }
+
+    /// Remove a child from the widget tree.
+    ///
+    /// The child and its descendants receive [`LifeCycle::WidgetRemoved`], then the child
+    /// is dropped. This also calls [`children_changed`](Self::children_changed).
+    ///
+    /// Widgets should use this method rather than simply dropping a child, so that
+    /// the child can release its resources.
+    pub fn remove_child(&mut self, mut child: WidgetPod<impl Widget>) {
+        trace!("remove_child");
+        // Children that never received WidgetAdded have no env, and nothing to clean up.
+        if let Some(env) = child.env.clone() {
+            let mut ctx = LifeCycleCtx {
+                global_state: self.global_state,
+                widget_state: self.widget_state,
+            };
+            child.lifecycle(&mut ctx, &LifeCycle::WidgetRemoved, &env);
+        }
+        self.children_changed();
+    }
+
     /// Set the disabled state for this widget.
     ///
     /// Setting this to `false` does not mean a widget is not still disabled; for instance it may
@@ -550,28 +677,32 @@ impl_context_method!(
         /// [`Command`]s back to the main thread.
         ///
         /// Once the function returns, an [`Event::PromiseResult`](crate::Event::PromiseResult)
-        /// is emitted with the return value.
+        /// is emitted with the return value. If the function panics, the promise is resolved
+        /// with a [`PromiseError`](crate::promise::PromiseError) instead, which can be read with
+        /// [`PromiseResult::try_get_result`](crate::promise::PromiseResult::try_get_result).
         pub fn compute_in_background<T: Any + Send>(
             &mut self,
             background_task: impl FnOnce(ExtEventSink) -> T + Send + 'static,
         ) -> PromiseToken<T> {
             let token = PromiseToken::<T>::new();
-
-            use std::thread;
-
-            let ext_event_sink = self.global_state.ext_event_sink.clone();
-            let widget_id = self.widget_state.id;
-            let window_id = self.global_state.window_id;
-            thread::spawn(move || {
-                let result = background_task(ext_event_sink.clone());
-                // TODO unwrap_or
-                let _ =
-                    ext_event_sink.resolve_promise(token.make_result(result), widget_id, window_id);
-            });
-
+            spawn_promise_task(
+                token,
+                self.global_state.ext_event_sink.clone(),
+                self.widget_state.id,
+                self.global_state.window_id,
+                background_task,
+            );
             token
         }
 
+        /// Cancel a promise returned by [`compute_in_background`](Self::compute_in_background).
+        ///
+        /// The background task keeps running, but its result is discarded instead of
+        /// being sent as an [`Event::PromiseResult`](crate::Event::PromiseResult).
+        pub fn cancel_promise<T: Any + Send>(&mut self, token: PromiseToken<T>) {
+            self.global_state.ext_event_sink.cancel_promise(token);
+        }
+
         /// Request a timer event.
         ///
         /// The return value is a token, which can be used to associate the
@@ -581,6 +712,26 @@ impl_context_method!(
             self.global_state
                 .request_timer(deadline, self.widget_state.id)
         }
+
+        /// Request a timer event that repeats every `interval`, until it's cancelled
+        /// with [`cancel_timer`](Self::cancel_timer).
+        ///
+        /// Every [`Event::Timer`](crate::Event::Timer) carries the returned token.
+        pub fn request_interval_timer(&mut self, interval: Duration) -> TimerToken {
+            trace!("request_interval_timer interval={:?}", interval);
+            self.global_state
+                .request_interval_timer(interval, self.widget_state.id)
+        }
+
+        /// Cancel a timer requested with [`request_timer`](Self::request_timer) or
+        /// [`request_interval_timer`](Self::request_interval_timer).
+        ///
+        /// The widget won't receive the matching [`Event::Timer`](crate::Event::Timer).
+        /// Cancelling a timer that already fired does nothing.
+        pub fn cancel_timer(&mut self, token: TimerToken) {
+            trace!("cancel_timer token={:?}", token);
+            self.global_state.cancel_timer(token);
+        }
     }
 );
 
@@ -626,7 +777,8 @@ impl EventCtx<'_, '_> {
 
     /// Send a signal to parent widgets to scroll this widget into view.
     pub fn request_pan_to_this(&mut self) {
-        self.request_pan_to_child = Some(self.widget_state.layout_rect());
+        // The rect is in this widget's coordinates; WidgetPod translates it on the way up.
+        self.request_pan_to_child = Some(self.widget_state.size.to_rect());
     }
 
     /// Set the "active" state of the widget.
@@ -850,6 +1002,48 @@ impl PaintCtx<'_, '_, '_> {
         &self.region
     }
 
+    /// Whether this widget's env asks for crisp rendering, through
+    /// [`theme::PREFERS_CRISP_EDGES`](crate::theme::PREFERS_CRISP_EDGES).
+    ///
+    /// The flag is read from the env the widget is painted with, so a container can
+    /// override it for its subtree.
+    #[inline]
+    pub fn prefers_crisp_edges(&self) -> bool {
+        self.crisp_edges
+    }
+
+    /// The interpolation mode images should use when they don't specify one.
+    ///
+    /// This is [`InterpolationMode::NearestNeighbor`] if the app
+    /// [prefers crisp edges](Self::prefers_crisp_edges), and
+    /// [`InterpolationMode::Bilinear`] otherwise.
+    pub fn default_interpolation(&self) -> InterpolationMode {
+        if self.crisp_edges {
+            InterpolationMode::NearestNeighbor
+        } else {
+            InterpolationMode::Bilinear
+        }
+    }
+
+    /// Snap an axis-aligned rect to device pixels if the app
+    /// [prefers crisp edges](Self::prefers_crisp_edges), so that filling it isn't
+    /// anti-aliased.
+    ///
+    /// The rect is returned unchanged otherwise, or if the current transform rotates
+    /// or skews it.
+    pub fn crisp_rect(&self, rect: Rect) -> Rect {
+        if !self.crisp_edges {
+            return rect;
+        }
+        let transform = self.render_ctx.current_transform();
+        let [_, skew_y, skew_x, _, _, _] = transform.as_coeffs();
+        if skew_y != 0.0 || skew_x != 0.0 {
+            return rect;
+        }
+        let device_rect = transform.transform_rect_bbox(rect).round();
+        transform.inverse().transform_rect_bbox(device_rect)
+    }
+
     /// Creates a temporary `PaintCtx` with a new visible region, and calls
     /// the provided function with that `PaintCtx`.
     ///
@@ -863,6 +1057,7 @@ impl PaintCtx<'_, '_, '_> {
             z_ops: Vec::new(),
             region: region.into(),
             depth: self.depth + 1,
+            crisp_edges: self.crisp_edges,
         };
         f(&mut child_ctx);
         self.z_ops.append(&mut child_ctx.z_ops);
@@ -925,11 +1120,14 @@ impl<'a> GlobalPassCtx<'a> {
         debug_logger: &'a mut DebugLogger,
         command_queue: &'a mut CommandQueue,
         action_queue: &'a mut ActionQueue,
-        timers: &'a mut HashMap<TimerToken, WidgetId>,
+        timers: &'a mut HashMap<TimerToken, WidgetTimer>,
         mock_timer_queue: Option<&'a mut MockTimerQueue>,
+        rng: &'a mut Rng,
         window: &'a WindowHandle,
         window_id: WindowId,
         focus_widget: Option<WidgetId>,
+        scale: Scale,
+        clipboard: Clipboard,
     ) -> Self {
         GlobalPassCtx {
             ext_event_sink,
@@ -938,10 +1136,14 @@ impl<'a> GlobalPassCtx<'a> {
             action_queue,
             timers,
             mock_timer_queue,
+            rng,
             window,
             window_id,
             focus_widget,
             text: window.text(),
+            painted_widgets: 0,
+            scale,
+            clipboard,
         }
     }
 
@@ -968,9 +1170,73 @@ impl<'a> GlobalPassCtx<'a> {
             self.window.request_timer(duration)
         };
 
-        self.timers.insert(timer_token, widget_id);
+        self.timers.insert(
+            timer_token,
+            WidgetTimer {
+                widget_id,
+                token: timer_token,
+                interval: None,
+                cancelled: false,
+            },
+        );
+        timer_token
+    }
+
+    pub(crate) fn request_interval_timer(
+        &mut self,
+        interval: Duration,
+        widget_id: WidgetId,
+    ) -> TimerToken {
+        if interval.is_zero() {
+            warn!("Interval timers must have a non-zero interval.");
+        }
+        let interval = interval.max(MIN_TIMER_INTERVAL);
+
+        let timer_token = if let Some(timer_queue) = self.mock_timer_queue.as_mut() {
+            // The mock queue re-arms interval timers itself.
+            timer_queue.add_interval_timer(interval)
+        } else {
+            // The window re-arms the platform timer each time it fires.
+            self.window.request_timer(interval)
+        };
+
+        self.timers.insert(
+            timer_token,
+            WidgetTimer {
+                widget_id,
+                token: timer_token,
+                interval: Some(interval),
+                cancelled: false,
+            },
+        );
         timer_token
     }
+
+    pub(crate) fn cancel_timer(&mut self, token: TimerToken) {
+        self.cancel_timers_where(|timer| timer.token == token);
+    }
+
+    /// Cancel all the timers requested by the given widget.
+    pub(crate) fn cancel_widget_timers(&mut self, widget_id: WidgetId) {
+        self.cancel_timers_where(|timer| timer.widget_id == widget_id);
+    }
+
+    fn cancel_timers_where(&mut self, mut predicate: impl FnMut(&WidgetTimer) -> bool) {
+        // Platform timers can't be cancelled; they're kept until they fire, so
+        // the window can tell them apart from unknown timers, and then ignored.
+        let mock_timer_queue = &mut self.mock_timer_queue;
+        self.timers.retain(|platform_token, timer| {
+            if timer.cancelled || !predicate(timer) {
+                return true;
+            }
+            if let Some(timer_queue) = mock_timer_queue.as_mut() {
+                timer_queue.cancel_timer(*platform_token);
+                return false;
+            }
+            timer.cancelled = true;
+            true
+        });
+    }
 }
 
 impl<'c> Deref for PaintCtx<'_, '_, 'c> {
@@ -986,3 +1252,14 @@ impl<'c> DerefMut for PaintCtx<'_, '_, 'c> {
         self.render_ctx
     }
 }
+
+/// Add `hotkey` to `accelerators`, or replace its command if it's already there.
+fn set_accelerator(accelerators: &mut Vec<(HotKey, Command)>, hotkey: HotKey, command: Command) {
+    match accelerators
+        .iter_mut()
+        .find(|(registered, _)| *registered == hotkey)
+    {
+        Some((_, registered_command)) => *registered_command = command,
+        None => accelerators.push((hotkey, command)),
+    }
+}
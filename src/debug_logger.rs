@@ -10,8 +10,9 @@ use std::sync::Arc;
 use crate::debug_values::{
     LayoutInfo, LayoutTree, LogId, MyWidgetId, Snapshot, StateTree, Timeline, Value,
 };
+use crate::event_log::EventKind;
 use crate::widget::WidgetRef;
-use crate::Widget;
+use crate::{Event, Widget, WidgetId};
 
 #[derive(Debug)]
 pub struct DebugLog {
@@ -20,6 +21,18 @@ pub struct DebugLog {
     children: Vec<LogId>,
 }
 
+/// A log message, as returned by [`DebugLogger::entries`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DebugLogEntry {
+    /// The id of the log, which is also the key of the snapshot taken when it was pushed.
+    pub id: LogId,
+    pub message: String,
+    /// Whether the log is a pass, eg `"EVENT MouseMove"` or `"LAYOUT"`.
+    pub important: bool,
+    /// The number of spans the log is nested in.
+    pub depth: usize,
+}
+
 #[derive(Debug)]
 pub struct DebugLogger {
     pub activated: bool,
@@ -34,6 +47,11 @@ pub struct DebugLogger {
     pub snapshots: HashMap<LogId, Snapshot>,
     pub span_stack: Vec<LogId>,
     pub log_id_counter: LogId,
+
+    /// The kinds of the events each widget received, if recording is enabled.
+    pub received_events: Option<HashMap<WidgetId, Vec<EventKind>>>,
+    /// The names of the lifecycle events each widget received, if recording is enabled.
+    pub received_lifecycle_events: Option<HashMap<WidgetId, Vec<&'static str>>>,
 }
 
 // ---
@@ -51,6 +69,8 @@ impl DebugLogger {
             snapshots: Default::default(),
             span_stack: Vec::new(),
             log_id_counter: LogId(0),
+            received_events: None,
+            received_lifecycle_events: None,
         };
         new_self.push_log(false, "initial value");
         new_self
@@ -96,6 +116,52 @@ impl DebugLogger {
         writer.flush().unwrap();
     }
 
+    /// The logs pushed so far, in the order they were pushed.
+    ///
+    /// The state of the widget tree when each log was pushed is in
+    /// [`snapshots`](Self::snapshots), under the log's id.
+    pub fn entries(&self) -> Vec<DebugLogEntry> {
+        fn add_entries(
+            entries: &mut Vec<DebugLogEntry>,
+            logs: &HashMap<LogId, DebugLog>,
+            log_ids: &[LogId],
+            depth: usize,
+        ) {
+            for log_id in log_ids {
+                let log = &logs[log_id];
+                entries.push(DebugLogEntry {
+                    id: *log_id,
+                    message: log.message.clone(),
+                    important: log.important,
+                    depth,
+                });
+                add_entries(entries, logs, &log.children, depth + 1);
+            }
+        }
+
+        let mut entries = Vec::new();
+        add_entries(&mut entries, &self.logs, &self.root_logs, 0);
+        entries
+    }
+
+    pub fn record_event(&mut self, widget_id: WidgetId, event: &Event) {
+        if let Some(received_events) = &mut self.received_events {
+            received_events
+                .entry(widget_id)
+                .or_default()
+                .push(EventKind::of(event));
+        }
+    }
+
+    pub fn record_lifecycle_event(&mut self, widget_id: WidgetId, event: &'static str) {
+        if let Some(received_lifecycle_events) = &mut self.received_lifecycle_events {
+            received_lifecycle_events
+                .entry(widget_id)
+                .or_default()
+                .push(event);
+        }
+    }
+
     pub fn push_log(&mut self, important: bool, message: &str) {
         if !self.activated {
             return;
@@ -263,3 +329,36 @@ impl DebugLogger {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::TestHarness;
+    use crate::widget::Label;
+
+    #[test]
+    fn harness_exposes_pass_logs() {
+        let mut harness = TestHarness::create(Label::new("Hello"));
+        assert!(harness.debug_logs().entries().is_empty());
+
+        harness.set_debug_logging(true);
+        harness.push_log("before move");
+        harness.mouse_move((10.0, 10.0));
+
+        let entries = harness.debug_logs().entries();
+        let marker = entries
+            .iter()
+            .position(|entry| entry.message == "before move")
+            .unwrap();
+        let event = entries
+            .iter()
+            .position(|entry| entry.message == "EVENT MouseMove")
+            .unwrap();
+        assert!(marker < event);
+        assert!(entries[event].important);
+        assert_eq!(entries[event].depth, 0);
+        assert!(harness
+            .debug_logs()
+            .snapshots
+            .contains_key(&entries[event].id));
+    }
+}
@@ -45,6 +45,7 @@ pub struct Env(Arc<EnvImpl>);
 #[derive(Debug, Clone)]
 struct EnvImpl {
     map: HashMap<ArcStr, Value>,
+    version: u64,
 }
 
 /// A typed [`Env`] key.
@@ -311,10 +312,20 @@ impl Env {
         self.0.map.iter()
     }
 
+    /// Returns a number identifying the current contents of the `Env`.
+    ///
+    /// The version is preserved by cloning, and changes every time a value is
+    /// added or set. Widgets that resolve many theme values can cache them, and
+    /// only resolve them again when the version differs from the cached one.
+    pub fn version(&self) -> u64 {
+        self.0.version
+    }
+
     /// Adds a key/value, acting like a builder.
     pub fn adding<V: ValueType>(mut self, key: Key<V>, value: impl Into<V>) -> Env {
         let env = Arc::make_mut(&mut self.0);
         env.map.insert(key.into(), value.into().into());
+        env.version = EnvImpl::next_version();
         self
     }
 
@@ -352,6 +363,7 @@ impl Env {
                 e.insert(raw);
             }
         }
+        env.version = EnvImpl::next_version();
         Ok(())
     }
 
@@ -467,7 +479,17 @@ impl Debug for Value {
 
 impl Data for Env {
     fn same(&self, other: &Env) -> bool {
-        Arc::ptr_eq(&self.0, &other.0) || self.0.deref().same(other.0.deref())
+        Arc::ptr_eq(&self.0, &other.0)
+            || self.version() == other.version()
+            || self.0.deref().same(other.0.deref())
+    }
+}
+
+impl EnvImpl {
+    fn next_version() -> u64 {
+        use druid_shell::Counter;
+        static ENV_VERSION_COUNTER: Counter = Counter::new();
+        ENV_VERSION_COUNTER.next()
     }
 }
 
@@ -511,6 +533,7 @@ impl Env {
     pub fn empty() -> Self {
         Env(Arc::new(EnvImpl {
             map: HashMap::new(),
+            version: EnvImpl::next_version(),
         }))
     }
 
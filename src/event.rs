@@ -4,13 +4,13 @@
 
 //! Events.
 
-use druid_shell::{Clipboard, KeyEvent, TimerToken};
+use druid_shell::{KeyEvent, TimerToken};
 
 use crate::kurbo::{Rect, Size};
 use crate::mouse::MouseEvent;
 // TODO - See issue #14
 use crate::promise::PromiseResult;
-use crate::{Command, Notification, WidgetId};
+use crate::{Clipboard, Command, Notification, WidgetId};
 
 /// An event, propagated downwards during event flow.
 ///
@@ -86,6 +86,13 @@ pub enum Event {
     /// [`set_cursor`]: struct.EventCtx.html#method.set_cursor
     MouseMove(MouseEvent),
 
+    /// Called when the mouse leaves the window.
+    ///
+    /// There is no mouse position to report, so no widget is hot afterwards. The
+    /// event is propagated to the widgets that were hot or active, after they receive
+    /// `StatusChange::HotChanged`, so they can reset their hover state.
+    MouseLeave,
+
     // TODO - What about trackpad scrolling? Touchscreens?
     /// Called when the mouse wheel or trackpad is scrolled.
     Wheel(MouseEvent),
@@ -181,12 +188,6 @@ pub enum Event {
 /// [`Event`]: enum.Event.html
 #[derive(Debug, Clone)]
 pub enum InternalEvent {
-    /// Sent in some cases when the mouse has left the window.
-    ///
-    /// This is used in cases when the platform no longer sends mouse events,
-    /// but we know that we've stopped receiving the mouse events.
-    MouseLeave,
-
     /// A command still in the process of being dispatched.
     TargetedCommand(Command),
 
@@ -228,6 +229,19 @@ pub enum LifeCycle {
     /// of events.
     WidgetAdded,
 
+    /// Sent to a `Widget` when it is about to be removed from the widget tree.
+    /// This is the last message the widget receives, and the counterpart of
+    /// [`WidgetAdded`](LifeCycle::WidgetAdded).
+    ///
+    /// Widgets should handle this event to release resources tied to the widget tree,
+    /// eg to cancel pending promises with [`cancel_promise`](crate::LifeCycleCtx::cancel_promise).
+    ///
+    /// Containers send this event to a child by removing it with
+    /// [`remove_child`](crate::EventCtx::remove_child), and must forward it to their
+    /// own children like other lifecycle events, including hidden ones. Widgets that
+    /// never received `WidgetAdded` don't receive this event either.
+    WidgetRemoved,
+
     // TODO - Put in StatusChange
     /// Called when the Disabled state of the widgets is changed.
     ///
@@ -344,6 +358,7 @@ impl Event {
             | Event::Command(_)
             | Event::PromiseResult(_)
             | Event::Notification(_)
+            | Event::MouseLeave
             | Event::Internal(_) => true,
             Event::MouseDown(_)
             | Event::MouseUp(_)
@@ -363,7 +378,6 @@ impl Event {
     pub fn short_name(&self) -> &'static str {
         match self {
             Event::Internal(internal) => match internal {
-                InternalEvent::MouseLeave => "MouseLeave",
                 InternalEvent::TargetedCommand(_) => "TargetedCommand",
                 InternalEvent::RouteTimer(_, _) => "RouteTimer",
                 InternalEvent::RoutePromiseResult(_, _) => "RoutePromiseResult",
@@ -381,6 +395,7 @@ impl Event {
             Event::MouseDown(_) => "MouseDown",
             Event::MouseUp(_) => "MouseUp",
             Event::MouseMove(_) => "MouseMove",
+            Event::MouseLeave => "MouseLeave",
             Event::Wheel(_) => "Wheel",
             Event::KeyDown(_) => "KeyDown",
             Event::KeyUp(_) => "KeyUp",
@@ -405,6 +420,7 @@ impl LifeCycle {
         match self {
             LifeCycle::Internal(internal) => internal.should_propagate_to_hidden(),
             LifeCycle::WidgetAdded => true,
+            LifeCycle::WidgetRemoved => true,
             LifeCycle::DisabledChanged(_) => true,
             LifeCycle::BuildFocusChain => false,
             LifeCycle::RequestPanToChild(_) => false,
@@ -414,7 +430,7 @@ impl LifeCycle {
     /// Short name, for debug logging.
     ///
     /// Essentially returns the enum variant name.
-    pub fn short_name(&self) -> &str {
+    pub fn short_name(&self) -> &'static str {
         match self {
             LifeCycle::Internal(internal) => match internal {
                 InternalLifeCycle::RouteWidgetAdded => "RouteWidgetAdded",
@@ -423,6 +439,7 @@ impl LifeCycle {
                 InternalLifeCycle::ParentWindowOrigin => "ParentWindowOrigin",
             },
             LifeCycle::WidgetAdded => "WidgetAdded",
+            LifeCycle::WidgetRemoved => "WidgetRemoved",
             LifeCycle::DisabledChanged(_) => "DisabledChanged",
             LifeCycle::BuildFocusChain => "BuildFocusChain",
             LifeCycle::RequestPanToChild(_) => "RequestPanToChild",
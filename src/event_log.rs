@@ -0,0 +1,187 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A log of the events a window receives.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+
+// Automatically defaults to std::time::Instant on non Wasm platforms
+use instant::Instant;
+use serde::Serialize;
+
+use crate::{Event, Handled};
+
+/// A broad category of [`Event`], used to filter an [`EventLog`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum EventKind {
+    /// Window lifetime and size events.
+    Window,
+    /// Pointer events, including wheel and zoom gestures.
+    Mouse,
+    /// Key presses and releases.
+    Keyboard,
+    /// Pastes and IME changes.
+    Text,
+    /// Timers firing.
+    Timer,
+    /// Animation frames.
+    Animation,
+    /// Commands, notifications and promise results.
+    Command,
+    /// Events used by Masonry to route other events.
+    Internal,
+}
+
+/// An event received by a window, as recorded by an [`EventLog`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EventLogEntry {
+    /// The time the event was received, relative to the creation of the log.
+    pub time: Duration,
+    /// The category of the event.
+    pub kind: EventKind,
+    /// The name of the event, as returned by [`Event::short_name`].
+    pub name: &'static str,
+    /// Whether a widget handled the event.
+    pub handled: bool,
+}
+
+/// A log of every event received by a window, and whether it was handled.
+///
+/// Unlike the [`DebugLogger`](crate::debug_logger::DebugLogger), which captures the
+/// state of the widget tree, this only records what comes into the window. It's meant
+/// to answer "did my window even get this event?".
+///
+/// Set it with [`WindowRoot::set_event_log`](crate::WindowRoot::set_event_log), or
+/// [`TestHarness::set_event_log`](crate::testing::TestHarness::set_event_log) in tests.
+#[derive(Clone, Debug)]
+pub struct EventLog {
+    start: Instant,
+    filter: Option<HashSet<EventKind>>,
+    entries: Vec<EventLogEntry>,
+}
+
+impl EventLog {
+    /// Create a log recording every event.
+    pub fn new() -> Self {
+        EventLog {
+            start: Instant::now(),
+            filter: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to only record events of the given kinds.
+    pub fn with_filter(mut self, kinds: impl IntoIterator<Item = EventKind>) -> Self {
+        self.filter = Some(kinds.into_iter().collect());
+        self
+    }
+
+    /// The events recorded so far, oldest first.
+    pub fn entries(&self) -> &[EventLogEntry] {
+        &self.entries
+    }
+
+    /// Forget the events recorded so far.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Serialize the recorded events to a JSON array.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.entries).unwrap()
+    }
+
+    /// Write the recorded events to a file, as a JSON array.
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        received: Instant,
+        kind: EventKind,
+        name: &'static str,
+        handled: Handled,
+    ) {
+        if let Some(filter) = &self.filter {
+            if !filter.contains(&kind) {
+                return;
+            }
+        }
+        self.entries.push(EventLogEntry {
+            time: received.duration_since(self.start),
+            kind,
+            name,
+            handled: handled.is_handled(),
+        });
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventKind {
+    /// The kind of the given event.
+    pub fn of(event: &Event) -> Self {
+        match event {
+            Event::WindowConnected
+            | Event::WindowCloseRequested
+            | Event::WindowDisconnected
+            | Event::WindowSize(_) => EventKind::Window,
+            Event::MouseDown(_)
+            | Event::MouseUp(_)
+            | Event::MouseMove(_)
+            | Event::MouseLeave
+            | Event::Wheel(_)
+            | Event::Zoom(_) => EventKind::Mouse,
+            Event::KeyDown(_) | Event::KeyUp(_) => EventKind::Keyboard,
+            Event::Paste(_) | Event::ImeStateChange => EventKind::Text,
+            Event::Timer(_) => EventKind::Timer,
+            Event::AnimFrame(_) => EventKind::Animation,
+            Event::Command(_) | Event::Notification(_) | Event::PromiseResult(_) => {
+                EventKind::Command
+            }
+            Event::Internal(_) => EventKind::Internal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::Label;
+
+    #[test]
+    fn filtered_log_captures_only_mouse_events() {
+        let mut harness = TestHarness::create(Label::new("Hello"));
+        harness.set_event_log(EventLog::new().with_filter([EventKind::Mouse]));
+
+        harness.mouse_move((10.0, 10.0));
+        harness.keyboard_type_chars("a");
+        harness.mouse_leave();
+
+        let names: Vec<_> = harness
+            .event_log()
+            .entries()
+            .iter()
+            .map(|entry| entry.name)
+            .collect();
+        assert_eq!(names, ["MouseMove", "MouseLeave"]);
+        assert!(harness
+            .event_log()
+            .entries()
+            .iter()
+            .all(|entry| entry.kind == EventKind::Mouse));
+
+        let json = harness.event_log().to_json();
+        assert!(json.contains("\"name\":\"MouseMove\""));
+        assert!(!json.contains("KeyDown"));
+    }
+}
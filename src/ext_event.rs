@@ -5,14 +5,14 @@
 //! Simple handle for submitting external events.
 
 use std::any::Any;
-use std::collections::VecDeque;
-use std::sync::{Arc, Mutex};
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
 
 use druid_shell::IdleHandle;
 
 use crate::command::SelectorSymbol;
 use crate::platform::EXT_EVENT_IDLE_TOKEN;
-use crate::promise::PromiseResult;
+use crate::promise::{PromiseResult, PromiseToken, PromiseTokenId};
 use crate::widget::WidgetId;
 use crate::{Selector, Target, WindowId};
 
@@ -29,6 +29,10 @@ pub(crate) enum ExtMessage {
 pub struct ExtEventSink {
     queue: Arc<Mutex<VecDeque<ExtMessage>>>,
     handle: Arc<Mutex<Option<IdleHandle>>>,
+    cancelled_promises: Arc<Mutex<HashSet<PromiseTokenId>>>,
+    app_alive: Weak<()>,
+    /// Set for sinks handed to widgets, so they stop working once their window closes.
+    window_alive: Option<Weak<()>>,
 }
 
 /// The stuff that we hold onto inside the app that is related to the
@@ -41,9 +45,15 @@ pub(crate) struct ExtEventQueue {
     /// reference here and can update it when needed. Note that this reference is shared with all
     /// `ExtEventSink`s, so that we can update them too.
     handle: Arc<Mutex<Option<IdleHandle>>>,
+    /// Cancelled promises whose results haven't arrived yet. Results for these are
+    /// dropped instead of delivered, and their id is removed. Shared with all
+    /// `ExtEventSink`s.
+    cancelled_promises: Arc<Mutex<HashSet<PromiseTokenId>>>,
     /// The window that the handle belongs to, so we can keep track of when
     /// we need to get a new handle.
     pub(crate) handle_window_id: Option<WindowId>,
+    /// Dropped along with the queue, so that sinks can tell the app is gone.
+    alive: Arc<()>,
 }
 
 /// An error that occurs if an external event cannot be submitted.
@@ -60,6 +70,9 @@ impl ExtEventQueue {
         ExtEventSink {
             queue: self.queue.clone(),
             handle: self.handle.clone(),
+            cancelled_promises: self.cancelled_promises.clone(),
+            app_alive: Arc::downgrade(&self.alive),
+            window_alive: None,
         }
     }
 
@@ -78,6 +91,28 @@ impl ExtEventQueue {
 }
 
 impl ExtEventSink {
+    /// Tie this sink to a window, which is alive as long as `window_alive` is.
+    pub(crate) fn for_window(mut self, window_alive: &Arc<()>) -> Self {
+        self.window_alive = Some(Arc::downgrade(window_alive));
+        self
+    }
+
+    /// Return `true` if the application this sink sends events to is still running.
+    ///
+    /// For sinks obtained from a widget context, this also requires the widget's
+    /// window to still be open.
+    ///
+    /// Long-running background tasks can check this to stop early once the app or
+    /// window has closed. When this returns `false`, submitting events fails with
+    /// [`ExtEventError`].
+    pub fn is_alive(&self) -> bool {
+        let window_alive = match &self.window_alive {
+            Some(window_alive) => window_alive.strong_count() > 0,
+            None => true,
+        };
+        self.app_alive.strong_count() > 0 && window_alive
+    }
+
     /// Submit a [`Command`] to the running application.
     ///
     /// [`Command`] is not thread safe, so you cannot submit it directly;
@@ -95,6 +130,9 @@ impl ExtEventSink {
         payload: impl Into<Box<T>>,
         target: impl Into<Target>,
     ) -> Result<(), ExtEventError> {
+        if !self.is_alive() {
+            return Err(ExtEventError);
+        }
         let target = target.into();
         let payload = payload.into();
         if let Some(handle) = self.handle.lock().unwrap().as_mut() {
@@ -114,15 +152,70 @@ impl ExtEventSink {
         target_widget: WidgetId,
         target_window: WindowId,
     ) -> Result<(), ExtEventError> {
+        if !self.is_alive() {
+            return Err(ExtEventError);
+        }
+        {
+            // The set stays locked until the result is queued, see `cancel_promise`.
+            let mut cancelled_promises = self.cancelled_promises();
+            // Each promise is resolved once, so the id isn't needed after this.
+            if cancelled_promises.remove(&result.token_id()) {
+                return Ok(());
+            }
+            self.queue
+                .lock()
+                .map_err(|_| ExtEventError)?
+                .push_back(ExtMessage::Promise(result, target_widget, target_window));
+        }
+
         if let Some(handle) = self.handle.lock().unwrap().as_mut() {
             handle.schedule_idle(EXT_EVENT_IDLE_TOKEN);
         }
-        self.queue
-            .lock()
-            .map_err(|_| ExtEventError)?
-            .push_back(ExtMessage::Promise(result, target_widget, target_window));
         Ok(())
     }
+
+    /// Cancel a promise, so that its result is never delivered.
+    ///
+    /// This doesn't stop the background task computing the result; the result is
+    /// discarded when it arrives. A result that is already queued is discarded too.
+    pub fn cancel_promise<T: Any + Send>(&self, token: PromiseToken<T>) {
+        let id = token.id();
+        // Both locks are held together, set first as in `resolve_promise`, so the result
+        // is either already queued or sees the cancellation when it arrives.
+        let mut cancelled_promises = self.cancelled_promises();
+        let mut queue = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+        let queued_len = queue.len();
+        queue.retain(|message| match message {
+            ExtMessage::Promise(result, _, _) => result.token_id() != id,
+            ExtMessage::Command(..) => true,
+        });
+        // If the result was queued, it's gone now, and nothing else will arrive.
+        if queue.len() == queued_len {
+            cancelled_promises.insert(id);
+        }
+    }
+
+    /// Return `true` if the promise was cancelled with [`cancel_promise`](Self::cancel_promise),
+    /// and its result hasn't arrived yet.
+    pub fn is_promise_cancelled<T: Any + Send>(&self, token: PromiseToken<T>) -> bool {
+        self.cancelled_promises().contains(&token.id())
+    }
+
+    /// Return `true` if the result of the promise is waiting to be delivered.
+    #[cfg(test)]
+    pub(crate) fn is_promise_queued<T: Any + Send>(&self, token: PromiseToken<T>) -> bool {
+        self.queue.lock().unwrap().iter().any(|message| {
+            matches!(message, ExtMessage::Promise(result, _, _) if result.token_id() == token.id())
+        })
+    }
+
+    // The set can't be left in an inconsistent state, so a panic while it was locked
+    // doesn't matter.
+    fn cancelled_promises(&self) -> MutexGuard<'_, HashSet<PromiseTokenId>> {
+        self.cancelled_promises
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 impl std::fmt::Display for ExtEventError {
@@ -132,3 +225,81 @@ impl std::fmt::Display for ExtEventError {
 }
 
 impl std::error::Error for ExtEventError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::Label;
+
+    const PING: Selector = Selector::new("masonry-test.ping");
+
+    #[test]
+    fn sink_dies_with_window() {
+        let queue = ExtEventQueue::new();
+        let window_alive = Arc::new(());
+        let app_sink = queue.make_sink();
+        let window_sink = queue.make_sink().for_window(&window_alive);
+        assert!(window_sink.is_alive());
+        assert!(window_sink
+            .submit_command(PING, Box::new(()), Target::Auto)
+            .is_ok());
+
+        // The app is still running, but the window is gone.
+        drop(window_alive);
+        assert!(app_sink.is_alive());
+        assert!(!window_sink.is_alive());
+        assert!(window_sink
+            .submit_command(PING, Box::new(()), Target::Auto)
+            .is_err());
+    }
+
+    #[test]
+    fn sink_dies_with_app() {
+        let harness = TestHarness::create(Label::new("Hello"));
+        let sink = harness.window().ext_event_sink.clone();
+        assert!(sink.is_alive());
+
+        drop(harness);
+        assert!(!sink.is_alive());
+        assert!(sink
+            .submit_command(PING, Box::new(()), Target::Auto)
+            .is_err());
+    }
+
+    #[test]
+    fn cancelled_result_is_discarded_once() {
+        let [label_id] = widget_ids();
+        let harness = TestHarness::create(Label::new("Hello").with_id(label_id));
+        let sink = harness.window().ext_event_sink.clone();
+        let window_id = harness.window().id;
+
+        let token = PromiseToken::<u32>::new();
+        sink.cancel_promise(token);
+        assert!(sink.is_promise_cancelled(token));
+
+        sink.resolve_promise(token.make_result(1), label_id, window_id)
+            .unwrap();
+        assert!(!sink.is_promise_queued(token));
+        // The id is forgotten once the result is discarded.
+        assert!(!sink.is_promise_cancelled(token));
+    }
+
+    #[test]
+    fn cancelling_drops_queued_result() {
+        let [label_id] = widget_ids();
+        let harness = TestHarness::create(Label::new("Hello").with_id(label_id));
+        let sink = harness.window().ext_event_sink.clone();
+        let window_id = harness.window().id;
+
+        let token = PromiseToken::<u32>::new();
+        sink.resolve_promise(token.make_result(1), label_id, window_id)
+            .unwrap();
+        assert!(sink.is_promise_queued(token));
+
+        sink.cancel_promise(token);
+        assert!(!sink.is_promise_queued(token));
+        // No other result will arrive, so the id isn't kept.
+        assert!(!sink.is_promise_cancelled(token));
+    }
+}
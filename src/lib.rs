@@ -101,18 +101,25 @@ mod app_launcher;
 mod app_root;
 mod bloom;
 mod box_constraints;
+mod clipboard;
 pub mod command;
 mod contexts;
 mod data;
 pub mod env;
 mod event;
+pub mod event_log;
 pub mod ext_event;
 mod mouse;
 mod platform;
 pub mod promise;
+mod rng;
+mod shortcut;
+pub mod store;
 pub mod testing;
 pub mod text;
+mod texture;
 pub mod theme;
+mod units;
 pub mod widget;
 
 // TODO
@@ -120,10 +127,11 @@ pub mod debug_logger;
 pub mod debug_values;
 
 pub use action::Action;
-pub use app_delegate::{AppDelegate, DelegateCtx};
+pub use app_delegate::{AppDelegate, ChainedDelegate, DelegateCtx};
 pub use app_launcher::AppLauncher;
-pub use app_root::{AppRoot, WindowRoot};
+pub use app_root::{AppRoot, FrameMetrics, WindowRoot};
 pub use box_constraints::BoxConstraints;
+pub use clipboard::Clipboard;
 pub use command::{Command, Notification, Selector, SingleUse, Target};
 pub use contexts::{EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, WidgetCtx};
 pub use data::Data;
@@ -136,6 +144,10 @@ pub use piet::{Color, ImageBuf, LinearGradient, RadialGradient, RenderContext, U
 pub use platform::{
     MasonryWinHandler, WindowConfig, WindowDescription, WindowId, WindowSizePolicy,
 };
+pub use rng::Rng;
+pub use shortcut::Shortcut;
 pub use text::ArcStr;
+pub use texture::TextureBackend;
+pub use units::{Dp, Px};
 pub use util::{AsAny, Handled};
 pub use widget::{BackgroundBrush, Widget, WidgetId, WidgetPod, WidgetState};
@@ -116,12 +116,11 @@ impl WinHandler for MasonryWinHandler {
     }
 
     fn size(&mut self, size: Size) {
-        let event = Event::WindowSize(size);
-        self.app_state.handle_event(event, self.window_id);
+        self.app_state.window_resized(self.window_id, size);
     }
 
-    fn scale(&mut self, _scale: Scale) {
-        // TODO: Do something with the scale
+    fn scale(&mut self, scale: Scale) {
+        self.app_state.window_scale_changed(self.window_id, scale);
     }
 
     fn command(&mut self, id: u32) {
@@ -154,7 +153,7 @@ impl WinHandler for MasonryWinHandler {
 
     fn mouse_leave(&mut self) {
         self.app_state
-            .handle_event(Event::Internal(InternalEvent::MouseLeave), self.window_id);
+            .handle_event(Event::MouseLeave, self.window_id);
     }
 
     fn key_down(&mut self, event: KeyEvent) -> bool {
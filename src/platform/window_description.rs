@@ -5,7 +5,7 @@
 use druid_shell::{Counter, WindowBuilder, WindowHandle, WindowLevel, WindowState};
 
 use crate::kurbo::{Point, Size};
-use crate::{ArcStr, Widget};
+use crate::{ArcStr, Widget, WidgetId};
 
 /// A unique identifier for a window.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -60,6 +60,7 @@ pub struct WindowConfig {
     pub(crate) level: Option<WindowLevel>,
     // TODO - Remove?
     pub(crate) state: Option<WindowState>,
+    pub(crate) initial_focus: Option<WidgetId>,
 }
 
 // ---
@@ -176,6 +177,14 @@ impl WindowDescription {
         self
     }
 
+    /// Set the widget that has keyboard focus when the window opens.
+    ///
+    /// See [`WindowConfig::with_initial_focus`].
+    pub fn with_initial_focus(mut self, id: WidgetId) -> Self {
+        self.config = self.config.with_initial_focus(id);
+        self
+    }
+
     /// Set the [`WindowConfig`] of the window.
     pub fn with_config(mut self, config: WindowConfig) -> Self {
         self.config = config;
@@ -278,6 +287,19 @@ impl WindowConfig {
         self
     }
 
+    /// Set the widget that has keyboard focus when the window opens.
+    ///
+    /// The widget is focused while the [`WindowConnected`](crate::Event::WindowConnected)
+    /// event is handled, so keyboard input reaches it without the user having to click first.
+    /// Since the widget must be identified before the window is built, this is usually used
+    /// with an id reserved with [`WidgetId::reserved`] or [`WidgetId::next`].
+    ///
+    /// This has no effect when applied to an existing window.
+    pub fn with_initial_focus(mut self, id: WidgetId) -> Self {
+        self.initial_focus = Some(id);
+        self
+    }
+
     /// Apply this window configuration to the given WindowBuilder
     pub fn apply_to_builder(&self, builder: &mut WindowBuilder) {
         if let Some(resizable) = self.resizable {
@@ -366,6 +388,7 @@ impl std::fmt::Debug for WindowConfig {
                 },
             )
             .field("state", &self.state)
+            .field("initial_focus", &self.initial_focus)
             .finish()
     }
 }
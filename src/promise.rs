@@ -6,18 +6,36 @@
 
 use std::any::Any;
 use std::num::NonZeroU64;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use tracing::error;
+
+use crate::ext_event::ExtEventSink;
+use crate::{WidgetId, WindowId};
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub(crate) struct PromiseTokenId(NonZeroU64);
 
 pub struct PromiseToken<T = ()>(PromiseTokenId, std::marker::PhantomData<T>);
 
+// The value of a promise, or the reason it doesn't have one. It's taken out of the
+// Mutex by the widget the promise was sent to.
+type PromisePayload = Arc<Mutex<Option<Result<Box<dyn Any + Send>, PromiseError>>>>;
+
 #[derive(Clone, Debug)]
 pub struct PromiseResult {
     token_id: PromiseTokenId,
     // TODO - Rework command system to remove Mutex
-    payload: Arc<Mutex<Option<Box<dyn Any + Send>>>>,
+    payload: PromisePayload,
+}
+
+/// The reason a promise was resolved without a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PromiseError {
+    /// The background task panicked, with the given message.
+    Panicked(String),
 }
 
 // ---
@@ -46,16 +64,31 @@ impl<T: Any + Send> PromiseToken<T> {
         Self::new()
     }
 
+    pub(crate) fn id(self) -> PromiseTokenId {
+        self.0
+    }
+
     pub fn make_result(&self, payload: T) -> PromiseResult {
         PromiseResult {
             token_id: self.0,
-            payload: Arc::new(Mutex::new(Some(Box::new(payload)))),
+            payload: Arc::new(Mutex::new(Some(Ok(Box::new(payload))))),
+        }
+    }
+
+    pub(crate) fn make_error(self, error: PromiseError) -> PromiseResult {
+        PromiseResult {
+            token_id: self.0,
+            payload: Arc::new(Mutex::new(Some(Err(error)))),
         }
     }
 }
 
 impl PromiseResult {
-    pub(crate) fn get_payload(&self) -> Box<dyn Any + Send> {
+    pub(crate) fn token_id(&self) -> PromiseTokenId {
+        self.token_id
+    }
+
+    pub(crate) fn get_payload(&self) -> Result<Box<dyn Any + Send>, PromiseError> {
         self.payload
             .lock()
             .unwrap()
@@ -69,25 +102,99 @@ impl PromiseResult {
         self.token_id == token.0
     }
 
+    /// Returns the value of the promise if it matches the token.
+    ///
+    /// Returns `None` (and logs an error) if the background task panicked. Use
+    /// [`try_get_result`](Self::try_get_result) to handle that case.
     pub fn try_get<T: Any + Send>(&self, token: PromiseToken<T>) -> Option<T> {
+        match self.try_get_result(token)? {
+            Ok(value) => Some(value),
+            Err(err) => {
+                error!("Cannot resolve promise: {}", err);
+                None
+            }
+        }
+    }
+
+    pub fn get<T: Any + Send>(&self, token: PromiseToken<T>) -> T {
+        self.get_result(token)
+            .unwrap_or_else(|err| panic!("Cannot resolve promise: {}", err))
+    }
+
+    /// Returns the value of the promise if it matches the token, or the reason
+    /// the background task didn't produce one.
+    pub fn try_get_result<T: Any + Send>(
+        &self,
+        token: PromiseToken<T>,
+    ) -> Option<Result<T, PromiseError>> {
         if self.token_id == token.0 {
-            let payload = self.get_payload();
-            let payload = payload.downcast::<T>().unwrap_or_else(|_| {
-                // This one should never happen given the public API given to users.
-                panic!("Cannot resolve promise: wrong payload type.")
+            let result = self.get_payload().map(|payload| {
+                let payload = payload.downcast::<T>().unwrap_or_else(|_| {
+                    // This one should never happen given the public API given to users.
+                    panic!("Cannot resolve promise: wrong payload type.")
+                });
+                *payload
             });
-            Some(*payload)
+            Some(result)
         } else {
             None
         }
     }
 
-    pub fn get<T: Any + Send>(&self, token: PromiseToken<T>) -> T {
-        self.try_get(token)
+    /// Returns the value of the promise, or the reason the background task didn't
+    /// produce one.
+    ///
+    /// Panics if the token doesn't match.
+    pub fn get_result<T: Any + Send>(&self, token: PromiseToken<T>) -> Result<T, PromiseError> {
+        self.try_get_result(token)
             .unwrap_or_else(|| panic!("Cannot resolve promise: mismatched token."))
     }
 }
 
+impl std::fmt::Display for PromiseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PromiseError::Panicked(message) => write!(f, "background task panicked: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for PromiseError {}
+
+impl PromiseError {
+    pub(crate) fn from_panic(payload: Box<dyn Any + Send>) -> PromiseError {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic payload".to_string()
+        };
+        PromiseError::Panicked(message)
+    }
+}
+
+/// Run `background_task` on a new thread, and resolve `token` with its result.
+///
+/// See [`EventCtx::compute_in_background`](crate::EventCtx::compute_in_background).
+pub(crate) fn spawn_promise_task<T: Any + Send>(
+    token: PromiseToken<T>,
+    ext_event_sink: ExtEventSink,
+    widget_id: WidgetId,
+    window_id: WindowId,
+    background_task: impl FnOnce(ExtEventSink) -> T + Send + 'static,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let sink = ext_event_sink.clone();
+        let result = match panic::catch_unwind(AssertUnwindSafe(move || background_task(sink))) {
+            Ok(result) => token.make_result(result),
+            Err(payload) => token.make_error(PromiseError::from_panic(payload)),
+        };
+        // TODO unwrap_or
+        let _ = ext_event_sink.resolve_promise(result, widget_id, window_id);
+    })
+}
+
 // ---
 
 impl<T> Copy for PromiseToken<T> {}
@@ -137,6 +244,18 @@ mod tests {
         assert!(promise_result.try_get(promise_token_2).is_none());
     }
 
+    #[test]
+    fn error_promise() {
+        let promise_token: PromiseToken<i32> = PromiseToken::new();
+        let error = PromiseError::Panicked("no value".to_string());
+
+        let promise_result = promise_token.make_error(error.clone());
+        assert!(promise_result.try_get(promise_token).is_none());
+
+        let promise_result = promise_token.make_error(error.clone());
+        assert_eq!(promise_result.get_result(promise_token), Err(error));
+    }
+
     #[should_panic]
     #[test]
     fn bad_promise_get() {
@@ -156,4 +275,85 @@ mod tests {
         promise_result.get(promise_token);
         promise_result.get(promise_token);
     }
+
+    #[test]
+    fn promise_for_removed_widget() {
+        use crate::testing::{widget_ids, Record, Recording, TestHarness, TestWidgetExt as _};
+        use crate::widget::{Flex, Label};
+        use crate::Event;
+
+        fn received_promise(recording: &Recording) -> bool {
+            recording
+                .drain()
+                .into_iter()
+                .any(|record| matches!(record, Record::E(Event::PromiseResult(_))))
+        }
+
+        let [label_id] = widget_ids();
+        let recording = Recording::default();
+        let widget =
+            Flex::row().with_child(Label::new("hello").record(&recording).with_id(label_id));
+
+        let mut harness = TestHarness::create(widget);
+        let sink = harness.window().ext_event_sink.clone();
+        let window_id = harness.window().id;
+        let promise_token: PromiseToken<i32> = PromiseToken::new();
+
+        recording.clear();
+        sink.resolve_promise(promise_token.make_result(42), label_id, window_id)
+            .unwrap();
+        harness.process_ext_events();
+        assert!(received_promise(&recording));
+
+        harness.edit_root_widget(|mut flex, _| {
+            let mut flex = flex.downcast::<Flex>().unwrap();
+            flex.remove_child(0);
+        });
+        assert!(harness.try_get_widget(label_id).is_none());
+
+        // The widget is gone: the result is silently discarded.
+        recording.clear();
+        sink.resolve_promise(promise_token.make_result(42), label_id, window_id)
+            .unwrap();
+        harness.process_ext_events();
+        assert!(!received_promise(&recording));
+    }
+
+    #[test]
+    fn panicking_task_resolves_to_error() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        use crate::testing::{widget_ids, ModularWidget, TestHarness, TestWidgetExt as _};
+        use crate::Event;
+
+        let [widget_id] = widget_ids();
+        let promise_token = PromiseToken::<i32>::new();
+        let received: Rc<RefCell<Option<Result<i32, PromiseError>>>> = Rc::default();
+
+        let widget = ModularWidget::new(received.clone())
+            .event_fn(move |received, _ctx, event, _| {
+                if let Event::PromiseResult(result) = event {
+                    *received.borrow_mut() = result.try_get_result(promise_token);
+                }
+            })
+            .with_id(widget_id);
+
+        let mut harness = TestHarness::create(widget);
+        // This is what compute_in_background runs, but we get to wait for the task.
+        let task = spawn_promise_task(
+            promise_token,
+            harness.window().ext_event_sink.clone(),
+            widget_id,
+            harness.window().id,
+            |_| -> i32 { panic!("no value") },
+        );
+        task.join().unwrap();
+        harness.process_ext_events();
+
+        assert_eq!(
+            received.take(),
+            Some(Err(PromiseError::Panicked("no value".to_string())))
+        );
+    }
 }
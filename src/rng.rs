@@ -0,0 +1,125 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A small seedable random number generator.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// A seedable pseudo-random number generator.
+///
+/// Each window owns an instance of this type, which widgets can access through
+/// the `rng()` method on their context (eg [`EventCtx::rng`](crate::EventCtx::rng)).
+///
+/// In a running app, the generator is seeded from OS entropy. In
+/// [`TestHarness`](crate::testing::TestHarness), it is seeded with
+/// [`HARNESS_DEFAULT_SEED`](crate::testing::HARNESS_DEFAULT_SEED), and can be
+/// reseeded with [`TestHarness::set_rng_seed`](crate::testing::TestHarness::set_rng_seed).
+/// Two harnesses with the same seed, receiving the same events, will see the same
+/// sequence of random values.
+///
+/// The generator uses the SplitMix64 algorithm. It is fast and has good statistical
+/// properties, but it is **not** cryptographically secure.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Create a generator with the given seed.
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// Create a generator seeded from OS entropy.
+    pub fn from_entropy() -> Self {
+        // RandomState is seeded with random keys by the standard library.
+        let seed = RandomState::new().build_hasher().finish();
+        Rng::new(seed)
+    }
+
+    /// Reset the generator with the given seed.
+    pub fn reseed(&mut self, seed: u64) {
+        self.state = seed;
+    }
+
+    /// Return a random `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Return a random `u32`.
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Return a random `f64` in the range `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        // Use the 53 high bits, which is the precision of an f64 mantissa.
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Return a random `f64` in the range `[low, high)`.
+    pub fn range_f64(&mut self, low: f64, high: f64) -> f64 {
+        low + self.next_f64() * (high - low)
+    }
+
+    /// Return a random `bool`.
+    pub fn next_bool(&mut self) -> bool {
+        self.next_u64() >> 63 == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{ModularWidget, TestHarness};
+    use crate::{Color, RenderContext, Size};
+
+    fn random_squares() -> ModularWidget<()> {
+        ModularWidget::new(()).paint_fn(|_state, ctx, _env| {
+            for _ in 0..20 {
+                let x = ctx.rng().range_f64(0.0, 350.0);
+                let y = ctx.rng().range_f64(0.0, 350.0);
+                let r = (ctx.rng().next_u32() % 256) as u8;
+                let g = (ctx.rng().next_u32() % 256) as u8;
+                let b = (ctx.rng().next_u32() % 256) as u8;
+                let rect = Size::new(50.0, 50.0).to_rect().with_origin((x, y));
+                ctx.fill(rect, &Color::rgb8(r, g, b));
+            }
+        })
+    }
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut rng_a = Rng::new(1234);
+        let mut rng_b = Rng::new(1234);
+        for _ in 0..100 {
+            assert_eq!(rng_a.next_u64(), rng_b.next_u64());
+        }
+
+        let mut rng = Rng::new(5);
+        for _ in 0..100 {
+            let value = rng.next_f64();
+            assert!((0.0..1.0).contains(&value));
+        }
+    }
+
+    #[test]
+    fn same_seed_same_render() {
+        let mut harness_a = TestHarness::create(random_squares());
+        let mut harness_b = TestHarness::create(random_squares());
+        assert_eq!(harness_a.render(), harness_b.render());
+
+        let mut harness_a = TestHarness::create(random_squares());
+        let mut harness_b = TestHarness::create(random_squares());
+        harness_a.set_rng_seed(42);
+        harness_b.set_rng_seed(42);
+        assert_eq!(harness_a.render(), harness_b.render());
+    }
+}
@@ -0,0 +1,150 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! Keyboard shortcuts, and how to display them.
+
+use std::fmt;
+
+use druid_shell::{HotKey, IntoKey, KbKey, KeyEvent, Modifiers, RawMods};
+
+/// A keyboard shortcut, eg Ctrl+S.
+///
+/// This is a [`HotKey`] that remembers its keys, so that it can be displayed
+/// to the user, eg next to the text of a [`Button`](crate::widget::Button).
+///
+/// The label of a shortcut follows the conventions of the current platform:
+/// `Ctrl+Shift+S` on Windows and Linux, `⌃⇧S` on macOS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shortcut {
+    mods: RawMods,
+    key: KbKey,
+}
+
+impl Shortcut {
+    /// Create a new shortcut.
+    ///
+    /// The arguments are the same as [`HotKey::new`]'s. In particular, [`SysMods`]
+    /// can be used for shortcuts that use the Command key on macOS and Ctrl elsewhere.
+    ///
+    /// [`SysMods`]: druid_shell::SysMods
+    pub fn new(mods: impl Into<Option<RawMods>>, key: impl IntoKey) -> Self {
+        Shortcut {
+            mods: mods.into().unwrap_or(RawMods::None),
+            key: key.into_key(),
+        }
+    }
+
+    /// Return the equivalent [`HotKey`].
+    pub fn hotkey(&self) -> HotKey {
+        HotKey::new(self.mods, self.key.clone())
+    }
+
+    /// Return `true` if the given [`KeyEvent`] triggers this shortcut.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        self.hotkey().matches(event)
+    }
+
+    /// The text shown to the user for this shortcut, eg `Ctrl+S`.
+    pub fn label(&self) -> String {
+        format_label(self.mods, &self.key, cfg!(target_os = "macos"))
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.label())
+    }
+}
+
+fn format_label(mods: RawMods, key: &KbKey, macos: bool) -> String {
+    let mods = Modifiers::from(mods);
+    let key = match key {
+        KbKey::Character(c) if c == " " => "Space".to_string(),
+        KbKey::Character(c) => c.to_uppercase(),
+        KbKey::ArrowLeft if macos => "←".to_string(),
+        KbKey::ArrowRight if macos => "→".to_string(),
+        KbKey::ArrowUp if macos => "↑".to_string(),
+        KbKey::ArrowDown if macos => "↓".to_string(),
+        KbKey::ArrowLeft => "Left".to_string(),
+        KbKey::ArrowRight => "Right".to_string(),
+        KbKey::ArrowUp => "Up".to_string(),
+        KbKey::ArrowDown => "Down".to_string(),
+        key => key.to_string(),
+    };
+
+    if macos {
+        // macOS lists modifiers as symbols, without separators, in this order.
+        let mut label = String::new();
+        for (active, symbol) in [
+            (mods.ctrl(), '⌃'),
+            (mods.alt(), '⌥'),
+            (mods.shift(), '⇧'),
+            (mods.meta(), '⌘'),
+        ] {
+            if active {
+                label.push(symbol);
+            }
+        }
+        label.push_str(&key);
+        label
+    } else {
+        let mut parts = Vec::new();
+        for (active, name) in [
+            (mods.ctrl(), "Ctrl"),
+            (mods.alt(), "Alt"),
+            (mods.shift(), "Shift"),
+            (mods.meta(), "Super"),
+        ] {
+            if active {
+                parts.push(name);
+            }
+        }
+        parts.push(&key);
+        parts.join("+")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels() {
+        let label = |mods, key: KbKey, macos| format_label(mods, &key, macos);
+
+        assert_eq!(
+            label(RawMods::Ctrl, KbKey::Character("s".into()), false),
+            "Ctrl+S"
+        );
+        assert_eq!(
+            label(RawMods::CtrlShift, KbKey::Character("z".into()), false),
+            "Ctrl+Shift+Z"
+        );
+        assert_eq!(label(RawMods::Alt, KbKey::ArrowLeft, false), "Alt+Left");
+        assert_eq!(label(RawMods::None, KbKey::F5, false), "F5");
+        assert_eq!(
+            label(RawMods::Ctrl, KbKey::Character(" ".into()), false),
+            "Ctrl+Space"
+        );
+
+        assert_eq!(
+            label(RawMods::Meta, KbKey::Character("s".into()), true),
+            "⌘S"
+        );
+        assert_eq!(
+            label(RawMods::MetaShift, KbKey::Character("z".into()), true),
+            "⇧⌘Z"
+        );
+        assert_eq!(label(RawMods::AltCtrl, KbKey::ArrowUp, true), "⌃⌥↑");
+        assert_eq!(label(RawMods::None, KbKey::Enter, true), "Enter");
+    }
+
+    #[test]
+    fn matches_key_events() {
+        let shortcut = Shortcut::new(RawMods::Ctrl, "s");
+        assert!(shortcut.matches(&KeyEvent::for_test(RawMods::Ctrl, "s")));
+        assert!(!shortcut.matches(&KeyEvent::for_test(RawMods::None, "s")));
+        assert!(!shortcut.matches(&KeyEvent::for_test(RawMods::Ctrl, "d")));
+    }
+}
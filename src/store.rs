@@ -0,0 +1,179 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A store of application state that widgets can be bound to.
+
+use tracing::trace;
+
+use crate::{Command, Data, DelegateCtx, Selector, WidgetId};
+
+/// Application state, with widgets bound to parts of it.
+///
+/// A binding associates a widget with a slice of the state, and a [`Selector`]. When an
+/// [`update`](Self::update) changes the slice, as determined by [`Data::same`], the widget
+/// receives a command with that selector and the new value of the slice. Widgets bound to
+/// parts of the state that didn't change receive nothing, so the delegate doesn't have
+/// to find and mutate each affected widget itself.
+///
+/// ```
+/// use masonry::{Selector, WidgetId};
+/// use masonry::store::Store;
+///
+/// #[derive(Clone)]
+/// struct AppState {
+///     task_count: u32,
+///     is_saved: bool,
+/// }
+///
+/// const SET_TASK_COUNT: Selector<u32> = Selector::new("my-app.set-task-count");
+///
+/// let counter_id = WidgetId::next();
+/// let mut store = Store::new(AppState { task_count: 0, is_saved: true });
+/// store.bind(counter_id, SET_TASK_COUNT, |state| state.task_count);
+/// ```
+pub struct Store<T> {
+    data: T,
+    bindings: Vec<Binding<T>>,
+}
+
+/// Return the command to send if the slice changed since the last call.
+type SliceCheck<T> = Box<dyn FnMut(&T) -> Option<Command>>;
+
+struct Binding<T> {
+    widget_id: WidgetId,
+    check: SliceCheck<T>,
+}
+
+impl<T> Store<T> {
+    /// Create a store with the given initial state, and no bindings.
+    pub fn new(data: T) -> Self {
+        Store {
+            data,
+            bindings: Vec::new(),
+        }
+    }
+
+    /// The current state.
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Bind a widget to the slice of the state returned by `slice`.
+    ///
+    /// After each update that changes the slice, the widget receives `selector` with the new
+    /// value. It doesn't receive the current value: the widget should already reflect it.
+    pub fn bind<S: Data>(
+        &mut self,
+        widget_id: WidgetId,
+        selector: Selector<S>,
+        slice: impl Fn(&T) -> S + 'static,
+    ) {
+        let mut last = slice(&self.data);
+        let check = move |data: &T| {
+            let value = slice(data);
+            if value.same(&last) {
+                return None;
+            }
+            last = value.clone();
+            Some(selector.with(value).to(widget_id))
+        };
+        self.bindings.push(Binding {
+            widget_id,
+            check: Box::new(check),
+        });
+    }
+
+    /// Remove all the bindings of the given widget, eg because it was removed.
+    pub fn unbind(&mut self, widget_id: WidgetId) {
+        self.bindings
+            .retain(|binding| binding.widget_id != widget_id);
+    }
+
+    /// Change the state, and send the new value of every changed slice to its widgets.
+    pub fn update(&mut self, ctx: &mut DelegateCtx, f: impl FnOnce(&mut T)) {
+        f(&mut self.data);
+        for binding in &mut self.bindings {
+            if let Some(command) = (binding.check)(&self.data) {
+                trace!("Store update sent to widget {:?}", binding.widget_id);
+                ctx.submit_command(command);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::testing::{widget_ids, ModularWidget, TestHarness};
+    use crate::widget::Flex;
+    use crate::*;
+
+    #[derive(Clone)]
+    struct AppState {
+        task_count: u32,
+        title_len: u32,
+    }
+
+    const SET_TASK_COUNT: Selector<u32> = Selector::new("masonry-test.set-task-count");
+    const SET_TITLE_LEN: Selector<u32> = Selector::new("masonry-test.set-title-len");
+    const ADD_TASK: Selector = Selector::new("masonry-test.add-task");
+
+    struct StoreDelegate(Store<AppState>);
+
+    impl AppDelegate for StoreDelegate {
+        fn on_command(&mut self, ctx: &mut DelegateCtx, cmd: &Command, _env: &Env) -> Handled {
+            if cmd.is(ADD_TASK) {
+                self.0.update(ctx, |state| state.task_count += 1);
+                return Handled::Yes;
+            }
+            Handled::No
+        }
+    }
+
+    /// A widget that counts the updates it receives.
+    fn update_counter(selector: Selector<u32>, updates: Rc<Cell<u32>>) -> impl Widget {
+        ModularWidget::new(updates).event_fn(move |updates, _ctx, event, _env| {
+            if let Event::Command(cmd) = event {
+                if cmd.is(selector) {
+                    updates.set(updates.get() + 1);
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn unrelated_change_skips_update() {
+        let [count_id, title_id] = widget_ids();
+        let count_updates: Rc<Cell<u32>> = Default::default();
+        let title_updates: Rc<Cell<u32>> = Default::default();
+
+        let widget = Flex::column()
+            .with_child_id(
+                update_counter(SET_TASK_COUNT, count_updates.clone()),
+                count_id,
+            )
+            .with_child_id(
+                update_counter(SET_TITLE_LEN, title_updates.clone()),
+                title_id,
+            );
+
+        let mut store = Store::new(AppState {
+            task_count: 0,
+            title_len: 5,
+        });
+        store.bind(count_id, SET_TASK_COUNT, |state| state.task_count);
+        store.bind(title_id, SET_TITLE_LEN, |state| state.title_len);
+
+        let mut harness = TestHarness::create(widget);
+        harness.set_delegate(StoreDelegate(store));
+
+        harness.submit_command(ADD_TASK);
+        harness.submit_command(ADD_TASK);
+        assert_eq!(count_updates.get(), 2);
+        assert_eq!(title_updates.get(), 0);
+    }
+}
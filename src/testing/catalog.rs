@@ -0,0 +1,94 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! Rendering a catalog of widgets into a single image.
+
+use super::{widget_ids, TestHarness, TestWidgetExt as _};
+use crate::piet::ImageBuf;
+use crate::widget::{Align, CrossAxisAlignment, Flex, Label, Table};
+use crate::{Size, UnitPoint, Widget};
+
+/// The number of widgets per row of the catalog.
+const CATALOG_COLUMNS: usize = 3;
+const CATALOG_COLUMN_SPACING: f64 = 16.0;
+/// The catalog is laid out in a window of this size, and must fit in it.
+const CATALOG_WINDOW_SIZE: Size = Size::new(2048.0, 2048.0);
+
+/// Render the given widgets into a single image, each under a label with its name.
+///
+/// The widgets are laid out in a grid, three per row, each at its natural size.
+/// The image is as large as the grid. This is meant for generating a visual
+/// catalog of components, eg for documentation.
+///
+/// ## Panics
+///
+/// Panics if the grid doesn't fit in a 2048x2048 window, or if the harness can't
+/// create a render target.
+pub fn render_catalog(widgets: Vec<(&str, Box<dyn Widget>)>) -> ImageBuf {
+    let mut table = Table::new().with_column_spacing(CATALOG_COLUMN_SPACING);
+    let mut widgets = widgets.into_iter().peekable();
+    while widgets.peek().is_some() {
+        let row = widgets
+            .by_ref()
+            .take(CATALOG_COLUMNS)
+            .map(|(name, widget)| {
+                Flex::column()
+                    .cross_axis_alignment(CrossAxisAlignment::Start)
+                    .with_child(Label::new(name))
+                    .with_default_spacer()
+                    .with_child(widget)
+                    .with_default_spacer()
+            });
+        table = table.with_row(row.collect::<Vec<_>>());
+    }
+
+    let [catalog_id] = widget_ids();
+    let root = Align::new(UnitPoint::TOP_LEFT, table.with_id(catalog_id));
+    let mut harness = TestHarness::create_with_size(root, CATALOG_WINDOW_SIZE);
+    harness.render_widget(catalog_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piet::Color;
+    use crate::widget::SizedBox;
+
+    fn contains_color(image: &ImageBuf, color: Color) -> bool {
+        let (r, g, b, a) = color.as_rgba8();
+        image
+            .raw_pixels()
+            .chunks_exact(4)
+            .any(|pixel| pixel == [r, g, b, a])
+    }
+
+    #[test]
+    fn two_widgets() {
+        let image = render_catalog(vec![
+            (
+                "Red",
+                Box::new(
+                    SizedBox::empty()
+                        .width(40.0)
+                        .height(40.0)
+                        .background(Color::RED),
+                ),
+            ),
+            (
+                "Blue",
+                Box::new(
+                    SizedBox::empty()
+                        .width(40.0)
+                        .height(40.0)
+                        .background(Color::BLUE),
+                ),
+            ),
+        ]);
+
+        assert!(image.width() >= 80 + CATALOG_COLUMN_SPACING as usize);
+        assert!(image.height() >= 40);
+        assert!(contains_color(&image, Color::RED));
+        assert!(contains_color(&image, Color::BLUE));
+    }
+}
@@ -4,34 +4,148 @@
 
 //! Tools and infrastructure for testing widgets.
 
+use std::any::TypeId;
 use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use druid_shell::{KeyEvent, Modifiers, MouseButton, MouseButtons};
+use druid_shell::{KbKey, KeyEvent, Modifiers, MouseButton, MouseButtons};
 pub use druid_shell::{
     RawMods, Region, Scalable, Scale, Screen, SysMods, TimerToken, WindowHandle, WindowLevel,
     WindowState,
 };
 use image::io::Reader as ImageReader;
+use image::RgbaImage;
 use instant::Duration;
-use shell::text::Selection;
 
-use super::screenshots::{get_image_diff, get_rgba_image};
-use super::snapshot_utils::get_cargo_workspace;
+use super::screenshots::{get_image_diff, get_rgba_image, DiffTolerance};
+use super::snapshot_utils::{
+    get_cargo_workspace, screenshot_path_stem, DEFAULT_SCREENSHOT_DIR, DEFAULT_SCREENSHOT_NAME,
+    SCREENSHOT_DIR_VAR, SCREENSHOT_NAME_VAR,
+};
 use super::MockTimerQueue;
 use crate::action::{Action, ActionQueue};
+use crate::app_delegate::{AppDelegate, DelegateCtx};
+use crate::app_root::{close_all_windows, confirm_quit};
 //use crate::ext_event::ExtEventHost;
 use crate::command::CommandQueue;
 use crate::contexts::GlobalPassCtx;
 use crate::debug_logger::DebugLogger;
-use crate::ext_event::ExtEventQueue;
+use crate::event_log::{EventKind, EventLog};
+use crate::ext_event::{ExtEventQueue, ExtMessage};
 use crate::piet::{BitmapTarget, Device, ImageFormat, Piet};
-use crate::widget::{StoreInWidgetMut, WidgetMut, WidgetRef};
+use crate::text::{
+    Direction, InputHandler, Movement, Selection, TextAction, TextLayoutInfo, VerticalMovement,
+};
+use crate::widget::{
+    CrossAxisAlignment, Flex, FocusChange, SizedBox, StoreInWidgetMut, WidgetMut, WidgetRef,
+};
 use crate::*;
 
 /// Default screen size for tests.
 pub const HARNESS_DEFAULT_SIZE: Size = Size::new(400., 400.);
 
+/// Duration of a single frame simulated by [`TestHarness::run_for`].
+pub const HARNESS_FRAME_DURATION: Duration = Duration::from_millis(16);
+
+/// Number of MouseMove events sent by [`TestHarness::mouse_drag`].
+pub const HARNESS_DRAG_STEPS: usize = 10;
+
+/// Default seed of the random number generator in tests.
+///
+/// See [`Rng`] for details.
+pub const HARNESS_DEFAULT_SEED: u64 = 0;
+
+/// An error returned by the `try_*` methods of [`TestHarness`].
+///
+/// The panicking versions of these methods panic with this error's message.
+#[derive(Debug)]
+pub enum HarnessError {
+    /// The harness failed to create a device or a render target.
+    DeviceInit(crate::piet::Error),
+    /// No widget with the given id exists in the widget tree.
+    MissingWidget(WidgetId),
+    /// The widget with the given id isn't entirely within the window, so it can't be rendered on its own.
+    WidgetOutsideWindow(WidgetId),
+    /// The rendered image differs from the reference snapshot.
+    ///
+    /// The new image and an image of the differences were written next to the reference.
+    SnapshotMismatch {
+        /// Path of the reference snapshot.
+        reference_path: PathBuf,
+        /// Path the new image was written to.
+        new_path: PathBuf,
+        /// Path the diff image was written to.
+        diff_path: PathBuf,
+    },
+    /// The rendered SVG document differs from the reference snapshot.
+    ///
+    /// The new document was written next to the reference.
+    SvgSnapshotMismatch {
+        /// Path of the reference snapshot.
+        reference_path: PathBuf,
+        /// Path the new document was written to.
+        new_path: PathBuf,
+    },
+    /// There is no reference snapshot to compare the rendered image to.
+    ///
+    /// The new image was written next to where the reference should be.
+    MissingReference {
+        /// Path where the reference snapshot was expected.
+        reference_path: PathBuf,
+        /// Path the new image was written to.
+        new_path: PathBuf,
+    },
+    /// Reading or writing a snapshot file failed.
+    Io(PathBuf, std::io::Error),
+    /// Decoding or encoding a snapshot image failed.
+    Image(PathBuf, image::ImageError),
+    /// The harness is in [layout-only mode](TestHarness::set_layout_only), so it doesn't render.
+    LayoutOnly,
+}
+
+impl fmt::Display for HarnessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HarnessError::DeviceInit(err) => write!(f, "harness failed to get device: {err}"),
+            HarnessError::MissingWidget(id) => write!(f, "could not find widget {id:?}"),
+            HarnessError::WidgetOutsideWindow(id) => {
+                write!(f, "widget {id:?} isn't entirely within the window")
+            }
+            HarnessError::SnapshotMismatch { new_path, .. } => {
+                write!(f, "Images are different, see '{}'", new_path.display())
+            }
+            HarnessError::SvgSnapshotMismatch { new_path, .. } => {
+                write!(
+                    f,
+                    "SVG documents are different, see '{}'",
+                    new_path.display()
+                )
+            }
+            HarnessError::MissingReference { reference_path, .. } => {
+                write!(f, "No reference file '{}'", reference_path.display())
+            }
+            HarnessError::Io(path, err) => write!(f, "I/O error on '{}': {err}", path.display()),
+            HarnessError::Image(path, err) => {
+                write!(f, "image error on '{}': {err}", path.display())
+            }
+            HarnessError::LayoutOnly => write!(f, "harness is in layout-only mode"),
+        }
+    }
+}
+
+impl std::error::Error for HarnessError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HarnessError::Io(_, err) => Some(err),
+            HarnessError::Image(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
 /// A safe headless environment to test widgets in.
 ///
 /// `TestHarness` is a type that simulates an [`AppRoot`](crate::AppRoot)
@@ -61,10 +175,15 @@ pub const HARNESS_DEFAULT_SIZE: Size = Size::new(400., 400.);
 ///
 /// `TestHarness` tries to act like the normal masonry environment. For instance, it will dispatch every `Command` sent during event handling, handle lifecycle methods, etc.
 ///
-/// The passage of time is simulated with the [`move_timers_forward`](Self::move_timers_forward) methods. **(TODO -
-/// Doesn't move animations forward.)**
+/// The passage of time is simulated with the [`move_timers_forward`](Self::move_timers_forward) methods.
+/// To simulate successive frames, including animation frames, use [`run_for`](Self::run_for).
 ///
-/// **(TODO - ExtEvents aren't handled.)**
+/// Widgets using randomness through [`Rng`] get a generator seeded with
+/// [`HARNESS_DEFAULT_SEED`], so tests are reproducible. The seed can be changed with
+/// [`set_rng_seed`](Self::set_rng_seed).
+///
+/// Commands and promise results sent through an [`ExtEventSink`](crate::ext_event::ExtEventSink) are only delivered
+/// when [`process_ext_events`](Self::process_ext_events) is called.
 ///
 /// **(TODO - Painting invalidation might not be accurate.)**
 ///
@@ -114,18 +233,40 @@ pub struct TestHarness {
     mock_app: MockAppRoot,
     mouse_state: MouseEvent,
     window_size: Size,
+    screenshot_dir: Option<PathBuf>,
+    screenshot_name: Option<String>,
+    batching_edits: bool,
+    initial_mouse_pos: Option<Point>,
+    is_connected: bool,
+    has_quit: bool,
+    window_removed: bool,
+    multi_roots: Vec<WidgetId>,
+    layout_only: bool,
+    render_targets_created: usize,
 }
 
 /// Assert a snapshot of a rendered frame of your app.
 ///
 /// This macro takes a test harness and a name, renders the current state of the app,
 /// and stores the render as a PNG next to the text, in a `./screenshots/` folder.
+/// The folder and the file names can be changed with
+/// [`TestHarness::set_screenshot_dir`] and [`TestHarness::set_screenshot_name`].
 ///
 /// If a screenshot already exists, the rendered value is compared against this screenshot.
+/// A screenshot suffixed with the current platform (eg `<test_name>.macos.png`) is
+/// preferred over the base one. If there are several numbered screenshots (eg
+/// `<test_name>.0.png`, `<test_name>.1.png`), matching any of them passes.
 /// The assert passes if both are equal; otherwise, a diff file is created.
 ///
 /// If a screeshot doesn't exist, the assert will fail; the new screenshot is stored as
 /// `./screenshots/<test_name>.new.png`, and must be renamed before the assert will pass.
+///
+/// An optional third argument is a [`DiffTolerance`](crate::testing::DiffTolerance),
+/// for renders which can differ slightly between machines:
+///
+/// ```ignore
+/// assert_render_snapshot!(harness, "hello", DiffTolerance::new(2, 0.01));
+/// ```
 #[macro_export]
 macro_rules! assert_render_snapshot {
     ($test_harness:expr, $name:expr) => {
@@ -136,6 +277,119 @@ macro_rules! assert_render_snapshot {
             $name,
         )
     };
+    ($test_harness:expr, $name:expr, $tolerance:expr) => {
+        $test_harness.check_render_snapshot_with_tolerance(
+            env!("CARGO_MANIFEST_DIR"),
+            file!(),
+            module_path!(),
+            $name,
+            $tolerance,
+        )
+    };
+}
+
+/// Assert an SVG snapshot of a rendered frame of your app.
+///
+/// Like [`assert_render_snapshot`], except the frame is rendered with
+/// [`TestHarness::render_to_svg`] and stored as an SVG file next to the PNG snapshots.
+/// SVG snapshots are smaller than PNG ones, and their changes can be reviewed as text,
+/// which suits vector-heavy widgets like [`Spinner`](crate::widget::Spinner).
+///
+/// The new document is stored as `./screenshots/<test_name>.new.svg` if it doesn't match
+/// the reference, or if there is no reference yet.
+///
+/// Only usable where [`TestHarness::render_to_svg`] is available.
+#[macro_export]
+macro_rules! assert_svg_snapshot {
+    ($test_harness:expr, $name:expr) => {
+        $test_harness.check_svg_snapshot(env!("CARGO_MANIFEST_DIR"), file!(), module_path!(), $name)
+    };
+}
+
+/// Return `path` with `suffix` appended.
+///
+/// Not `with_extension`, which would truncate test names containing a dot.
+pub(crate) fn with_path_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Return the existing references for a snapshot, given its path without the extension.
+///
+/// This is the unnumbered reference `<stem>.<extension>`, followed by the numbered
+/// references `<stem>.0.<extension>`, `<stem>.1.<extension>`, etc, up to the first
+/// missing one.
+fn reference_candidates(path_stem: &Path, extension: &str) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    let unnumbered = with_path_suffix(path_stem, &format!(".{extension}"));
+    if unnumbered.exists() {
+        candidates.push(unnumbered);
+    }
+    for i in 0.. {
+        let numbered = with_path_suffix(path_stem, &format!(".{i}.{extension}"));
+        if !numbered.exists() {
+            break;
+        }
+        candidates.push(numbered);
+    }
+    candidates
+}
+
+/// Return the references for a snapshot, given its path without the extension.
+///
+/// References specific to the current platform take precedence.
+pub(crate) fn reference_paths(path_stem: &Path, extension: &str) -> Vec<PathBuf> {
+    let platform_stem = with_path_suffix(path_stem, &format!(".{}", std::env::consts::OS));
+    let platform_paths = reference_candidates(&platform_stem, extension);
+    if platform_paths.is_empty() {
+        reference_candidates(path_stem, extension)
+    } else {
+        platform_paths
+    }
+}
+
+/// Renumber the ids cairo gives to surfaces and images in an SVG document, in order of
+/// first appearance.
+///
+/// Cairo numbers them with a counter shared by every surface in the process, so the same
+/// frame would get different ids depending on what was rendered before it.
+#[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "openbsd"))]
+fn normalize_svg_ids(document: &str) -> String {
+    const PREFIXES: [&str; 3] = ["surface", "image", "source"];
+    // Ids are only rewritten where they're defined or referenced.
+    const ID_MARKERS: [&str; 2] = ["id=\"", "#"];
+
+    // Return the numbered id at the start of `text`, and its prefix.
+    fn numbered_id(text: &str) -> Option<(&'static str, &str)> {
+        PREFIXES.iter().find_map(|prefix| {
+            let after_prefix = text.strip_prefix(prefix)?;
+            let digits = after_prefix.bytes().take_while(u8::is_ascii_digit).count();
+            let ends_id = !after_prefix[digits..]
+                .starts_with(|c: char| c.is_alphanumeric() || c == '-' || c == '_');
+            (digits > 0 && ends_id).then(|| (*prefix, &text[..prefix.len() + digits]))
+        })
+    }
+
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    let mut normalized = String::with_capacity(document.len());
+    let mut rest = document;
+    while let Some(c) = rest.chars().next() {
+        let Some(marker) = ID_MARKERS.iter().find(|marker| rest.starts_with(*marker)) else {
+            normalized.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+        normalized.push_str(marker);
+        rest = &rest[marker.len()..];
+        if let Some((prefix, id)) = numbered_id(rest) {
+            let next_index = ids.len();
+            let index = *ids.entry(id).or_insert(next_index);
+            normalized.push_str(&format!("{prefix}-{index}"));
+            rest = &rest[id.len()..];
+        }
+    }
+    normalized
 }
 
 // TODO - merge
@@ -147,6 +401,10 @@ struct MockAppRoot {
     command_queue: CommandQueue,
     action_queue: ActionQueue,
     debug_logger: DebugLogger,
+    ext_event_queue: ExtEventQueue,
+    delegate: Option<Box<dyn AppDelegate>>,
+    // Shared with the window, see `TestHarness::clipboard`.
+    clipboard: Clipboard,
 }
 
 impl TestHarness {
@@ -159,13 +417,80 @@ impl TestHarness {
 
     /// Builds harness with given root widget and window size.
     pub fn create_with_size(root: impl Widget, window_size: Size) -> Self {
+        let mut harness = Self::create_uninitialized_with_size(root, window_size);
+        harness.connect();
+        harness
+    }
+
+    /// Builds harness with given root widget, window size and scale factor.
+    ///
+    /// The window is laid out in display points, so widgets get the same layout rects
+    /// as at scale 1.0, but [`ctx.scale()`](crate::EventCtx::scale) reports `scale`, and
+    /// renders have `scale` times as many pixels on each axis. Render snapshots taken at
+    /// a scale other than 1.0 have the scale in their name, eg `hello@2x.png`.
+    pub fn create_with_scale(root: impl Widget, window_size: Size, scale: Scale) -> Self {
+        let mut harness = Self::create_uninitialized_with_size(root, window_size);
+        harness.mock_app.window.scale = scale;
+        harness.connect();
+        harness
+    }
+
+    /// Builds harness with given root widget and window config.
+    ///
+    /// The config's window size is used if it has one; otherwise window size will be
+    /// [`HARNESS_DEFAULT_SIZE`]. Its [initial focus](WindowConfig::with_initial_focus)
+    /// is applied when the window connects.
+    pub fn create_with_config(root: impl Widget, config: WindowConfig) -> Self {
+        let window_size = config.size.unwrap_or(HARNESS_DEFAULT_SIZE);
+        let mut harness = Self::create_uninitialized_with_size(root, window_size);
+        harness.mock_app.window.initial_focus = config.initial_focus;
+        harness.connect();
+        harness
+    }
+
+    /// Builds harness with several independent widget trees, arranged side by side.
+    ///
+    /// The trees are placed in a row, left to right, and share the width of the window
+    /// equally. Each of them fills the height of the window. Use
+    /// [`multi_root`](Self::multi_root) to access them.
+    ///
+    /// Window size will be [`HARNESS_DEFAULT_SIZE`].
+    pub fn create_multi(roots: Vec<Box<dyn Widget>>) -> Self {
+        let mut root_ids = Vec::new();
+        let mut row = Flex::row().cross_axis_alignment(CrossAxisAlignment::Fill);
+        for root in roots {
+            let id = WidgetId::next();
+            row = row.with_flex_child(SizedBox::new_with_id(root, id), 1.0);
+            root_ids.push(id);
+        }
+
+        let mut harness = Self::create(row);
+        harness.multi_roots = root_ids;
+        harness
+    }
+
+    /// Builds harness with given root widget, without connecting the window.
+    ///
+    /// Unlike [`create`](Self::create), this doesn't send the [`WindowConnected`](Event::WindowConnected)
+    /// and [`WindowSize`](Event::WindowSize) events, so the widgets aren't added, laid out
+    /// or painted until [`connect`](Self::connect) is called.
+    ///
+    /// Window size will be [`HARNESS_DEFAULT_SIZE`].
+    pub fn create_uninitialized(root: impl Widget) -> Self {
+        Self::create_uninitialized_with_size(root, HARNESS_DEFAULT_SIZE)
+    }
+
+    /// Builds harness with given root widget and window size, without connecting the window.
+    ///
+    /// See [`create_uninitialized`](Self::create_uninitialized).
+    pub fn create_uninitialized_with_size(root: impl Widget, window_size: Size) -> Self {
         //let ext_host = ExtEventHost::default();
         //let ext_handle = ext_host.make_sink();
 
         // FIXME
         let event_queue = ExtEventQueue::new();
 
-        let window = WindowRoot::new(
+        let mut window = WindowRoot::new(
             WindowId::next(),
             Default::default(),
             event_queue.make_sink(),
@@ -175,6 +500,9 @@ impl TestHarness {
             WindowSizePolicy::User,
             Some(MockTimerQueue::new()),
         );
+        window.rng = Rng::new(HARNESS_DEFAULT_SEED);
+        let clipboard = Clipboard::mock();
+        window.clipboard = clipboard.clone();
 
         let mouse_state = MouseEvent {
             pos: Point::ZERO,
@@ -194,21 +522,167 @@ impl TestHarness {
                 command_queue: VecDeque::new(),
                 action_queue: VecDeque::new(),
                 debug_logger: DebugLogger::new(false),
+                ext_event_queue: event_queue,
+                delegate: None,
+                clipboard,
             },
             mouse_state,
             window_size,
+            screenshot_dir: None,
+            screenshot_name: None,
+            batching_edits: false,
+            initial_mouse_pos: None,
+            is_connected: false,
+            has_quit: false,
+            window_removed: false,
+            multi_roots: Vec::new(),
+            layout_only: false,
+            render_targets_created: 0,
         };
 
         // verify that all widgets are marked as having children_changed
         // (this should always be true for a new widget)
         harness.inspect_widgets(|widget| assert!(widget.state().children_changed));
 
-        harness.process_event(Event::WindowConnected);
-        harness.process_event(Event::WindowSize(window_size));
-
         harness
     }
 
+    /// Send the [`WindowConnected`](Event::WindowConnected) and [`WindowSize`](Event::WindowSize)
+    /// events to a harness built with [`create_uninitialized`](Self::create_uninitialized).
+    ///
+    /// Harnesses built with [`create`](Self::create) are already connected.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the harness is already connected.
+    pub fn connect(&mut self) {
+        assert!(!self.is_connected, "harness is already connected");
+        self.is_connected = true;
+        self.process_event(Event::WindowConnected);
+        self.process_event(Event::WindowSize(self.window_size));
+
+        if let Some(pos) = self.initial_mouse_pos.take() {
+            self.mouse_move(pos);
+        }
+    }
+
+    /// Builder-style method for setting the position of the mouse when the window connects.
+    ///
+    /// Widgets under that position will be hot from the start, without the test needing to
+    /// call [`mouse_move`](Self::mouse_move). This should be used with
+    /// [`create_uninitialized`](Self::create_uninitialized); on a connected harness, the
+    /// mouse is moved immediately.
+    pub fn with_initial_mouse_pos(mut self, pos: impl Into<Point>) -> Self {
+        let pos = pos.into();
+        if self.root_widget().state().is_new {
+            self.mouse_state.pos = pos;
+            self.mouse_state.window_pos = pos;
+            self.initial_mouse_pos = Some(pos);
+        } else {
+            self.mouse_move(pos);
+        }
+        self
+    }
+
+    /// Builder-style method for setting modifier keys that are held down during the whole test.
+    ///
+    /// See [`set_held_modifiers`](Self::set_held_modifiers).
+    pub fn with_held_modifiers(mut self, mods: Modifiers) -> Self {
+        self.set_held_modifiers(mods);
+        self
+    }
+
+    /// Set the modifier keys that are held down.
+    ///
+    /// These modifiers are added to every mouse and keyboard event sent by the harness,
+    /// until this is called again.
+    pub fn set_held_modifiers(&mut self, mods: Modifiers) {
+        self.mouse_state.mods = mods;
+    }
+
+    /// Set the [`AppDelegate`] of the test app.
+    ///
+    /// Once a delegate is set, commands sent by widgets or with
+    /// [`submit_command`](Self::submit_command) go through its
+    /// [`on_command`](AppDelegate::on_command) hook first. Actions are passed to its
+    /// [`on_action`](AppDelegate::on_action) hook, the way a running app does, instead of
+    /// being queued for [`pop_action`](Self::pop_action).
+    ///
+    /// The harness doesn't call the [`on_event`](AppDelegate::on_event) hook.
+    pub fn set_delegate(&mut self, delegate: impl AppDelegate + 'static) {
+        self.mock_app.delegate = Some(Box::new(delegate));
+    }
+
+    /// Resize the window, notifying the delegate and sending a
+    /// [`WindowSize`](Event::WindowSize) event.
+    pub fn resize_window(&mut self, size: Size) {
+        self.window_size = size;
+        let window_id = self.mock_app.window.id;
+        self.mock_app.with_delegate(|delegate, ctx, env| {
+            delegate.on_window_resized(ctx, window_id, size, env)
+        });
+        self.process_event(Event::WindowSize(size));
+    }
+
+    /// Change the scale factor of the window, notifying the delegate if it changed.
+    pub fn set_scale(&mut self, scale: Scale) {
+        let old_scale = std::mem::replace(&mut self.mock_app.window.scale, scale);
+        if old_scale == scale {
+            return;
+        }
+
+        let window_id = self.mock_app.window.id;
+        self.mock_app.with_delegate(|delegate, ctx, env| {
+            delegate.on_scale_changed(ctx, window_id, old_scale, scale, env)
+        });
+        self.process_state_after_event();
+    }
+
+    /// Set the parts of the mock window which are obscured, eg by rounded corners or a
+    /// camera notch, and lay the window out again.
+    ///
+    /// See [`WindowRoot::set_safe_area_insets`].
+    pub fn set_safe_area_insets(&mut self, insets: Insets) {
+        self.mock_app.window.set_safe_area_insets(insets);
+        self.process_state_after_event();
+    }
+
+    /// Return `true` if a [`QUIT_APP`](crate::command::QUIT_APP) command was handled,
+    /// and the delegate didn't veto it.
+    ///
+    /// Once the app has quit, the harness window is considered removed, but the widget tree
+    /// can still be inspected.
+    pub fn has_quit(&self) -> bool {
+        self.has_quit
+    }
+
+    fn quit_app(&mut self) {
+        let mock_app = &mut self.mock_app;
+        let vetoed = confirm_quit(self.has_quit, || {
+            mock_app
+                .with_delegate(|delegate, ctx, env| delegate.on_app_will_quit(ctx, env))
+                .unwrap_or(Handled::No)
+        });
+        if vetoed == Handled::Yes {
+            return;
+        }
+        self.has_quit = true;
+        self.close_all_windows();
+    }
+
+    fn close_all_windows(&mut self) {
+        close_all_windows(std::iter::once(&mut self.mock_app.window));
+
+        // The harness stands in for the platform, which destroys the window once
+        // it's closed. The window is only removed once.
+        if self.mock_app.window.is_closing() && !std::mem::replace(&mut self.window_removed, true) {
+            let window_id = self.mock_app.window.id;
+            self.mock_app.with_delegate(|delegate, ctx, env| {
+                delegate.on_window_removed(ctx, window_id, env)
+            });
+        }
+    }
+
     /// Send an event to the widget.
     ///
     /// If this event triggers lifecycle events, they will also be dispatched,
@@ -222,13 +696,34 @@ impl TestHarness {
 
     fn process_state_after_event(&mut self) {
         loop {
-            let cmd = self.mock_app.command_queue.pop_front();
-            match cmd {
-                Some(cmd) => self
+            if let Some(cmd) = self.mock_app.command_queue.pop_front() {
+                let handled = self
                     .mock_app
-                    .event(Event::Internal(InternalEvent::TargetedCommand(cmd))),
-                None => break,
-            };
+                    .with_delegate(|delegate, ctx, env| delegate.on_command(ctx, &cmd, env));
+                if handled != Some(Handled::Yes) {
+                    if cmd.is(crate::command::QUIT_APP) {
+                        self.quit_app();
+                    } else if cmd.is(crate::command::CLOSE_ALL_WINDOWS) {
+                        self.close_all_windows();
+                    } else {
+                        self.mock_app
+                            .event(Event::Internal(InternalEvent::TargetedCommand(cmd)));
+                    }
+                }
+                continue;
+            }
+
+            if self.mock_app.delegate.is_some() {
+                let next_action = self.mock_app.action_queue.pop_front();
+                if let Some((action, widget_id, window_id)) = next_action {
+                    self.mock_app.with_delegate(|delegate, ctx, env| {
+                        delegate.on_action(ctx, window_id, widget_id, action, env)
+                    });
+                    continue;
+                }
+            }
+
+            break;
         }
 
         // TODO - this might be too coarse
@@ -262,22 +757,202 @@ impl TestHarness {
 
     /// Create a Piet bitmap render context (an array of pixels), paint the
     /// window and return the bitmap.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the harness can't create a render target. See [`try_render`](Self::try_render).
     pub fn render(&mut self) -> Arc<[u8]> {
-        let mut device = Device::new().expect("harness failed to get device");
+        self.try_render().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Create a Piet bitmap render context (an array of pixels), paint the
+    /// window and return the bitmap.
+    ///
+    /// Returns [`HarnessError::LayoutOnly`] in [layout-only mode](Self::set_layout_only).
+    pub fn try_render(&mut self) -> Result<Arc<[u8]>, HarnessError> {
+        self.with_render_target(|harness, render_target| {
+            harness.render_to(render_target);
+            Ok(render_target
+                .to_image_buf(ImageFormat::RgbaPremul)
+                .map_err(HarnessError::DeviceInit)?
+                .raw_pixels_shared())
+        })?
+    }
+
+    /// Paint the whole window into a new texture created by `backend`, and return it.
+    ///
+    /// This is a sketch of how embedders could composite the widget tree, eg into a
+    /// GPU scene. See [`TextureBackend`].
+    ///
+    /// Returns [`HarnessError::LayoutOnly`] in [layout-only mode](Self::set_layout_only).
+    pub fn render_to_texture<B: TextureBackend>(
+        &mut self,
+        backend: &mut B,
+    ) -> Result<B::Texture, HarnessError> {
+        // The texture is a fresh surface, so repaint everything.
+        *self.window_mut().invalid_mut() = Region::from(self.window_size.to_rect());
+        self.with_render_target(|harness, render_target| {
+            harness.render_to(render_target);
+            let image = render_target
+                .to_image_buf(ImageFormat::RgbaPremul)
+                .map_err(HarnessError::DeviceInit)?;
+            let (width, height) = (image.width(), image.height());
+            let mut texture = backend.create_texture(width, height);
+            backend.upload(&mut texture, image.raw_pixels(), width, height);
+            Ok(texture)
+        })?
+    }
+
+    /// Paint the whole window into an SVG document, and return it.
+    ///
+    /// The window is painted through cairo's SVG surface instead of a bitmap, so shapes
+    /// stay vectors, and text is drawn as glyph outlines. The document is sized in
+    /// display points, whatever the scale factor. See [`assert_svg_snapshot`].
+    ///
+    /// Only available where piet paints through cairo, ie on Linux and the BSDs.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the harness can't create the SVG surface. See
+    /// [`try_render_to_svg`](Self::try_render_to_svg).
+    #[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "openbsd"))]
+    pub fn render_to_svg(&mut self) -> String {
+        self.try_render_to_svg()
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Non-panicking version of [`render_to_svg`](Self::render_to_svg).
+    ///
+    /// Returns [`HarnessError::LayoutOnly`] in [layout-only mode](Self::set_layout_only).
+    #[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "openbsd"))]
+    pub fn try_render_to_svg(&mut self) -> Result<String, HarnessError> {
+        use crate::piet::cairo::{Context, SvgSurface};
+
+        if self.layout_only {
+            return Err(HarnessError::LayoutOnly);
+        }
+        fn backend_error(err: impl std::error::Error + 'static) -> HarnessError {
+            HarnessError::DeviceInit(crate::piet::Error::BackendError(Box::new(err)))
+        }
+
+        let size = self.window_size;
+        let surface = SvgSurface::for_stream(size.width, size.height, Vec::<u8>::new())
+            .map_err(backend_error)?;
+        {
+            let cairo_ctx = Context::new(&surface).map_err(backend_error)?;
+            let mut piet = Piet::new(&cairo_ctx);
+            // The document is a fresh surface, so repaint everything.
+            *self.window_mut().invalid_mut() = Region::EMPTY;
+            self.mock_app
+                .paint_region(&mut piet, &Region::from(size.to_rect()));
+            piet.finish().map_err(HarnessError::DeviceInit)?;
+        }
+        let document = surface
+            .finish_output_stream()
+            .map_err(|err| backend_error(err.error))?;
+        let document = document.downcast::<Vec<u8>>().unwrap();
+        Ok(normalize_svg_ids(&String::from_utf8_lossy(&document)))
+    }
+
+    fn with_render_target<R>(
+        &mut self,
+        f: impl FnOnce(&mut Self, &mut BitmapTarget) -> R,
+    ) -> Result<R, HarnessError> {
+        if self.layout_only {
+            return Err(HarnessError::LayoutOnly);
+        }
+        let pixel_size = self.pixel_size();
+        let mut device = Device::new().map_err(HarnessError::DeviceInit)?;
         let mut render_target = device
             .bitmap_target(
-                self.window_size.width as usize,
-                self.window_size.height as usize,
-                1.0,
+                pixel_size.width as usize,
+                pixel_size.height as usize,
+                self.mock_app.window.scale.x(),
             )
-            .expect("failed to create bitmap_target");
+            .map_err(HarnessError::DeviceInit)?;
+        self.render_targets_created += 1;
+
+        Ok(f(self, &mut render_target))
+    }
 
-        self.render_to(&mut render_target);
+    /// The size of renders in pixels, ie the window size multiplied by the scale factor.
+    fn pixel_size(&self) -> Size {
+        let scale = self.mock_app.window.scale;
+        Size::new(
+            Dp(self.window_size.width).to_px_x(scale).round().0,
+            Dp(self.window_size.height).to_px_y(scale).round().0,
+        )
+    }
 
-        render_target
-            .to_image_buf(ImageFormat::RgbaPremul)
-            .unwrap()
-            .raw_pixels_shared()
+    /// Skip painting entirely, and only run layout.
+    ///
+    /// In layout-only mode, the harness never creates a device or a render target:
+    /// [`run_for`](Self::run_for) doesn't paint frames, and the `render` methods and
+    /// render snapshot checks fail with [`HarnessError::LayoutOnly`]. Tests can still check layout rects, eg with
+    /// [`get_widget`](Self::get_widget), and don't need a graphics stack to run.
+    pub fn set_layout_only(&mut self, layout_only: bool) {
+        self.layout_only = layout_only;
+    }
+
+    /// The number of render targets the harness created so far.
+    #[allow(dead_code)]
+    pub(crate) fn render_target_count(&self) -> usize {
+        self.render_targets_created
+    }
+
+    /// Paint the window and return the pixels covered by the given widget.
+    ///
+    /// The returned image has the size of the widget's layout rect, rounded to whole
+    /// pixels. Everything painted in that rect is included, eg the background of the
+    /// window and of the widget's parents, but not the parts of the widget that paint
+    /// outside of its layout rect.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if no widget with this id can be found, if the widget isn't entirely
+    /// within the window, or if the harness can't create a render target.
+    /// See [`try_render_widget`](Self::try_render_widget).
+    pub fn render_widget(&mut self, id: WidgetId) -> ImageBuf {
+        self.try_render_widget(id)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Paint the window and return the pixels covered by the given widget.
+    ///
+    /// See [`render_widget`](Self::render_widget).
+    pub fn try_render_widget(&mut self, id: WidgetId) -> Result<ImageBuf, HarnessError> {
+        let widget_rect = self
+            .try_get_widget(id)
+            .ok_or(HarnessError::MissingWidget(id))?
+            .state()
+            .window_layout_rect()
+            .round();
+        let window_rect = self.window_size.to_rect();
+        if widget_rect.intersect(window_rect) != widget_rect {
+            return Err(HarnessError::WidgetOutsideWindow(id));
+        }
+
+        // The widget might not be in the invalid region, so repaint everything.
+        *self.window_mut().invalid_mut() = Region::from(window_rect);
+        let image = self.render_rgba()?;
+
+        let scale = self.mock_app.window.scale;
+        let widget_rect = widget_rect.to_px(scale).round();
+        let cropped = image::imageops::crop_imm(
+            &image,
+            widget_rect.x0 as u32,
+            widget_rect.y0 as u32,
+            widget_rect.width() as u32,
+            widget_rect.height() as u32,
+        )
+        .to_image();
+        let (width, height) = cropped.dimensions();
+        Ok(ImageBuf::from_raw(
+            cropped.into_raw(),
+            ImageFormat::RgbaSeparate,
+            width as usize,
+            height as usize,
+        ))
     }
 
     // --- Event helpers ---
@@ -293,10 +968,25 @@ impl TestHarness {
         self.process_event(Event::MouseMove(self.mouse_state.clone()));
     }
 
+    /// Send a MouseLeave event to the window, as if the mouse left it.
+    ///
+    /// Every hot widget stops being hot and receives [`StatusChange::HotChanged`], which
+    /// lets tests check that hover effects are reset. See [`WidgetState::is_hot`].
+    pub fn mouse_leave(&mut self) {
+        self.process_event(Event::MouseLeave);
+    }
+
     /// Send a MouseDown event to the window.
+    ///
+    /// The event has a click count of 1.
     pub fn mouse_button_press(&mut self, button: MouseButton) {
+        self.mouse_button_press_with_count(button, 1);
+    }
+
+    fn mouse_button_press_with_count(&mut self, button: MouseButton, count: u8) {
         self.mouse_state.buttons.insert(button);
         self.mouse_state.button = button;
+        self.mouse_state.count = count;
 
         self.process_event(Event::MouseDown(self.mouse_state.clone()));
     }
@@ -305,6 +995,7 @@ impl TestHarness {
     pub fn mouse_button_release(&mut self, button: MouseButton) {
         self.mouse_state.buttons.remove(button);
         self.mouse_state.button = button;
+        self.mouse_state.count = 0;
 
         self.process_event(Event::MouseUp(self.mouse_state.clone()));
     }
@@ -322,12 +1013,79 @@ impl TestHarness {
     ///
     /// Combines [`mouse_move`](Self::mouse_move), [`mouse_button_press`](Self::mouse_button_press), and [`mouse_button_release`](Self::mouse_button_release).
     pub fn mouse_click_on(&mut self, id: WidgetId) {
+        self.mouse_click_on_with_button(id, MouseButton::Left);
+    }
+
+    /// Send events that lead to a given widget being clicked with the given button.
+    ///
+    /// Like [`mouse_click_on`](Self::mouse_click_on), but with any button, eg
+    /// `MouseButton::Right` to open a context menu.
+    pub fn mouse_click_on_with_button(&mut self, id: WidgetId, button: MouseButton) {
+        self.mouse_multi_click_on(id, button, 1);
+    }
+
+    /// Send events that lead to a given widget being double-clicked.
+    ///
+    /// Like druid-shell, this sends two presses and releases of the left button, the
+    /// first MouseDown event having a click count of 1 and the second a click count of 2.
+    pub fn mouse_double_click_on(&mut self, id: WidgetId) {
+        self.mouse_multi_click_on(id, MouseButton::Left, 2);
+    }
+
+    /// Send events that lead to a given widget being triple-clicked.
+    ///
+    /// See [`mouse_double_click_on`](Self::mouse_double_click_on). The MouseDown events
+    /// have click counts of 1, 2 and 3.
+    pub fn mouse_triple_click_on(&mut self, id: WidgetId) {
+        self.mouse_multi_click_on(id, MouseButton::Left, 3);
+    }
+
+    fn mouse_multi_click_on(&mut self, id: WidgetId, button: MouseButton, clicks: u8) {
         let widget_rect = self.get_widget(id).state().window_layout_rect();
         let widget_center = widget_rect.center();
 
         self.mouse_move(widget_center);
-        self.mouse_button_press(MouseButton::Left);
-        self.mouse_button_release(MouseButton::Left);
+        for count in 1..=clicks {
+            self.mouse_button_press_with_count(button, count);
+            self.mouse_button_release(button);
+        }
+    }
+
+    /// Simulate dragging the mouse from one point to another with the given button held.
+    ///
+    /// This moves the mouse to `from`, presses `button`, sends [`HARNESS_DRAG_STEPS`]
+    /// MouseMove events along a straight line to `to`, then releases `button`.
+    /// See [`mouse_drag_with_steps`](Self::mouse_drag_with_steps).
+    pub fn mouse_drag(
+        &mut self,
+        from: impl Into<Point>,
+        to: impl Into<Point>,
+        button: MouseButton,
+    ) {
+        self.mouse_drag_with_steps(from, to, button, HARNESS_DRAG_STEPS);
+    }
+
+    /// Simulate dragging the mouse, with the given number of MouseMove events.
+    ///
+    /// The moves are evenly spaced between `from` and `to`, the last one being at `to`.
+    /// `button` is held in their `buttons` field. If `steps` is zero, a single move is sent.
+    pub fn mouse_drag_with_steps(
+        &mut self,
+        from: impl Into<Point>,
+        to: impl Into<Point>,
+        button: MouseButton,
+        steps: usize,
+    ) {
+        let from = from.into();
+        let to = to.into();
+        let steps = steps.max(1);
+
+        self.mouse_move(from);
+        self.mouse_button_press(button);
+        for step in 1..=steps {
+            self.mouse_move(from.lerp(to, step as f64 / steps as f64));
+        }
+        self.mouse_button_release(button);
     }
 
     /// Use [`mouse_move`](Self::mouse_move) to set the internal mouse pos to the center of the given widget.
@@ -340,71 +1098,433 @@ impl TestHarness {
         self.mouse_move(widget_center);
     }
 
-    // TODO - Handle complicated IME
-
     /// Simulate typing the given text.
     ///
     /// For every character in the input string (more specifically,
     /// for every Unicode Scalar Value), this sends a KeyDown and a
     /// KeyUp event to the window.
     ///
-    /// Obviously this works better with ASCII text.
-    ///
-    /// **(Note: IME mocking is a future feature)**
+    /// Obviously this works better with ASCII text. To simulate an IME composition,
+    /// use [`ime_session`](Self::ime_session).
     pub fn keyboard_type_chars(&mut self, text: &str) {
+        self.keyboard_type_chars_with_mods(text, RawMods::None);
+    }
+
+    /// Simulate typing the given text while holding the given modifiers.
+    ///
+    /// This works like [`keyboard_type_chars`](Self::keyboard_type_chars), except that
+    /// the key events carry `mods`. If Ctrl or Meta is held, unhandled characters are
+    /// treated as shortcuts and aren't inserted into the focused text widget.
+    pub fn keyboard_type_chars_with_mods(&mut self, text: &str, mods: RawMods) {
         // For each character
         for c in text.split("").filter(|s| !s.is_empty()) {
-            let event = KeyEvent::for_test(RawMods::None, c);
+            let key = KbKey::Character(c.to_string());
+            let handled = self.send_key_event(true, key.clone(), mods);
+            let is_shortcut = {
+                let mods = Modifiers::from(mods) | self.mouse_state.mods;
+                mods.ctrl() || mods.meta()
+            };
 
-            if self.mock_app.event(Event::KeyDown(event.clone())) == Handled::No {
-                if let Some(mut input_handler) = self.mock_app.window.get_focused_ime_handler(true)
-                {
+            if handled == Handled::No && !is_shortcut {
+                self.try_edit_focused_text(|input_handler| {
                     // This is copy-pasted from druid-shell's simulate_input function
                     let selection = input_handler.selection();
                     input_handler.replace_range(selection.range(), c);
                     let new_caret_index = selection.min() + c.len();
                     input_handler.set_selection(Selection::caret(new_caret_index));
-
-                    let modified_widget = self.mock_app.window.release_focused_ime_handler();
-
-                    if let Some(widget_id) = modified_widget {
-                        let event = Event::Internal(InternalEvent::RouteImeStateChange(widget_id));
-                        self.mock_app.event(event);
-                    }
-                }
+                });
             }
-            self.mock_app.event(Event::KeyUp(event.clone()));
+            self.send_key_event(false, key, mods);
         }
         self.process_state_after_event();
     }
 
-    #[doc(alias = "send_command")]
-    /// Send a command to a target.
-    pub fn submit_command(&mut self, command: impl Into<Command>) {
-        let command = command.into().default_to(self.mock_app.window.id.into());
-        let event = Event::Internal(InternalEvent::TargetedCommand(command));
-        self.process_event(event);
+    /// Send a KeyDown event for the given key to the window.
+    ///
+    /// Unlike [`keyboard_press_key`](Self::keyboard_press_key), this only sends the
+    /// event: if no widget handles it, nothing else happens. Modifiers held with
+    /// [`set_held_modifiers`](Self::set_held_modifiers) are added to `mods`.
+    pub fn key_down(&mut self, key: KbKey, mods: RawMods) {
+        self.send_key_event(true, key, mods);
+        self.process_state_after_event();
     }
 
-    /// Simulate the passage of time.
+    /// Send a KeyUp event for the given key to the window.
     ///
-    /// If you create any timer in a widget, this method is the only way to trigger
-    /// them in unit tests. The testing model assumes that everything else executes
-    /// instantly, and timers are never triggered "spontaneously".
+    /// See [`key_down`](Self::key_down).
+    pub fn key_up(&mut self, key: KbKey, mods: RawMods) {
+        self.send_key_event(false, key, mods);
+        self.process_state_after_event();
+    }
+
+    fn send_key_event(&mut self, down: bool, key: KbKey, mods: RawMods) -> Handled {
+        let mut event = KeyEvent::for_test(mods, key);
+        event.mods |= self.mouse_state.mods;
+        if down {
+            self.mock_app.event(Event::KeyDown(event))
+        } else {
+            self.mock_app.event(Event::KeyUp(event))
+        }
+    }
+
+    /// Simulate pasting the given text into the focused text widget.
     ///
-    /// **(TODO - Doesn't move animations forward.)**
-    pub fn move_timers_forward(&mut self, duration: Duration) {
-        // TODO - handle animations
-        let tokens = self
-            .mock_app
-            .window
-            .mock_timer_queue
+    /// Unlike [`keyboard_type_chars`](Self::keyboard_type_chars), the text is inserted with a
+    /// single edit through the widget's IME handler, replacing the current selection, the way
+    /// the platform delivers pastes. No key events are sent.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the focused widget isn't registered as accepting text input.
+    pub fn paste(&mut self, text: &str) {
+        self.edit_focused_text(|input_handler| {
+            let selection = input_handler.selection();
+            input_handler.replace_range(selection.range(), text);
+            input_handler.set_selection(Selection::caret(selection.min() + text.len()));
+        });
+    }
+
+    /// Start simulating an IME session in the focused text widget.
+    ///
+    /// The returned [`ImeSession`] edits the text through the widget's IME handler, the
+    /// way the platform does while the user composes text, eg with a Japanese input
+    /// method: the composing region is replaced several times before the final text
+    /// is committed. No key events are sent.
+    pub fn ime_session(&mut self) -> ImeSession<'_> {
+        ImeSession { harness: self }
+    }
+
+    /// Edit the focused text widget through its IME handler, then send it an
+    /// [`Event::ImeStateChange`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the focused widget isn't registered as accepting text input.
+    fn edit_focused_text(&mut self, f: impl FnOnce(&mut dyn InputHandler)) {
+        self.try_edit_focused_text(f)
+            .expect("focused widget doesn't accept text input");
+    }
+
+    /// Like [`edit_focused_text`](Self::edit_focused_text), but returns `None` instead
+    /// of panicking if the focused widget doesn't accept text input.
+    fn try_edit_focused_text(&mut self, f: impl FnOnce(&mut dyn InputHandler)) -> Option<()> {
+        let mut input_handler = self.mock_app.window.get_focused_ime_handler(true)?;
+        f(&mut *input_handler);
+        drop(input_handler);
+
+        if let Some(widget_id) = self.mock_app.window.release_focused_ime_handler() {
+            let event = Event::Internal(InternalEvent::RouteImeStateChange(widget_id));
+            self.process_event(event);
+        }
+        Some(())
+    }
+
+    /// Simulate pressing a navigation key (eg an arrow key, Home or End), Enter or Tab.
+    ///
+    /// This sends a KeyDown and a KeyUp event to the window. If the KeyDown event
+    /// isn't handled, the key is applied to the focused text widget as a
+    /// [`TextAction`](crate::text::TextAction), the way druid-shell does on platforms
+    /// without IME support: Ctrl+Left/Right move by word, Home/End move to the start
+    /// and end of the line, Shift extends the selection, Enter inserts a newline, and
+    /// Tab and Shift+Tab insert a tab and a backtab.
+    pub fn keyboard_press_key(&mut self, key: KbKey, mods: RawMods) {
+        if self.send_key_event(true, key.clone(), mods) == Handled::No {
+            let held_mods = Modifiers::from(mods) | self.mouse_state.mods;
+            let ctrl = held_mods.ctrl();
+            let movement = match key {
+                KbKey::ArrowLeft if ctrl => Some(Movement::Word(Direction::Left)),
+                KbKey::ArrowLeft => Some(Movement::Grapheme(Direction::Left)),
+                KbKey::ArrowRight if ctrl => Some(Movement::Word(Direction::Right)),
+                KbKey::ArrowRight => Some(Movement::Grapheme(Direction::Right)),
+                KbKey::Home if ctrl => Some(Movement::Vertical(VerticalMovement::DocumentStart)),
+                KbKey::Home => Some(Movement::Line(Direction::Upstream)),
+                KbKey::End if ctrl => Some(Movement::Vertical(VerticalMovement::DocumentEnd)),
+                KbKey::End => Some(Movement::Line(Direction::Downstream)),
+                _ => None,
+            };
+
+            // This mirrors druid-shell's simulate_input function
+            let action = match movement {
+                Some(movement) if held_mods.shift() => Some(TextAction::MoveSelecting(movement)),
+                Some(movement) => Some(TextAction::Move(movement)),
+                None if key == KbKey::Enter => Some(TextAction::InsertNewLine {
+                    ignore_hotkey: false,
+                    newline_type: '\n',
+                }),
+                None if key == KbKey::Tab && held_mods.shift() => Some(TextAction::InsertBacktab),
+                None if key == KbKey::Tab => Some(TextAction::InsertTab {
+                    ignore_hotkey: false,
+                }),
+                None => None,
+            };
+
+            if let Some(action) = action {
+                self.try_edit_focused_text(|input_handler| input_handler.handle_action(action));
+            }
+        }
+        self.send_key_event(false, key, mods);
+        self.process_state_after_event();
+    }
+
+    /// Move focus to the next widget in the focus chain, as Tab would.
+    ///
+    /// This uses the same logic as when the focused widget calls
+    /// [`EventCtx::focus_next`]: focus wraps around at the end of the chain, stays in
+    /// the active focus trap if there is one, and the widgets losing and gaining focus
+    /// receive [`StatusChange::FocusChanged`]. If no widget has focus, nothing happens.
+    pub fn focus_next(&mut self) {
+        self.mock_app.change_focus(FocusChange::Next);
+        self.process_state_after_event();
+    }
+
+    /// Move focus to the previous widget in the focus chain, as Shift+Tab would.
+    ///
+    /// See [`focus_next`](Self::focus_next).
+    pub fn focus_prev(&mut self) {
+        self.mock_app.change_focus(FocusChange::Previous);
+        self.process_state_after_event();
+    }
+
+    /// Return the selection of the text widget with the given id.
+    ///
+    /// The selection is read through the widget's IME handler, the same way the
+    /// platform would read it.
+    ///
+    /// Returns `None` if the widget isn't registered as accepting text input.
+    pub fn text_selection(&self, id: WidgetId) -> Option<Selection> {
+        let input_handler = self.mock_app.window.get_widget_ime_handler(id, false)?;
+        let selection = input_handler.selection();
+        drop(input_handler);
+        self.mock_app.window.release_widget_ime_handler(id);
+        Some(selection)
+    }
+
+    /// Return the computed layout of the text of the widget with the given id.
+    ///
+    /// The layout is read through [`Widget::text_layout_info`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if no widget with this id can be found, or if the widget doesn't expose
+    /// a text layout.
+    pub fn text_layout(&self, id: WidgetId) -> TextLayoutInfo {
+        let widget = self.get_widget(id);
+        widget.text_layout_info().unwrap_or_else(|| {
+            panic!(
+                "widget #{} ({}) doesn't expose a text layout",
+                id.to_raw(),
+                widget.short_type_name()
+            )
+        })
+    }
+
+    /// Set the selection of the text widget with the given id.
+    ///
+    /// The selection is set through the widget's IME handler, the same way the
+    /// platform would set it, and the widget then receives an [`Event::ImeStateChange`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the widget isn't registered as accepting text input.
+    pub fn set_text_selection(&mut self, id: WidgetId, selection: Selection) {
+        let mut input_handler = self
+            .mock_app
+            .window
+            .get_widget_ime_handler(id, true)
+            .expect("widget doesn't accept text input");
+        input_handler.set_selection(selection);
+        drop(input_handler);
+
+        if let Some(widget_id) = self.mock_app.window.release_widget_ime_handler(id) {
+            let event = Event::Internal(InternalEvent::RouteImeStateChange(widget_id));
+            self.process_event(event);
+        }
+    }
+
+    #[doc(alias = "send_command")]
+    /// Send a command to a target.
+    pub fn submit_command(&mut self, command: impl Into<Command>) {
+        let command = command.into().default_to(self.mock_app.window.id.into());
+        self.mock_app.command_queue.push_back(command);
+        self.process_state_after_event();
+    }
+
+    /// Deliver the commands and promise results sent through an [`ExtEventSink`](crate::ext_event::ExtEventSink).
+    ///
+    /// Background tasks started with [`compute_in_background`](EventCtx::compute_in_background)
+    /// run on other threads; this only delivers the results of tasks that have
+    /// already finished. Results for widgets that have since been removed are discarded.
+    pub fn process_ext_events(&mut self) {
+        while let Some(message) = self.mock_app.ext_event_queue.recv() {
+            match message {
+                ExtMessage::Command(selector, payload, target) => {
+                    let command = Command::from_ext(selector, payload, target);
+                    self.process_event(Event::Internal(InternalEvent::TargetedCommand(command)));
+                }
+                ExtMessage::Promise(promise_result, widget_id, window_id) => {
+                    if window_id != self.mock_app.window.id {
+                        continue;
+                    }
+                    // Same filtering as the app: results for removed widgets are discarded.
+                    let event = self
+                        .mock_app
+                        .window
+                        .promise_result_event(promise_result, widget_id);
+                    if let Some(event) = event {
+                        self.process_event(event);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Simulate the passage of time.
+    ///
+    /// If you create any timer in a widget, this method is the only way to trigger
+    /// them in unit tests. The testing model assumes that everything else executes
+    /// instantly, and timers are never triggered "spontaneously".
+    ///
+    /// Animations are moved forward by a single frame spanning the whole duration; see
+    /// [`animate_frame`](Self::animate_frame). Use [`run_for`](Self::run_for) to simulate
+    /// successive frames.
+    pub fn move_timers_forward(&mut self, duration: Duration) {
+        let tokens = self
+            .mock_app
+            .window
+            .mock_timer_queue
             .as_mut()
             .unwrap()
             .move_forward(duration);
         for token in tokens {
             self.process_event(Event::Timer(token));
         }
+        self.animate_frame(duration);
+    }
+
+    /// Return the timers that haven't fired yet, soonest first, with the time left
+    /// before each of them fires.
+    ///
+    /// Interval timers are listed once, with the time left before their next fire.
+    pub fn pending_timers(&self) -> Vec<(TimerToken, Duration)> {
+        self.mock_app
+            .window
+            .mock_timer_queue
+            .as_ref()
+            .unwrap()
+            .pending_timers()
+    }
+
+    /// Return the widgets that requested an animation frame.
+    ///
+    /// Because the request is stored in the same flag as the requests of a widget's
+    /// descendants, a widget is only listed if none of its descendants also requested
+    /// a frame.
+    pub fn pending_anim_widgets(&self) -> Vec<WidgetId> {
+        fn collect(widget: WidgetRef<'_, dyn Widget>, ids: &mut Vec<WidgetId>) -> bool {
+            if !widget.state().request_anim {
+                return false;
+            }
+            let mut descendant_requested = false;
+            for child in widget.children() {
+                descendant_requested |= collect(child, ids);
+            }
+            if !descendant_requested {
+                ids.push(widget.state().id);
+            }
+            true
+        }
+
+        let mut ids = Vec::new();
+        collect(self.root_widget(), &mut ids);
+        ids
+    }
+
+    /// Panic if any widget is still requesting animation frames.
+    ///
+    /// Call this after [`run_for`](Self::run_for) to check that an animation settles,
+    /// instead of requesting frames forever.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if [`pending_anim_widgets`](Self::pending_anim_widgets) isn't empty, with a
+    /// message listing those widgets.
+    pub fn assert_no_pending_animations(&self) {
+        let pending = self.pending_anim_widgets();
+        if pending.is_empty() {
+            return;
+        }
+        let widgets: Vec<String> = pending
+            .iter()
+            .map(|id| {
+                let widget = self.get_widget(*id);
+                format!("{} #{}", widget.short_type_name(), id.to_raw())
+            })
+            .collect();
+        panic!("animations still running in: {}", widgets.join(", "));
+    }
+
+    /// Simulate the passage of time, frame by frame.
+    ///
+    /// Time is advanced in steps of [`HARNESS_FRAME_DURATION`]. On each step, timers and
+    /// animations are moved forward, and the window is laid out and painted, the same way
+    /// a running app renders successive frames.
+    ///
+    /// If `duration` isn't a multiple of [`HARNESS_FRAME_DURATION`], the last step is shorter.
+    /// In [layout-only mode](Self::set_layout_only), frames are laid out but not painted.
+    pub fn run_for(&mut self, duration: Duration) {
+        let mut remaining = duration;
+        while !remaining.is_zero() {
+            let step = remaining.min(HARNESS_FRAME_DURATION);
+            remaining -= step;
+
+            self.move_timers_forward(step);
+            if !self.layout_only {
+                self.render();
+            }
+        }
+    }
+
+    /// Send an [`Event::AnimFrame`] with the given interval to the widgets that requested
+    /// an animation frame.
+    ///
+    /// Widgets that didn't call `request_anim_frame` don't receive the event. If no widget
+    /// requested a frame, this does nothing.
+    pub fn animate_frame(&mut self, interval: Duration) {
+        if self.mock_app.window.wants_animation_frame() {
+            self.process_event(Event::AnimFrame(interval.as_nanos() as u64));
+        }
+    }
+
+    /// Reseed the random number generator widgets access through `ctx.rng()`.
+    ///
+    /// The harness is created with a generator seeded with [`HARNESS_DEFAULT_SEED`].
+    /// Two harnesses with the same seed, receiving the same events, will see the same
+    /// random values.
+    pub fn set_rng_seed(&mut self, seed: u64) {
+        self.mock_app.window.rng.reseed(seed);
+    }
+
+    /// Set the folder render snapshots are stored in.
+    ///
+    /// Relative paths are resolved relative to the folder of the test file.
+    ///
+    /// If this isn't called, the folder is read from the `MASONRY_SCREENSHOT_DIR`
+    /// environment variable, and defaults to `screenshots`.
+    pub fn set_screenshot_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.screenshot_dir = Some(dir.into());
+    }
+
+    /// Set the template used to name render snapshots.
+    ///
+    /// `{module}`, `{test}` and `{os}` in the template are replaced by the module path
+    /// of the test (with `::` replaced by `__`), the name of the snapshot and the
+    /// target OS, eg `"{os}/{module}__{test}"` to keep per-platform snapshots in
+    /// different subfolders. The `.png` extension is added automatically.
+    ///
+    /// If this isn't called, the template is read from the `MASONRY_SCREENSHOT_NAME`
+    /// environment variable, and defaults to `"{module}__{test}"`.
+    pub fn set_screenshot_name(&mut self, template: impl Into<String>) {
+        self.screenshot_name = Some(template.into());
     }
 
     // --- Getters ---
@@ -424,16 +1544,31 @@ impl TestHarness {
         self.mock_app.window.root.as_dyn()
     }
 
+    /// Return the root of one of the trees passed to [`create_multi`](Self::create_multi).
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `index` is out of bounds, or if the harness wasn't created with
+    /// `create_multi`.
+    pub fn multi_root(&self, index: usize) -> WidgetRef<'_, dyn Widget> {
+        let id = self.multi_roots.get(index).unwrap_or_else(|| {
+            panic!(
+                "no root at index {}, harness has {} roots from create_multi",
+                index,
+                self.multi_roots.len()
+            )
+        });
+        self.get_widget(*id)
+    }
+
     /// Return the widget with the given id.
     ///
     /// ## Panics
     ///
     /// Panics if no Widget with this id can be found.
     pub fn get_widget(&self, id: WidgetId) -> WidgetRef<'_, dyn Widget> {
-        self.mock_app
-            .window
-            .find_widget_by_id(id)
-            .expect("could not find widget")
+        self.try_get_widget(id)
+            .unwrap_or_else(|| panic!("{}", HarnessError::MissingWidget(id)))
     }
 
     /// Try to return the widget with the given id.
@@ -447,19 +1582,116 @@ impl TestHarness {
         self.mock_app.window.focused_widget()
     }
 
+    /// Start recording the events the window receives in `event_log`.
+    ///
+    /// This replaces the events recorded so far, if any. See [`EventLog`].
+    pub fn set_event_log(&mut self, event_log: EventLog) {
+        self.mock_app.window.set_event_log(Some(event_log));
+    }
+
+    /// Return the events recorded since [`set_event_log`](Self::set_event_log) was called.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `set_event_log` wasn't called.
+    pub fn event_log(&self) -> &EventLog {
+        self.mock_app
+            .window
+            .event_log()
+            .expect("event_log called without set_event_log")
+    }
+
+    /// Start or stop recording which events reach which widgets.
+    ///
+    /// Starting a recording clears the events recorded so far. See
+    /// [`events_received`](Self::events_received).
+    pub fn set_record_events(&mut self, record: bool) {
+        self.mock_app.debug_logger.received_events = record.then(HashMap::new);
+    }
+
+    /// Start or stop recording the lifecycle events each widget receives.
+    ///
+    /// Starting a recording clears the lifecycle events recorded so far. See
+    /// [`lifecycle_events`](Self::lifecycle_events).
+    pub fn set_record_lifecycle_events(&mut self, record: bool) {
+        self.mock_app.debug_logger.received_lifecycle_events = record.then(HashMap::new);
+    }
+
+    /// Return the names of the lifecycle events the given widget received, in order,
+    /// since recording was started with [`set_record_lifecycle_events`](Self::set_record_lifecycle_events).
+    ///
+    /// The names are those returned by [`LifeCycle::short_name`].
+    pub fn lifecycle_events(&self, id: WidgetId) -> Vec<&'static str> {
+        self.mock_app
+            .debug_logger
+            .received_lifecycle_events
+            .as_ref()
+            .and_then(|received_lifecycle_events| received_lifecycle_events.get(&id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Return the kinds of the events the given widget received, in order, since
+    /// recording was started with [`set_record_events`](Self::set_record_events).
+    pub fn events_received(&self, id: WidgetId) -> Vec<EventKind> {
+        self.mock_app
+            .debug_logger
+            .received_events
+            .as_ref()
+            .and_then(|received_events| received_events.get(&id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Return a rough estimate of the memory used by the widget tree.
+    ///
+    /// See [`WidgetRef::estimated_memory`].
+    pub fn estimated_memory(&self) -> usize {
+        self.mock_app.window.estimated_memory()
+    }
+
     /// Call the provided visitor on every widget in the widget tree.
     pub fn inspect_widgets(&mut self, f: impl Fn(WidgetRef<'_, dyn Widget>) + 'static) {
-        fn inspect(
-            widget: WidgetRef<'_, dyn Widget>,
-            f: &(impl Fn(WidgetRef<'_, dyn Widget>) + 'static),
-        ) {
-            f(widget);
-            for child in widget.deref().children() {
-                inspect(child, f);
-            }
-        }
+        self.mock_app.window.root.as_dyn().subtree().for_each(f);
+    }
+
+    /// Return the first widget matching `predicate` in the widget tree, in depth-first order.
+    ///
+    /// Unlike [`inspect_widgets`](Self::inspect_widgets), the predicate can borrow
+    /// variables from the test, eg:
+    ///
+    /// ```ignore
+    /// let text = "Hello";
+    /// let label = harness.find_widget(|widget| {
+    ///     widget.downcast::<Label>().map_or(false, |label| label.text().as_ref() == text)
+    /// });
+    /// ```
+    pub fn find_widget(
+        &self,
+        predicate: impl Fn(WidgetRef<'_, dyn Widget>) -> bool,
+    ) -> Option<WidgetRef<'_, dyn Widget>> {
+        self.root_widget().find_widget(predicate)
+    }
+
+    /// Return the first widget of type `W` in the widget tree, in depth-first order.
+    ///
+    /// Useful to find widgets created by other widgets, which the test can't give an
+    /// id, eg the [`Image`](crate::widget::Image) inside a
+    /// [`WebImage`](crate::widget::WebImage).
+    pub fn find_widget_by_type<W: Widget>(&self) -> Option<WidgetRef<'_, W>> {
+        self.root_widget()
+            .find_widget_by_type_id(TypeId::of::<W>())?
+            .downcast()
+    }
 
-        inspect(self.mock_app.window.root.as_dyn(), &f);
+    /// Return every widget of type `W` in the widget tree, in depth-first order.
+    ///
+    /// See [`find_widget_by_type`](Self::find_widget_by_type).
+    pub fn find_all_widgets_by_type<W: Widget>(&self) -> Vec<WidgetRef<'_, W>> {
+        self.root_widget()
+            .subtree()
+            .filter_map(|widget| widget.downcast())
+            .collect()
     }
 
     /// Get a [`WidgetMut`] to the root widget.
@@ -469,54 +1701,42 @@ impl TestHarness {
         &mut self,
         f: impl FnOnce(WidgetMut<'_, '_, Box<dyn Widget>>, &Env) -> R,
     ) -> R {
-        // TODO - Move to MockAppRoot?
-        let window = &mut self.mock_app.window;
-        let mut fake_widget_state;
-        let mut timers = HashMap::new();
-        let res = {
-            let mut global_state = GlobalPassCtx::new(
-                window.ext_event_sink.clone(),
-                &mut self.mock_app.debug_logger,
-                &mut self.mock_app.command_queue,
-                &mut self.mock_app.action_queue,
-                &mut timers,
-                window.mock_timer_queue.as_mut(),
-                &window.handle,
-                window.id,
-                window.focus,
-            );
-            fake_widget_state = window.root.state.clone();
+        let res = self
+            .mock_app
+            .edit_root_widget(|root_widget, _, env| f(root_widget, env));
+        if !self.batching_edits {
+            self.process_state_after_event();
+        }
 
-            let root_widget = WidgetMut {
-                inner: Box::<dyn Widget>::from_widget_and_ctx(
-                    &mut window.root.inner,
-                    WidgetCtx {
-                        global_state: &mut global_state,
-                        widget_state: &mut window.root.state,
-                    },
-                ),
-                parent_widget_state: &mut fake_widget_state,
-            };
+        res
+    }
 
-            f(root_widget, &self.mock_app.env)
-        };
+    /// Run a callback which can make several calls to [`edit_root_widget`](Self::edit_root_widget),
+    /// with a single layout pass once the callback returns.
+    ///
+    /// Without batching, each edit that invalidates layout is immediately followed by a
+    /// layout pass. Queued commands are also only processed once the callback returns.
+    ///
+    /// This mirrors what happens in a running app, where edits made by an
+    /// [`AppDelegate`](crate::AppDelegate) only get laid out before the next paint.
+    pub fn edit_root_widget_batched<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let was_batching = std::mem::replace(&mut self.batching_edits, true);
+        let res = f(self);
+        self.batching_edits = was_batching;
+
+        if !was_batching {
+            self.process_state_after_event();
+        }
 
-        // Timer creation should use mock_timer_queue instead
-        assert!(timers.is_empty());
+        res
+    }
 
-        // TODO - handle cursor and validation
+    /// Edit the [`Env`] passed to widgets, then lay out and repaint the whole window.
+    pub fn edit_env(&mut self, f: impl FnOnce(&mut Env)) {
+        f(&mut self.mock_app.env);
 
-        window.post_event_processing(
-            &mut fake_widget_state,
-            &mut self.mock_app.debug_logger,
-            &mut self.mock_app.command_queue,
-            &mut self.mock_app.action_queue,
-            &self.mock_app.env,
-            false,
-        );
+        self.mock_app.window.root.state.needs_layout = true;
         self.process_state_after_event();
-
-        res
     }
 
     /// Pop next action from the queue
@@ -532,7 +1752,21 @@ impl TestHarness {
     /// Method used by [`assert_render_snapshot`]. Use the macro instead.
     ///
     /// Renders the current Widget tree to a pixmap, and compares the pixmap against the
-    /// snapshot stored in `./screenshots/module_path__test_name.png`.
+    /// snapshot stored in `./screenshots/module_path__test_name.png`, or wherever
+    /// [`set_screenshot_dir`](Self::set_screenshot_dir) and
+    /// [`set_screenshot_name`](Self::set_screenshot_name) say.
+    ///
+    /// If a reference suffixed with the current platform exists (eg
+    /// `module_path__test_name.macos.png`), it's used instead of the base reference.
+    /// This lets projects keep separate snapshots for platforms where rendering
+    /// legitimately differs. The platform names are those of [`std::env::consts::OS`].
+    ///
+    /// A snapshot can also have several acceptable references, numbered from zero
+    /// (eg `module_path__test_name.0.png`, `module_path__test_name.1.png`), in which
+    /// case the render must match any one of them. Numbering stops at the first
+    /// missing index. An unnumbered reference is tried before the numbered ones.
+    /// Numbered references can also be platform-specific, eg
+    /// `module_path__test_name.macos.0.png`.
     ///
     /// * **manifest_dir:** directory where `Cargo.toml` can be found.
     /// * **test_file_path:** file path the current test is in.
@@ -545,49 +1779,274 @@ impl TestHarness {
         test_module_path: &str,
         test_name: &str,
     ) {
-        let mut device = Device::new().expect("harness failed to get device");
-        let mut render_target = device
-            .bitmap_target(
-                self.window_size.width as usize,
-                self.window_size.height as usize,
-                1.0,
-            )
-            .expect("failed to create bitmap_target");
+        self.try_check_render_snapshot(manifest_dir, test_file_path, test_module_path, test_name)
+            .unwrap_or_else(|err| panic!("{err}"));
+    }
 
-        self.render_to(&mut render_target);
+    /// Method used by [`assert_render_snapshot`] when given a tolerance. Use the macro instead.
+    ///
+    /// Same as [`check_render_snapshot`](Self::check_render_snapshot), except the render
+    /// matches a reference if their difference is within `tolerance`.
+    pub fn check_render_snapshot_with_tolerance(
+        &mut self,
+        manifest_dir: &str,
+        test_file_path: &str,
+        test_module_path: &str,
+        test_name: &str,
+        tolerance: DiffTolerance,
+    ) {
+        self.try_check_render_snapshot_with_tolerance(
+            manifest_dir,
+            test_file_path,
+            test_module_path,
+            test_name,
+            tolerance,
+        )
+        .unwrap_or_else(|err| panic!("{err}"));
+    }
 
-        let new_image = get_rgba_image(&mut render_target, self.window_size);
+    /// Non-panicking version of [`check_render_snapshot`](Self::check_render_snapshot).
+    ///
+    /// Returns [`HarnessError::SnapshotMismatch`] or [`HarnessError::MissingReference`]
+    /// where `check_render_snapshot` would fail the test, and [`HarnessError::LayoutOnly`]
+    /// in [layout-only mode](Self::set_layout_only).
+    pub fn try_check_render_snapshot(
+        &mut self,
+        manifest_dir: &str,
+        test_file_path: &str,
+        test_module_path: &str,
+        test_name: &str,
+    ) -> Result<(), HarnessError> {
+        self.try_check_render_snapshot_with_tolerance(
+            manifest_dir,
+            test_file_path,
+            test_module_path,
+            test_name,
+            DiffTolerance::EXACT,
+        )
+    }
 
+    /// Non-panicking version of
+    /// [`check_render_snapshot_with_tolerance`](Self::check_render_snapshot_with_tolerance).
+    pub fn try_check_render_snapshot_with_tolerance(
+        &mut self,
+        manifest_dir: &str,
+        test_file_path: &str,
+        test_module_path: &str,
+        test_name: &str,
+        tolerance: DiffTolerance,
+    ) -> Result<(), HarnessError> {
+        let new_image = self.render_rgba()?;
+
+        let path_stem =
+            self.snapshot_path_stem(manifest_dir, test_file_path, test_module_path, test_name)?;
+        let with_suffix = |suffix: &str| with_path_suffix(&path_stem, suffix);
+        let reference_paths = reference_paths(&path_stem, "png");
+        let new_path = with_suffix(".new.png");
+        let diff_path = with_suffix(".diff.png");
+
+        let Some(first_reference_path) = reference_paths.first().cloned() else {
+            // Remove '<test_name>.new.png' file if it exists
+            let _ = std::fs::remove_file(&new_path);
+            new_image
+                .save(&new_path)
+                .map_err(|err| HarnessError::Image(new_path.clone(), err))?;
+            return Err(HarnessError::MissingReference {
+                reference_path: with_suffix(".png"),
+                new_path,
+            });
+        };
+
+        let mut first_diff_image = None;
+        for reference_path in reference_paths {
+            let ref_image = ImageReader::open(&reference_path)
+                .map_err(|err| HarnessError::Io(reference_path.clone(), err))?
+                .decode()
+                .map_err(|err| HarnessError::Image(reference_path.clone(), err))?
+                .to_rgba8();
+
+            match get_image_diff(&ref_image, &new_image, tolerance) {
+                None => return Ok(()),
+                Some(diff_image) => {
+                    first_diff_image.get_or_insert(diff_image);
+                }
+            }
+        }
+
+        // None of the references match; report the differences with the first one.
+        let diff_image = first_diff_image.unwrap();
+        // Remove '<test_name>.new.png' '<test_name>.diff.png' files if they exist
+        let _ = std::fs::remove_file(&new_path);
+        let _ = std::fs::remove_file(&diff_path);
+        new_image
+            .save(&new_path)
+            .map_err(|err| HarnessError::Image(new_path.clone(), err))?;
+        diff_image
+            .save(&diff_path)
+            .map_err(|err| HarnessError::Image(diff_path.clone(), err))?;
+        Err(HarnessError::SnapshotMismatch {
+            reference_path: first_reference_path,
+            new_path,
+            diff_path,
+        })
+    }
+
+    /// Method used by [`assert_svg_snapshot`]. Use the macro instead.
+    ///
+    /// Renders the current Widget tree with [`render_to_svg`](Self::render_to_svg), and
+    /// compares the document against the snapshot stored in
+    /// `./screenshots/module_path__test_name.svg`. References are looked up like those
+    /// of [`check_render_snapshot`](Self::check_render_snapshot), and must match exactly.
+    #[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "openbsd"))]
+    pub fn check_svg_snapshot(
+        &mut self,
+        manifest_dir: &str,
+        test_file_path: &str,
+        test_module_path: &str,
+        test_name: &str,
+    ) {
+        self.try_check_svg_snapshot(manifest_dir, test_file_path, test_module_path, test_name)
+            .unwrap_or_else(|err| panic!("{err}"));
+    }
+
+    /// Non-panicking version of [`check_svg_snapshot`](Self::check_svg_snapshot).
+    ///
+    /// Returns [`HarnessError::SvgSnapshotMismatch`] or [`HarnessError::MissingReference`]
+    /// where `check_svg_snapshot` would fail the test, and [`HarnessError::LayoutOnly`]
+    /// in [layout-only mode](Self::set_layout_only).
+    #[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "openbsd"))]
+    pub fn try_check_svg_snapshot(
+        &mut self,
+        manifest_dir: &str,
+        test_file_path: &str,
+        test_module_path: &str,
+        test_name: &str,
+    ) -> Result<(), HarnessError> {
+        let new_document = self.try_render_to_svg()?;
+
+        let path_stem =
+            self.snapshot_path_stem(manifest_dir, test_file_path, test_module_path, test_name)?;
+        let reference_paths = reference_paths(&path_stem, "svg");
+        let new_path = with_path_suffix(&path_stem, ".new.svg");
+        // Remove '<test_name>.new.svg' file if it exists
+        let _ = std::fs::remove_file(&new_path);
+
+        for reference_path in &reference_paths {
+            let reference = std::fs::read_to_string(reference_path)
+                .map_err(|err| HarnessError::Io(reference_path.clone(), err))?;
+            if reference == new_document {
+                return Ok(());
+            }
+        }
+
+        std::fs::write(&new_path, new_document)
+            .map_err(|err| HarnessError::Io(new_path.clone(), err))?;
+        match reference_paths.into_iter().next() {
+            Some(reference_path) => Err(HarnessError::SvgSnapshotMismatch {
+                reference_path,
+                new_path,
+            }),
+            None => Err(HarnessError::MissingReference {
+                reference_path: with_path_suffix(&path_stem, ".svg"),
+                new_path,
+            }),
+        }
+    }
+
+    /// Return the path of a snapshot without its extension, and create its folder.
+    fn snapshot_path_stem(
+        &self,
+        manifest_dir: &str,
+        test_file_path: &str,
+        test_module_path: &str,
+        test_name: &str,
+    ) -> Result<PathBuf, HarnessError> {
         let workspace_path = get_cargo_workspace(manifest_dir);
         let test_file_path_abs = workspace_path.join(test_file_path);
         let folder_path = test_file_path_abs.parent().unwrap();
 
-        let screenshots_folder = folder_path.join("screenshots");
-        std::fs::create_dir_all(&screenshots_folder).unwrap();
+        let screenshot_dir = self
+            .screenshot_dir
+            .clone()
+            .or_else(|| std::env::var_os(SCREENSHOT_DIR_VAR).map(PathBuf::from))
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_SCREENSHOT_DIR));
+        let screenshot_name = self
+            .screenshot_name
+            .clone()
+            .or_else(|| std::env::var(SCREENSHOT_NAME_VAR).ok())
+            .unwrap_or_else(|| DEFAULT_SCREENSHOT_NAME.to_string());
+
+        let scale = self.mock_app.window.scale;
+        let test_name = if scale.x() == 1.0 {
+            test_name.to_string()
+        } else {
+            format!("{test_name}@{}x", scale.x())
+        };
+        let path_stem = screenshot_path_stem(
+            folder_path,
+            &screenshot_dir,
+            &screenshot_name,
+            test_module_path,
+            &test_name,
+        );
+        let screenshots_folder = path_stem.parent().unwrap();
+        std::fs::create_dir_all(screenshots_folder)
+            .map_err(|err| HarnessError::Io(screenshots_folder.to_path_buf(), err))?;
+        Ok(path_stem)
+    }
+
+    /// Render the current Widget tree and compare it against the image at `reference_path`.
+    ///
+    /// Returns `Some` image of the differences if the images differ, and `None` if they
+    /// match. Unlike [`assert_render_snapshot`], this doesn't write any files.
+    ///
+    /// Returns [`HarnessError::Io`] if the reference can't be opened.
+    pub fn render_diff_against(
+        &mut self,
+        reference_path: impl AsRef<Path>,
+    ) -> Result<Option<ImageBuf>, HarnessError> {
+        let reference_path = reference_path.as_ref();
+        let ref_image = ImageReader::open(reference_path)
+            .map_err(|err| HarnessError::Io(reference_path.to_path_buf(), err))?
+            .decode()
+            .map_err(|err| HarnessError::Image(reference_path.to_path_buf(), err))?
+            .to_rgba8();
+        let new_image = self.render_rgba()?;
+
+        Ok(
+            get_image_diff(&ref_image, &new_image, DiffTolerance::EXACT).map(|diff_image| {
+                let (width, height) = diff_image.dimensions();
+                ImageBuf::from_raw(
+                    diff_image.into_raw(),
+                    ImageFormat::RgbaSeparate,
+                    width as usize,
+                    height as usize,
+                )
+            }),
+        )
+    }
 
-        let module_str = test_module_path.replace("::", "__");
+    fn render_rgba(&mut self) -> Result<RgbaImage, HarnessError> {
+        self.with_render_target(|harness, render_target| {
+            harness.render_to(render_target);
+            get_rgba_image(render_target, harness.pixel_size())
+        })
+    }
 
-        let reference_path = screenshots_folder.join(format!("{module_str}__{test_name}.png"));
-        let new_path = screenshots_folder.join(format!("{module_str}__{test_name}.new.png"));
-        let diff_path = screenshots_folder.join(format!("{module_str}__{test_name}.diff.png"));
+    // --- Clipboard ---
 
-        if let Ok(reference_file) = ImageReader::open(&reference_path) {
-            let ref_image = reference_file.decode().unwrap().to_rgba8();
+    /// The clipboard widgets see through [`EventCtx::clipboard`] and [`Event::Paste`].
+    ///
+    /// The harness never touches the system clipboard: this is an in-memory clipboard,
+    /// which starts empty. Use eg `harness.clipboard().get_string()` to check what a
+    /// widget copied.
+    pub fn clipboard(&self) -> &Clipboard {
+        &self.mock_app.clipboard
+    }
 
-            if let Some(diff_image) = get_image_diff(&ref_image, &new_image) {
-                // Remove '<test_name>.new.png' '<test_name>.diff.png' files if they exist
-                let _ = std::fs::remove_file(&new_path);
-                let _ = std::fs::remove_file(&diff_path);
-                new_image.save(&new_path).unwrap();
-                diff_image.save(&diff_path).unwrap();
-                panic!("Images are different");
-            }
-        } else {
-            // Remove '<test_name>.new.png' file if it exists
-            let _ = std::fs::remove_file(&new_path);
-            new_image.save(&new_path).unwrap();
-            panic!("No reference file");
-        }
+    /// Replace the contents of the clipboard, eg before sending a paste shortcut.
+    pub fn set_clipboard_contents(&mut self, text: &str) {
+        self.mock_app.clipboard.put_string(text);
     }
 
     // --- Debug logger ---
@@ -601,6 +2060,21 @@ impl TestHarness {
         self.mock_app.debug_logger.push_log(false, message);
     }
 
+    /// Start or stop capturing debug logs.
+    ///
+    /// Logs aren't captured by default. While they are, every pass pushes a log, with a
+    /// snapshot of the widget tree. See [`debug_logs`](Self::debug_logs).
+    pub fn set_debug_logging(&mut self, activated: bool) {
+        self.mock_app.debug_logger.activated = activated;
+    }
+
+    /// The debug logs captured so far, eg to check which passes ran.
+    ///
+    /// See [`DebugLogger::entries`].
+    pub fn debug_logs(&self) -> &DebugLogger {
+        &self.mock_app.debug_logger
+    }
+
     // ex: harness.write_debug_logs("test_log.json");
     #[allow(missing_docs)]
     pub fn write_debug_logs(&mut self, path: &str) {
@@ -610,6 +2084,77 @@ impl TestHarness {
 
 #[allow(dead_code)]
 impl MockAppRoot {
+    /// Returns `None` if no delegate is set.
+    fn with_delegate<R>(
+        &mut self,
+        f: impl FnOnce(&mut dyn AppDelegate, &mut DelegateCtx, &Env) -> R,
+    ) -> Option<R> {
+        let mut delegate = self.delegate.take()?;
+        let res = self.edit_root_widget(|main_root_widget, ext_event_queue, env| {
+            let mut ctx = DelegateCtx {
+                ext_event_queue,
+                main_root_widget,
+            };
+            f(delegate.as_mut(), &mut ctx, env)
+        });
+        self.delegate = Some(delegate);
+
+        Some(res)
+    }
+
+    /// Run a callback with a [`WidgetMut`] to the root widget, then clean up after any
+    /// changes it made.
+    fn edit_root_widget<R>(
+        &mut self,
+        f: impl for<'a, 'b> FnOnce(WidgetMut<'a, 'b, Box<dyn Widget>>, &'a ExtEventQueue, &Env) -> R,
+    ) -> R {
+        let window = &mut self.window;
+        let mut fake_widget_state;
+        let res = {
+            let mut global_state = GlobalPassCtx::new(
+                window.ext_event_sink.clone(),
+                &mut self.debug_logger,
+                &mut self.command_queue,
+                &mut self.action_queue,
+                &mut window.timers,
+                window.mock_timer_queue.as_mut(),
+                &mut window.rng,
+                &window.handle,
+                window.id,
+                window.focus,
+                window.scale,
+                window.clipboard.clone(),
+            );
+            fake_widget_state = window.root.state.clone();
+
+            let root_widget = WidgetMut {
+                inner: Box::<dyn Widget>::from_widget_and_ctx(
+                    &mut window.root.inner,
+                    WidgetCtx {
+                        global_state: &mut global_state,
+                        widget_state: &mut window.root.state,
+                    },
+                ),
+                parent_widget_state: &mut fake_widget_state,
+            };
+
+            f(root_widget, &self.ext_event_queue, &self.env)
+        };
+
+        // TODO - handle cursor and validation
+
+        window.post_event_processing(
+            &mut fake_widget_state,
+            &mut self.debug_logger,
+            &mut self.command_queue,
+            &mut self.action_queue,
+            &self.env,
+            false,
+        );
+
+        res
+    }
+
     fn event(&mut self, event: Event) -> Handled {
         self.window.event(
             event,
@@ -620,6 +2165,16 @@ impl MockAppRoot {
         )
     }
 
+    fn change_focus(&mut self, focus_change: FocusChange) {
+        self.window.change_focus(
+            focus_change,
+            &mut self.debug_logger,
+            &mut self.command_queue,
+            &mut self.action_queue,
+            &self.env,
+        );
+    }
+
     fn lifecycle(&mut self, event: LifeCycle) {
         self.window.lifecycle(
             &event,
@@ -651,3 +2206,97 @@ impl MockAppRoot {
         );
     }
 }
+
+/// A simulated IME session, returned by [`TestHarness::ime_session`].
+///
+/// Each method edits the focused text widget through its IME handler, then sends the
+/// widget an [`Event::ImeStateChange`].
+///
+/// ## Panics
+///
+/// Every method panics if the focused widget isn't registered as accepting text input.
+pub struct ImeSession<'a> {
+    harness: &'a mut TestHarness,
+}
+
+impl ImeSession<'_> {
+    /// Replace the composing region with `text`, which becomes the new composing region.
+    ///
+    /// If there is no composing region, `text` replaces the selection. The caret is
+    /// placed at the end of `text`.
+    pub fn set_composing_region(&mut self, text: &str) -> &mut Self {
+        self.harness.edit_focused_text(|input_handler| {
+            let range = composing_or_selected_range(input_handler);
+            input_handler.replace_range(range.clone(), text);
+            let end = range.start + text.len();
+            input_handler.set_composition_range(Some(range.start..end));
+            input_handler.set_selection(Selection::caret(end));
+        });
+        self
+    }
+
+    /// Replace the composing region with the final `text`, and end the composition.
+    ///
+    /// If there is no composing region, `text` replaces the selection. The caret is
+    /// placed at the end of `text`.
+    pub fn commit(&mut self, text: &str) -> &mut Self {
+        self.harness.edit_focused_text(|input_handler| {
+            let range = composing_or_selected_range(input_handler);
+            input_handler.replace_range(range.clone(), text);
+            input_handler.set_composition_range(None);
+            input_handler.set_selection(Selection::caret(range.start + text.len()));
+        });
+        self
+    }
+
+    /// Set the selection, eg to move the caret within the composing region.
+    pub fn set_selection(&mut self, selection: Selection) -> &mut Self {
+        self.harness
+            .edit_focused_text(|input_handler| input_handler.set_selection(selection));
+        self
+    }
+
+    /// The current composing region, if any.
+    pub fn composing_region(&mut self) -> Option<Range<usize>> {
+        let window = &mut self.harness.mock_app.window;
+        let input_handler = window
+            .get_focused_ime_handler(false)
+            .expect("focused widget doesn't accept text input");
+        let range = input_handler.composition_range();
+        drop(input_handler);
+        window.release_focused_ime_handler();
+        range
+    }
+}
+
+fn composing_or_selected_range(input_handler: &mut dyn InputHandler) -> Range<usize> {
+    input_handler
+        .composition_range()
+        .unwrap_or_else(|| input_handler.selection().range())
+}
+
+// The only helpers tested here are the ones for SVG snapshots.
+#[cfg(all(
+    test,
+    any(target_os = "freebsd", target_os = "linux", target_os = "openbsd")
+))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_svg_id_definitions_and_references() {
+        let document = concat!(
+            r##"<g id="surface12"><use xlink:href="#image7"/>"##,
+            r##"<path fill="url(#source3)"/><image id="image7"/>"##,
+            r##"<text>surface12 image7</text><g id="surface12a"/></g>"##,
+        );
+        assert_eq!(
+            normalize_svg_ids(document),
+            concat!(
+                r##"<g id="surface-0"><use xlink:href="#image-1"/>"##,
+                r##"<path fill="url(#source-2)"/><image id="image-1"/>"##,
+                r##"<text>surface12 image7</text><g id="surface12a"/></g>"##,
+            )
+        );
+    }
+}
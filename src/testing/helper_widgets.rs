@@ -25,6 +25,7 @@ pub type EventFn<S> = dyn FnMut(&mut S, &mut EventCtx, &Event, &Env);
 pub type StatusChangeFn<S> = dyn FnMut(&mut S, &mut LifeCycleCtx, &StatusChange, &Env);
 pub type LifeCycleFn<S> = dyn FnMut(&mut S, &mut LifeCycleCtx, &LifeCycle, &Env);
 pub type LayoutFn<S> = dyn FnMut(&mut S, &mut LayoutCtx, &BoxConstraints, &Env) -> Size;
+pub type SizeChangedFn<S> = dyn FnMut(&mut S, &mut LayoutCtx, Size, Size, &Env);
 pub type PaintFn<S> = dyn FnMut(&mut S, &mut PaintCtx, &Env);
 pub type ChildrenFn<S> = dyn Fn(&S) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]>;
 
@@ -39,6 +40,7 @@ pub struct ModularWidget<S> {
     on_status_change: Option<Box<StatusChangeFn<S>>>,
     lifecycle: Option<Box<LifeCycleFn<S>>>,
     layout: Option<Box<LayoutFn<S>>>,
+    on_size_changed: Option<Box<SizeChangedFn<S>>>,
     paint: Option<Box<PaintFn<S>>>,
     children: Option<Box<ChildrenFn<S>>>,
 }
@@ -103,6 +105,10 @@ pub trait TestWidgetExt: Widget + Sized + 'static {
     fn with_id(self, id: WidgetId) -> SizedBox {
         SizedBox::new_with_id(self, id)
     }
+
+    fn with_name(self, name: &'static str) -> SizedBox {
+        SizedBox::new_with_name(self, name)
+    }
 }
 
 impl<W: Widget + 'static> TestWidgetExt for W {}
@@ -115,6 +121,7 @@ impl<S> ModularWidget<S> {
             on_status_change: None,
             lifecycle: None,
             layout: None,
+            on_size_changed: None,
             paint: None,
             children: None,
         }
@@ -152,6 +159,14 @@ impl<S> ModularWidget<S> {
         self
     }
 
+    pub fn size_changed_fn(
+        mut self,
+        f: impl FnMut(&mut S, &mut LayoutCtx, Size, Size, &Env) + 'static,
+    ) -> Self {
+        self.on_size_changed = Some(Box::new(f));
+        self
+    }
+
     pub fn paint_fn(mut self, f: impl FnMut(&mut S, &mut PaintCtx, &Env) + 'static) -> Self {
         self.paint = Some(Box::new(f));
         self
@@ -197,6 +212,12 @@ impl<S: 'static> Widget for ModularWidget<S> {
             .unwrap_or_else(|| Size::new(100., 100.))
     }
 
+    fn on_size_changed(&mut self, ctx: &mut LayoutCtx, old_size: Size, new_size: Size, env: &Env) {
+        if let Some(f) = self.on_size_changed.as_mut() {
+            f(&mut self.state, ctx, old_size, new_size, env)
+        }
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
         if let Some(f) = self.paint.as_mut() {
             f(&mut self.state, ctx, env)
@@ -224,12 +245,17 @@ impl Widget for ReplaceChild {
     fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
         if let Event::Command(cmd) = event {
             if cmd.is(REPLACE_CHILD) {
-                self.child = (self.replacer)();
-                ctx.children_changed();
+                let old_child = std::mem::replace(&mut self.child, (self.replacer)());
+                ctx.remove_child(old_child);
                 return;
             }
         }
-        self.child.on_event(ctx, event, env)
+        self.child.on_event(ctx, event, env);
+        // We report the child's children as our own, but the child doesn't
+        // forward every event to them (eg WindowSize).
+        for grandchild in self.child.widget().children() {
+            grandchild.state().mark_as_visited(true);
+        }
     }
 
     fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {
@@ -306,6 +332,10 @@ impl<W: Widget> Widget for Recorder<W> {
         size
     }
 
+    fn on_size_changed(&mut self, ctx: &mut LayoutCtx, old_size: Size, new_size: Size, env: &Env) {
+        self.child.on_size_changed(ctx, old_size, new_size, env)
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
         self.child.paint(ctx, env);
         self.recording.push(Record::Paint)
@@ -18,11 +18,14 @@ use instant::Duration;
 /// timer tokens and durations, and when the user calls [`TestHarness::move_timers_forward`],
 /// the timers are "manually" mutated and checked, and the matching events fired.
 ///
+/// Interval timers are re-queued by the queue itself, keeping their token, so that
+/// moving forward by several intervals fires them several times.
+///
 /// To avoid polluting the code with `#[cfg(test)]` annotations, MockTimerQueue is also
 /// present in non-test code, but it's always empty.
 pub(crate) struct MockTimerQueue {
     pub current_time: Duration,
-    pub queue: VecDeque<(Duration, TimerToken)>,
+    pub queue: VecDeque<(Duration, TimerToken, Option<Duration>)>,
 }
 
 impl MockTimerQueue {
@@ -35,27 +38,53 @@ impl MockTimerQueue {
 
     #[must_use]
     pub(crate) fn add_timer(&mut self, duration: Duration) -> TimerToken {
-        let deadline = self.current_time + duration;
         let token = TimerToken::next();
-        let idx = self
-            .queue
-            .binary_search_by_key(&deadline, |(d, _t)| *d)
-            .unwrap_or_else(|x| x);
-        self.queue.insert(idx, (deadline, token));
+        self.insert(self.current_time + duration, token, None);
+        token
+    }
 
+    #[must_use]
+    pub(crate) fn add_interval_timer(&mut self, interval: Duration) -> TimerToken {
+        let token = TimerToken::next();
+        self.insert(self.current_time + interval, token, Some(interval));
         token
     }
 
+    pub(crate) fn cancel_timer(&mut self, token: TimerToken) {
+        self.queue.retain(|(_deadline, t, _interval)| *t != token);
+    }
+
+    /// Returns the pending timers, soonest first, with the time left before they fire.
+    pub(crate) fn pending_timers(&self) -> Vec<(TimerToken, Duration)> {
+        self.queue
+            .iter()
+            .map(|(deadline, token, _interval)| (*token, *deadline - self.current_time))
+            .collect()
+    }
+
     #[must_use]
     pub(crate) fn move_forward(&mut self, duration: Duration) -> Vec<TimerToken> {
-        self.current_time += duration;
+        let end_time = self.current_time + duration;
+        let mut tokens = Vec::new();
+        while let Some(&(deadline, token, interval)) = self.queue.front() {
+            if deadline > end_time {
+                break;
+            }
+            self.queue.pop_front();
+            self.current_time = deadline;
+            if let Some(interval) = interval {
+                self.insert(deadline + interval, token, Some(interval));
+            }
+            tokens.push(token);
+        }
+        self.current_time = end_time;
+        tokens
+    }
+
+    fn insert(&mut self, deadline: Duration, token: TimerToken, interval: Option<Duration>) {
         let idx = self
             .queue
-            .partition_point(|(deadline, _token)| *deadline <= self.current_time);
-
-        self.queue
-            .drain(0..idx)
-            .map(|(_deadline, token)| token)
-            .collect()
+            .partition_point(|(d, _t, _interval)| *d <= deadline);
+        self.queue.insert(idx, (deadline, token, interval));
     }
 }
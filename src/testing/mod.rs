@@ -6,6 +6,8 @@
 
 #![cfg(not(tarpaulin_include))]
 
+#[cfg(not(tarpaulin_include))]
+mod catalog;
 #[cfg(not(tarpaulin_include))]
 mod harness;
 #[cfg(not(tarpaulin_include))]
@@ -15,14 +17,22 @@ mod mock_timer_queue;
 #[cfg(not(tarpaulin_include))]
 mod screenshots;
 #[cfg(not(tarpaulin_include))]
+mod snapshot_review;
+#[cfg(not(tarpaulin_include))]
 mod snapshot_utils;
 
+pub use catalog::render_catalog;
 use druid_shell::{Modifiers, MouseButton, MouseButtons};
-pub use harness::{TestHarness, HARNESS_DEFAULT_SIZE};
+pub use harness::{
+    HarnessError, ImeSession, TestHarness, HARNESS_DEFAULT_SEED, HARNESS_DEFAULT_SIZE,
+    HARNESS_DRAG_STEPS, HARNESS_FRAME_DURATION,
+};
 pub use helper_widgets::{
     ModularWidget, Record, Recorder, Recording, ReplaceChild, TestWidgetExt, REPLACE_CHILD,
 };
 pub(crate) use mock_timer_queue::MockTimerQueue;
+pub use screenshots::DiffTolerance;
+pub use snapshot_review::{review_snapshots, PendingSnapshot};
 
 use crate::kurbo::{Point, Vec2};
 use crate::{MouseEvent, WidgetId};
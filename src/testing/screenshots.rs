@@ -27,16 +27,59 @@ pub(crate) fn get_rgba_image(render_target: &mut BitmapTarget, window_size: Size
     .unwrap()
 }
 
-pub(crate) fn get_image_diff(ref_image: &RgbaImage, new_image: &RgbaImage) -> Option<RgbaImage> {
-    let mut is_changed = false;
+/// How different a render can be from its reference before a snapshot test fails.
+///
+/// Anti-aliasing and text rendering can differ slightly between machines, so an
+/// exact comparison can make snapshot tests flaky. Use with
+/// [`assert_render_snapshot`](crate::assert_render_snapshot).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiffTolerance {
+    /// The largest difference allowed in any one channel of a pixel for the pixel
+    /// to still count as equal.
+    pub per_channel: u8,
+    /// The largest fraction of pixels, between 0.0 and 1.0, that can differ (by more
+    /// than `per_channel`) for the images to still count as equal.
+    pub max_differing_fraction: f64,
+}
+
+impl DiffTolerance {
+    /// Every pixel must be exactly equal.
+    pub const EXACT: DiffTolerance = DiffTolerance {
+        per_channel: 0,
+        max_differing_fraction: 0.0,
+    };
+
+    /// Create a tolerance with the given thresholds.
+    pub fn new(per_channel: u8, max_differing_fraction: f64) -> Self {
+        DiffTolerance {
+            per_channel,
+            max_differing_fraction,
+        }
+    }
+}
 
-    if ref_image.width() != new_image.width() || ref_image.height() != new_image.height() {
-        is_changed = true;
+impl Default for DiffTolerance {
+    fn default() -> Self {
+        Self::EXACT
     }
+}
+
+/// Compare two images, and return an image of the differing pixels if the difference
+/// exceeds `tolerance`.
+///
+/// Images with different sizes always differ.
+pub(crate) fn get_image_diff(
+    ref_image: &RgbaImage,
+    new_image: &RgbaImage,
+    tolerance: DiffTolerance,
+) -> Option<RgbaImage> {
+    let size_changed =
+        ref_image.width() != new_image.width() || ref_image.height() != new_image.height();
 
     let width = std::cmp::max(ref_image.width(), new_image.width());
     let height = std::cmp::max(ref_image.height(), new_image.height());
 
+    let mut differing_pixels = 0_u64;
     let diff_image = RgbaImage::from_fn(width, height, |x, y| {
         let ref_pixel = if ref_image.in_bounds(x, y) {
             *ref_image.get_pixel(x, y)
@@ -49,17 +92,65 @@ pub(crate) fn get_image_diff(ref_image: &RgbaImage, new_image: &RgbaImage) -> Op
             [255, 255, 255, 255].into()
         };
 
-        if new_pixel != ref_pixel {
-            is_changed = true;
+        let pixel_differs = ref_pixel
+            .0
+            .iter()
+            .zip(new_pixel.0.iter())
+            .any(|(a, b)| a.abs_diff(*b) > tolerance.per_channel);
+        if pixel_differs {
+            differing_pixels += 1;
             new_pixel
         } else {
             [0, 0, 0, 0].into()
         }
     });
 
-    if is_changed {
+    let pixel_count = u64::from(width) * u64::from(height);
+    let differing_fraction = if pixel_count == 0 {
+        0.0
+    } else {
+        differing_pixels as f64 / pixel_count as f64
+    };
+
+    if size_changed || differing_fraction > tolerance.max_differing_fraction {
         Some(diff_image)
     } else {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image_with_pixels(pixels: &[[u8; 4]]) -> RgbaImage {
+        RgbaImage::from_raw(pixels.len() as u32, 1, pixels.concat()).unwrap()
+    }
+
+    #[test]
+    fn tolerance_thresholds() {
+        let reference = image_with_pixels(&[[10, 10, 10, 255]; 4]);
+        let slightly_off = image_with_pixels(&[
+            [12, 10, 10, 255],
+            [10, 10, 10, 255],
+            [10, 10, 10, 255],
+            [10, 10, 10, 255],
+        ]);
+        let one_pixel_off = image_with_pixels(&[
+            [200, 10, 10, 255],
+            [10, 10, 10, 255],
+            [10, 10, 10, 255],
+            [10, 10, 10, 255],
+        ]);
+
+        assert!(get_image_diff(&reference, &reference, DiffTolerance::EXACT).is_none());
+        assert!(get_image_diff(&reference, &slightly_off, DiffTolerance::EXACT).is_some());
+        assert!(get_image_diff(&reference, &slightly_off, DiffTolerance::new(2, 0.0)).is_none());
+
+        assert!(get_image_diff(&reference, &one_pixel_off, DiffTolerance::new(2, 0.2)).is_some());
+        assert!(get_image_diff(&reference, &one_pixel_off, DiffTolerance::new(2, 0.25)).is_none());
+
+        let smaller = image_with_pixels(&[[10, 10, 10, 255]; 3]);
+        assert!(get_image_diff(&reference, &smaller, DiffTolerance::new(255, 1.0)).is_some());
+    }
+}
@@ -0,0 +1,188 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! Helpers for reviewing the render snapshots left behind by failed tests.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::testing::harness::{reference_paths, with_path_suffix};
+
+// The extensions of PNG and SVG snapshots.
+const EXTENSIONS: [&str; 2] = ["png", "svg"];
+
+/// A render snapshot waiting to be accepted or rejected.
+///
+/// When [`assert_render_snapshot`](crate::assert_render_snapshot) fails, it writes the
+/// new render as `<name>.new.png`, and if there was a reference, the differences as
+/// `<name>.diff.png`. [`assert_svg_snapshot`](crate::assert_svg_snapshot) writes
+/// `<name>.new.svg`, without a diff. Accepting the snapshot replaces the reference with
+/// the new render.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingSnapshot {
+    /// The reference the new render will replace if accepted.
+    ///
+    /// This is the reference the snapshot check compared against first, eg the
+    /// platform-specific reference if one exists.
+    pub reference_path: PathBuf,
+    /// The new render.
+    pub new_path: PathBuf,
+    /// The image of the differences between the reference and the new render.
+    ///
+    /// `None` if there was no reference to compare against.
+    pub diff_path: Option<PathBuf>,
+}
+
+impl PendingSnapshot {
+    /// Return `true` if a reference already exists for this snapshot.
+    pub fn has_reference(&self) -> bool {
+        self.reference_path.exists()
+    }
+
+    /// Promote the new render to reference, and remove the diff image.
+    pub fn accept(&self) -> io::Result<()> {
+        std::fs::rename(&self.new_path, &self.reference_path)?;
+        self.remove_diff()
+    }
+
+    /// Remove the new render and the diff image, keeping the current reference.
+    pub fn reject(&self) -> io::Result<()> {
+        std::fs::remove_file(&self.new_path)?;
+        self.remove_diff()
+    }
+
+    fn remove_diff(&self) -> io::Result<()> {
+        match &self.diff_path {
+            Some(diff_path) => match std::fs::remove_file(diff_path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+                _ => Ok(()),
+            },
+            None => Ok(()),
+        }
+    }
+}
+
+/// Find all pending render snapshots in `dir` and its subfolders.
+///
+/// This is meant for tools presenting failed snapshots to the user, eg a review CLI.
+/// Snapshots are returned sorted by path.
+pub fn review_snapshots(dir: impl AsRef<Path>) -> io::Result<Vec<PendingSnapshot>> {
+    let mut snapshots = Vec::new();
+    find_pending_snapshots(dir.as_ref(), &mut snapshots)?;
+    snapshots.sort_by(|a, b| a.new_path.cmp(&b.new_path));
+    Ok(snapshots)
+}
+
+fn find_pending_snapshots(dir: &Path, snapshots: &mut Vec<PendingSnapshot>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_pending_snapshots(&path, snapshots)?;
+            continue;
+        }
+
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some((stem, extension)) = EXTENSIONS.iter().find_map(|extension| {
+            let stem = file_name.strip_suffix(&format!(".new.{extension}"))?;
+            Some((stem, extension))
+        }) else {
+            continue;
+        };
+
+        // Same lookup as `TestHarness::check_render_snapshot`.
+        let path_stem = path.with_file_name(stem);
+        let reference_path = reference_paths(&path_stem, extension)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| with_path_suffix(&path_stem, &format!(".{extension}")));
+        let diff_path = with_path_suffix(&path_stem, &format!(".diff.{extension}"));
+
+        snapshots.push(PendingSnapshot {
+            reference_path,
+            diff_path: diff_path.exists().then_some(diff_path),
+            new_path: path,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_dir_for_test;
+
+    #[test]
+    fn review_and_accept() {
+        let dir = temp_dir_for_test();
+        let subdir = dir.join("screenshots");
+        std::fs::create_dir_all(&subdir).unwrap();
+
+        std::fs::write(subdir.join("changed.png"), "old").unwrap();
+        std::fs::write(subdir.join("changed.new.png"), "new").unwrap();
+        std::fs::write(subdir.join("changed.diff.png"), "diff").unwrap();
+        std::fs::write(subdir.join("added.new.png"), "added").unwrap();
+        std::fs::write(subdir.join("unchanged.png"), "same").unwrap();
+        std::fs::write(subdir.join("numbered.0.png"), "old").unwrap();
+        std::fs::write(subdir.join("numbered.1.png"), "older").unwrap();
+        std::fs::write(subdir.join("numbered.new.png"), "new").unwrap();
+        std::fs::write(subdir.join("vector.svg"), "<svg/>").unwrap();
+        std::fs::write(subdir.join("vector.new.svg"), "<svg></svg>").unwrap();
+
+        let snapshots = review_snapshots(&dir).unwrap();
+        assert_eq!(
+            snapshots,
+            [
+                PendingSnapshot {
+                    reference_path: subdir.join("added.png"),
+                    new_path: subdir.join("added.new.png"),
+                    diff_path: None,
+                },
+                PendingSnapshot {
+                    reference_path: subdir.join("changed.png"),
+                    new_path: subdir.join("changed.new.png"),
+                    diff_path: Some(subdir.join("changed.diff.png")),
+                },
+                PendingSnapshot {
+                    reference_path: subdir.join("numbered.0.png"),
+                    new_path: subdir.join("numbered.new.png"),
+                    diff_path: None,
+                },
+                PendingSnapshot {
+                    reference_path: subdir.join("vector.svg"),
+                    new_path: subdir.join("vector.new.svg"),
+                    diff_path: None,
+                },
+            ]
+        );
+        assert!(!snapshots[0].has_reference());
+        assert!(snapshots[1].has_reference());
+
+        snapshots[1].accept().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(subdir.join("changed.png")).unwrap(),
+            "new"
+        );
+        assert!(!subdir.join("changed.diff.png").exists());
+
+        snapshots[0].reject().unwrap();
+        assert!(!subdir.join("added.new.png").exists());
+        assert!(!subdir.join("added.png").exists());
+
+        snapshots[2].accept().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(subdir.join("numbered.0.png")).unwrap(),
+            "new"
+        );
+
+        snapshots[3].accept().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(subdir.join("vector.svg")).unwrap(),
+            "<svg></svg>"
+        );
+
+        assert_eq!(review_snapshots(&dir).unwrap(), []);
+    }
+}
@@ -12,7 +12,7 @@
 
 use std::collections::BTreeMap;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use once_cell::sync::Lazy;
@@ -49,3 +49,65 @@ pub(crate) fn get_cargo_workspace(manifest_dir: &str) -> Arc<PathBuf> {
         path
     }
 }
+
+/// Environment variable overriding the folder render snapshots are stored in.
+pub(crate) const SCREENSHOT_DIR_VAR: &str = "MASONRY_SCREENSHOT_DIR";
+
+/// Environment variable overriding the file name template of render snapshots.
+pub(crate) const SCREENSHOT_NAME_VAR: &str = "MASONRY_SCREENSHOT_NAME";
+
+/// Default folder render snapshots are stored in, relative to the test file.
+pub(crate) const DEFAULT_SCREENSHOT_DIR: &str = "screenshots";
+
+/// Default file name template of render snapshots.
+pub(crate) const DEFAULT_SCREENSHOT_NAME: &str = "{module}__{test}";
+
+/// Return the path of a snapshot, without the extension.
+///
+/// `dir` is resolved relative to `test_folder`, and `{module}`, `{test}` and `{os}`
+/// in `name_template` are replaced by the module path (with `::` replaced by `__`),
+/// the test name and the target OS.
+pub(crate) fn screenshot_path_stem(
+    test_folder: &Path,
+    dir: &Path,
+    name_template: &str,
+    test_module_path: &str,
+    test_name: &str,
+) -> PathBuf {
+    let name = name_template
+        .replace("{module}", &test_module_path.replace("::", "__"))
+        .replace("{test}", test_name)
+        .replace("{os}", env::consts::OS);
+    test_folder.join(dir).join(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screenshot_paths() {
+        let test_folder = Path::new("/repo/src/widget");
+
+        assert_eq!(
+            screenshot_path_stem(
+                test_folder,
+                Path::new(DEFAULT_SCREENSHOT_DIR),
+                DEFAULT_SCREENSHOT_NAME,
+                "masonry::widget::label::tests",
+                "hello",
+            ),
+            Path::new("/repo/src/widget/screenshots/masonry__widget__label__tests__hello")
+        );
+        assert_eq!(
+            screenshot_path_stem(
+                test_folder,
+                Path::new("/snapshots"),
+                "{os}/{test}",
+                "masonry::widget::label::tests",
+                "hello",
+            ),
+            Path::new("/snapshots").join(env::consts::OS).join("hello")
+        );
+    }
+}
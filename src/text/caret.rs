@@ -0,0 +1,86 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! The blinking text caret.
+
+use std::time::Duration;
+
+use crate::shell::TimerToken;
+use crate::{theme, Env, EventCtx, LifeCycleCtx};
+
+/// The blink state of a text caret, for text widgets to share.
+///
+/// While its widget has focus, the caret blinks on an interval timer, at the rate set by
+/// [`theme::CURSOR_BLINK_INTERVAL`]. Calling [`reset`](Self::reset), eg when the user
+/// types, makes the caret solid; it starts blinking again once a full interval has passed
+/// without another reset. An interval of zero disables blinking.
+///
+/// The widget must forward its timer events to [`on_timer`](Self::on_timer) and its
+/// focus changes to [`focus_changed`](Self::focus_changed).
+#[derive(Debug)]
+pub struct CaretBlink {
+    is_visible: bool,
+    timer: TimerToken,
+}
+
+impl CaretBlink {
+    /// Create the blink state of an unfocused widget, where the caret is hidden.
+    pub fn new() -> Self {
+        CaretBlink {
+            is_visible: false,
+            timer: TimerToken::INVALID,
+        }
+    }
+
+    /// Whether the caret should currently be painted.
+    pub fn is_visible(&self) -> bool {
+        self.is_visible
+    }
+
+    /// Make the caret solid, and restart the blink from the beginning.
+    pub fn reset(&mut self, ctx: &mut EventCtx, env: &Env) {
+        ctx.cancel_timer(self.timer);
+        self.timer = match blink_interval(env) {
+            Some(interval) => ctx.request_interval_timer(interval),
+            None => TimerToken::INVALID,
+        };
+        self.is_visible = true;
+        ctx.request_paint();
+    }
+
+    /// Show and start blinking the caret when the widget gains focus, hide it when the
+    /// widget loses focus.
+    pub fn focus_changed(&mut self, ctx: &mut LifeCycleCtx, has_focus: bool, env: &Env) {
+        ctx.cancel_timer(self.timer);
+        self.timer = match blink_interval(env) {
+            Some(interval) if has_focus => ctx.request_interval_timer(interval),
+            _ => TimerToken::INVALID,
+        };
+        self.is_visible = has_focus;
+        ctx.request_paint();
+    }
+
+    /// Toggle the caret if `token` belongs to the blink timer.
+    ///
+    /// Returns `false` if the timer belongs to something else.
+    pub fn on_timer(&mut self, ctx: &mut EventCtx, token: TimerToken) -> bool {
+        if token == TimerToken::INVALID || token != self.timer {
+            return false;
+        }
+        self.is_visible = !self.is_visible;
+        ctx.request_paint();
+        true
+    }
+}
+
+impl Default for CaretBlink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn blink_interval(env: &Env) -> Option<Duration> {
+    let millis = env.get(theme::CURSOR_BLINK_INTERVAL);
+    (millis > 0).then(|| Duration::from_millis(millis))
+}
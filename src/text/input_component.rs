@@ -20,8 +20,8 @@ use crate::kurbo::{Line, Point, Rect, Vec2};
 use crate::piet::TextLayout as _;
 use crate::widget::WidgetRef;
 use crate::{
-    text, theme, BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
-    PaintCtx, RenderContext, Selector, Size, StatusChange, Widget,
+    text, theme, BoxConstraints, Clipboard, Env, Event, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, RenderContext, Selector, Size, StatusChange, Widget,
 };
 
 /// A widget that accepts text input.
@@ -463,7 +463,6 @@ impl<T: TextStorage + EditableText> Widget for TextComponent<T> {
 
         let selection = self.borrow().selection();
         let composition = self.borrow().composition_range();
-        let sel_rects = self.borrow().layout.rects_for_range(selection.range());
         if let Some(composition) = composition {
             // I believe selection should always be contained in composition range while composing?
             assert!(composition.start <= selection.anchor && composition.end >= selection.active);
@@ -473,12 +472,14 @@ impl<T: TextStorage + EditableText> Widget for TextComponent<T> {
                 let line = Line::new((region.min_x(), y), (region.max_x(), y)) + text_offset;
                 ctx.stroke(line, &cursor_color, 1.0);
             }
+            let sel_rects = self.borrow().layout.rects_for_range(selection.range());
             for region in sel_rects {
                 let y = region.max_y().floor();
                 let line = Line::new((region.min_x(), y), (region.max_x(), y)) + text_offset;
                 ctx.stroke(line, &cursor_color, 2.0);
             }
         } else {
+            let sel_rects = self.borrow().layout.selection_rects(selection);
             for region in sel_rects {
                 let rounded = (region + text_offset).to_rounded_rect(1.0);
                 ctx.fill(rounded, &selection_color);
@@ -542,6 +543,11 @@ impl<T> EditSession<T> {
         self.alignment
     }
 
+    /// The horizontal offset of the text in the component, from its alignment.
+    pub(crate) fn alignment_offset(&self) -> f64 {
+        self.alignment_offset
+    }
+
     /// Returns any invalidation action that should be passed to the platform.
     ///
     /// The user of this component *must* check this after calling `update`.
@@ -609,16 +615,14 @@ impl<T: TextStorage + EditableText> EditSession<T> {
     ///
     /// Returns `true` if the clipboard was set, and `false` if not (indicating)
     /// that the selection was empty.)
-    pub fn set_clipboard(&self) -> bool {
+    pub fn set_clipboard(&self, clipboard: &mut Clipboard) -> bool {
         if let Some(text) = self
             .layout
             .text()
             .and_then(|txt| txt.slice(self.selection.range()))
         {
             if !text.is_empty() {
-                druid_shell::Application::global()
-                    .clipboard()
-                    .put_string(text);
+                clipboard.put_string(text);
                 return true;
             }
         }
@@ -815,6 +819,10 @@ impl<T: TextStorage + EditableText> EditSession<T> {
             Some(layout) => layout,
             None => return pos..pos,
         };
+        // Selecting a single word would give away where the words of obscured text are.
+        if self.layout.is_obscured() {
+            return 0..self.layout.text_len();
+        }
 
         let line_n = layout.hit_test_text_position(pos).line;
         let lm = layout.line_metric(line_n).unwrap();
@@ -870,19 +878,13 @@ impl<T: TextStorage + EditableText> InputHandler for EditSessionHandle<T> {
     }
 
     fn hit_test_point(&self, point: Point) -> crate::piet::HitTestPoint {
-        self.inner
-            .borrow()
-            .layout
-            .layout()
-            .map(|layout| layout.hit_test_point(point))
-            .unwrap_or_default()
+        self.inner.borrow().layout.hit_test_point(point)
     }
 
     fn line_range(&self, index: usize, _affinity: druid_shell::text::Affinity) -> Range<usize> {
         let inner = self.inner.borrow();
-        let layout = inner.layout.layout().unwrap();
-        let hit = layout.hit_test_text_position(index);
-        let metric = layout.line_metric(hit.line).unwrap();
+        let hit = inner.layout.hit_test_text_position(index);
+        let metric = inner.layout.line_metric(hit.line).unwrap();
         metric.range()
     }
 
@@ -895,10 +897,9 @@ impl<T: TextStorage + EditableText> InputHandler for EditSessionHandle<T> {
         let origin = self.inner.borrow().origin;
         let layout = &self.inner.borrow().layout;
         if range.is_empty() {
-            let hit = layout
-                .layout()
-                .map(|l| l.hit_test_text_position(range.start))?;
-            let line = layout.layout().and_then(|l| l.line_metric(hit.line))?;
+            layout.layout()?;
+            let hit = layout.hit_test_text_position(range.start);
+            let line = layout.line_metric(hit.line)?;
             let x = hit.point.x;
             Some(Rect::new(x, line.y_offset, x, line.y_offset + line.height))
         } else {
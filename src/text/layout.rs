@@ -7,14 +7,37 @@
 use std::ops::Range;
 use std::rc::Rc;
 
-use super::{FontDescriptor, Link, TextStorage};
-use crate::kurbo::{Line, Point, Rect, Size};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{FontDescriptor, Link, Selection, TextStorage};
+use crate::kurbo::{Line, Point, Rect, Size, Vec2};
 use crate::piet::{
-    Color, PietText, PietTextLayout, Text as _, TextAlignment, TextAttribute, TextLayout as _,
-    TextLayoutBuilder as _,
+    Color, HitTestPoint, HitTestPosition, LineMetric, PietText, PietTextLayout, Text as _,
+    TextAlignment, TextAttribute, TextLayout as _, TextLayoutBuilder as _,
 };
 use crate::{Env, KeyOrValue, PaintCtx, RenderContext};
 
+/// The character displayed in place of each grapheme of obscured text.
+const OBSCURING_CHAR: char = '\u{2022}';
+
+/// A snapshot of the computed layout of a widget's text.
+///
+/// This is returned by [`TestHarness::text_layout`](crate::testing::TestHarness::text_layout),
+/// so that tests can check line wrapping and caret placement without comparing pixels.
+/// All positions are in the widget's coordinate space.
+#[derive(Clone)]
+pub struct TextLayoutInfo {
+    line_rects: Vec<Rect>,
+    /// Carets are hit-tested on demand, since tests usually only check a few of them.
+    layout: Rc<dyn CaretLookup>,
+    offset: Vec2,
+}
+
+/// Type-erased access to the carets of a [`TextLayout`].
+trait CaretLookup {
+    fn caret_line(&self, text_pos: usize) -> Option<Line>;
+}
+
 /// A component for displaying text on screen.
 ///
 /// This is a type intended to be used by other widgets that display text.
@@ -49,6 +72,7 @@ pub struct TextLayout<T> {
     alignment: TextAlignment,
     links: Rc<[(Rect, usize)]>,
     text_is_rtl: bool,
+    obscured: bool,
 }
 
 /// Metrics describing the layout text.
@@ -80,6 +104,7 @@ impl<T> TextLayout<T> {
             alignment: Default::default(),
             links: Rc::new([]),
             text_is_rtl: false,
+            obscured: false,
         }
     }
 
@@ -146,6 +171,26 @@ impl<T> TextLayout<T> {
         }
     }
 
+    /// Set whether the text is obscured.
+    ///
+    /// Obscured text is displayed as one bullet (`•`) per grapheme, eg
+    /// for password fields. All the methods of this type still take and return
+    /// offsets into the underlying text; only [`layout`](Self::layout) returns
+    /// a layout of the obscured text.
+    pub fn set_obscured(&mut self, obscured: bool) {
+        if self.obscured != obscured {
+            self.obscured = obscured;
+            self.layout = None;
+        }
+    }
+
+    /// Returns `true` if the text is obscured.
+    ///
+    /// See [`set_obscured`](Self::set_obscured).
+    pub fn is_obscured(&self) -> bool {
+        self.obscured
+    }
+
     /// Returns `true` if this layout's text appears to be right-to-left.
     ///
     /// See [`piet::util::first_strong_rtl`] for more information.
@@ -206,6 +251,95 @@ impl<T: TextStorage> TextLayout<T> {
         self.layout.as_ref()
     }
 
+    /// Given an offset into the underlying text, return the matching offset
+    /// into the text of the [`layout`](Self::layout).
+    ///
+    /// These are different if the text is obscured.
+    fn layout_offset(&self, text_pos: usize) -> usize {
+        match &self.text {
+            Some(text) if self.obscured => {
+                let graphemes = text
+                    .as_str()
+                    .grapheme_indices(true)
+                    .take_while(|(ix, _)| *ix < text_pos)
+                    .count();
+                graphemes * OBSCURING_CHAR.len_utf8()
+            }
+            _ => text_pos,
+        }
+    }
+
+    /// The inverse of [`layout_offset`](Self::layout_offset).
+    fn text_offset(&self, layout_pos: usize) -> usize {
+        match &self.text {
+            Some(text) if self.obscured => {
+                let text = text.as_str();
+                text.grapheme_indices(true)
+                    .nth(layout_pos / OBSCURING_CHAR.len_utf8())
+                    .map(|(ix, _)| ix)
+                    .unwrap_or(text.len())
+            }
+            _ => layout_pos,
+        }
+    }
+
+    /// Hit-test a point (relative to this object's origin) against the laid-out text.
+    ///
+    /// This is not meaningful until [`rebuild_if_needed`] has been called.
+    ///
+    /// [`rebuild_if_needed`]: #method.rebuild_if_needed
+    pub fn hit_test_point(&self, point: Point) -> HitTestPoint {
+        self.layout
+            .as_ref()
+            .map(|layout| {
+                let hit = layout.hit_test_point(point);
+                HitTestPoint::new(self.text_offset(hit.idx), hit.is_inside)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Return the position and line of the given utf-8 position in the underlying text.
+    ///
+    /// This is not meaningful until [`rebuild_if_needed`] has been called.
+    ///
+    /// [`rebuild_if_needed`]: #method.rebuild_if_needed
+    pub fn hit_test_text_position(&self, text_pos: usize) -> HitTestPosition {
+        self.layout
+            .as_ref()
+            .map(|layout| layout.hit_test_text_position(self.layout_offset(text_pos)))
+            .unwrap_or_default()
+    }
+
+    /// Return the [`LineMetric`] for the given line, with offsets into the underlying text.
+    ///
+    /// This is not meaningful until [`rebuild_if_needed`] has been called.
+    ///
+    /// [`rebuild_if_needed`]: #method.rebuild_if_needed
+    pub fn line_metric(&self, line_number: usize) -> Option<LineMetric> {
+        let mut metric = self.layout.as_ref()?.line_metric(line_number)?;
+        if self.obscured {
+            let start_offset = self.text_offset(metric.start_offset);
+            let end_offset = self.text_offset(metric.end_offset);
+            let whitespace_start = self.text_offset(metric.end_offset - metric.trailing_whitespace);
+            metric.trailing_whitespace = end_offset - whitespace_start;
+            metric.start_offset = start_offset;
+            metric.end_offset = end_offset;
+        }
+        Some(metric)
+    }
+
+    /// The number of lines in the laid-out text.
+    ///
+    /// This is not meaningful until [`rebuild_if_needed`] has been called.
+    ///
+    /// [`rebuild_if_needed`]: #method.rebuild_if_needed
+    pub fn line_count(&self) -> usize {
+        self.layout
+            .as_ref()
+            .map(|layout| layout.line_count())
+            .unwrap_or_default()
+    }
+
     /// The size of the laid-out text.
     ///
     /// This is not meaningful until [`rebuild_if_needed`] has been called.
@@ -247,10 +381,7 @@ impl<T: TextStorage> TextLayout<T> {
     /// For a given `Point` (relative to this object's origin), returns index
     /// into the underlying text of the nearest grapheme boundary.
     pub fn text_position_for_point(&self, point: Point) -> usize {
-        self.layout
-            .as_ref()
-            .map(|layout| layout.hit_test_point(point).idx)
-            .unwrap_or_default()
+        self.hit_test_point(point).idx
     }
 
     /// Given the utf-8 position of a character boundary in the underlying text,
@@ -261,10 +392,7 @@ impl<T: TextStorage> TextLayout<T> {
     ///
     /// Panics if `text_pos` is not a character boundary.
     pub fn point_for_text_position(&self, text_pos: usize) -> Point {
-        self.layout
-            .as_ref()
-            .map(|layout| layout.hit_test_text_position(text_pos).point)
-            .unwrap_or_default()
+        self.hit_test_text_position(text_pos).point
     }
 
     /// Given a utf-8 range in the underlying text, return a `Vec` of `Rect`s
@@ -276,10 +404,62 @@ impl<T: TextStorage> TextLayout<T> {
     pub fn rects_for_range(&self, range: Range<usize>) -> Vec<Rect> {
         self.layout
             .as_ref()
-            .map(|layout| layout.rects_for_range(range))
+            .map(|layout| {
+                layout
+                    .rects_for_range(self.layout_offset(range.start)..self.layout_offset(range.end))
+            })
             .unwrap_or_default()
     }
 
+    /// Return the rects to fill behind the text to highlight `selection`.
+    ///
+    /// There is one rect per visual run of selected text: usually one per line, but a
+    /// line that mixes left-to-right and right-to-left text can need several. Touching
+    /// rects on the same line are merged, and every rect spans the full height of its
+    /// line, so that the highlights of consecutive lines don't leave gaps between them.
+    ///
+    /// Returns an empty `Vec` if the selection is a caret.
+    pub fn selection_rects(&self, selection: Selection) -> Vec<Rect> {
+        if selection.is_caret() {
+            return Vec::new();
+        }
+
+        let mut rects: Vec<Rect> = self
+            .rects_for_range(selection.range())
+            .into_iter()
+            .map(|rect| self.extend_to_line_height(rect))
+            .collect();
+        rects.sort_by(|a, b| a.y0.total_cmp(&b.y0).then(a.x0.total_cmp(&b.x0)));
+
+        let mut merged: Vec<Rect> = Vec::with_capacity(rects.len());
+        for rect in rects {
+            match merged.last_mut() {
+                // Runs of a bidi line can be separated by rounding errors.
+                Some(last) if last.y0 == rect.y0 && rect.x0 <= last.x1 + 0.5 => {
+                    *last = last.union(rect);
+                }
+                _ => merged.push(rect),
+            }
+        }
+        merged
+    }
+
+    fn extend_to_line_height(&self, rect: Rect) -> Rect {
+        let y = rect.center().y;
+        (0..self.line_count())
+            .filter_map(|line| self.line_metric(line))
+            .find(|metric| metric.y_offset <= y && y < metric.y_offset + metric.height)
+            .map(|metric| {
+                Rect::new(
+                    rect.x0,
+                    metric.y_offset,
+                    rect.x1,
+                    metric.y_offset + metric.height,
+                )
+            })
+            .unwrap_or(rect)
+    }
+
     /// Return a line suitable for underlining a range of text.
     ///
     /// This is really only intended to be used to indicate the composition
@@ -290,8 +470,8 @@ impl<T: TextStorage> TextLayout<T> {
         self.layout
             .as_ref()
             .map(|layout| {
-                let p1 = layout.hit_test_text_position(range.start);
-                let p2 = layout.hit_test_text_position(range.end);
+                let p1 = layout.hit_test_text_position(self.layout_offset(range.start));
+                let p2 = layout.hit_test_text_position(self.layout_offset(range.end));
                 let line_metric = layout.line_metric(p1.line).unwrap();
                 // heuristic; 1/5 of height is a rough guess at the descender pos?
                 let y_pos = line_metric.baseline + (line_metric.height / 5.0);
@@ -306,7 +486,7 @@ impl<T: TextStorage> TextLayout<T> {
         self.layout
             .as_ref()
             .map(|layout| {
-                let pos = layout.hit_test_text_position(text_pos);
+                let pos = layout.hit_test_text_position(self.layout_offset(text_pos));
                 let line_metrics = layout.line_metric(pos.line).unwrap();
                 let p1 = (pos.point.x, line_metrics.y_offset);
                 let p2 = (pos.point.x, (line_metrics.y_offset + line_metrics.height));
@@ -356,20 +536,36 @@ impl<T: TextStorage> TextLayout<T> {
                     font
                 };
 
-                let builder = factory
-                    .new_text_layout(text.clone())
+                let builder = if self.obscured {
+                    let obscured_text: String = text
+                        .as_str()
+                        .graphemes(true)
+                        .map(|_| OBSCURING_CHAR)
+                        .collect();
+                    factory.new_text_layout(obscured_text)
+                } else {
+                    factory.new_text_layout(text.clone())
+                };
+                let builder = builder
                     .max_width(self.wrap_width)
                     .alignment(self.alignment)
                     .font(descriptor.family.clone(), descriptor.size)
                     .default_attribute(descriptor.weight)
                     .default_attribute(descriptor.style)
                     .default_attribute(TextAttribute::TextColor(color));
-                let layout = text.add_attributes(builder, env).build().unwrap();
+                // Attributes and links are defined in terms of the underlying text, and
+                // would give away its content anyway.
+                let layout = if self.obscured {
+                    builder.build().unwrap()
+                } else {
+                    text.add_attributes(builder, env).build().unwrap()
+                };
 
                 self.links = text
                     .links()
                     .iter()
                     .enumerate()
+                    .filter(|_| !self.obscured)
                     .flat_map(|(i, link)| {
                         layout
                             .rects_for_range(link.range())
@@ -429,3 +625,109 @@ impl<T: TextStorage> Default for TextLayout<T> {
         Self::new()
     }
 }
+
+impl<T: TextStorage> CaretLookup for TextLayout<T> {
+    fn caret_line(&self, text_pos: usize) -> Option<Line> {
+        let text = self.text().map(|text| text.as_str()).unwrap_or_default();
+        if !text.is_char_boundary(text_pos) {
+            return None;
+        }
+        Some(self.cursor_line_for_text_position(text_pos))
+    }
+}
+
+impl TextLayoutInfo {
+    /// Take a snapshot of `layout`, which is drawn at `origin` in the widget.
+    pub fn new<T: TextStorage>(layout: &TextLayout<T>, origin: Point) -> Self {
+        let offset = origin.to_vec2();
+        let line_rects = (0..layout.line_count())
+            .filter_map(|line| layout.line_metric(line))
+            .map(|metric| {
+                let end = metric.end_offset - metric.trailing_whitespace;
+                let x0 = layout.point_for_text_position(metric.start_offset).x;
+                let x1 = layout.point_for_text_position(end).x;
+                Rect::new(
+                    x0.min(x1),
+                    metric.y_offset,
+                    x0.max(x1),
+                    metric.y_offset + metric.height,
+                ) + offset
+            })
+            .collect();
+
+        TextLayoutInfo {
+            line_rects,
+            layout: Rc::new(layout.clone()),
+            offset,
+        }
+    }
+
+    /// The number of lines of text.
+    pub fn line_count(&self) -> usize {
+        self.line_rects.len()
+    }
+
+    /// The bounding rect of each line, without trailing whitespace.
+    pub fn line_rects(&self) -> &[Rect] {
+        &self.line_rects
+    }
+
+    /// The line where the caret is drawn at the given byte offset of the text.
+    ///
+    /// Returns `None` if the offset isn't a character boundary.
+    pub fn caret_position(&self, offset: usize) -> Option<Line> {
+        self.layout
+            .caret_line(offset)
+            .map(|line| line + self.offset)
+    }
+}
+
+impl std::fmt::Debug for TextLayoutInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextLayoutInfo")
+            .field("line_rects", &self.line_rects)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piet::Device;
+    use crate::text::ArcStr;
+
+    fn text_factory() -> PietText {
+        let mut device = Device::new().unwrap();
+        let mut target = device.bitmap_target(1, 1, 1.0).unwrap();
+        let mut piet = target.render_context();
+        let text = piet.text().clone();
+        piet.finish().unwrap();
+        text
+    }
+
+    fn laid_out(text: &str) -> TextLayout<ArcStr> {
+        let mut layout = TextLayout::from_text(ArcStr::from(text));
+        layout.rebuild_if_needed(&mut text_factory(), &Env::with_theme());
+        layout
+    }
+
+    #[test]
+    fn multi_line_selection_rects() {
+        let layout = laid_out("first line\nsecond line\nthird line");
+        assert_eq!(layout.line_count(), 3);
+
+        // From the middle of the first line to the middle of the third one.
+        let rects = layout.selection_rects(Selection::new(6, 27));
+        assert_eq!(rects.len(), 3);
+
+        // Consecutive highlights touch.
+        assert_eq!(rects[0].y1, rects[1].y0);
+        assert_eq!(rects[1].y1, rects[2].y0);
+    }
+
+    #[test]
+    fn caret_has_no_selection_rects() {
+        let layout = laid_out("first line\nsecond line");
+        assert!(layout.selection_rects(Selection::caret(3)).is_empty());
+    }
+}
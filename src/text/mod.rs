@@ -6,6 +6,7 @@
 
 mod attribute;
 mod backspace;
+mod caret;
 mod editable_text;
 mod font_descriptor;
 
@@ -16,6 +17,7 @@ mod movement;
 mod rich_text;
 mod storage;
 
+pub use caret::CaretBlink;
 pub use druid_shell::text::{
     Action as TextAction, Affinity, Direction, Event as ImeInvalidation, InputHandler, Movement,
     Selection, VerticalMovement, WritingDirection,
@@ -30,6 +32,6 @@ pub use self::attribute::{Attribute, AttributeSpans, Link};
 pub use self::backspace::offset_for_delete_backwards;
 pub use self::editable_text::{EditableText, EditableTextCursor, StringCursor};
 pub use self::font_descriptor::FontDescriptor;
-pub use self::layout::{LayoutMetrics, TextLayout};
-pub use self::movement::movement;
+pub use self::layout::{LayoutMetrics, TextLayout, TextLayoutInfo};
+pub use self::movement::{movement, next_word_boundary, prev_word_boundary};
 pub use crate::piet::{FontFamily, FontStyle, FontWeight, TextAlignment};
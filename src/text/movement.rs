@@ -6,12 +6,13 @@
 
 use std::ops::Range;
 
+use unicode_bidi::BidiInfo;
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::kurbo::Point;
-use crate::piet::TextLayout as _;
 use crate::text::{
-    EditableText, Movement, Selection, TextLayout, TextStorage, VerticalMovement, WritingDirection,
+    Direction, EditableText, Movement, Selection, TextLayout, TextStorage, VerticalMovement,
+    WritingDirection,
 };
 
 /// Compute the result of a [`Movement`] on a [`Selection`].
@@ -27,8 +28,8 @@ pub fn movement<T: EditableText + TextStorage>(
     layout: &TextLayout<T>,
     modify: bool,
 ) -> Selection {
-    let (text, layout) = match (layout.text(), layout.layout()) {
-        (Some(text), Some(layout)) => (text, layout),
+    let text = match (layout.text(), layout.layout()) {
+        (Some(text), Some(_)) => text,
         _ => {
             debug_assert!(false, "movement() called before layout rebuild");
             return s;
@@ -41,23 +42,62 @@ pub fn movement<T: EditableText + TextStorage>(
         WritingDirection::LeftToRight
     };
 
+    // Moving left or right in bidirectional text follows the visual order of the text.
+    let bidi_info = match m {
+        Movement::Grapheme(Direction::Left | Direction::Right) if !layout.is_obscured() => {
+            Some(BidiInfo::new(text.as_str(), None)).filter(|bidi_info| bidi_info.has_rtl())
+        }
+        _ => None,
+    };
+    let line_range = |pos: usize| {
+        let hit = layout.hit_test_text_position(pos);
+        let lm = layout.line_metric(hit.line).unwrap();
+        lm.start_offset..lm.end_offset
+    };
+
     let (offset, h_pos) = match m {
-        Movement::Grapheme(d) if d.is_upstream_for_direction(writing_direction) => {
-            if s.is_caret() || modify {
-                text.prev_grapheme_offset(s.active)
+        Movement::Grapheme(d) if s.is_caret() || modify => {
+            let line = bidi_info.as_ref().map(|_| line_range(s.active));
+            let visual_offset =
+                bidi_info
+                    .as_ref()
+                    .zip(line.clone())
+                    .and_then(|(bidi_info, line)| {
+                        visual_grapheme_offset(bidi_info, line, s.active, d == Direction::Right)
+                    });
+
+            match (visual_offset, line) {
+                (Some(offset), _) => (offset, None),
+                // We're at the visual edge of the line; jump to the adjacent line, if any.
+                (None, Some(line)) if d.is_upstream_for_direction(writing_direction) => text
+                    .prev_grapheme_offset(line.start)
                     .map(|off| (off, None))
-                    .unwrap_or((0, s.h_pos))
-            } else {
-                (s.min(), None)
+                    .unwrap_or((s.active, s.h_pos)),
+                (None, Some(line)) if line.end < text.len() => (line.end, None),
+                (None, Some(_)) => (s.active, s.h_pos),
+                (None, None) if d.is_upstream_for_direction(writing_direction) => text
+                    .prev_grapheme_offset(s.active)
+                    .map(|off| (off, None))
+                    .unwrap_or((0, s.h_pos)),
+                (None, None) => text
+                    .next_grapheme_offset(s.active)
+                    .map(|off| (off, None))
+                    .unwrap_or((s.active, s.h_pos)),
             }
         }
-        Movement::Grapheme(_) => {
-            if s.is_caret() || modify {
-                text.next_grapheme_offset(s.active)
-                    .map(|off| (off, None))
-                    .unwrap_or((s.active, s.h_pos))
-            } else {
-                (s.max(), None)
+        // Collapse the selection to its visual edge, if it's on a single line.
+        Movement::Grapheme(d) => {
+            let visual_edge = bidi_info.as_ref().and_then(|bidi_info| {
+                let line = line_range(s.min());
+                if line != line_range(s.max()) {
+                    return None;
+                }
+                visual_selection_edge(bidi_info, line, s, d == Direction::Right)
+            });
+            match visual_edge {
+                Some(offset) => (offset, None),
+                None if d.is_upstream_for_direction(writing_direction) => (s.min(), None),
+                None => (s.max(), None),
             }
         }
         Movement::Vertical(VerticalMovement::LineUp) => {
@@ -112,9 +152,17 @@ pub fn movement<T: EditableText + TextStorage>(
             };
             (offset, None)
         }
+        // Moving by word would give away where the words of obscured text are.
+        Movement::Word(d) if layout.is_obscured() => {
+            if d.is_upstream_for_direction(writing_direction) {
+                (0, None)
+            } else {
+                (text.len(), None)
+            }
+        }
         Movement::Word(d) if d.is_upstream_for_direction(writing_direction) => {
             let offset = if s.is_caret() || modify {
-                text.prev_word_offset(s.active).unwrap_or(0)
+                prev_word_boundary(text.as_str(), s.active)
             } else {
                 s.min()
             };
@@ -122,7 +170,7 @@ pub fn movement<T: EditableText + TextStorage>(
         }
         Movement::Word(_) => {
             let offset = if s.is_caret() || modify {
-                text.next_word_offset(s.active).unwrap_or(s.active)
+                next_word_boundary(text.as_str(), s.active)
             } else {
                 s.max()
             };
@@ -143,6 +191,166 @@ pub fn movement<T: EditableText + TextStorage>(
     Selection::new(start, offset).with_h_pos(h_pos)
 }
 
+/// Return the grapheme boundary visually to the left or right of `pos`.
+///
+/// In bidirectional text, the logical order of characters (the order they're
+/// stored in) and their visual order (the order they're displayed in) differ:
+/// within a right-to-left run, moving the caret to the right means moving it
+/// towards the *start* of the text. This function reorders the graphemes of
+/// `line` using the Unicode Bidirectional Algorithm ([UAX#9]), and returns the
+/// boundary next to `pos` in visual order.
+///
+/// `bidi_info` is computed from the whole text, and `line` is the byte range of
+/// the visual line containing `pos`.
+///
+/// Returns `None` if `pos` is already at the left or right edge of the line.
+///
+/// [UAX#9]: http://www.unicode.org/reports/tr9/
+pub(crate) fn visual_grapheme_offset(
+    bidi_info: &BidiInfo,
+    line: Range<usize>,
+    pos: usize,
+    right: bool,
+) -> Option<usize> {
+    let offsets = visual_caret_offsets(bidi_info, line);
+    let idx = offsets.iter().position(|offset| *offset == pos)?;
+    if right {
+        offsets.get(idx + 1).copied()
+    } else {
+        idx.checked_sub(1).map(|idx| offsets[idx])
+    }
+}
+
+/// Return the edge of `selection` displayed furthest to the left or right.
+///
+/// Both edges must be on `line`. Returns `None` if either isn't a caret position.
+fn visual_selection_edge(
+    bidi_info: &BidiInfo,
+    line: Range<usize>,
+    selection: Selection,
+    right: bool,
+) -> Option<usize> {
+    let offsets = visual_caret_offsets(bidi_info, line);
+    let anchor_idx = offsets
+        .iter()
+        .position(|offset| *offset == selection.anchor)?;
+    let active_idx = offsets
+        .iter()
+        .position(|offset| *offset == selection.active)?;
+    if right == (anchor_idx > active_idx) {
+        Some(selection.anchor)
+    } else {
+        Some(selection.active)
+    }
+}
+
+/// Return every caret position of the given line, sorted from left to right.
+///
+/// Each grapheme boundary is placed at the leading edge of the grapheme that
+/// follows it, so a boundary between runs of opposite directions is displayed
+/// next to the grapheme it precedes in logical order. When two boundaries share
+/// the same visual position, they're both kept, so that every position on the
+/// line can be reached with the arrow keys.
+fn visual_caret_offsets(bidi_info: &BidiInfo, line: Range<usize>) -> Vec<usize> {
+    let text = bidi_info.text;
+    let para = match bidi_info
+        .paragraphs
+        .iter()
+        .find(|para| para.range.contains(&line.start))
+    {
+        Some(para) => para,
+        None => return vec![line.start],
+    };
+    let (levels, runs) = bidi_info.visual_runs(para, line.clone());
+
+    // Graphemes of the line in visual order, and whether they're displayed right-to-left.
+    let mut graphemes = Vec::new();
+    for run in runs {
+        let is_rtl = levels[run.start].is_rtl();
+        let run_graphemes = text[run.clone()]
+            .grapheme_indices(true)
+            .map(|(ix, grapheme)| (run.start + ix..run.start + ix + grapheme.len(), is_rtl));
+        if is_rtl {
+            graphemes.extend(run_graphemes.rev());
+        } else {
+            graphemes.extend(run_graphemes);
+        }
+    }
+
+    // Pairs of (visual position, offset), where visual position N is the gap
+    // between the N-1th and the Nth grapheme.
+    let mut carets: Vec<(usize, usize)> = graphemes
+        .iter()
+        .enumerate()
+        .map(|(idx, (range, is_rtl))| (if *is_rtl { idx + 1 } else { idx }, range.start))
+        .collect();
+    // The end of the text isn't the start of any grapheme; it's displayed at
+    // the trailing edge of the last grapheme.
+    if line.end == text.len() {
+        let last = graphemes
+            .iter()
+            .enumerate()
+            .find(|(_, (range, _))| range.end == line.end);
+        match last {
+            Some((idx, (_, is_rtl))) => {
+                carets.push((if *is_rtl { idx } else { idx + 1 }, line.end))
+            }
+            None => carets.push((0, line.end)),
+        }
+    }
+
+    carets.sort_unstable();
+    carets.into_iter().map(|(_, offset)| offset).collect()
+}
+
+/// Return the offset of the start of the word before `pos`.
+///
+/// This is where the caret lands when the user presses Ctrl+Left (Option+Left
+/// on macOS). Whitespace and punctuation between `pos` and the previous word
+/// are skipped.
+///
+/// Words are split using Unicode word boundaries, as defined in [UAX#29], so
+/// the returned offset is never inside a grapheme cluster: combining
+/// characters and emoji ZWJ sequences are never split. Scripts that don't use
+/// spaces between words (eg CJK) move one ideograph at a time.
+///
+/// Returns 0 if there is no word before `pos`.
+///
+/// [UAX#29]: http://www.unicode.org/reports/tr29/
+pub fn prev_word_boundary(text: &str, pos: usize) -> usize {
+    text.split_word_bound_indices()
+        .rev()
+        .find(|(ix, word)| *ix < pos && is_word(word))
+        .map(|(ix, _)| ix)
+        .unwrap_or(0)
+}
+
+/// Return the offset of the end of the word after `pos`.
+///
+/// This is where the caret lands when the user presses Ctrl+Right (Option+Right
+/// on macOS). Whitespace and punctuation between `pos` and the next word
+/// are skipped.
+///
+/// See [`prev_word_boundary`] for how words are split.
+///
+/// Returns the length of the text if there is no word after `pos`.
+pub fn next_word_boundary(text: &str, pos: usize) -> usize {
+    text.split_word_bound_indices()
+        .map(|(ix, word)| ix + word.len())
+        .zip(text.split_word_bounds())
+        .find(|(end, word)| *end > pos && is_word(word))
+        .map(|(end, _)| end)
+        .unwrap_or(text.len())
+}
+
+/// Whether a segment returned by `split_word_bounds` is something the caret
+/// should stop at, as opposed to whitespace or punctuation.
+fn is_word(segment: &str) -> bool {
+    segment
+        .chars()
+        .any(|c| !c.is_whitespace() && !c.is_ascii_punctuation())
+}
+
 /// Given a position in some text, return the containing word boundaries.
 ///
 /// The returned range may not necessary be a 'word'; for instance it could be
@@ -165,6 +373,113 @@ pub(crate) fn word_range_for_pos(text: &str, pos: usize) -> Range<usize> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn visual_offsets_ltr() {
+        let bidi_info = BidiInfo::new("abc", None);
+        assert_eq!(visual_caret_offsets(&bidi_info, 0..3), vec![0, 1, 2, 3]);
+        assert_eq!(visual_grapheme_offset(&bidi_info, 0..3, 1, true), Some(2));
+        assert_eq!(visual_grapheme_offset(&bidi_info, 0..3, 1, false), Some(0));
+        assert_eq!(visual_grapheme_offset(&bidi_info, 0..3, 3, true), None);
+    }
+
+    #[test]
+    fn visual_offsets_rtl_run() {
+        // Hebrew letters are 2 bytes long. This is displayed as "ab גבא".
+        let bidi_info = BidiInfo::new("ab \u{5d0}\u{5d1}\u{5d2}", None);
+        assert_eq!(
+            visual_caret_offsets(&bidi_info, 0..9),
+            vec![0, 1, 2, 9, 7, 5, 3]
+        );
+
+        // Pressing Right inside the RTL run moves towards the start of the text.
+        assert_eq!(visual_grapheme_offset(&bidi_info, 0..9, 2, true), Some(9));
+        assert_eq!(visual_grapheme_offset(&bidi_info, 0..9, 9, true), Some(7));
+        assert_eq!(visual_grapheme_offset(&bidi_info, 0..9, 7, true), Some(5));
+        assert_eq!(visual_grapheme_offset(&bidi_info, 0..9, 3, true), None);
+
+        assert_eq!(visual_grapheme_offset(&bidi_info, 0..9, 3, false), Some(5));
+        assert_eq!(visual_grapheme_offset(&bidi_info, 0..9, 9, false), Some(2));
+    }
+
+    #[test]
+    fn visual_offsets_rtl_paragraph() {
+        // This is displayed as "ab בא" but in a right-to-left paragraph,
+        // so the LTR run is displayed to the left of the RTL run.
+        let bidi_info = BidiInfo::new("\u{5d0}\u{5d1} ab", None);
+        let offsets = visual_caret_offsets(&bidi_info, 0..7);
+        assert_eq!(offsets.first(), Some(&5));
+        assert_eq!(offsets.last(), Some(&0));
+        assert_eq!(visual_grapheme_offset(&bidi_info, 0..7, 0, false), Some(2));
+        assert_eq!(visual_grapheme_offset(&bidi_info, 0..7, 2, false), Some(4));
+    }
+
+    #[test]
+    fn visual_selection_edges() {
+        // Displayed as "ab גבא", with carets 0, 1, 2, 9, 7, 5, 3 from left to right.
+        let bidi_info = BidiInfo::new("ab \u{5d0}\u{5d1}\u{5d2}", None);
+        let edge = |anchor, active, right| {
+            visual_selection_edge(&bidi_info, 0..9, Selection::new(anchor, active), right)
+        };
+        assert_eq!(edge(7, 9, false), Some(9));
+        assert_eq!(edge(7, 9, true), Some(7));
+        assert_eq!(edge(9, 7, false), Some(9));
+        assert_eq!(edge(1, 5, false), Some(1));
+        assert_eq!(edge(1, 5, true), Some(5));
+    }
+
+    #[test]
+    fn word_boundary_simple() {
+        let text = "hello, big  world";
+        assert_eq!(next_word_boundary(text, 0), 5);
+        assert_eq!(next_word_boundary(text, 5), 10);
+        assert_eq!(next_word_boundary(text, 7), 10);
+        assert_eq!(next_word_boundary(text, 10), 17);
+        assert_eq!(next_word_boundary(text, 17), 17);
+
+        assert_eq!(prev_word_boundary(text, 17), 12);
+        assert_eq!(prev_word_boundary(text, 12), 7);
+        assert_eq!(prev_word_boundary(text, 9), 7);
+        assert_eq!(prev_word_boundary(text, 7), 0);
+        assert_eq!(prev_word_boundary(text, 0), 0);
+    }
+
+    #[test]
+    fn word_boundary_cjk() {
+        // Each ideograph is 3 bytes long, and is its own word.
+        let text = "漢字 テスト";
+        assert_eq!(next_word_boundary(text, 0), 3);
+        assert_eq!(next_word_boundary(text, 3), 6);
+        // Katakana runs are kept together.
+        assert_eq!(next_word_boundary(text, 6), 16);
+
+        assert_eq!(prev_word_boundary(text, 16), 7);
+        assert_eq!(prev_word_boundary(text, 7), 3);
+        assert_eq!(prev_word_boundary(text, 3), 0);
+    }
+
+    #[test]
+    fn word_boundary_combining_characters() {
+        // "e" followed by a combining acute accent (2 bytes).
+        let text = "cafe\u{301} ole\u{301}";
+        assert_eq!(next_word_boundary(text, 0), 6);
+        assert_eq!(next_word_boundary(text, 6), 12);
+        assert_eq!(prev_word_boundary(text, 12), 7);
+        assert_eq!(prev_word_boundary(text, 7), 0);
+    }
+
+    #[test]
+    fn word_boundary_emoji() {
+        // Family emoji: man, ZWJ, woman, ZWJ, girl (18 bytes).
+        let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+        let text = format!("a {family} b");
+        assert_eq!(next_word_boundary(&text, 1), 20);
+        assert_eq!(next_word_boundary(&text, 2), 20);
+        assert_eq!(next_word_boundary(&text, 20), 22);
+        assert_eq!(prev_word_boundary(&text, 21), 2);
+        assert_eq!(prev_word_boundary(&text, 20), 2);
+        assert_eq!(prev_word_boundary(&text, 2), 0);
+    }
+
     #[test]
     fn word_range_simple() {
         assert_eq!(word_range_for_pos("hello world", 3), 0..5);
@@ -0,0 +1,82 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! Rendering the widget tree into a texture owned by an embedder.
+
+/// A graphics backend that the window can be rendered into, eg a GPU texture that a
+/// game engine composites into its scene.
+///
+/// Embedders implement this for their own texture type, and pass it to
+/// [`TestHarness::render_to_texture`](crate::testing::TestHarness::render_to_texture).
+///
+/// Widgets paint through piet, whose backend is picked at compile time and renders
+/// into CPU memory. So for now, the window is painted into a bitmap, which the backend
+/// uploads into its texture. Once [`PaintCtx`](crate::PaintCtx) can wrap other render
+/// contexts, backends will be able to paint into their texture directly.
+///
+/// This API is preliminary, and may be changed or removed without warning.
+pub trait TextureBackend {
+    /// The handle to a texture, which is returned to the embedder.
+    type Texture;
+
+    /// Create a texture of the given size, in physical pixels.
+    fn create_texture(&mut self, width: usize, height: usize) -> Self::Texture;
+
+    /// Copy the painted window into `texture`.
+    ///
+    /// `pixels` is in premultiplied RGBA order, with 4 bytes per pixel and rows of
+    /// `width` pixels. The texture was created with the same size.
+    fn upload(&mut self, texture: &mut Self::Texture, pixels: &[u8], width: usize, height: usize);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::{Label, SizedBox};
+    use crate::{Color, Size};
+
+    /// A mock GPU backend, whose "textures" are indices into a list of uploaded frames.
+    #[derive(Default)]
+    struct MockBackend {
+        textures: Vec<(usize, usize, Vec<u8>)>,
+        uploads: usize,
+    }
+
+    impl TextureBackend for MockBackend {
+        type Texture = usize;
+
+        fn create_texture(&mut self, width: usize, height: usize) -> usize {
+            self.textures.push((width, height, Vec::new()));
+            self.textures.len() - 1
+        }
+
+        fn upload(&mut self, texture: &mut usize, pixels: &[u8], width: usize, height: usize) {
+            let (texture_width, texture_height, contents) = &mut self.textures[*texture];
+            assert_eq!((*texture_width, *texture_height), (width, height));
+            assert_eq!(pixels.len(), width * height * 4);
+            *contents = pixels.to_vec();
+            self.uploads += 1;
+        }
+    }
+
+    #[test]
+    fn render_into_mock_texture() {
+        let widget = SizedBox::new(Label::new("Hello"))
+            .expand()
+            .background(Color::rgb8(0xff, 0, 0));
+        let mut harness = TestHarness::create_with_size(widget, Size::new(40.0, 30.0));
+
+        let expected = harness.render();
+        let mut backend = MockBackend::default();
+        let texture = harness.render_to_texture(&mut backend).unwrap();
+        assert_eq!(backend.uploads, 1);
+
+        let (width, height, pixels) = &backend.textures[texture];
+        assert_eq!((*width, *height), (40, 30));
+        // The window is painted into the texture, eg the red background.
+        assert_eq!(pixels[..4], [0xff, 0, 0, 0xff]);
+        assert!(pixels[..] == *expected); // We don't use assert_eq because we don't want rich assert
+    }
+}
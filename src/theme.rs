@@ -46,6 +46,8 @@ pub const SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR: Key<Color> =
     Key::new("org.masonry.theme.selection_color_inactive");
 pub const SELECTION_TEXT_COLOR: Key<Color> = Key::new("org.masonry.theme.selection_text_color");
 pub const CURSOR_COLOR: Key<Color> = Key::new("org.masonry.theme.cursor_color");
+/// The time, in milliseconds, between two blinks of the text caret. Zero disables blinking.
+pub const CURSOR_BLINK_INTERVAL: Key<u64> = Key::new("org.masonry.theme.cursor_blink_interval");
 
 pub const TEXT_SIZE_NORMAL: Key<f64> = Key::new("org.masonry.theme.text_size_normal");
 pub const TEXT_SIZE_LARGE: Key<f64> = Key::new("org.masonry.theme.text_size_large");
@@ -89,6 +91,31 @@ pub const SCROLLBAR_EDGE_WIDTH: Key<f64> = Key::new("org.masonry.theme.scrollbar
 /// scrollbar's primary axis.
 pub const SCROLLBAR_MIN_SIZE: Key<f64> = Key::new("org.linebender.theme.scrollbar_min_size");
 
+pub const SPINNER_COLOR: Key<Color> = Key::new("org.masonry.theme.spinner_color");
+pub const ICON_COLOR: Key<Color> = Key::new("org.masonry.theme.icon_color");
+
+pub const TOAST_INFO_COLOR: Key<Color> = Key::new("org.masonry.theme.toast_info_color");
+pub const TOAST_WARNING_COLOR: Key<Color> = Key::new("org.masonry.theme.toast_warning_color");
+pub const TOAST_ERROR_COLOR: Key<Color> = Key::new("org.masonry.theme.toast_error_color");
+
+/// The background of [`Badge`](crate::widget::Badge) counts.
+pub const BADGE_COLOR: Key<Color> = Key::new("org.masonry.theme.badge_color");
+
+/// Render crisply, for pixel-art or retro UIs.
+///
+/// When set, images are scaled with nearest-neighbor interpolation unless they specify
+/// otherwise, and axis-aligned rects are snapped to device pixels. See
+/// [`PaintCtx::prefers_crisp_edges`](crate::PaintCtx::prefers_crisp_edges).
+pub const PREFERS_CRISP_EDGES: Key<bool> = Key::new("org.masonry.theme.prefers_crisp_edges");
+
+/// The parts of the window, from each edge, which may be obscured, eg by rounded corners
+/// or a camera notch.
+///
+/// Masonry sets this for each window from
+/// [`WindowRoot::set_safe_area_insets`](crate::WindowRoot::set_safe_area_insets), so
+/// setting it in the app's env has no effect. See [`SafeArea`](crate::widget::SafeArea).
+pub const SAFE_AREA_INSETS: Key<Insets> = Key::new("org.masonry.theme.safe_area_insets");
+
 /// An initial theme.
 pub(crate) fn add_to_env(env: Env) -> Env {
     env.adding(WINDOW_BACKGROUND_COLOR, Color::rgb8(0x29, 0x29, 0x29))
@@ -119,6 +146,7 @@ pub(crate) fn add_to_env(env: Env) -> Env {
         .adding(SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR, Color::grey8(0x74))
         .adding(SELECTION_TEXT_COLOR, Color::rgb8(0x00, 0x00, 0x00))
         .adding(CURSOR_COLOR, Color::WHITE)
+        .adding(CURSOR_BLINK_INTERVAL, 500u64)
         .adding(TEXT_SIZE_NORMAL, 15.0)
         .adding(TEXT_SIZE_LARGE, 24.0)
         .adding(BASIC_WIDGET_HEIGHT, 18.0)
@@ -139,6 +167,14 @@ pub(crate) fn add_to_env(env: Env) -> Env {
         .adding(WIDGET_PADDING_VERTICAL, 10.0)
         .adding(WIDGET_PADDING_HORIZONTAL, 8.0)
         .adding(WIDGET_CONTROL_COMPONENT_PADDING, 4.0)
+        .adding(SPINNER_COLOR, Color::rgb8(0xf0, 0xf0, 0xea))
+        .adding(ICON_COLOR, Color::rgb8(0xf0, 0xf0, 0xea))
+        .adding(TOAST_INFO_COLOR, Color::rgb8(0x2d, 0x4a, 0x6b))
+        .adding(TOAST_WARNING_COLOR, Color::rgb8(0x8a, 0x6d, 0x1e))
+        .adding(TOAST_ERROR_COLOR, Color::rgb8(0x8b, 0x2c, 0x2c))
+        .adding(BADGE_COLOR, Color::rgb8(0xd9, 0x30, 0x25))
+        .adding(PREFERS_CRISP_EDGES, false)
+        .adding(SAFE_AREA_INSETS, Insets::ZERO)
         .adding(
             UI_FONT,
             FontDescriptor::new(FontFamily::SYSTEM_UI).with_size(15.0),
@@ -0,0 +1,160 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! Typed lengths, for code that mixes display points and physical pixels.
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::shell::Scale;
+
+/// A length in display points, also known as logical pixels.
+///
+/// All of Masonry's layout happens in display points: the [`Size`](crate::Size),
+/// [`Rect`](crate::Rect) and [`BoxConstraints`](crate::BoxConstraints) widgets deal with
+/// are all in this unit, and don't change when the window moves to a screen with a
+/// different [`Scale`]. Wrapping a raw `f64` in `Dp` documents which unit it's in, and
+/// means converting it to [`Px`] has to go through a scale factor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Dp(pub f64);
+
+/// A length in physical pixels, ie the pixels of the screen or of a render target.
+///
+/// A length of `Dp(1.0)` is `Px(2.0)` on a screen with a scale factor of 2.0.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Px(pub f64);
+
+impl Dp {
+    /// The equivalent horizontal length in physical pixels.
+    pub fn to_px_x(self, scale: Scale) -> Px {
+        Px(self.0 * scale.x())
+    }
+
+    /// The equivalent vertical length in physical pixels.
+    pub fn to_px_y(self, scale: Scale) -> Px {
+        Px(self.0 * scale.y())
+    }
+
+    /// Round a horizontal length to a whole number of physical pixels.
+    ///
+    /// Lengths rounded this way end up on the pixel grid once painted, which avoids
+    /// blurry edges on screens where display points aren't whole pixels.
+    pub fn round_to_px_x(self, scale: Scale) -> Dp {
+        self.to_px_x(scale).round().to_dp_x(scale)
+    }
+
+    /// Round a vertical length to a whole number of physical pixels.
+    ///
+    /// See [`round_to_px_x`](Self::round_to_px_x).
+    pub fn round_to_px_y(self, scale: Scale) -> Dp {
+        self.to_px_y(scale).round().to_dp_y(scale)
+    }
+}
+
+impl Px {
+    /// The equivalent horizontal length in display points.
+    pub fn to_dp_x(self, scale: Scale) -> Dp {
+        Dp(self.0 / scale.x())
+    }
+
+    /// The equivalent vertical length in display points.
+    pub fn to_dp_y(self, scale: Scale) -> Dp {
+        Dp(self.0 / scale.y())
+    }
+
+    /// Round to the nearest whole pixel.
+    pub fn round(self) -> Px {
+        Px(self.0.round())
+    }
+}
+
+macro_rules! impl_length_ops {
+    ($unit:ident) => {
+        impl Add for $unit {
+            type Output = $unit;
+
+            fn add(self, other: $unit) -> $unit {
+                $unit(self.0 + other.0)
+            }
+        }
+
+        impl Sub for $unit {
+            type Output = $unit;
+
+            fn sub(self, other: $unit) -> $unit {
+                $unit(self.0 - other.0)
+            }
+        }
+
+        impl Neg for $unit {
+            type Output = $unit;
+
+            fn neg(self) -> $unit {
+                $unit(-self.0)
+            }
+        }
+
+        impl Mul<f64> for $unit {
+            type Output = $unit;
+
+            fn mul(self, factor: f64) -> $unit {
+                $unit(self.0 * factor)
+            }
+        }
+
+        impl Div<f64> for $unit {
+            type Output = $unit;
+
+            fn div(self, factor: f64) -> $unit {
+                $unit(self.0 / factor)
+            }
+        }
+
+        impl From<$unit> for f64 {
+            fn from(length: $unit) -> f64 {
+                length.0
+            }
+        }
+    };
+}
+
+impl_length_ops!(Dp);
+impl_length_ops!(Px);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_at_scale_2() {
+        let scale = Scale::new(2.0, 2.0);
+
+        assert_eq!(Dp(10.0).to_px_x(scale), Px(20.0));
+        assert_eq!(Dp(10.0).to_px_y(scale), Px(20.0));
+        assert_eq!(Px(15.0).to_dp_x(scale), Dp(7.5));
+        assert_eq!(Px(15.0).to_dp_y(scale), Dp(7.5));
+
+        // Half a display point is a whole pixel at 2x, so it's already on the grid.
+        assert_eq!(Dp(3.5).round_to_px_x(scale), Dp(3.5));
+        assert_eq!(Dp(3.3).round_to_px_x(scale), Dp(3.5));
+    }
+
+    #[test]
+    fn convert_at_uneven_scale() {
+        let scale = Scale::new(1.5, 2.0);
+
+        assert_eq!(Dp(10.0).to_px_x(scale), Px(15.0));
+        assert_eq!(Dp(10.0).to_px_y(scale), Px(20.0));
+        assert_eq!(Dp(1.0).round_to_px_x(scale), Px(2.0).to_dp_x(scale));
+        assert_eq!(Dp(1.0).round_to_px_y(scale), Dp(1.0));
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(Dp(1.0) + Dp(2.0), Dp(3.0));
+        assert_eq!(Dp(1.0) - Dp(2.0), -Dp(1.0));
+        assert_eq!(Px(3.0) * 2.0, Px(6.0));
+        assert_eq!(Px(3.0) / 2.0, Px(1.5));
+        assert_eq!(f64::from(Dp(4.0)), 4.0);
+    }
+}
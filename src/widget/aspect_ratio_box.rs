@@ -0,0 +1,173 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A widget that keeps its child at a fixed aspect ratio.
+
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, warn, Span};
+
+use crate::widget::{WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Point,
+    Size, StatusChange, Widget,
+};
+
+/// A widget that keeps its child at a fixed width:height ratio.
+///
+/// The child is made as large as possible within the available space while
+/// keeping the ratio. If the `AspectRatioBox` is forced to be larger than its
+/// child (eg because it's given tight constraints), the child is centered.
+pub struct AspectRatioBox {
+    child: WidgetPod<Box<dyn Widget>>,
+    ratio: f64,
+}
+
+crate::declare_widget!(AspectRatioBoxMut, AspectRatioBox);
+
+impl AspectRatioBox {
+    /// Create a new `AspectRatioBox`.
+    ///
+    /// `ratio` is the width of the child divided by its height, eg `16.0 / 9.0`.
+    pub fn new(child: impl Widget, ratio: f64) -> Self {
+        AspectRatioBox {
+            child: WidgetPod::new(child).boxed(),
+            ratio: clamp_ratio(ratio),
+        }
+    }
+
+    /// Return the width:height ratio of the child.
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    fn child_size(&self, bc: &BoxConstraints) -> Option<Size> {
+        let max = bc.max();
+        let size = match (bc.is_width_bounded(), bc.is_height_bounded()) {
+            (true, true) => {
+                let width = max.width.min(max.height * self.ratio);
+                Size::new(width, width / self.ratio)
+            }
+            (true, false) => Size::new(max.width, max.width / self.ratio),
+            (false, true) => Size::new(max.height * self.ratio, max.height),
+            (false, false) => return None,
+        };
+        Some(size)
+    }
+}
+
+impl<'a, 'b> AspectRatioBoxMut<'a, 'b> {
+    /// Set the width:height ratio of the child.
+    pub fn set_ratio(&mut self, ratio: f64) {
+        self.1.ratio = clamp_ratio(ratio);
+        self.0.request_layout();
+    }
+
+    /// Return a mutable reference to the child.
+    pub fn child_mut(&mut self) -> WidgetMut<'_, 'b, Box<dyn Widget>> {
+        self.0.get_mut(&mut self.1.child)
+    }
+}
+
+fn clamp_ratio(ratio: f64) -> f64 {
+    if ratio.is_finite() && ratio > 0.0 {
+        ratio
+    } else {
+        warn!("AspectRatioBox ratio must be finite and positive, got {ratio}");
+        1.0
+    }
+}
+
+impl Widget for AspectRatioBox {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        self.child.on_event(ctx, event, env);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        self.child.lifecycle(ctx, event, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let child_size = match self.child_size(bc) {
+            Some(size) => {
+                let child_bc = BoxConstraints::tight(size);
+                self.child.layout(ctx, &child_bc, env)
+            }
+            None => {
+                // With no bounds at all, there's nothing to fit the ratio to.
+                warn!("AspectRatioBox was given unbounded constraints.");
+                self.child.layout(ctx, &bc.loosen(), env)
+            }
+        };
+
+        let size = bc.constrain(child_size);
+        let origin = Point::new(
+            ((size.width - child_size.width) / 2.0).max(0.0),
+            ((size.height - child_size.height) / 2.0).max(0.0),
+        );
+        ctx.place_child(&mut self.child, origin, env);
+
+        trace!("Computed layout: size={}, child_size={}", size, child_size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        self.child.paint(ctx, env);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.child.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("AspectRatioBox")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kurbo::Rect;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::{Flex, SizedBox};
+
+    #[test]
+    fn width_limited() {
+        let [child_id] = widget_ids();
+        let widget = AspectRatioBox::new(SizedBox::empty().with_id(child_id), 2.0);
+
+        let harness = TestHarness::create_with_size(widget, Size::new(200.0, 400.0));
+        assert_eq!(
+            harness.get_widget(child_id).state().window_layout_rect(),
+            Rect::new(0.0, 150.0, 200.0, 250.0)
+        );
+    }
+
+    #[test]
+    fn height_limited() {
+        let [child_id] = widget_ids();
+        let widget = AspectRatioBox::new(SizedBox::empty().with_id(child_id), 2.0);
+
+        let harness = TestHarness::create_with_size(widget, Size::new(400.0, 100.0));
+        assert_eq!(
+            harness.get_widget(child_id).state().window_layout_rect(),
+            Rect::new(100.0, 0.0, 300.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn loose_constraints() {
+        let [box_id] = widget_ids();
+        let widget = Flex::row().with_child(
+            SizedBox::new(AspectRatioBox::new(SizedBox::empty(), 0.5).with_id(box_id)).width(50.0),
+        );
+
+        let harness = TestHarness::create(widget);
+        assert_eq!(
+            harness.get_widget(box_id).state().layout_rect().size(),
+            Size::new(50.0, 100.0)
+        );
+    }
+}
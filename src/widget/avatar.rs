@@ -0,0 +1,297 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A circular picture of a user, with their initials as a fallback.
+
+use smallvec::{smallvec, SmallVec};
+use tracing::{error, trace_span, Span};
+
+use crate::promise::PromiseToken;
+use crate::widget::web_image::load_image;
+use crate::widget::{
+    Align, BackgroundBrush, FillStrat, Image, Label, RoundedClip, SizedBox, WidgetPod, WidgetRef,
+};
+use crate::{
+    theme, ArcStr, BoxConstraints, Color, Env, Event, EventCtx, ImageBuf, KeyOrValue, LayoutCtx,
+    LifeCycle, LifeCycleCtx, PaintCtx, Point, Size, StatusChange, Widget,
+};
+
+const DEFAULT_DIAMETER: f64 = 40.0;
+// The size of the initials, relative to the diameter.
+const INITIALS_SCALE: f64 = 0.4;
+
+/// A circular picture of a user, eg in a profile or next to a message.
+///
+/// The avatar shows an image clipped to a circle, or the user's initials on a colored
+/// disc. An avatar created with [`from_url`](Avatar::from_url) shows the initials while
+/// the image loads, and keeps showing them if the image can't be loaded.
+pub struct Avatar {
+    url: Option<String>,
+    initials: ArcStr,
+    diameter: f64,
+    color: KeyOrValue<Color>,
+    image: Option<ImageBuf>,
+    image_promise: PromiseToken<Option<ImageBuf>>,
+    is_loading: bool,
+    content: WidgetPod<RoundedClip>,
+}
+
+crate::declare_widget!(AvatarMut, Avatar);
+
+impl Avatar {
+    fn new(url: Option<String>, initials: ArcStr) -> Self {
+        let mut avatar = Avatar {
+            url,
+            initials,
+            diameter: DEFAULT_DIAMETER,
+            color: theme::PRIMARY_DARK.into(),
+            image: None,
+            image_promise: PromiseToken::empty(),
+            is_loading: false,
+            content: WidgetPod::new(RoundedClip::new(SizedBox::empty(), 0.0)),
+        };
+        avatar.content = WidgetPod::new(avatar.make_content());
+        avatar
+    }
+
+    /// Create an avatar showing the image at `url`.
+    ///
+    /// Until the image is loaded, or if it fails to load, the avatar shows the initials
+    /// set with [`with_initials`](Self::with_initials), or just a colored disc.
+    pub fn from_url(url: impl Into<String>) -> Self {
+        Self::new(Some(url.into()), ArcStr::from(""))
+    }
+
+    /// Create an avatar showing the given initials, eg `"AB"`.
+    pub fn from_initials(initials: impl Into<ArcStr>) -> Self {
+        Self::new(None, initials.into())
+    }
+
+    /// Create an avatar showing an image which is already loaded.
+    pub fn from_image(image: ImageBuf) -> Self {
+        let mut avatar = Self::new(None, ArcStr::from(""));
+        avatar.image = Some(image);
+        avatar.content = WidgetPod::new(avatar.make_content());
+        avatar
+    }
+
+    /// Builder-style method to set the initials shown until the image is loaded.
+    pub fn with_initials(mut self, initials: impl Into<ArcStr>) -> Self {
+        self.initials = initials.into();
+        self.content = WidgetPod::new(self.make_content());
+        self
+    }
+
+    /// Builder-style method to set the diameter of the avatar.
+    ///
+    /// The default is 40 display points.
+    pub fn with_diameter(mut self, diameter: f64) -> Self {
+        self.diameter = diameter;
+        self.content = WidgetPod::new(self.make_content());
+        self
+    }
+
+    /// Builder-style method to set the color of the disc behind the initials.
+    ///
+    /// The default is [`theme::PRIMARY_DARK`].
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.color = color.into();
+        self.content = WidgetPod::new(self.make_content());
+        self
+    }
+
+    /// The image clipped to a circle if we have one, or the initials otherwise.
+    fn make_content(&self) -> RoundedClip {
+        if let Some(image) = &self.image {
+            let image = Image::new(image.clone()).fill_mode(FillStrat::Cover);
+            return RoundedClip::new(image, self.diameter / 2.0);
+        }
+
+        let label = Label::new(self.initials.clone())
+            .with_text_size(self.diameter * INITIALS_SCALE)
+            .with_text_color(theme::TEXT_COLOR);
+        let disc = SizedBox::new(Align::centered(label))
+            .expand()
+            .background(BackgroundBrush::Color(self.color.clone()));
+        RoundedClip::new(disc, self.diameter / 2.0)
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for Avatar {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        if let Event::PromiseResult(result) = event {
+            if let Some(image) = result.try_get_result(self.image_promise) {
+                self.is_loading = false;
+                // Ignore any later result, eg if the load was resolved twice.
+                self.image_promise = PromiseToken::empty();
+                match image {
+                    Ok(Some(image)) => {
+                        self.image = Some(image);
+                        let image_content = WidgetPod::new(self.make_content());
+                        let initials_content = std::mem::replace(&mut self.content, image_content);
+                        ctx.remove_child(initials_content);
+                    }
+                    // load_image already logged why.
+                    Ok(None) => {}
+                    Err(err) => {
+                        error!(
+                            "Cannot load avatar at '{}': {}",
+                            self.url.as_deref().unwrap_or_default(),
+                            err
+                        );
+                    }
+                }
+                ctx.skip_child(&mut self.content);
+                return;
+            }
+        }
+        self.content.on_event(ctx, event, env);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                if let Some(url) = self.url.clone() {
+                    self.image_promise = ctx.compute_in_background(move |_| load_image(&url));
+                    self.is_loading = true;
+                }
+            }
+            LifeCycle::WidgetRemoved if self.is_loading => {
+                ctx.cancel_promise(self.image_promise);
+            }
+            _ => {}
+        }
+        self.content.lifecycle(ctx, event, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let size = bc.constrain(Size::new(self.diameter, self.diameter));
+        self.content.layout(ctx, &BoxConstraints::tight(size), env);
+        ctx.place_child(&mut self.content, Point::ORIGIN, env);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        self.content.paint(ctx, env);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.content.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Avatar")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_render_snapshot;
+    use crate::piet::ImageFormat;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::Flex;
+
+    // An avatar waiting for its image. It has no url, so it doesn't start a network
+    // load when added; tests resolve the returned promise themselves.
+    fn loading_avatar(initials: &str) -> (Avatar, PromiseToken<Option<ImageBuf>>) {
+        let mut avatar = Avatar::from_initials(initials);
+        avatar.image_promise = PromiseToken::new();
+        avatar.is_loading = true;
+        let promise_token = avatar.image_promise;
+        (avatar, promise_token)
+    }
+
+    #[test]
+    fn initials() {
+        let widget = Flex::row()
+            .with_child(Avatar::from_initials("AB"))
+            .with_child(
+                Avatar::from_initials("CD")
+                    .with_diameter(60.0)
+                    .with_color(Color::rgb8(0x8b, 0x2c, 0x2c)),
+            );
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(120.0, 70.0));
+        assert_render_snapshot!(harness, "initials");
+    }
+
+    #[test]
+    fn image() {
+        let image_data = ImageBuf::from_raw(
+            vec![
+                255, 255, 255, 0, 0, 0, // row 0
+                0, 0, 0, 255, 255, 255, // row 1
+            ],
+            ImageFormat::Rgb,
+            2,
+            2,
+        );
+        let widget = Avatar::from_image(image_data).with_diameter(60.0);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(60.0, 60.0));
+        assert_render_snapshot!(harness, "image");
+    }
+
+    #[test]
+    fn failed_load_keeps_initials() {
+        let [avatar_id] = widget_ids();
+        let (avatar, promise_token) = loading_avatar("AB");
+        let widget = avatar.with_id(avatar_id);
+
+        let mut harness = TestHarness::create(widget);
+        harness
+            .window()
+            .ext_event_sink
+            .resolve_promise(
+                promise_token.make_result(None),
+                avatar_id,
+                harness.window().id,
+            )
+            .unwrap();
+        harness.process_ext_events();
+
+        assert!(harness.find_widget_by_type::<Image>().is_none());
+        let label = harness.find_widget_by_type::<Label>().unwrap();
+        assert_eq!(label.text().to_string(), "AB");
+    }
+
+    #[test]
+    fn loaded_image_replaces_initials() {
+        let [avatar_id] = widget_ids();
+        let (avatar, promise_token) = loading_avatar("AB");
+        let widget = avatar.with_id(avatar_id);
+
+        let mut harness = TestHarness::create(widget);
+        let label_id = harness.find_widget_by_type::<Label>().unwrap().state().id;
+
+        let image_data = ImageBuf::from_raw(vec![0x5c, 0xc4, 0xff], ImageFormat::Rgb, 1, 1);
+        harness
+            .window()
+            .ext_event_sink
+            .resolve_promise(
+                promise_token.make_result(Some(image_data)),
+                avatar_id,
+                harness.window().id,
+            )
+            .unwrap();
+        harness.process_ext_events();
+
+        assert!(harness.find_widget_by_type::<Image>().is_some());
+        assert!(harness.find_widget_by_type::<Label>().is_none());
+        // The initials were removed from the tree, not just hidden.
+        assert!(harness.try_get_widget(label_id).is_none());
+        assert!(
+            !harness
+                .get_widget(avatar_id)
+                .downcast::<Avatar>()
+                .unwrap()
+                .is_loading
+        );
+    }
+}
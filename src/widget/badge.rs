@@ -0,0 +1,248 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A count indicator overlaid on the corner of a widget.
+
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+
+use crate::kurbo::RoundedRect;
+use crate::widget::{Label, WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    theme, ArcStr, BoxConstraints, Color, Env, Event, EventCtx, KeyOrValue, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, Rect, RenderContext, Size, StatusChange, Widget,
+};
+
+const DEFAULT_CAP: u32 = 99;
+const BADGE_TEXT_SIZE: f64 = 10.0;
+// Horizontal space between the count and the edges of the badge.
+const BADGE_X_PADDING: f64 = 3.0;
+
+/// A count indicator overlaid on the top-right corner of a widget, eg the number of
+/// unread notifications on a toolbar icon.
+///
+/// The badge is hidden while the count is zero. Counts above the cap, 99 by default,
+/// are shown as eg `"99+"`.
+///
+/// Usually created with [`WidgetExt::badge`](crate::widget::WidgetExt::badge).
+pub struct Badge {
+    child: WidgetPod<Box<dyn Widget>>,
+    label: WidgetPod<Label>,
+    count: u32,
+    cap: u32,
+    color: KeyOrValue<Color>,
+    badge_rect: Rect,
+}
+
+crate::declare_widget!(BadgeMut, Badge);
+
+impl Badge {
+    /// Create a new `Badge` around `child`, with a count of zero.
+    pub fn new(child: impl Widget) -> Self {
+        Badge {
+            child: WidgetPod::new(child).boxed(),
+            label: WidgetPod::new(Label::new("0").with_text_size(BADGE_TEXT_SIZE)),
+            count: 0,
+            cap: DEFAULT_CAP,
+            color: theme::BADGE_COLOR.into(),
+            badge_rect: Rect::ZERO,
+        }
+    }
+
+    /// Builder-style method to set the count.
+    pub fn with_count(mut self, count: u32) -> Self {
+        self.count = count;
+        self.label = WidgetPod::new(Label::new(self.text()).with_text_size(BADGE_TEXT_SIZE));
+        self
+    }
+
+    /// Builder-style method to set the largest count shown as is.
+    pub fn with_cap(mut self, cap: u32) -> Self {
+        self.cap = cap;
+        self.label = WidgetPod::new(Label::new(self.text()).with_text_size(BADGE_TEXT_SIZE));
+        self
+    }
+
+    /// Builder-style method to set the color of the badge.
+    ///
+    /// The default is [`theme::BADGE_COLOR`].
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// The count shown in the badge.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Whether the badge is shown, ie whether the count isn't zero.
+    pub fn is_badge_visible(&self) -> bool {
+        self.count != 0
+    }
+
+    fn text(&self) -> ArcStr {
+        if self.count > self.cap {
+            format!("{}+", self.cap).into()
+        } else {
+            self.count.to_string().into()
+        }
+    }
+}
+
+impl<'a, 'b> BadgeMut<'a, 'b> {
+    /// Set the count.
+    pub fn set_count(&mut self, count: u32) {
+        self.1.count = count;
+        self.update_text();
+    }
+
+    /// Set the largest count shown as is.
+    pub fn set_cap(&mut self, cap: u32) {
+        self.1.cap = cap;
+        self.update_text();
+    }
+
+    /// Set the color of the badge.
+    pub fn set_color(&mut self, color: impl Into<KeyOrValue<Color>>) {
+        self.1.color = color.into();
+        self.0.request_paint();
+    }
+
+    /// Return a mutable reference to the child.
+    pub fn child_mut(&mut self) -> WidgetMut<'_, 'b, Box<dyn Widget>> {
+        self.0.get_mut(&mut self.1.child)
+    }
+
+    fn update_text(&mut self) {
+        let text = self.1.text();
+        self.0.get_mut(&mut self.1.label).set_text(text);
+        self.0.request_layout();
+        self.0.request_paint();
+    }
+}
+
+impl Widget for Badge {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        self.child.on_event(ctx, event, env);
+        if self.is_badge_visible() || event.should_propagate_to_hidden() {
+            self.label.on_event(ctx, event, env);
+        } else {
+            ctx.skip_child(&mut self.label);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        self.child.lifecycle(ctx, event, env);
+        if self.is_badge_visible() || event.should_propagate_to_hidden() {
+            self.label.lifecycle(ctx, event, env);
+        } else {
+            ctx.skip_child(&mut self.label);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, env);
+        ctx.place_child(&mut self.child, Point::ORIGIN, env);
+        ctx.set_baseline_offset(self.child.baseline_offset());
+
+        let label_size = self.label.layout(ctx, &BoxConstraints::UNBOUNDED, env);
+        // A pill for long counts, a circle for single digits.
+        let badge_height = label_size.height;
+        let badge_width = (label_size.width + 2.0 * BADGE_X_PADDING).max(badge_height);
+        self.badge_rect = Rect::from_origin_size(
+            Point::new(size.width - badge_width, 0.0),
+            Size::new(badge_width, badge_height),
+        );
+        let label_origin = Point::new(
+            self.badge_rect.center().x - label_size.width / 2.0,
+            self.badge_rect.y0,
+        );
+        ctx.place_child(&mut self.label, label_origin, env);
+
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        self.child.paint(ctx, env);
+
+        if self.is_badge_visible() {
+            let color = self.color.resolve(env);
+            let pill = RoundedRect::from_rect(self.badge_rect, self.badge_rect.height() / 2.0);
+            ctx.fill(pill, &color);
+            self.label.paint(ctx, env);
+        } else {
+            ctx.skip_child(&mut self.label);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.child.as_dyn(), self.label.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Badge")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.count.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_render_snapshot;
+    use crate::testing::TestHarness;
+    use crate::widget::{Icon, SizedBox, WidgetExt as _};
+
+    const BELL: &str = "M12 22a2 2 0 0 0 2-2h-4a2 2 0 0 0 2 2zm6-6v-5c0-3.1-1.6-5.6-4.5-6.3V4a1.5 1.5 0 0 0-3 0v.7C7.6 5.4 6 7.9 6 11v5l-2 2v1h16v-1z";
+
+    fn bell() -> SizedBox {
+        SizedBox::new(Icon::from_svg_path(BELL).unwrap())
+            .width(40.0)
+            .height(40.0)
+    }
+
+    #[test]
+    fn badged_icon() {
+        let widget = bell().badge(3);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(40.0, 40.0));
+        assert_render_snapshot!(harness, "badged_icon");
+    }
+
+    #[test]
+    fn zero_count_hides_badge() {
+        let widget = bell().badge(3);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(40.0, 40.0));
+        let badged_render = harness.render();
+
+        let mut bare_harness = TestHarness::create_with_size(bell(), Size::new(40.0, 40.0));
+        let bare_render = bare_harness.render();
+        // We don't use assert_eq because we don't want rich assert
+        assert!(badged_render != bare_render);
+
+        harness.edit_root_widget(|mut badge, _| {
+            let mut badge = badge.downcast::<Badge>().unwrap();
+            badge.set_count(0);
+        });
+        let badge = harness.find_widget_by_type::<Badge>().unwrap();
+        assert!(!badge.is_badge_visible());
+        assert!(harness.render() == bare_render);
+    }
+
+    #[test]
+    fn count_above_cap() {
+        let harness = TestHarness::create(Badge::new(SizedBox::empty()).with_count(150));
+        let label = harness.find_widget_by_type::<Label>().unwrap();
+        assert_eq!(label.text().to_string(), "99+");
+
+        let harness = TestHarness::create(Badge::new(SizedBox::empty()).with_cap(9).with_count(9));
+        let label = harness.find_widget_by_type::<Label>().unwrap();
+        assert_eq!(label.text().to_string(), "9");
+    }
+}
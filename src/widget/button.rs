@@ -8,10 +8,12 @@ use smallvec::SmallVec;
 use tracing::{trace, trace_span, Span};
 
 use crate::action::Action;
+use crate::shortcut::Shortcut;
 use crate::widget::{Label, WidgetMut, WidgetPod, WidgetRef};
 use crate::{
     theme, ArcStr, BoxConstraints, Env, Event, EventCtx, Insets, LayoutCtx, LifeCycle,
-    LifeCycleCtx, LinearGradient, PaintCtx, RenderContext, Size, StatusChange, UnitPoint, Widget,
+    LifeCycleCtx, LinearGradient, PaintCtx, Point, RenderContext, Size, StatusChange, UnitPoint,
+    Widget,
 };
 
 // the minimum padding added to a button.
@@ -19,11 +21,18 @@ use crate::{
 // should be reevaluated at some point.
 const LABEL_INSETS: Insets = Insets::uniform_xy(8., 2.);
 
+// the minimum space between the label and the shortcut label.
+pub(crate) const SHORTCUT_SPACING: f64 = 16.0;
+
 /// A button with a text label.
 ///
 /// Emits [`Action::ButtonPressed`] when pressed.
+///
+/// A button can display a keyboard [`Shortcut`] right-aligned after its text.
 pub struct Button {
     label: WidgetPod<Label>,
+    shortcut: Option<Shortcut>,
+    shortcut_label: Option<WidgetPod<Label>>,
 }
 
 crate::declare_widget!(ButtonMut, Button);
@@ -56,8 +65,40 @@ impl Button {
     pub fn from_label(label: Label) -> Button {
         Button {
             label: WidgetPod::new(label),
+            shortcut: None,
+            shortcut_label: None,
         }
     }
+
+    /// Builder-style method to display a keyboard shortcut in the button.
+    ///
+    /// The shortcut's label (eg "Ctrl+S") is displayed right-aligned after the
+    /// button's text. This only displays the shortcut; it doesn't make the
+    /// shortcut press the button.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use masonry::shell::SysMods;
+    /// use masonry::widget::Button;
+    /// use masonry::Shortcut;
+    ///
+    /// let button = Button::new("Save").with_shortcut(Shortcut::new(SysMods::Cmd, "s"));
+    /// ```
+    pub fn with_shortcut(mut self, shortcut: Shortcut) -> Self {
+        self.shortcut_label = Some(WidgetPod::new(shortcut_label(&shortcut)));
+        self.shortcut = Some(shortcut);
+        self
+    }
+
+    /// The keyboard shortcut displayed in the button, if any.
+    pub fn shortcut(&self) -> Option<&Shortcut> {
+        self.shortcut.as_ref()
+    }
+}
+
+pub(crate) fn shortcut_label(shortcut: &Shortcut) -> Label {
+    Label::new(shortcut.label()).with_text_color(theme::DISABLED_TEXT_COLOR)
 }
 
 impl<'a, 'b> ButtonMut<'a, 'b> {
@@ -69,6 +110,24 @@ impl<'a, 'b> ButtonMut<'a, 'b> {
     pub fn label_mut(&mut self) -> WidgetMut<'_, 'b, Label> {
         self.0.get_mut(&mut self.1.label)
     }
+
+    /// Set the keyboard shortcut displayed in the button.
+    ///
+    /// See [`Button::with_shortcut`].
+    pub fn set_shortcut(&mut self, shortcut: Option<Shortcut>) {
+        let old_label = std::mem::replace(
+            &mut self.1.shortcut_label,
+            shortcut
+                .as_ref()
+                .map(|shortcut| WidgetPod::new(shortcut_label(shortcut))),
+        );
+        if let Some(old_label) = old_label {
+            self.0.remove_child(old_label);
+        }
+        self.1.shortcut = shortcut;
+        self.0.children_changed();
+        self.0.request_layout();
+    }
 }
 
 impl Widget for Button {
@@ -98,7 +157,10 @@ impl Widget for Button {
     }
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
-        self.label.lifecycle(ctx, event, env)
+        self.label.lifecycle(ctx, event, env);
+        if let Some(shortcut_label) = &mut self.shortcut_label {
+            shortcut_label.lifecycle(ctx, event, env);
+        }
     }
 
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
@@ -109,18 +171,39 @@ impl Widget for Button {
         let label_bc = bc.shrink(padding).loosen();
 
         let label_size = self.label.layout(ctx, &label_bc, env);
+        let shortcut_size = match &mut self.shortcut_label {
+            Some(shortcut_label) => shortcut_label.layout(ctx, &label_bc, env),
+            None => Size::ZERO,
+        };
 
         // HACK: to make sure we look okay at default sizes when beside a textbox,
         // we make sure we will have at least the same height as the default textbox.
         let min_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
 
+        let content_width = if self.shortcut_label.is_some() {
+            label_size.width + SHORTCUT_SPACING + shortcut_size.width
+        } else {
+            label_size.width
+        };
+        let content_height = label_size.height.max(shortcut_size.height);
         let button_size = bc.constrain(Size::new(
-            label_size.width + padding.width,
-            (label_size.height + padding.height).max(min_height),
+            content_width + padding.width,
+            (content_height + padding.height).max(min_height),
         ));
 
-        let label_offset = (button_size.to_vec2() - label_size.to_vec2()) / 2.0;
-        ctx.place_child(&mut self.label, label_offset.to_point(), env);
+        if let Some(shortcut_label) = &mut self.shortcut_label {
+            // The text is left-aligned and the shortcut is right-aligned.
+            let label_y = (button_size.height - label_size.height) / 2.0;
+            ctx.place_child(&mut self.label, Point::new(LABEL_INSETS.x0, label_y), env);
+            let shortcut_offset = Point::new(
+                button_size.width - LABEL_INSETS.x1 - shortcut_size.width,
+                (button_size.height - shortcut_size.height) / 2.0,
+            );
+            ctx.place_child(shortcut_label, shortcut_offset, env);
+        } else {
+            let label_offset = (button_size.to_vec2() - label_size.to_vec2()) / 2.0;
+            ctx.place_child(&mut self.label, label_offset.to_point(), env);
+        }
 
         trace!("Computed button size: {}", button_size);
         button_size
@@ -170,6 +253,9 @@ impl Widget for Button {
         ctx.fill(rounded_rect, &bg_gradient);
 
         self.label.paint(ctx, env);
+        if let Some(shortcut_label) = &mut self.shortcut_label {
+            shortcut_label.paint(ctx, env);
+        }
     }
 
     fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
@@ -181,7 +267,11 @@ impl Widget for Button {
     }
 
     fn get_debug_text(&self) -> Option<String> {
-        Some(self.label.as_ref().text().to_string())
+        let text = self.label.as_ref().text().to_string();
+        match &self.shortcut {
+            Some(shortcut) => Some(format!("{} ({})", text, shortcut)),
+            None => Some(text),
+        }
     }
 }
 
@@ -191,8 +281,10 @@ mod tests {
 
     use super::*;
     use crate::assert_render_snapshot;
+    use crate::shell::{KbKey, RawMods};
     use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
     use crate::theme::PRIMARY_LIGHT;
+    use crate::widget::Flex;
 
     #[test]
     fn simple_button() {
@@ -213,6 +305,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn button_with_shortcut() {
+        let [button_id, plain_button_id] = widget_ids();
+        // F5 is labelled the same way on every platform.
+        let widget = Flex::column()
+            .with_child(
+                Button::new("Refresh")
+                    .with_shortcut(Shortcut::new(RawMods::None, KbKey::F5))
+                    .with_id(button_id),
+            )
+            .with_child(Button::new("Refresh").with_id(plain_button_id));
+
+        let harness = TestHarness::create(widget);
+
+        assert_debug_snapshot!(harness.root_widget());
+
+        let button_width = harness.get_widget(button_id).state().layout_rect().width();
+        let plain_button_width = harness
+            .get_widget(plain_button_id)
+            .state()
+            .layout_rect()
+            .width();
+        assert!(button_width > plain_button_width + SHORTCUT_SPACING);
+    }
+
     #[test]
     fn edit_button() {
         let image_1 = {
@@ -0,0 +1,358 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A searchable list of commands.
+
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+
+use crate::shell::KbKey;
+use crate::widget::{CrossAxisAlignment, Flex, Label, SizedBox, TextBox, WidgetMut, WidgetRef};
+use crate::{
+    theme, ArcStr, BoxConstraints, Command, Env, Event, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, Size, StatusChange, Widget, WidgetPod,
+};
+
+// The maximum number of matches shown at once.
+const MAX_VISIBLE_MATCHES: usize = 10;
+
+/// A text field above a list of commands, filtered by what the user types.
+///
+/// The query is fuzzy-matched against the title of each command: a command
+/// matches if its title contains all the characters of the query, in order,
+/// ignoring case. The best matches are listed first.
+///
+/// The user can pick a match with the up and down arrow keys. Pressing Enter
+/// submits the [`Command`] of the selected match.
+pub struct CommandPalette {
+    text_box: WidgetPod<TextBox>,
+    list: WidgetPod<Flex>,
+    entries: Vec<(ArcStr, Command)>,
+    /// Indices into `entries`, best match first.
+    matches: Vec<usize>,
+    /// Index into `matches`.
+    selected: usize,
+    query: String,
+}
+
+crate::declare_widget!(CommandPaletteMut, CommandPalette);
+
+impl CommandPalette {
+    /// Create a new `CommandPalette` with no commands.
+    pub fn new() -> Self {
+        CommandPalette {
+            text_box: WidgetPod::new(TextBox::new("").with_placeholder("Search commands")),
+            list: WidgetPod::new(Flex::column()),
+            entries: Vec::new(),
+            matches: Vec::new(),
+            selected: 0,
+            query: String::new(),
+        }
+    }
+
+    /// Builder-style method to add a command to the palette.
+    ///
+    /// `title` is the text shown to the user and matched against their query.
+    pub fn with_command(mut self, title: impl Into<ArcStr>, command: impl Into<Command>) -> Self {
+        self.entries.push((title.into(), command.into()));
+        self.update_matches();
+        self.list = WidgetPod::new(self.build_list());
+        self
+    }
+
+    /// Return the palette's current query.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    fn update_matches(&mut self) {
+        let mut scored: Vec<_> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (title, _))| Some((fuzzy_score(&self.query, title)?, i)))
+            .collect();
+        // Best score first; ties keep the order the commands were added in.
+        scored.sort_by(|(score_a, i_a), (score_b, i_b)| score_b.cmp(score_a).then(i_a.cmp(i_b)));
+        self.matches = scored
+            .into_iter()
+            .map(|(_, i)| i)
+            .take(MAX_VISIBLE_MATCHES)
+            .collect();
+        self.selected = 0;
+    }
+
+    fn build_list(&self) -> Flex {
+        let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Fill);
+        for row in self.rows() {
+            list = list.with_child(row);
+        }
+        list
+    }
+
+    fn rows(&self) -> impl Iterator<Item = SizedBox> + '_ {
+        self.matches.iter().enumerate().map(|(row, &i)| {
+            let label = SizedBox::new(Label::new(self.entries[i].0.clone())).expand_width();
+            if row == self.selected {
+                label.background(theme::SELECTED_TEXT_BACKGROUND_COLOR)
+            } else {
+                label
+            }
+        })
+    }
+
+    fn rebuild_list(&mut self, ctx: &mut EventCtx) {
+        let rows: Vec<_> = self.rows().collect();
+        let mut list = ctx.get_mut(&mut self.list);
+        list.clear();
+        for row in rows {
+            list.add_child(row);
+        }
+    }
+
+    fn check_query_changed(&mut self, ctx: &mut EventCtx) {
+        let query = self.text_box.widget().text();
+        if query == self.query {
+            return;
+        }
+        self.query = query;
+        self.update_matches();
+        self.rebuild_list(ctx);
+    }
+
+    fn submit_selected(&mut self, ctx: &mut EventCtx) {
+        if let Some(&i) = self.matches.get(self.selected) {
+            let (title, command) = &self.entries[i];
+            trace!("CommandPalette {:?} submitted {}", ctx.widget_id(), title);
+            ctx.submit_command(command.clone());
+        }
+    }
+}
+
+impl Default for CommandPalette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Score how well `candidate` matches `query`, or return `None` if it doesn't match.
+///
+/// All the (non-whitespace) characters of the query must appear in the candidate,
+/// in order, ignoring case. Higher scores are better: matches at the start of a
+/// word and runs of consecutive matches score higher.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut prev_match = None;
+
+    for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+        let pos = (next..candidate.len())
+            .find(|&pos| candidate[pos].to_lowercase().eq(query_char.to_lowercase()))?;
+
+        score += 1;
+        if pos == 0 || !candidate[pos - 1].is_alphanumeric() {
+            score += 3;
+        }
+        if prev_match.is_some() && prev_match == pos.checked_sub(1) {
+            score += 5;
+        }
+        prev_match = Some(pos);
+        next = pos + 1;
+    }
+    Some(score)
+}
+
+impl<'a, 'b> CommandPaletteMut<'a, 'b> {
+    pub fn text_box_mut(&mut self) -> WidgetMut<'_, 'b, TextBox> {
+        self.0.get_mut(&mut self.1.text_box)
+    }
+}
+
+impl Widget for CommandPalette {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        if let Event::KeyDown(key) = event {
+            let handled = match key.key {
+                KbKey::ArrowDown | KbKey::ArrowUp if !self.matches.is_empty() => {
+                    let len = self.matches.len();
+                    self.selected = if key.key == KbKey::ArrowDown {
+                        (self.selected + 1) % len
+                    } else {
+                        (self.selected + len - 1) % len
+                    };
+                    self.rebuild_list(ctx);
+                    true
+                }
+                KbKey::Enter => {
+                    self.submit_selected(ctx);
+                    true
+                }
+                _ => false,
+            };
+            if handled {
+                // The key was for the palette, not for the text box.
+                ctx.skip_child(&mut self.text_box);
+                ctx.skip_child(&mut self.list);
+                ctx.set_handled();
+                return;
+            }
+        }
+
+        self.text_box.on_event(ctx, event, env);
+        self.list.on_event(ctx, event, env);
+        self.check_query_changed(ctx);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        self.text_box.lifecycle(ctx, event, env);
+        self.list.lifecycle(ctx, event, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let text_box_bc = bc.loosen();
+        let text_box_size = self.text_box.layout(ctx, &text_box_bc, env);
+        ctx.place_child(&mut self.text_box, Point::ORIGIN, env);
+
+        let width = bc.constrain(text_box_size).width;
+        let list_bc = BoxConstraints::new(
+            Size::new(width, 0.0),
+            Size::new(width, (bc.max().height - text_box_size.height).max(0.0)),
+        );
+        let list_size = self.list.layout(ctx, &list_bc, env);
+        ctx.place_child(&mut self.list, Point::new(0.0, text_box_size.height), env);
+
+        bc.constrain(Size::new(width, text_box_size.height + list_size.height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        self.text_box.paint(ctx, env);
+        self.list.paint(ctx, env);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.text_box.as_dyn(), self.list.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("CommandPalette")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.query.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::RawMods;
+    use crate::testing::{widget_ids, Record, Recording, TestHarness, TestWidgetExt as _};
+    use crate::{Selector, WidgetId};
+
+    const OPEN_FILE: Selector = Selector::new("masonry-test.open-file");
+    const CLOSE_WINDOW: Selector = Selector::new("masonry-test.close-window");
+    const FORMAT_DOCUMENT: Selector = Selector::new("masonry-test.format-document");
+
+    fn palette_with_target(
+        palette_id: WidgetId,
+        target_id: WidgetId,
+        recording: &Recording,
+    ) -> Flex {
+        let palette = CommandPalette::new()
+            .with_command("Open File", OPEN_FILE.to(target_id))
+            .with_command("Close Window", CLOSE_WINDOW.to(target_id))
+            .with_command("Format Document", FORMAT_DOCUMENT.to(target_id))
+            .with_id(palette_id);
+        Flex::column()
+            .with_child(palette)
+            .with_child(Label::new("target").record(recording).with_id(target_id))
+    }
+
+    fn match_titles(harness: &TestHarness, palette_id: WidgetId) -> Vec<String> {
+        let palette = harness.get_widget(palette_id);
+        let palette = palette.downcast::<CommandPalette>().unwrap();
+        palette
+            .matches
+            .iter()
+            .map(|&i| palette.entries[i].0.to_string())
+            .collect()
+    }
+
+    fn received_commands(recording: &Recording) -> Vec<Command> {
+        recording
+            .drain()
+            .into_iter()
+            .filter_map(|record| match record {
+                Record::E(Event::Command(command)) => Some(command),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fuzzy_scores() {
+        assert_eq!(fuzzy_score("", "Open File"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "Open File"), None);
+        assert_eq!(fuzzy_score("fo", "Open File"), None);
+        assert!(fuzzy_score("of", "Open File").is_some());
+        // Consecutive matches at the start of a word beat scattered matches.
+        assert!(fuzzy_score("fil", "Open File") > fuzzy_score("fil", "Format Detail"));
+        assert_eq!(
+            fuzzy_score("OPEN", "open file"),
+            fuzzy_score("open", "Open File")
+        );
+    }
+
+    #[test]
+    fn filter_and_submit() {
+        let [palette_id, target_id] = widget_ids();
+        let recording = Recording::default();
+        let mut harness =
+            TestHarness::create(palette_with_target(palette_id, target_id, &recording));
+
+        let text_box_id = harness.get_widget(palette_id).children()[0].id();
+        harness.mouse_click_on(text_box_id);
+        assert_eq!(
+            match_titles(&harness, palette_id),
+            ["Open File", "Close Window", "Format Document"]
+        );
+
+        harness.keyboard_type_chars("fi");
+        assert_eq!(match_titles(&harness, palette_id), ["Open File"]);
+
+        recording.clear();
+        harness.keyboard_press_key(KbKey::Enter, RawMods::None);
+        let commands = received_commands(&recording);
+        assert_eq!(commands.len(), 1);
+        assert!(commands[0].is(OPEN_FILE));
+    }
+
+    #[test]
+    fn keyboard_navigation() {
+        let [palette_id, target_id] = widget_ids();
+        let recording = Recording::default();
+        let mut harness =
+            TestHarness::create(palette_with_target(palette_id, target_id, &recording));
+
+        let text_box_id = harness.get_widget(palette_id).children()[0].id();
+        harness.mouse_click_on(text_box_id);
+        harness.keyboard_type_chars("o");
+        assert_eq!(
+            match_titles(&harness, palette_id),
+            ["Open File", "Close Window", "Format Document"]
+        );
+
+        harness.keyboard_press_key(KbKey::ArrowDown, RawMods::None);
+        harness.keyboard_press_key(KbKey::ArrowDown, RawMods::None);
+        harness.keyboard_press_key(KbKey::ArrowUp, RawMods::None);
+
+        recording.clear();
+        harness.keyboard_press_key(KbKey::Enter, RawMods::None);
+        let commands = received_commands(&recording);
+        assert_eq!(commands.len(), 1);
+        assert!(commands[0].is(CLOSE_WINDOW));
+    }
+}
@@ -11,8 +11,9 @@ use crate::kurbo::common::FloatExt;
 use crate::kurbo::Vec2;
 use crate::widget::{WidgetMut, WidgetRef};
 use crate::{
-    BoxConstraints, Data, Env, Event, EventCtx, KeyOrValue, LayoutCtx, LifeCycle, LifeCycleCtx,
-    PaintCtx, Point, Rect, RenderContext, Size, StatusChange, Widget, WidgetId, WidgetPod,
+    ArcStr, BoxConstraints, Data, Env, Event, EventCtx, KeyOrValue, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, Rect, RenderContext, Size, StatusChange, Widget, WidgetId,
+    WidgetPod,
 };
 
 /// A container with either horizontal or vertical layout.
@@ -24,6 +25,7 @@ pub struct Flex {
     main_alignment: MainAxisAlignment,
     fill_major_axis: bool,
     children: Vec<Child>,
+    empty_placeholder: Option<WidgetPod<Box<dyn Widget>>>,
 }
 
 crate::declare_widget!(FlexMut, Flex);
@@ -103,6 +105,7 @@ impl Flex {
             cross_alignment: CrossAxisAlignment::Center,
             main_alignment: MainAxisAlignment::Start,
             fill_major_axis: false,
+            empty_placeholder: None,
         }
     }
 
@@ -144,6 +147,16 @@ impl Flex {
         self
     }
 
+    /// Builder-style method to set a widget shown in place of the children when
+    /// there are none, eg a "No items" label.
+    ///
+    /// Spacers don't count as children: the placeholder is shown as long as no
+    /// widget has been added.
+    pub fn empty_placeholder(mut self, placeholder: impl Widget) -> Self {
+        self.empty_placeholder = Some(WidgetPod::new(Box::new(placeholder)));
+        self
+    }
+
     /// Builder-style variant of `add_child`.
     ///
     /// Convenient for assembling a group of widgets in a single expression.
@@ -156,6 +169,19 @@ impl Flex {
         self
     }
 
+    /// Builder-style variant of `add_keyed_child`.
+    ///
+    /// When the children are later reconciled with a new list, with `reconcile`, the
+    /// child with the same key is kept, along with its state.
+    pub fn with_keyed_child(mut self, key: impl Into<ArcStr>, child: impl Widget) -> Self {
+        let child = Child::Fixed {
+            widget: WidgetPod::new_with_key(Box::new(child), key),
+            alignment: None,
+        };
+        self.children.push(child);
+        self
+    }
+
     /// Builder-style variant of `add_child`, that takes the id that the child will have.
     ///
     /// Useful for unit tests.
@@ -236,6 +262,12 @@ impl Flex {
     }
 }
 
+impl Flex {
+    fn shows_placeholder(&self) -> bool {
+        self.empty_placeholder.is_some() && self.children.iter().all(|c| c.widget().is_none())
+    }
+}
+
 // --- Mutate live Flex - WidgetMut ---
 
 impl<'a, 'b> FlexMut<'a, 'b> {
@@ -265,6 +297,25 @@ impl<'a, 'b> FlexMut<'a, 'b> {
         self.0.widget_state.needs_layout = true;
     }
 
+    /// Set the widget shown in place of the children when there are none.
+    ///
+    /// See also [`empty_placeholder`](Flex::empty_placeholder).
+    pub fn set_empty_placeholder(&mut self, placeholder: impl Widget) {
+        let placeholder = WidgetPod::new(placeholder).boxed();
+        if let Some(old_placeholder) = self.1.empty_placeholder.replace(placeholder) {
+            self.0.remove_child(old_placeholder);
+        }
+        self.0.children_changed();
+    }
+
+    /// Remove the widget shown in place of the children when there are none.
+    pub fn remove_empty_placeholder(&mut self) {
+        if let Some(old_placeholder) = self.1.empty_placeholder.take() {
+            self.0.remove_child(old_placeholder);
+        }
+        self.0.children_changed();
+    }
+
     /// Add a non-flex child widget.
     ///
     /// See also [`with_child`].
@@ -292,6 +343,72 @@ impl<'a, 'b> FlexMut<'a, 'b> {
         self.0.widget_state.needs_layout = true;
     }
 
+    /// Add a non-flex child widget with a key.
+    ///
+    /// See [`reconcile`](Self::reconcile) for what keys are used for.
+    pub fn add_keyed_child(&mut self, key: impl Into<ArcStr>, child: impl Widget) {
+        let child = Child::Fixed {
+            widget: WidgetPod::new_with_key(Box::new(child), key),
+            alignment: None,
+        };
+        self.1.children.push(child);
+        self.0.widget_state.children_changed = true;
+        self.0.widget_state.needs_layout = true;
+    }
+
+    /// Update the children to match a new list of keyed children.
+    ///
+    /// The new list is diffed against the existing children by key. When an existing
+    /// child has the same key as a new one, the existing child is kept, with its flex
+    /// parameters, and moved to the new position; the new widget is dropped. This
+    /// preserves everything about the child: its id, focus, scroll position, selection,
+    /// etc. Only new keys add children, and only existing children whose key isn't in
+    /// the new list are removed, along with unkeyed children and spacers.
+    ///
+    /// New children are added as non-flex children.
+    pub fn reconcile<K: Into<ArcStr>>(
+        &mut self,
+        items: impl IntoIterator<Item = (K, Box<dyn Widget>)>,
+    ) {
+        let mut old_children = std::mem::take(&mut self.1.children);
+        let old_ids: Vec<WidgetId> = old_children
+            .iter()
+            .filter_map(|child| child.widget().map(|widget| widget.id()))
+            .collect();
+
+        for (key, widget) in items {
+            let key = key.into();
+            let existing = old_children
+                .iter()
+                .position(|child| child.widget().and_then(|widget| widget.key()) == Some(&key));
+            let child = match existing {
+                Some(idx) => old_children.remove(idx),
+                None => Child::Fixed {
+                    widget: WidgetPod::new_with_key(widget, key),
+                    alignment: None,
+                },
+            };
+            self.1.children.push(child);
+        }
+
+        for child in old_children {
+            if let Some(widget) = child.into_widget() {
+                self.0.remove_child(widget);
+            }
+        }
+
+        // Reordering children changes the focus chain, even if no child was added.
+        let new_ids = self
+            .1
+            .children
+            .iter()
+            .filter_map(|child| child.widget().map(|widget| widget.id()));
+        if !new_ids.eq(old_ids) {
+            self.0.children_changed();
+        }
+        self.0.widget_state.needs_layout = true;
+    }
+
     /// Add a flexible child widget.
     pub fn add_flex_child(&mut self, child: impl Widget, params: impl Into<FlexParams>) {
         let params = params.into();
@@ -458,7 +575,10 @@ impl<'a, 'b> FlexMut<'a, 'b> {
     }
 
     pub fn remove_child(&mut self, idx: usize) {
-        self.1.children.remove(idx);
+        if let Some(widget) = self.1.children.remove(idx).into_widget() {
+            self.0.remove_child(widget);
+        }
+        self.0.widget_state.children_changed = true;
         self.0.widget_state.needs_layout = true;
     }
 
@@ -474,7 +594,12 @@ impl<'a, 'b> FlexMut<'a, 'b> {
     }
 
     pub fn clear(&mut self) {
-        self.1.children.clear();
+        for child in std::mem::take(&mut self.1.children) {
+            if let Some(widget) = child.into_widget() {
+                self.0.remove_child(widget);
+            }
+        }
+        self.0.widget_state.children_changed = true;
         self.0.widget_state.needs_layout = true;
     }
 }
@@ -484,6 +609,14 @@ impl Widget for Flex {
         for child in self.children.iter_mut().filter_map(|x| x.widget_mut()) {
             child.on_event(ctx, event, env);
         }
+        let shows_placeholder = self.shows_placeholder();
+        if let Some(placeholder) = &mut self.empty_placeholder {
+            if shows_placeholder || event.should_propagate_to_hidden() {
+                placeholder.on_event(ctx, event, env);
+            } else {
+                ctx.skip_child(placeholder);
+            }
+        }
     }
 
     fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
@@ -492,9 +625,29 @@ impl Widget for Flex {
         for child in self.children.iter_mut().filter_map(|x| x.widget_mut()) {
             child.lifecycle(ctx, event, env);
         }
+        let shows_placeholder = self.shows_placeholder();
+        if let Some(placeholder) = &mut self.empty_placeholder {
+            if shows_placeholder || event.should_propagate_to_hidden() {
+                placeholder.lifecycle(ctx, event, env);
+            } else {
+                ctx.skip_child(placeholder);
+            }
+        }
     }
 
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let shows_placeholder = self.shows_placeholder();
+        if let Some(placeholder) = &mut self.empty_placeholder {
+            if shows_placeholder {
+                let size = bc.constrain(placeholder.layout(ctx, &bc.loosen(), env));
+                ctx.place_child(placeholder, Point::ORIGIN, env);
+                ctx.set_baseline_offset(placeholder.baseline_offset());
+                trace!("Computed layout with placeholder: size={}", size);
+                return size;
+            }
+            ctx.skip_child(placeholder);
+        }
+
         // we loosen our constraints when passing to children.
         let loosened_bc = bc.loosen();
 
@@ -697,6 +850,14 @@ impl Widget for Flex {
         for child in self.children.iter_mut().filter_map(|x| x.widget_mut()) {
             child.paint(ctx, env);
         }
+        let shows_placeholder = self.shows_placeholder();
+        if let Some(placeholder) = &mut self.empty_placeholder {
+            if shows_placeholder {
+                placeholder.paint(ctx, env);
+            } else {
+                ctx.skip_child(placeholder);
+            }
+        }
 
         // paint the baseline if we're debugging layout
         if env.get(Env::DEBUG_PAINT) && ctx.widget_state.baseline_offset != 0.0 {
@@ -712,6 +873,7 @@ impl Widget for Flex {
         self.children
             .iter()
             .filter_map(|child| child.widget())
+            .chain(&self.empty_placeholder)
             .map(|widget_pod| widget_pod.as_dyn())
             .collect()
     }
@@ -998,14 +1160,90 @@ impl Child {
             _ => None,
         }
     }
+    fn into_widget(self) -> Option<WidgetPod<Box<dyn Widget>>> {
+        match self {
+            Child::Fixed { widget, .. } | Child::Flex { widget, .. } => Some(widget),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::assert_render_snapshot;
-    use crate::testing::TestHarness;
-    use crate::widget::Label;
+    use crate::event_log::EventKind;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::{Label, SizedBox, TextBox};
+
+    #[test]
+    fn reconcile_keeps_keyed_children() {
+        let widget = Flex::column()
+            .with_keyed_child("a", TextBox::new("a"))
+            .with_keyed_child("b", TextBox::new("b"));
+        let mut harness = TestHarness::create(widget);
+
+        let id_of = |harness: &TestHarness, idx: usize| harness.root_widget().children()[idx].id();
+        let text_of = |harness: &TestHarness, idx: usize| {
+            let root = harness.root_widget();
+            let child = root.children()[idx];
+            child.downcast::<TextBox>().unwrap().text()
+        };
+        let id_a = id_of(&harness, 0);
+        let id_b = id_of(&harness, 1);
+
+        harness.edit_root_widget(|mut flex, _| {
+            let mut flex = flex.downcast::<Flex>().unwrap();
+            let mut child = flex.child_mut(0).unwrap();
+            let mut textbox = child.downcast::<TextBox>().unwrap();
+            textbox.set_text("edited");
+        });
+
+        // The rebuild creates fresh widgets, in a different order.
+        harness.edit_root_widget(|mut flex, _| {
+            let mut flex = flex.downcast::<Flex>().unwrap();
+            flex.reconcile([
+                ("c", Box::new(TextBox::new("c")) as Box<dyn Widget>),
+                ("a", Box::new(TextBox::new("a"))),
+            ]);
+        });
+
+        assert_eq!(harness.root_widget().children().len(), 2);
+        assert_ne!(id_of(&harness, 0), id_b);
+        assert_eq!(text_of(&harness, 0), "c");
+        assert_eq!(id_of(&harness, 1), id_a);
+        assert_eq!(text_of(&harness, 1), "edited");
+        assert!(harness.try_get_widget(id_b).is_none());
+    }
+
+    #[test]
+    fn reconcile_reordered_list() {
+        let widget = Flex::column()
+            .with_keyed_child("a", TextBox::new("a"))
+            .with_keyed_child("b", TextBox::new("b"))
+            .with_keyed_child("c", TextBox::new("c"));
+        let mut harness = TestHarness::create(widget);
+
+        let child_ids = |harness: &TestHarness| -> Vec<WidgetId> {
+            let root = harness.root_widget();
+            root.children().iter().map(|child| child.id()).collect()
+        };
+        let old_ids = child_ids(&harness);
+
+        harness.edit_root_widget(|mut flex, _| {
+            let mut flex = flex.downcast::<Flex>().unwrap();
+            flex.reconcile(["c", "a", "b"].map(|key| {
+                let textbox: Box<dyn Widget> = Box::new(TextBox::new(key));
+                (key, textbox)
+            }));
+        });
+
+        let new_ids = vec![old_ids[2], old_ids[0], old_ids[1]];
+        assert_eq!(child_ids(&harness), new_ids);
+        // Tab order follows the new order.
+        let focus_ids: Vec<WidgetId> = harness.window().focus_chain().to_vec();
+        assert_eq!(focus_ids, new_ids);
+    }
 
     #[test]
     #[allow(clippy::cognitive_complexity)]
@@ -1391,4 +1629,69 @@ mod tests {
 
         // TODO - test out-of-bounds access?
     }
+
+    #[test]
+    fn remove_child_frees_memory() {
+        let widget = Flex::column()
+            .with_child(Label::new("hello"))
+            .with_child(Label::new("world"))
+            .with_child(Label::new("!"));
+
+        let mut harness = TestHarness::create(widget);
+        let initial_memory = harness.estimated_memory();
+
+        harness.edit_root_widget(|mut flex, _| {
+            let mut flex = flex.downcast::<Flex>().unwrap();
+            flex.remove_child(2);
+        });
+        let memory = harness.estimated_memory();
+        assert!(memory < initial_memory);
+
+        harness.edit_root_widget(|mut flex, _| {
+            let mut flex = flex.downcast::<Flex>().unwrap();
+            flex.clear();
+        });
+        assert!(harness.estimated_memory() < memory);
+    }
+
+    #[test]
+    fn empty_placeholder() {
+        let [placeholder_id] = widget_ids();
+        let placeholder = SizedBox::empty()
+            .width(50.0)
+            .height(20.0)
+            .with_id(placeholder_id);
+        let widget = Flex::column().empty_placeholder(placeholder);
+
+        let mut harness = TestHarness::create(widget);
+        harness.set_record_events(true);
+        harness.mouse_move((10.0, 10.0));
+        assert!(harness
+            .events_received(placeholder_id)
+            .contains(&EventKind::Mouse));
+
+        harness.edit_root_widget(|mut flex, _| {
+            let mut flex = flex.downcast::<Flex>().unwrap();
+            flex.add_child(Label::new("First item"));
+        });
+        harness.set_record_events(true);
+        harness.mouse_move((11.0, 11.0));
+        assert_eq!(harness.events_received(placeholder_id), []);
+    }
+
+    #[test]
+    fn remove_empty_placeholder() {
+        let [placeholder_id] = widget_ids();
+        let widget = Flex::column().empty_placeholder(TextBox::new("").with_id(placeholder_id));
+
+        let mut harness = TestHarness::create(widget);
+        assert_eq!(harness.window().focus_chain(), &[placeholder_id]);
+
+        harness.edit_root_widget(|mut flex, _| {
+            let mut flex = flex.downcast::<Flex>().unwrap();
+            flex.remove_empty_placeholder();
+        });
+        assert!(harness.try_get_widget(placeholder_id).is_none());
+        assert!(harness.window().focus_chain().is_empty());
+    }
 }
@@ -0,0 +1,231 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A vector icon widget.
+
+use smallvec::SmallVec;
+use tracing::trace;
+
+use crate::kurbo::{BezPath, Shape, SvgParseError};
+use crate::widget::WidgetRef;
+use crate::{
+    theme, Affine, BoxConstraints, Color, Env, Event, EventCtx, KeyOrValue, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Rect, RenderContext, Size, StatusChange, Vec2, Widget,
+};
+
+/// A widget that fills a vector path, scaled to fit its size and centered.
+///
+/// Unlike [`Image`](crate::widget::Image), an icon stays crisp at any size, and is drawn
+/// with a single color: [`theme::ICON_COLOR`] unless another color is set.
+///
+/// The icon's natural size is the size of its view box, which defaults to the bounding box
+/// of the path. To customize the icon's size, you can place it inside a [`SizedBox`]
+/// that has a fixed width and height.
+///
+/// [`SizedBox`]: crate::widget::SizedBox
+pub struct Icon {
+    path: BezPath,
+    view_box: Rect,
+    color: KeyOrValue<Color>,
+}
+
+crate::declare_widget!(IconMut, Icon);
+
+impl Icon {
+    /// Create an icon from a path.
+    pub fn new(path: BezPath) -> Icon {
+        Icon {
+            view_box: path.bounding_box(),
+            path,
+            color: theme::ICON_COLOR.into(),
+        }
+    }
+
+    /// Create an icon from SVG path data, eg the `d` attribute of a `<path>` element.
+    pub fn from_svg_path(svg_path: &str) -> Result<Icon, SvgParseError> {
+        Ok(Icon::new(BezPath::from_svg(svg_path)?))
+    }
+
+    /// Builder-style method for setting the icon's color.
+    ///
+    /// The argument can be either a `Color` or a [`Key<Color>`].
+    ///
+    /// [`Key<Color>`]: ../struct.Key.html
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Builder-style method for setting the area of the path's coordinate space that is
+    /// scaled to fit the widget.
+    ///
+    /// Icon sets usually draw their paths in a fixed grid, eg `(0, 0)` to `(24, 24)`, with some
+    /// padding around the visible shape. Using that grid as the view box keeps the padding,
+    /// and keeps icons of the same set consistently sized.
+    pub fn with_view_box(mut self, view_box: Rect) -> Self {
+        self.view_box = view_box;
+        self
+    }
+}
+
+impl IconMut<'_, '_> {
+    /// Set the icon's path.
+    ///
+    /// This also resets the view box to the bounding box of the new path.
+    pub fn set_path(&mut self, path: BezPath) {
+        self.1.view_box = path.bounding_box();
+        self.1.path = path;
+        self.0.request_layout();
+    }
+
+    /// Set the icon's color.
+    ///
+    /// The argument can be either a `Color` or a [`Key<Color>`].
+    ///
+    /// [`Key<Color>`]: ../struct.Key.html
+    pub fn set_color(&mut self, color: impl Into<KeyOrValue<Color>>) {
+        self.1.color = color.into();
+        self.0.request_paint();
+    }
+
+    /// Set the icon's view box.
+    ///
+    /// See [`Icon::with_view_box`].
+    pub fn set_view_box(&mut self, view_box: Rect) {
+        self.1.view_box = view_box;
+        self.0.request_layout();
+    }
+}
+
+impl Icon {
+    /// The transform that scales the view box to fit in `size`, centered.
+    fn transform(&self, size: Size) -> Affine {
+        let view_box_size = self.view_box.size();
+        if view_box_size.width <= 0.0 || view_box_size.height <= 0.0 {
+            return Affine::IDENTITY;
+        }
+        let scale = (size.width / view_box_size.width).min(size.height / view_box_size.height);
+        let offset = Vec2::new(
+            (size.width - view_box_size.width * scale) / 2.0,
+            (size.height - view_box_size.height * scale) / 2.0,
+        );
+        Affine::translate(offset)
+            * Affine::scale(scale)
+            * Affine::translate(-self.view_box.origin().to_vec2())
+    }
+}
+
+impl Widget for Icon {
+    fn on_event(&mut self, _ctx: &mut EventCtx, _event: &Event, _env: &Env) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _env: &Env) {}
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _env: &Env) -> Size {
+        let size = bc.constrain(self.view_box.size());
+
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        let color = self.color.resolve(env);
+        let path = self.transform(ctx.size()) * self.path.clone();
+        ctx.fill(path, &color);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        SmallVec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_render_snapshot;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::{Flex, SizedBox};
+
+    // A 24x24 "plus" icon.
+    const PLUS: &str = "M11 5h2v6h6v2h-6v6h-2v-6h-6v-2h6z";
+
+    #[test]
+    fn icon_sizes_and_colors() {
+        let render = |size: f64, color: Color, name: &str| {
+            let icon = Icon::from_svg_path(PLUS)
+                .unwrap()
+                .with_view_box(Rect::new(0.0, 0.0, 24.0, 24.0))
+                .with_color(color);
+            let mut harness = TestHarness::create_with_size(icon, Size::new(size, size));
+            assert_render_snapshot!(harness, name);
+            harness.render()
+        };
+
+        let small_white = render(24.0, Color::WHITE, "small_white");
+        let small_purple = render(24.0, Color::PURPLE, "small_purple");
+        let large_white = render(48.0, Color::WHITE, "large_white");
+        let large_purple = render(48.0, Color::PURPLE, "large_purple");
+
+        // We don't use assert_eq because we don't want rich assert
+        assert!(small_white != small_purple);
+        assert!(large_white != large_purple);
+
+        // The center of the plus is filled at both sizes, the corners aren't.
+        let pixel = |image: &[u8], width: usize, x: usize, y: usize| {
+            let idx = (y * width + x) * 4;
+            [image[idx], image[idx + 1], image[idx + 2]]
+        };
+        let (r, g, b, _) = Color::PURPLE.as_rgba8();
+        assert_eq!(pixel(&small_purple, 24, 12, 12), [r, g, b]);
+        assert_eq!(pixel(&large_purple, 48, 24, 24), [r, g, b]);
+        assert_ne!(pixel(&large_purple, 48, 2, 2), [r, g, b]);
+    }
+
+    #[test]
+    fn edit_icon() {
+        let image_1 = {
+            let icon = Icon::from_svg_path(PLUS).unwrap().with_color(Color::PURPLE);
+
+            let mut harness = TestHarness::create_with_size(icon, Size::new(30.0, 30.0));
+            harness.render()
+        };
+
+        let image_2 = {
+            let icon = Icon::from_svg_path(PLUS).unwrap();
+
+            let mut harness = TestHarness::create_with_size(icon, Size::new(30.0, 30.0));
+
+            harness.edit_root_widget(|mut icon, _| {
+                let mut icon = icon.downcast::<Icon>().unwrap();
+                icon.set_color(Color::PURPLE);
+            });
+
+            harness.render()
+        };
+
+        // We don't use assert_eq because we don't want rich assert
+        assert!(image_1 == image_2);
+    }
+
+    #[test]
+    fn icon_color_theme_key() {
+        let [icon_id] = widget_ids();
+        let icon = Icon::from_svg_path(PLUS).unwrap().with_id(icon_id);
+        let widget = Flex::row().with_child(SizedBox::new(icon).width(24.0).height(24.0));
+
+        let mut harness = TestHarness::create(widget);
+        let before = harness.render_widget(icon_id);
+
+        harness.edit_env(|env| env.set(theme::ICON_COLOR, Color::PURPLE));
+        let after = harness.render_widget(icon_id);
+
+        assert!(before.raw_pixels() != after.raw_pixels());
+    }
+
+    #[test]
+    fn invalid_svg_path() {
+        assert!(Icon::from_svg_path("M 1 2 Q").is_err());
+    }
+}
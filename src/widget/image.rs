@@ -21,8 +21,9 @@ pub struct Image {
     image_data: ImageBuf,
     paint_data: Option<PietImage>,
     fill: FillStrat,
-    interpolation: InterpolationMode,
+    interpolation: Option<InterpolationMode>,
     clip_area: Option<Rect>,
+    preserve_aspect_ratio: bool,
 }
 
 crate::declare_widget!(ImageMut, Image);
@@ -31,7 +32,8 @@ impl Image {
     /// Create an image drawing widget from an image buffer.
     ///
     /// By default, the Image will scale to fit its box constraints ([`FillStrat::Fill`])
-    /// and will be scaled bilinearly ([`InterpolationMode::Bilinear`])
+    /// and will be scaled with [`PaintCtx::default_interpolation`], which is bilinear
+    /// unless the app [prefers crisp edges](crate::theme::PREFERS_CRISP_EDGES).
     ///
     /// The underlying `ImageBuf` uses `Arc` for buffer data, making it cheap to clone.
     #[inline]
@@ -40,8 +42,9 @@ impl Image {
             image_data,
             paint_data: None,
             fill: FillStrat::default(),
-            interpolation: InterpolationMode::Bilinear,
+            interpolation: None,
             clip_area: None,
+            preserve_aspect_ratio: false,
         }
     }
 
@@ -55,7 +58,7 @@ impl Image {
     /// Builder-style method for specifying the interpolation strategy.
     #[inline]
     pub fn interpolation_mode(mut self, interpolation: InterpolationMode) -> Self {
-        self.interpolation = interpolation;
+        self.interpolation = Some(interpolation);
         self
     }
 
@@ -67,6 +70,21 @@ impl Image {
         self.clip_area = clip_area;
         self
     }
+
+    /// Builder-style method for making the widget keep the image's aspect ratio.
+    ///
+    /// By default, when both the width and the height are bounded, the widget takes
+    /// the image's size clamped to the constraints, which can stretch the image, or
+    /// leave empty bands around it with [`FillStrat::Contain`]. With this set, the
+    /// image is instead scaled down to fit the constraints with its aspect ratio
+    /// preserved, so that eg a parent [`Flex`](crate::widget::Flex) is sized to the
+    /// image. The image is never scaled up beyond its own size, except to satisfy
+    /// minimum constraints.
+    #[inline]
+    pub fn preserve_aspect_ratio(mut self, preserve: bool) -> Self {
+        self.preserve_aspect_ratio = preserve;
+        self
+    }
 }
 
 impl<'a, 'b> ImageMut<'a, 'b> {
@@ -80,7 +98,7 @@ impl<'a, 'b> ImageMut<'a, 'b> {
     /// Modify the widget's interpolation mode.
     #[inline]
     pub fn set_interpolation_mode(&mut self, interpolation: InterpolationMode) {
-        self.1.interpolation = interpolation;
+        self.1.interpolation = Some(interpolation);
         self.0.request_paint();
     }
 
@@ -93,6 +111,15 @@ impl<'a, 'b> ImageMut<'a, 'b> {
         self.0.request_paint();
     }
 
+    /// Set whether the widget keeps the image's aspect ratio.
+    ///
+    /// See [`Image::preserve_aspect_ratio`].
+    #[inline]
+    pub fn set_preserve_aspect_ratio(&mut self, preserve: bool) {
+        self.1.preserve_aspect_ratio = preserve;
+        self.0.request_layout();
+    }
+
     /// Set new `ImageBuf`.
     #[inline]
     pub fn set_image_data(&mut self, image_data: ImageBuf) {
@@ -121,6 +148,11 @@ impl Widget for Image {
         } else if bc.is_height_bounded() && !bc.is_width_bounded() {
             let ratio = max.height / image_size.height;
             Size::new(ratio * image_size.width, max.height)
+        } else if self.preserve_aspect_ratio && !image_size.is_empty() {
+            let ratio = (max.width / image_size.width)
+                .min(max.height / image_size.height)
+                .min(1.0);
+            bc.constrain(image_size * ratio)
         } else {
             bc.constrain(self.image_data.size())
         };
@@ -128,6 +160,54 @@ impl Widget for Image {
         size
     }
 
+    fn compute_min_intrinsic_width(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        _height: f64,
+        _env: &Env,
+    ) -> f64 {
+        // Images can be scaled down to any size.
+        0.0
+    }
+
+    fn compute_max_intrinsic_width(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        height: f64,
+        _env: &Env,
+    ) -> f64 {
+        // Same as layout: a bounded height scales the image.
+        let image_size = self.image_data.size();
+        if height.is_finite() && image_size.height > 0.0 {
+            height / image_size.height * image_size.width
+        } else {
+            image_size.width
+        }
+    }
+
+    fn compute_min_intrinsic_height(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        _width: f64,
+        _env: &Env,
+    ) -> f64 {
+        0.0
+    }
+
+    fn compute_max_intrinsic_height(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        width: f64,
+        _env: &Env,
+    ) -> f64 {
+        let image_size = self.image_data.size();
+        if width.is_finite() && image_size.width > 0.0 {
+            width / image_size.width * image_size.height
+        } else {
+            image_size.height
+        }
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, _env: &Env) {
         let offset_matrix = self.fill.affine_to_fill(ctx.size(), self.image_data.size());
 
@@ -147,6 +227,9 @@ impl Widget for Image {
             // zero-sized image = nothing to draw
             return;
         }
+        let interpolation = self
+            .interpolation
+            .unwrap_or_else(|| ctx.default_interpolation());
         ctx.with_save(|ctx| {
             // we have to re-do this because the whole struct is moved into the closure.
             let piet_image = {
@@ -160,14 +243,10 @@ impl Widget for Image {
                     piet_image,
                     area,
                     self.image_data.size().to_rect(),
-                    self.interpolation,
+                    interpolation,
                 );
             } else {
-                ctx.draw_image(
-                    piet_image,
-                    self.image_data.size().to_rect(),
-                    self.interpolation,
-                );
+                ctx.draw_image(piet_image, self.image_data.size().to_rect(), interpolation);
             }
         });
     }
@@ -193,6 +272,7 @@ mod tests {
     use crate::piet::ImageFormat;
     use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
     use crate::theme::PRIMARY_LIGHT;
+    use crate::widget::{Flex, SizedBox};
 
     /// Painting an empty image shouldn't crash.
     #[test]
@@ -222,6 +302,30 @@ mod tests {
         assert_render_snapshot!(harness, "tall_paint");
     }
 
+    #[test]
+    fn aspect_ratio_in_flex() {
+        let [image_id, below_id] = widget_ids();
+        let image_data = ImageBuf::from_raw(vec![255; 3 * 400 * 200], ImageFormat::Rgb, 400, 200);
+        let widget = Flex::column()
+            .with_flex_child(
+                Image::new(image_data)
+                    .fill_mode(FillStrat::Contain)
+                    .preserve_aspect_ratio(true)
+                    .with_id(image_id),
+                1.0,
+            )
+            .with_child(SizedBox::empty().width(10.0).height(10.0).with_id(below_id));
+
+        let harness = TestHarness::create_with_size(widget, Size::new(100.0, 110.0));
+
+        // The image is scaled down to the available width, keeping its 2:1 ratio, and
+        // there's no empty band between it and the next child.
+        let image_rect = harness.get_widget(image_id).state().window_layout_rect();
+        assert_eq!(image_rect.size(), Size::new(100.0, 50.0));
+        let below_rect = harness.get_widget(below_id).state().window_layout_rect();
+        assert_eq!(below_rect.y0, image_rect.y1);
+    }
+
     #[test]
     fn edit_image_attributes() {
         let image_data = ImageBuf::from_raw(
@@ -297,4 +401,77 @@ mod tests {
         // We don't use assert_eq because we don't want rich assert
         assert!(render_1 == render_2);
     }
+
+    #[test]
+    fn crisp_edges_hint() {
+        // A 2x2 checkerboard, scaled up so interpolation is visible.
+        let image_data = ImageBuf::from_raw(
+            vec![255, 255, 255, 0, 0, 0, 0, 0, 0, 255, 255, 255],
+            ImageFormat::Rgb,
+            2,
+            2,
+        );
+
+        let mut harness =
+            TestHarness::create_with_size(Image::new(image_data.clone()), Size::new(40., 40.));
+        assert_render_snapshot!(harness, "scaled_smooth");
+        let smooth = harness.render();
+
+        let mut harness =
+            TestHarness::create_with_size(Image::new(image_data.clone()), Size::new(40., 40.));
+        harness.edit_env(|env| env.set(crate::theme::PREFERS_CRISP_EDGES, true));
+        assert_render_snapshot!(harness, "scaled_crisp");
+        let crisp = harness.render();
+
+        // The hint gives the same result as asking for nearest-neighbor explicitly.
+        let mut harness = TestHarness::create_with_size(
+            Image::new(image_data).interpolation_mode(InterpolationMode::NearestNeighbor),
+            Size::new(40., 40.),
+        );
+        let explicit = harness.render();
+
+        // We don't use assert_eq because we don't want rich assert
+        assert!(smooth != crisp);
+        assert!(crisp == explicit);
+    }
+
+    #[test]
+    fn crisp_edges_subtree_override() {
+        use smallvec::smallvec;
+
+        use crate::testing::ModularWidget;
+        use crate::{Point, WidgetPod};
+
+        let image_data = ImageBuf::from_raw(
+            vec![255, 255, 255, 0, 0, 0, 0, 0, 0, 255, 255, 255],
+            ImageFormat::Rgb,
+            2,
+            2,
+        );
+
+        // A container that paints its child as if the app preferred crisp edges.
+        let crisp_container = ModularWidget::new(WidgetPod::new(Image::new(image_data.clone())))
+            .event_fn(|child, ctx, event, env| child.on_event(ctx, event, env))
+            .lifecycle_fn(|child, ctx, event, env| child.lifecycle(ctx, event, env))
+            .layout_fn(|child, ctx, bc, env| {
+                let size = child.layout(ctx, bc, env);
+                ctx.place_child(child, Point::ZERO, env);
+                size
+            })
+            .paint_fn(|child, ctx, env| {
+                let env = env.clone().adding(crate::theme::PREFERS_CRISP_EDGES, true);
+                child.paint(ctx, &env);
+            })
+            .children_fn(|child| smallvec![child.as_dyn()]);
+        let mut harness = TestHarness::create_with_size(crisp_container, Size::new(40., 40.));
+        let overridden = harness.render();
+
+        let mut harness =
+            TestHarness::create_with_size(Image::new(image_data), Size::new(40., 40.));
+        harness.edit_env(|env| env.set(crate::theme::PREFERS_CRISP_EDGES, true));
+        let crisp = harness.render();
+
+        // We don't use assert_eq because we don't want rich assert
+        assert!(overridden == crisp);
+    }
 }
@@ -13,7 +13,7 @@ use smallvec::SmallVec;
 use tracing::{trace, trace_span, Span};
 
 use crate::kurbo::Vec2;
-use crate::text::{FontDescriptor, TextAlignment, TextLayout};
+use crate::text::{FontDescriptor, TextAlignment, TextLayout, TextLayoutInfo};
 use crate::widget::WidgetRef;
 use crate::{
     ArcStr, BoxConstraints, Color, Data, Env, Event, EventCtx, KeyOrValue, LayoutCtx, LifeCycle,
@@ -269,6 +269,34 @@ impl Widget for Label {
         size
     }
 
+    fn compute_min_intrinsic_width(&mut self, ctx: &mut LayoutCtx, height: f64, env: &Env) -> f64 {
+        match self.line_break_mode {
+            LineBreaking::WordWrap => {
+                // Wrapping at every opportunity leaves the widest word on the widest line.
+                self.text_layout.set_wrap_width(0.0);
+                self.text_layout.rebuild_if_needed(ctx.text(), env);
+                self.text_layout.layout_metrics().size.width + 2. * LABEL_X_PADDING
+            }
+            _ => self.compute_max_intrinsic_width(ctx, height, env),
+        }
+    }
+
+    fn compute_max_intrinsic_width(&mut self, ctx: &mut LayoutCtx, _height: f64, env: &Env) -> f64 {
+        self.text_layout.set_wrap_width(f64::INFINITY);
+        self.text_layout.rebuild_if_needed(ctx.text(), env);
+        self.text_layout.layout_metrics().size.width + 2. * LABEL_X_PADDING
+    }
+
+    fn compute_max_intrinsic_height(&mut self, ctx: &mut LayoutCtx, width: f64, env: &Env) -> f64 {
+        let wrap_width = match self.line_break_mode {
+            LineBreaking::WordWrap => width - LABEL_X_PADDING * 2.0,
+            _ => f64::INFINITY,
+        };
+        self.text_layout.set_wrap_width(wrap_width);
+        self.text_layout.rebuild_if_needed(ctx.text(), env);
+        self.text_layout.layout_metrics().size.height
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, _env: &Env) {
         let origin = Point::new(LABEL_X_PADDING, 0.0);
         let label_size = ctx.size();
@@ -290,6 +318,11 @@ impl Widget for Label {
     fn get_debug_text(&self) -> Option<String> {
         Some(self.current_text.to_string())
     }
+
+    fn text_layout_info(&self) -> Option<TextLayoutInfo> {
+        let origin = Point::new(LABEL_X_PADDING, 0.0);
+        Some(TextLayoutInfo::new(&self.text_layout, origin))
+    }
 }
 
 impl Data for LineBreaking {
@@ -305,10 +338,30 @@ mod tests {
 
     use super::*;
     use crate::assert_render_snapshot;
-    use crate::testing::TestHarness;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
     use crate::theme::{PRIMARY_DARK, PRIMARY_LIGHT};
     use crate::widget::{Flex, SizedBox};
 
+    #[test]
+    fn wrapped_label_line_count() {
+        let [label_id] = widget_ids();
+        let label = Label::new("The quick brown fox jumps over the lazy dog")
+            .with_line_break_mode(LineBreaking::WordWrap);
+        let harness =
+            TestHarness::create_with_size(label.with_id(label_id), Size::new(60.0, 400.0));
+        let layout = harness.text_layout(label_id);
+        assert!(layout.line_count() > 3);
+        assert!(layout
+            .line_rects()
+            .iter()
+            .all(|rect| rect.width() <= 60.0 - 2.0 * LABEL_X_PADDING));
+
+        // The caret at the start of the text is at the label's padding.
+        let caret = layout.caret_position(0).unwrap();
+        assert_eq!(caret.p0.x, LABEL_X_PADDING);
+        assert_eq!(layout.caret_position(100), None);
+    }
+
     #[test]
     fn simple_label() {
         let label = Label::new("Hello");
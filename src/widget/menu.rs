@@ -0,0 +1,519 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A vertical list of commands.
+
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+
+use crate::kurbo::{Insets, Rect};
+use crate::shell::KbKey;
+use crate::shortcut::Shortcut;
+use crate::widget::button::{shortcut_label, SHORTCUT_SPACING};
+use crate::widget::{Label, WidgetRef};
+use crate::{
+    theme, ArcStr, BoxConstraints, Command, Env, Event, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, RenderContext, Selector, Size, StatusChange, Widget, WidgetPod,
+};
+
+const ENTRY_PADDING: Insets = Insets::uniform_xy(8.0, 4.0);
+
+/// A vertical list of commands, as used in dropdown menus.
+///
+/// The entry under the mouse is highlighted. Clicking an entry submits its [`Command`],
+/// then sends a [`Menu::ENTRY_ACTIVATED`] notification so that the widget hosting the
+/// menu can close it.
+///
+/// When focused, the menu can be used with the keyboard: the up and down arrow keys move the
+/// highlight, wrapping around at either end, and Enter activates the highlighted entry.
+/// Typing a letter highlights the next entry whose title starts with it. Escape sends a
+/// [`Menu::DISMISSED`] notification.
+///
+/// Entries can display a keyboard [`Shortcut`], right-aligned after their title.
+pub struct Menu {
+    entries: Vec<MenuEntry>,
+    /// The highlighted entry, if any.
+    selected: Option<usize>,
+}
+
+struct MenuEntry {
+    title: ArcStr,
+    command: Command,
+    label: WidgetPod<Label>,
+    shortcut_label: Option<WidgetPod<Label>>,
+    /// The area of the entry's row, set during layout.
+    rect: Rect,
+}
+
+crate::declare_widget!(MenuMut, Menu);
+
+impl Menu {
+    /// Notification sent by the menu after one of its entries is activated.
+    pub const ENTRY_ACTIVATED: Selector = Selector::new("masonry-builtin.menu-entry-activated");
+
+    /// Notification sent by the menu when the user presses Escape.
+    pub const DISMISSED: Selector = Selector::new("masonry-builtin.menu-dismissed");
+
+    /// Create a new `Menu` with no entries.
+    pub fn new() -> Self {
+        Menu {
+            entries: Vec::new(),
+            selected: None,
+        }
+    }
+
+    /// Builder-style method to add an entry to the menu.
+    ///
+    /// `title` is the text shown to the user.
+    pub fn with_entry(mut self, title: impl Into<ArcStr>, command: impl Into<Command>) -> Self {
+        self.entries
+            .push(MenuEntry::new(title.into(), command.into()));
+        self
+    }
+
+    /// Builder-style method to add an entry displaying a keyboard shortcut.
+    ///
+    /// The shortcut's label (eg "Ctrl+S") is displayed right-aligned after the title.
+    /// This only displays the shortcut; it doesn't make the shortcut activate the entry.
+    pub fn with_shortcut_entry(
+        mut self,
+        title: impl Into<ArcStr>,
+        shortcut: Shortcut,
+        command: impl Into<Command>,
+    ) -> Self {
+        self.entries
+            .push(MenuEntry::new(title.into(), command.into()).with_shortcut(&shortcut));
+        self
+    }
+
+    /// Return the number of entries in the menu.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return `true` if the menu has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Return the title of the entry at the given index.
+    pub fn title(&self, idx: usize) -> Option<&ArcStr> {
+        self.entries.get(idx).map(|entry| &entry.title)
+    }
+
+    /// Return the index of the highlighted entry, if any.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Return the area of the entry at the given index, in the menu's coordinate space.
+    pub fn entry_rect(&self, idx: usize) -> Option<Rect> {
+        self.entries.get(idx).map(|entry| entry.rect)
+    }
+
+    fn entry_at(&self, pos: Point) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.rect.contains(pos))
+    }
+
+    /// Return the entry `offset` steps away from the highlighted one, wrapping around.
+    ///
+    /// With no highlighted entry, moving forward starts at the first entry, and moving
+    /// backward starts at the last one.
+    fn entry_after(&self, offset: isize) -> usize {
+        let len = self.entries.len() as isize;
+        let idx = match self.selected {
+            Some(idx) => idx as isize + offset,
+            None if offset > 0 => offset - 1,
+            None => len + offset,
+        };
+        idx.rem_euclid(len) as usize
+    }
+
+    /// Return the next entry after the highlighted one whose title starts with `prefix`,
+    /// ignoring case.
+    fn entry_starting_with(&self, prefix: &str) -> Option<usize> {
+        let prefix = prefix.to_lowercase();
+        let len = self.entries.len();
+        let start = self.selected.map_or(0, |idx| idx + 1);
+        (0..len)
+            .map(|offset| (start + offset) % len)
+            .find(|&idx| self.entries[idx].title.to_lowercase().starts_with(&prefix))
+    }
+
+    fn activate(&mut self, ctx: &mut EventCtx, idx: usize) {
+        let entry = &self.entries[idx];
+        trace!("Menu {:?} activated {}", ctx.widget_id(), entry.title);
+        ctx.submit_command(entry.command.clone());
+        ctx.submit_notification(Menu::ENTRY_ACTIVATED);
+    }
+}
+
+impl MenuEntry {
+    fn new(title: ArcStr, command: Command) -> Self {
+        MenuEntry {
+            label: WidgetPod::new(Label::new(title.clone())),
+            shortcut_label: None,
+            title,
+            command,
+            rect: Rect::ZERO,
+        }
+    }
+
+    fn with_shortcut(mut self, shortcut: &Shortcut) -> Self {
+        self.shortcut_label = Some(WidgetPod::new(shortcut_label(shortcut)));
+        self
+    }
+}
+
+impl Default for Menu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MenuMut<'_, '_> {
+    /// Add an entry at the end of the menu.
+    pub fn add_entry(&mut self, title: impl Into<ArcStr>, command: impl Into<Command>) {
+        self.1
+            .entries
+            .push(MenuEntry::new(title.into(), command.into()));
+        self.0.children_changed();
+    }
+
+    /// Add an entry displaying a keyboard shortcut at the end of the menu.
+    ///
+    /// See [`Menu::with_shortcut_entry`].
+    pub fn add_shortcut_entry(
+        &mut self,
+        title: impl Into<ArcStr>,
+        shortcut: Shortcut,
+        command: impl Into<Command>,
+    ) {
+        self.1
+            .entries
+            .push(MenuEntry::new(title.into(), command.into()).with_shortcut(&shortcut));
+        self.0.children_changed();
+    }
+
+    /// Remove all entries from the menu.
+    pub fn clear(&mut self) {
+        for entry in self.1.entries.drain(..) {
+            self.0.remove_child(entry.label);
+            if let Some(shortcut_label) = entry.shortcut_label {
+                self.0.remove_child(shortcut_label);
+            }
+        }
+        self.1.selected = None;
+        self.0.request_layout();
+    }
+
+    /// Highlight the entry at the given index, or no entry.
+    pub fn set_selected(&mut self, selected: Option<usize>) {
+        self.1.selected = selected.filter(|&idx| idx < self.1.entries.len());
+        self.0.request_paint();
+    }
+}
+
+impl Widget for Menu {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        match event {
+            Event::MouseMove(mouse) => {
+                let hovered = self.entry_at(mouse.pos);
+                if hovered.is_some() && hovered != self.selected {
+                    self.selected = hovered;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseDown(_) => {
+                ctx.set_active(true);
+            }
+            Event::MouseUp(mouse) => {
+                if ctx.is_active() {
+                    if let Some(idx) = self.entry_at(mouse.pos) {
+                        self.activate(ctx, idx);
+                    }
+                }
+                ctx.set_active(false);
+            }
+            Event::KeyDown(key) => {
+                let has_entries = !self.entries.is_empty();
+                match &key.key {
+                    KbKey::ArrowDown | KbKey::ArrowUp if has_entries => {
+                        let offset = if key.key == KbKey::ArrowDown { 1 } else { -1 };
+                        self.selected = Some(self.entry_after(offset));
+                        ctx.request_paint();
+                        ctx.set_handled();
+                    }
+                    KbKey::Enter => {
+                        if let Some(idx) = self.selected {
+                            self.activate(ctx, idx);
+                        }
+                        ctx.set_handled();
+                    }
+                    KbKey::Escape => {
+                        ctx.submit_notification(Menu::DISMISSED);
+                        ctx.set_handled();
+                    }
+                    KbKey::Character(chars) if has_entries && !key.mods.ctrl() => {
+                        if let Some(idx) = self.entry_starting_with(chars) {
+                            self.selected = Some(idx);
+                            ctx.request_paint();
+                        }
+                        ctx.set_handled();
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+
+        for entry in &mut self.entries {
+            entry.label.on_event(ctx, event, env);
+            if let Some(shortcut_label) = &mut entry.shortcut_label {
+                shortcut_label.on_event(ctx, event, env);
+            }
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        if let LifeCycle::BuildFocusChain = event {
+            ctx.register_for_focus();
+        }
+        for entry in &mut self.entries {
+            entry.label.lifecycle(ctx, event, env);
+            if let Some(shortcut_label) = &mut entry.shortcut_label {
+                shortcut_label.lifecycle(ctx, event, env);
+            }
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let label_bc = BoxConstraints::new(
+            Size::ZERO,
+            Size::new(
+                (bc.max().width - ENTRY_PADDING.x_value()).max(0.0),
+                f64::INFINITY,
+            ),
+        );
+        let sizes: Vec<(Size, Option<Size>)> = self
+            .entries
+            .iter_mut()
+            .map(|entry| {
+                let label_size = entry.label.layout(ctx, &label_bc, env);
+                let shortcut_size = entry
+                    .shortcut_label
+                    .as_mut()
+                    .map(|shortcut_label| shortcut_label.layout(ctx, &label_bc, env));
+                (label_size, shortcut_size)
+            })
+            .collect();
+
+        let label_width = sizes
+            .iter()
+            .map(|(label_size, _)| label_size.width)
+            .fold(0.0, f64::max);
+        let shortcut_width = sizes
+            .iter()
+            .filter_map(|(_, shortcut_size)| shortcut_size.map(|size| size.width))
+            .reduce(f64::max);
+        let width = match shortcut_width {
+            Some(shortcut_width) => label_width + SHORTCUT_SPACING + shortcut_width,
+            None => label_width,
+        };
+        let width = width + ENTRY_PADDING.x_value();
+        let width = width.clamp(bc.min().width, bc.max().width);
+
+        let mut y = 0.0;
+        for (entry, (label_size, shortcut_size)) in self.entries.iter_mut().zip(sizes) {
+            let content_height = label_size
+                .height
+                .max(shortcut_size.unwrap_or_default().height);
+            let height = content_height + ENTRY_PADDING.y_value();
+            entry.rect = Rect::new(0.0, y, width, y + height);
+            ctx.place_child(
+                &mut entry.label,
+                Point::new(ENTRY_PADDING.x0, y + ENTRY_PADDING.y0),
+                env,
+            );
+            // The title is left-aligned and the shortcut is right-aligned.
+            if let (Some(shortcut_label), Some(shortcut_size)) =
+                (&mut entry.shortcut_label, shortcut_size)
+            {
+                let shortcut_origin = Point::new(
+                    width - ENTRY_PADDING.x1 - shortcut_size.width,
+                    y + ENTRY_PADDING.y0,
+                );
+                ctx.place_child(shortcut_label, shortcut_origin, env);
+            }
+            y += height;
+        }
+
+        let size = bc.constrain(Size::new(width, y));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        let rect = ctx.size().to_rect();
+        ctx.fill(rect, &env.get(theme::BACKGROUND_LIGHT));
+
+        if let Some(entry) = self.selected.and_then(|idx| self.entries.get(idx)) {
+            ctx.fill(entry.rect, &env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR));
+        }
+        for entry in &mut self.entries {
+            entry.label.paint(ctx, env);
+            if let Some(shortcut_label) = &mut entry.shortcut_label {
+                shortcut_label.paint(ctx, env);
+            }
+        }
+
+        ctx.stroke(rect.inset(-0.5), &env.get(theme::BORDER_DARK), 1.0);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        self.entries
+            .iter()
+            .flat_map(|entry| {
+                std::iter::once(entry.label.as_dyn())
+                    .chain(entry.shortcut_label.as_ref().map(|label| label.as_dyn()))
+            })
+            .collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Menu")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        let entry = self.entries.get(self.selected?)?;
+        Some(entry.title.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_render_snapshot;
+    use crate::shell::RawMods;
+    use crate::testing::{widget_ids, Record, Recording, TestHarness, TestWidgetExt as _};
+    use crate::widget::Flex;
+    use crate::{WidgetId, WindowConfig};
+
+    const CUT: Selector = Selector::new("masonry-test.cut");
+    const COPY: Selector = Selector::new("masonry-test.copy");
+    const PASTE: Selector = Selector::new("masonry-test.paste");
+    const CLOSE: Selector = Selector::new("masonry-test.close");
+
+    fn focused_menu(recording: &Recording) -> (TestHarness, WidgetId) {
+        let [menu_id, target_id] = widget_ids();
+        let menu = Menu::new()
+            .with_entry("Cut", CUT.to(target_id))
+            .with_entry("Copy", COPY.to(target_id))
+            .with_entry("Paste", PASTE.to(target_id))
+            .with_entry("Close", CLOSE.to(target_id))
+            .with_id(menu_id);
+        let widget = Flex::column()
+            .with_child(menu.record(recording))
+            .with_child(Label::new("target").record(recording).with_id(target_id));
+
+        let config = WindowConfig::default().with_initial_focus(menu_id);
+        let harness = TestHarness::create_with_config(widget, config);
+        (harness, menu_id)
+    }
+
+    fn selected(harness: &TestHarness, menu_id: WidgetId) -> Option<usize> {
+        let menu = harness.get_widget(menu_id);
+        menu.downcast::<Menu>().unwrap().selected()
+    }
+
+    #[test]
+    fn arrow_navigation_wraps() {
+        let recording = Recording::default();
+        let (mut harness, menu_id) = focused_menu(&recording);
+        assert_eq!(selected(&harness, menu_id), None);
+
+        harness.keyboard_press_key(KbKey::ArrowDown, RawMods::None);
+        assert_eq!(selected(&harness, menu_id), Some(0));
+
+        harness.keyboard_press_key(KbKey::ArrowUp, RawMods::None);
+        assert_eq!(selected(&harness, menu_id), Some(3));
+
+        harness.keyboard_press_key(KbKey::ArrowDown, RawMods::None);
+        assert_eq!(selected(&harness, menu_id), Some(0));
+
+        harness.keyboard_press_key(KbKey::ArrowDown, RawMods::None);
+        harness.keyboard_press_key(KbKey::ArrowDown, RawMods::None);
+        assert_eq!(selected(&harness, menu_id), Some(2));
+    }
+
+    #[test]
+    fn type_ahead() {
+        let recording = Recording::default();
+        let (mut harness, menu_id) = focused_menu(&recording);
+
+        harness.keyboard_type_chars("p");
+        assert_eq!(selected(&harness, menu_id), Some(2));
+
+        // Typing the same letter again cycles through the matching entries.
+        harness.keyboard_type_chars("c");
+        assert_eq!(selected(&harness, menu_id), Some(3));
+        harness.keyboard_type_chars("C");
+        assert_eq!(selected(&harness, menu_id), Some(0));
+        harness.keyboard_type_chars("c");
+        assert_eq!(selected(&harness, menu_id), Some(1));
+
+        // No entry starts with "x".
+        harness.keyboard_type_chars("x");
+        assert_eq!(selected(&harness, menu_id), Some(1));
+    }
+
+    #[test]
+    fn enter_and_escape() {
+        let recording = Recording::default();
+        let (mut harness, _) = focused_menu(&recording);
+
+        harness.keyboard_type_chars("p");
+        recording.clear();
+        harness.keyboard_press_key(KbKey::Enter, RawMods::None);
+        let records = recording.drain();
+        assert!(records
+            .iter()
+            .any(|record| matches!(record, Record::E(Event::Command(cmd)) if cmd.is(PASTE))));
+        assert!(records.iter().any(|record| matches!(
+            record,
+            Record::E(Event::Notification(note)) if note.is(Menu::ENTRY_ACTIVATED)
+        )));
+
+        harness.keyboard_press_key(KbKey::Escape, RawMods::None);
+        assert!(recording.drain().iter().any(|record| matches!(
+            record,
+            Record::E(Event::Notification(note)) if note.is(Menu::DISMISSED)
+        )));
+    }
+
+    #[test]
+    fn menu_with_shortcuts() {
+        let [target_id] = widget_ids();
+        let menu = Menu::new()
+            .with_shortcut_entry("Cut", Shortcut::new(RawMods::Ctrl, "x"), CUT.to(target_id))
+            .with_shortcut_entry(
+                "Copy",
+                Shortcut::new(RawMods::Ctrl, "c"),
+                COPY.to(target_id),
+            )
+            .with_shortcut_entry(
+                "Paste",
+                Shortcut::new(RawMods::Ctrl, "v"),
+                PASTE.to(target_id),
+            )
+            .with_entry("Close", CLOSE.to(target_id));
+
+        let mut harness = TestHarness::create_with_size(menu, Size::new(150.0, 120.0));
+        assert_render_snapshot!(harness, "menu_with_shortcuts");
+    }
+}
@@ -6,6 +6,7 @@
 
 #[allow(clippy::module_inception)]
 mod widget;
+mod widget_ext;
 mod widget_mut;
 mod widget_pod;
 mod widget_ref;
@@ -15,36 +16,71 @@ mod widget_state;
 mod tests;
 
 mod align;
+mod aspect_ratio_box;
+mod avatar;
+mod badge;
 mod button;
 mod checkbox;
+mod command_palette;
 mod flex;
+mod icon;
 mod image;
 mod label;
+mod menu;
+mod number_field;
 mod portal;
+mod radio_group;
+mod resize_handle;
+mod rounded_clip;
+mod safe_area;
 mod scroll_bar;
 mod sized_box;
 mod spinner;
 mod split;
+mod table;
+mod text_area;
 mod textbox;
+mod toast;
+mod toolbar;
+mod visibility;
 mod web_image;
+mod when;
+mod window_drag_region;
 
 pub use align::Align;
+pub use aspect_ratio_box::AspectRatioBox;
+pub use avatar::Avatar;
+pub use badge::Badge;
 pub use button::Button;
 pub use checkbox::Checkbox;
+pub use command_palette::CommandPalette;
 pub use flex::{Axis, CrossAxisAlignment, Flex, FlexParams, MainAxisAlignment};
+pub use icon::Icon;
 pub use label::{Label, LineBreaking};
+pub use menu::Menu;
+pub use number_field::NumberField;
 pub use portal::Portal;
+pub use radio_group::RadioGroup;
+pub use resize_handle::ResizeHandle;
+pub use rounded_clip::RoundedClip;
+pub use safe_area::SafeArea;
 pub use scroll_bar::ScrollBar;
 pub use sized_box::SizedBox;
 pub use spinner::Spinner;
 pub use split::Split;
+pub use table::{ColumnWidth, Table, TableColumn};
+pub use text_area::TextArea;
 pub use textbox::TextBox;
+pub use toast::{Toast, ToastHost, ToastLevel};
+pub use toolbar::Toolbar;
+pub use visibility::Visibility;
 pub use web_image::WebImage;
+pub use when::When;
 pub use widget::StoreInWidgetMut;
 #[doc(hidden)]
 pub use widget::{Widget, WidgetId};
-//#[doc(hidden)]
-//pub use widget_ext::WidgetExt;
+pub use widget_ext::WidgetExt;
+pub use window_drag_region::WindowDragRegion;
 //pub use widget_wrapper::WidgetWrapper;
 pub use widget_mut::WidgetMut;
 pub use widget_pod::WidgetPod;
@@ -0,0 +1,422 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A text box for entering numbers.
+
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+
+use crate::action::Action;
+use crate::shell::KbKey;
+use crate::text::Selection;
+use crate::widget::{TextBox, WidgetMut, WidgetRef};
+use crate::{
+    BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Point,
+    Size, StatusChange, Widget, WidgetPod,
+};
+
+/// A [`TextBox`] that only accepts numbers.
+///
+/// Characters that can't be part of a number are rejected as they are typed.
+/// Each time the text changes to a valid number, the field emits
+/// [`Action::NumberChanged`]. When the field loses focus, its text is reformatted
+/// with the configured number of decimals and digit grouping.
+///
+/// The digit grouping and decimal separators default to `,` and `.`; use
+/// [`with_separators`](NumberField::with_separators) to match the user's locale.
+pub struct NumberField {
+    inner: WidgetPod<TextBox>,
+    value: f64,
+    decimals: usize,
+    min: f64,
+    max: f64,
+    group_separator: Option<char>,
+    decimal_separator: char,
+    /// The text of the inner TextBox last time we checked it.
+    last_text: String,
+    /// Whether the inner TextBox was focused last time we checked it.
+    had_focus: bool,
+}
+
+crate::declare_widget!(NumberFieldMut, NumberField);
+
+impl NumberField {
+    /// Create a new `NumberField` showing the given value, with no decimals.
+    pub fn new(value: f64) -> Self {
+        let mut this = NumberField {
+            inner: WidgetPod::new(TextBox::new("")),
+            value,
+            decimals: 0,
+            min: f64::NEG_INFINITY,
+            max: f64::INFINITY,
+            group_separator: Some(','),
+            decimal_separator: '.',
+            last_text: String::new(),
+            had_focus: false,
+        };
+        this.reset_text();
+        this
+    }
+
+    /// Builder-style method to set the number of decimals shown.
+    ///
+    /// If this is zero (the default), the field only accepts integers.
+    pub fn with_decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self.reset_text();
+        self
+    }
+
+    /// Builder-style method to set the range of accepted values.
+    ///
+    /// Values outside of the range are clamped to it.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        assert!(min <= max, "min must not be greater than max");
+        self.min = min;
+        self.max = max;
+        self.value = self.clamp(self.value);
+        self.reset_text();
+        self
+    }
+
+    /// Builder-style method to set the digit grouping and decimal separators.
+    ///
+    /// For instance, `with_separators(Some('.'), ',')` formats one million as `1.000.000,00`.
+    /// If `group_separator` is `None`, digits aren't grouped.
+    pub fn with_separators(
+        mut self,
+        group_separator: Option<char>,
+        decimal_separator: char,
+    ) -> Self {
+        assert!(
+            group_separator != Some(decimal_separator),
+            "group and decimal separators must be different"
+        );
+        self.group_separator = group_separator;
+        self.decimal_separator = decimal_separator;
+        self.reset_text();
+        self
+    }
+
+    /// The last valid value entered in the field.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Return the field's current text.
+    pub fn text(&self) -> String {
+        self.inner.widget().text()
+    }
+
+    // Only used in builder methods, before the TextBox is added to the widget tree.
+    fn reset_text(&mut self) {
+        self.last_text = self.format(self.value);
+        self.inner = WidgetPod::new(TextBox::new(self.last_text.clone()));
+    }
+
+    fn clamp(&self, value: f64) -> f64 {
+        value.max(self.min).min(self.max)
+    }
+
+    fn accepts_char(&self, c: char) -> bool {
+        c.is_ascii_digit()
+            || (c == '-' && self.min < 0.0)
+            || (c == self.decimal_separator && self.decimals > 0)
+            || Some(c) == self.group_separator
+    }
+
+    /// Parse the given text, returning `None` if it isn't a valid number.
+    ///
+    /// The returned value isn't clamped.
+    fn parse(&self, text: &str) -> Option<f64> {
+        let text: String = text
+            .trim()
+            .chars()
+            .filter(|c| Some(*c) != self.group_separator)
+            .map(|c| if c == self.decimal_separator { '.' } else { c })
+            .collect();
+        // Rust's parser accepts strings like "inf" or "1e5", which we don't.
+        if !text
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '-' || c == '.')
+        {
+            return None;
+        }
+        text.parse::<f64>().ok().filter(|value| value.is_finite())
+    }
+
+    fn format(&self, value: f64) -> String {
+        let digits = format!("{:.*}", self.decimals, value.abs());
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (digits.as_str(), None),
+        };
+
+        let mut text = String::new();
+        if value < 0.0 && digits.chars().any(|c| c != '0' && c != '.') {
+            text.push('-');
+        }
+        for (i, digit) in integer.chars().enumerate() {
+            if let Some(separator) = self.group_separator {
+                if i > 0 && (integer.len() - i) % 3 == 0 {
+                    text.push(separator);
+                }
+            }
+            text.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            text.push(self.decimal_separator);
+            text.push_str(fraction);
+        }
+        text
+    }
+
+    /// Emit an action if the user typed a new valid number.
+    fn check_text_changed(&mut self, ctx: &mut EventCtx) {
+        let mut text = self.inner.widget().text();
+        if text == self.last_text {
+            return;
+        }
+        // Pasted text doesn't go through key events, so it's filtered after the fact.
+        if !text.chars().all(|c| self.accepts_char(c)) {
+            let caret = self.inner.widget().selection().active.min(text.len());
+            let caret = text[..caret]
+                .chars()
+                .filter(|c| self.accepts_char(*c))
+                .map(char::len_utf8)
+                .sum();
+            text.retain(|c| self.accepts_char(c));
+            trace!(
+                "NumberField {:?} filtered text to {:?}",
+                ctx.widget_id(),
+                text
+            );
+
+            let mut text_box = ctx.get_mut(&mut self.inner);
+            text_box.set_text(text.clone());
+            text_box.set_selection(Selection::caret(caret));
+        }
+        if let Some(value) = self.parse(&text).map(|value| self.clamp(value)) {
+            if value != self.value {
+                trace!("NumberField {:?} changed to {}", ctx.widget_id(), value);
+                self.value = value;
+                ctx.submit_action(Action::NumberChanged(value));
+            }
+        }
+        self.last_text = text;
+    }
+
+    /// Replace the text with the formatted value.
+    fn format_text(&mut self, ctx: &mut LifeCycleCtx) {
+        let text = self.format(self.value);
+        if text != self.inner.widget().text() {
+            ctx.get_mut(&mut self.inner).set_text(text.clone());
+        }
+        self.last_text = text;
+    }
+}
+
+impl<'a, 'b> NumberFieldMut<'a, 'b> {
+    /// Set the value, clamping it to the field's range.
+    ///
+    /// This doesn't emit [`Action::NumberChanged`].
+    pub fn set_value(&mut self, value: f64) {
+        self.1.value = self.1.clamp(value);
+        let text = self.1.format(self.1.value);
+        self.1.last_text = text.clone();
+        self.text_box_mut().set_text(text);
+    }
+
+    /// Return a [`WidgetMut`] to the inner [`TextBox`].
+    ///
+    /// Text set through it isn't filtered or parsed.
+    pub fn text_box_mut(&mut self) -> WidgetMut<'_, 'b, TextBox> {
+        self.0.get_mut(&mut self.1.inner)
+    }
+}
+
+impl Widget for NumberField {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        if let Event::KeyDown(key) = event {
+            if let KbKey::Character(chars) = &key.key {
+                let is_shortcut = key.mods.ctrl() || key.mods.meta() || key.mods.alt();
+                if !is_shortcut && !chars.chars().all(|c| self.accepts_char(c)) {
+                    trace!("NumberField {:?} rejected {:?}", ctx.widget_id(), chars);
+                    ctx.skip_child(&mut self.inner);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+        }
+
+        self.inner.on_event(ctx, event, env);
+        self.check_text_changed(ctx);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        self.inner.lifecycle(ctx, event, env);
+
+        // We don't get a StatusChange when our TextBox loses focus, so we check
+        // the focus of our subtree instead.
+        let has_focus = ctx.has_focus();
+        if self.had_focus && !has_focus {
+            self.format_text(ctx);
+        }
+        self.had_focus = has_focus;
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let size = self.inner.layout(ctx, bc, env);
+        ctx.place_child(&mut self.inner, Point::ORIGIN, env);
+        ctx.set_baseline_offset(self.inner.baseline_offset());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        self.inner.paint(ctx, env);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.inner.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("NumberField")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::RawMods;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::Flex;
+    use crate::WidgetId;
+
+    fn pop_number_changed(harness: &mut TestHarness, id: WidgetId) -> Vec<f64> {
+        let mut values = Vec::new();
+        while let Some((action, action_id)) = harness.pop_action() {
+            if let (Action::NumberChanged(value), true) = (action, action_id == id) {
+                values.push(value);
+            }
+        }
+        values
+    }
+
+    #[test]
+    fn format_and_parse() {
+        let field = NumberField::new(0.0).with_decimals(2);
+        assert_eq!(field.format(1234567.891), "1,234,567.89");
+        assert_eq!(field.format(-12.0), "-12.00");
+        assert_eq!(field.format(-0.001), "0.00");
+        assert_eq!(field.parse("1,234.5"), Some(1234.5));
+        assert_eq!(field.parse("-"), None);
+        assert_eq!(field.parse("inf"), None);
+
+        let field = NumberField::new(0.0)
+            .with_decimals(1)
+            .with_separators(Some('.'), ',');
+        assert_eq!(field.format(1234.5), "1.234,5");
+        assert_eq!(field.parse("1.234,5"), Some(1234.5));
+        assert_eq!(field.text(), "0,0");
+    }
+
+    #[test]
+    fn reject_invalid_chars() {
+        let [field_id] = widget_ids();
+        let field = NumberField::new(0.0)
+            .with_range(0.0, 1000.0)
+            .with_id(field_id);
+
+        let mut harness = TestHarness::create(field);
+        let text_box_id = harness.get_widget(field_id).children()[0].id();
+        harness.mouse_click_on(field_id);
+        // Select the initial "0" so that typing replaces it.
+        harness.set_text_selection(text_box_id, Selection::new(0, 1));
+
+        harness.keyboard_type_chars("1a-2.");
+        let field = harness.get_widget(field_id);
+        let field = field.downcast::<NumberField>().unwrap();
+        assert_eq!(field.text(), "12");
+        assert_eq!(field.value(), 12.0);
+        assert_eq!(pop_number_changed(&mut harness, field_id), vec![1.0, 12.0]);
+    }
+
+    #[test]
+    fn filter_pasted_text() {
+        let [field_id] = widget_ids();
+        let field = NumberField::new(0.0)
+            .with_range(0.0, 1_000_000.0)
+            .with_id(field_id);
+        let paste_mods = if cfg!(target_os = "macos") {
+            RawMods::Meta
+        } else {
+            RawMods::Ctrl
+        };
+
+        let mut harness = TestHarness::create(field);
+        let text_box_id = harness.get_widget(field_id).children()[0].id();
+        harness.mouse_click_on(field_id);
+        harness.set_text_selection(text_box_id, Selection::new(0, 1));
+
+        // Through the platform's text input.
+        harness.paste("1a2");
+        let field = harness.get_widget(field_id);
+        assert_eq!(field.downcast::<NumberField>().unwrap().text(), "12");
+
+        // Through the clipboard.
+        harness.set_clipboard_contents("x3-4");
+        harness.keyboard_press_key(KbKey::Character("v".into()), paste_mods);
+        let field = harness.get_widget(field_id);
+        let field = field.downcast::<NumberField>().unwrap();
+        assert_eq!(field.text(), "1234");
+        assert_eq!(field.value(), 1234.0);
+
+        // The caret stays after the pasted text.
+        harness.keyboard_type_chars("5");
+        let field = harness.get_widget(field_id);
+        assert_eq!(field.downcast::<NumberField>().unwrap().text(), "12345");
+    }
+
+    #[test]
+    fn format_on_blur() {
+        let [field_id, other_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child(
+                NumberField::new(0.0)
+                    .with_decimals(2)
+                    .with_range(0.0, 5000.0)
+                    .with_id(field_id),
+            )
+            .with_child(TextBox::new("").with_id(other_id));
+
+        let mut harness = TestHarness::create(widget);
+        let text_box_id = harness.get_widget(field_id).children()[0].id();
+        harness.mouse_click_on(field_id);
+        // Select the initial "0.00" so that typing replaces it.
+        harness.set_text_selection(text_box_id, Selection::new(0, 4));
+
+        harness.keyboard_type_chars("12345.6");
+        assert_eq!(
+            harness
+                .get_widget(field_id)
+                .downcast::<NumberField>()
+                .unwrap()
+                .text(),
+            "12345.6"
+        );
+
+        harness.mouse_click_on(other_id);
+        let field = harness.get_widget(field_id);
+        let field = field.downcast::<NumberField>().unwrap();
+        assert_eq!(field.value(), 5000.0);
+        assert_eq!(field.text(), "5,000.00");
+    }
+}
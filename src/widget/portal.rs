@@ -130,6 +130,11 @@ fn compute_pan_range(mut viewport: Range<f64>, target: Range<f64>) -> Range<f64>
 }
 
 impl<W: Widget> Portal<W> {
+    // Used by TextBox builder methods, before the portal is added to the widget tree.
+    pub(crate) fn set_constrain_horizontal_raw(&mut self, constrain: bool) {
+        self.constrain_horizontal = constrain;
+    }
+
     // TODO - rename
     fn set_viewport_pos_raw(&mut self, portal_size: Size, content_size: Size, pos: Point) -> bool {
         let viewport_max_pos =
@@ -146,6 +151,41 @@ impl<W: Widget> Portal<W> {
             false
         }
     }
+
+    // How far the viewport is scrolled along each axis, from 0 to 1.
+    // Axes along which the content fits in the portal have no progress.
+    fn viewport_progress(&self, portal_size: Size, content_size: Size) -> Vec2 {
+        let scroll_range = content_size - portal_size;
+        let progress = |pos: f64, range: f64| {
+            if range > 0.0 {
+                (pos / range).clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+        Vec2::new(
+            progress(self.viewport_pos.x, scroll_range.width),
+            progress(self.viewport_pos.y, scroll_range.height),
+        )
+    }
+
+    // Note - Rect is in child coordinates
+    fn viewport_pos_to_show(&self, portal_size: Size, target: Rect) -> Point {
+        let viewport = Rect::from_origin_size(self.viewport_pos, portal_size);
+
+        let new_pos_x = compute_pan_range(
+            viewport.min_x()..viewport.max_x(),
+            target.min_x()..target.max_x(),
+        )
+        .start;
+        let new_pos_y = compute_pan_range(
+            viewport.min_y()..viewport.max_y(),
+            target.min_y()..target.max_y(),
+        )
+        .start;
+
+        Point::new(new_pos_x, new_pos_y)
+    }
 }
 
 impl<'a, 'b, W: Widget> PortalMut<'a, 'b, W> {
@@ -204,12 +244,11 @@ impl<'a, 'b, W: Widget> PortalMut<'a, 'b, W> {
             .1
             .set_viewport_pos_raw(portal_size, content_size, position);
         if pos_changed {
-            let progress_x = self.1.viewport_pos.x / (content_size - portal_size).width;
+            let progress = self.1.viewport_progress(portal_size, content_size);
             self.horizontal_scrollbar_mut()
-                .set_cursor_progress(progress_x);
-            let progress_y = self.1.viewport_pos.y / (content_size - portal_size).height;
+                .set_cursor_progress(progress.x);
             self.vertical_scrollbar_mut()
-                .set_cursor_progress(progress_y);
+                .set_cursor_progress(progress.y);
             self.0.request_layout();
         }
         pos_changed
@@ -221,20 +260,10 @@ impl<'a, 'b, W: Widget> PortalMut<'a, 'b, W> {
 
     // Note - Rect is in child coordinates
     pub fn pan_viewport_to(&mut self, target: Rect) -> bool {
-        let viewport = Rect::from_origin_size(self.1.viewport_pos, self.0.widget_state.size);
-
-        let new_pos_x = compute_pan_range(
-            viewport.min_x()..viewport.max_x(),
-            target.min_x()..target.max_x(),
-        )
-        .start;
-        let new_pos_y = compute_pan_range(
-            viewport.min_y()..viewport.max_y(),
-            target.min_y()..target.max_y(),
-        )
-        .start;
-
-        self.set_viewport_pos(Point::new(new_pos_x, new_pos_y))
+        let new_pos = self
+            .1
+            .viewport_pos_to_show(self.0.widget_state.size, target);
+        self.set_viewport_pos(new_pos)
     }
 }
 
@@ -252,8 +281,9 @@ impl<W: Widget> Widget for Portal<W> {
                     self.viewport_pos + wheel_event.wheel_delta,
                 );
                 // TODO - horizontal scrolling?
+                let progress = self.viewport_progress(portal_size, content_size);
                 ctx.get_mut(&mut self.scrollbar_vertical)
-                    .set_cursor_progress(self.viewport_pos.y / (content_size - portal_size).height);
+                    .set_cursor_progress(progress.y);
             }
             Event::Notification(notif) => {
                 if let Some((axis, progress)) = notif.try_get(SCROLLBAR_MOVED) {
@@ -282,8 +312,21 @@ impl<W: Widget> Widget for Portal<W> {
             LifeCycle::WidgetAdded => {
                 ctx.register_as_portal();
             }
-            //TODO
-            //LifeCycle::RequestPanToChild(target_rect) => {}
+            LifeCycle::RequestPanToChild(target_rect) => {
+                // The target is in our coordinates; convert it to the child's.
+                let target_rect = *target_rect - self.child.layout_rect().origin().to_vec2();
+                let portal_size = ctx.size();
+                let content_size = self.child.layout_rect().size();
+                let new_pos = self.viewport_pos_to_show(portal_size, target_rect);
+                if self.set_viewport_pos_raw(portal_size, content_size, new_pos) {
+                    let progress = self.viewport_progress(portal_size, content_size);
+                    ctx.get_mut(&mut self.scrollbar_horizontal)
+                        .set_cursor_progress(progress.x);
+                    ctx.get_mut(&mut self.scrollbar_vertical)
+                        .set_cursor_progress(progress.y);
+                    ctx.request_layout();
+                }
+            }
             _ => {}
         }
 
@@ -381,8 +424,10 @@ mod tests {
 
     use super::*;
     use crate::assert_render_snapshot;
-    use crate::testing::{widget_ids, TestHarness};
+    use crate::shell::{KbKey, RawMods};
+    use crate::testing::{widget_ids, ModularWidget, TestHarness};
     use crate::widget::{Button, Flex, SizedBox};
+    use crate::Selector;
 
     fn button(text: &str) -> impl Widget {
         SizedBox::new(Button::new(text)).width(70.0).height(40.0)
@@ -453,6 +498,71 @@ mod tests {
         assert_render_snapshot!(harness, "button_list_scroll_to_item_13");
     }
 
+    const REQUEST_FOCUS: Selector = Selector::new("masonry-test.request-focus");
+
+    // A field that moves the focus forward when Tab is pressed.
+    fn field() -> impl Widget {
+        ModularWidget::new(())
+            .event_fn(|_, ctx, event, _| match event {
+                Event::Command(cmd) if cmd.is(REQUEST_FOCUS) => ctx.request_focus(),
+                Event::KeyDown(key) if key.key == KbKey::Tab => ctx.focus_next(),
+                _ => (),
+            })
+            .lifecycle_fn(|_, ctx, event, _| {
+                if let LifeCycle::BuildFocusChain = event {
+                    ctx.register_for_focus();
+                }
+            })
+            .layout_fn(|_, _, bc, _| bc.constrain(Size::new(100.0, 40.0)))
+    }
+
+    #[test]
+    fn tab_scrolls_focused_widget_into_view() {
+        let [first_id, second_id] = widget_ids();
+
+        let widget = Portal::new(
+            Flex::column()
+                .with_child_id(field(), first_id)
+                .with_spacer(1000.0)
+                .with_child_id(field(), second_id),
+        );
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400., 400.));
+        harness.submit_command(REQUEST_FOCUS.to(first_id));
+        harness.keyboard_press_key(KbKey::Tab, RawMods::None);
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(second_id));
+
+        // The second field ends at 40 + 1000 + 40, at the bottom of the viewport.
+        let portal = harness.root_widget();
+        let portal = portal.downcast::<Portal<Flex>>().unwrap();
+        assert_eq!(portal.get_viewport_pos(), Point::new(0.0, 680.0));
+    }
+
+    #[test]
+    fn pan_to_child_in_content_as_wide_as_portal() {
+        let [first_id, second_id] = widget_ids();
+
+        let widget = Portal::new(
+            Flex::column()
+                .with_child_id(field(), first_id)
+                .with_spacer(1000.0)
+                .with_child_id(field(), second_id),
+        );
+
+        // The fields are as wide as the portal, so it can't scroll horizontally.
+        let mut harness = TestHarness::create_with_size(widget, Size::new(100., 400.));
+        harness.submit_command(REQUEST_FOCUS.to(first_id));
+        harness.keyboard_press_key(KbKey::Tab, RawMods::None);
+
+        let portal = harness.root_widget();
+        let portal = portal.downcast::<Portal<Flex>>().unwrap();
+        assert_eq!(portal.get_viewport_pos(), Point::new(0.0, 680.0));
+        let horizontal_progress = portal.scrollbar_horizontal.widget().cursor_progress();
+        let vertical_progress = portal.scrollbar_vertical.widget().cursor_progress();
+        assert_eq!(horizontal_progress, 0.0);
+        assert_eq!(vertical_progress, 1.0);
+    }
+
     // Helper function for panning tests
     fn make_range(repr: &str) -> Range<f64> {
         let repr = &repr[repr.find('_').unwrap()..];
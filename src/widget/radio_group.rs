@@ -0,0 +1,316 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A group of mutually exclusive options.
+
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+
+use crate::action::Action;
+use crate::kurbo::{Circle, Size};
+use crate::piet::RenderContext;
+use crate::shell::KbKey;
+use crate::widget::{Label, WidgetRef};
+use crate::{
+    theme, ArcStr, BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, Point, StatusChange, Widget, WidgetPod,
+};
+
+/// A group of mutually exclusive options, of which exactly one is selected.
+///
+/// The group is a single stop in the focus chain: Tab moves focus into and out of the
+/// whole group, while the arrow keys move the selection between options, wrapping
+/// around at either end. Home and End select the first and last option.
+///
+/// Submits [`Action::RadioSelected`] with the index of the new option whenever the
+/// selection changes.
+pub struct RadioGroup {
+    options: Vec<WidgetPod<Label>>,
+    selected: usize,
+    // The vertical span of each option, in our coordinates.
+    row_spans: Vec<(f64, f64)>,
+}
+
+crate::declare_widget!(RadioGroupMut, RadioGroup);
+
+impl RadioGroup {
+    /// Create a new `RadioGroup` with a text label for each option, with the first
+    /// option selected.
+    pub fn new(options: impl IntoIterator<Item = impl Into<ArcStr>>) -> RadioGroup {
+        RadioGroup {
+            options: options
+                .into_iter()
+                .map(|text| WidgetPod::new(Label::new(text)))
+                .collect(),
+            selected: 0,
+            row_spans: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to set the selected option.
+    pub fn with_selected(mut self, index: usize) -> Self {
+        self.selected = index;
+        self
+    }
+
+    /// The index of the selected option.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    fn option_at(&self, y: f64) -> Option<usize> {
+        self.row_spans
+            .iter()
+            .position(|&(y0, y1)| (y0..y1).contains(&y))
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, index: usize) {
+        if index == self.selected {
+            return;
+        }
+        self.selected = index;
+        ctx.submit_action(Action::RadioSelected(index));
+        ctx.request_paint();
+        trace!("RadioGroup {:?} selected option {}", ctx.widget_id(), index);
+    }
+}
+
+impl<'a, 'b> RadioGroupMut<'a, 'b> {
+    /// Set the selected option.
+    ///
+    /// This doesn't submit an action.
+    pub fn set_selected(&mut self, index: usize) {
+        self.1.selected = index;
+        self.0.request_paint();
+    }
+}
+
+impl Widget for RadioGroup {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        for option in &mut self.options {
+            option.on_event(ctx, event, env);
+        }
+        if ctx.is_handled() || ctx.is_disabled() {
+            return;
+        }
+
+        match event {
+            Event::MouseDown(mouse) => {
+                let Some(index) = self.option_at(mouse.pos.y) else {
+                    return;
+                };
+                ctx.request_focus();
+                self.select(ctx, index);
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                let count = self.options.len();
+                if key.key == KbKey::Tab {
+                    // The options aren't separate focus stops, so Tab leaves the group.
+                    if key.mods.shift() {
+                        ctx.focus_prev();
+                    } else {
+                        ctx.focus_next();
+                    }
+                    ctx.set_handled();
+                    return;
+                }
+                if count == 0 {
+                    return;
+                }
+                let index = match key.key {
+                    KbKey::ArrowDown | KbKey::ArrowRight => (self.selected + 1) % count,
+                    KbKey::ArrowUp | KbKey::ArrowLeft => (self.selected + count - 1) % count,
+                    KbKey::Home => 0,
+                    KbKey::End => count - 1,
+                    _ => return,
+                };
+                self.select(ctx, index);
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        if let LifeCycle::BuildFocusChain = event {
+            ctx.register_for_focus();
+        }
+        for option in &mut self.options {
+            option.lifecycle(ctx, event, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let x_padding = env.get(theme::WIDGET_CONTROL_COMPONENT_PADDING);
+        let y_padding = env.get(theme::WIDGET_PADDING_VERTICAL);
+        let radio_size = env.get(theme::BASIC_WIDGET_HEIGHT);
+
+        let label_bc = bc.loosen().shrink((radio_size + x_padding, 0.0));
+        let mut width: f64 = 0.0;
+        let mut y = 0.0;
+        self.row_spans.clear();
+        for (index, option) in self.options.iter_mut().enumerate() {
+            if index != 0 {
+                y += y_padding;
+            }
+            let label_size = option.layout(ctx, &label_bc, env);
+            let row_height = radio_size.max(label_size.height);
+            let label_y = y + (row_height - label_size.height) / 2.0;
+            ctx.place_child(option, Point::new(radio_size + x_padding, label_y), env);
+
+            width = width.max(radio_size + x_padding + label_size.width);
+            self.row_spans.push((y, y + row_height));
+            y += row_height;
+        }
+
+        let size = bc.constrain(Size::new(width, y));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        let radio_size = env.get(theme::BASIC_WIDGET_HEIGHT);
+        let border_width = 1.;
+
+        let border_color = if ctx.is_focused() {
+            env.get(theme::PRIMARY_LIGHT)
+        } else if ctx.is_hot() && !ctx.is_disabled() {
+            env.get(theme::BORDER_LIGHT)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+        let dot_color = if ctx.is_disabled() {
+            env.get(theme::DISABLED_TEXT_COLOR)
+        } else {
+            env.get(theme::TEXT_COLOR)
+        };
+
+        for (index, &(y0, y1)) in self.row_spans.iter().enumerate() {
+            let center = Point::new(radio_size / 2.0, (y0 + y1) / 2.0);
+            let circle = Circle::new(center, (radio_size - border_width) / 2.0);
+            ctx.fill(circle, &env.get(theme::BACKGROUND_LIGHT));
+            ctx.stroke(circle, &border_color, border_width);
+
+            if index == self.selected {
+                ctx.fill(Circle::new(center, radio_size / 4.0), &dot_color);
+            }
+        }
+
+        for option in &mut self.options {
+            option.paint(ctx, env);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        self.options.iter().map(|option| option.as_dyn()).collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("RadioGroup")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.selected.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::RawMods;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::{Flex, TextBox};
+    use crate::WindowConfig;
+
+    #[test]
+    fn tab_enters_group_once() {
+        let [first_id, second_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child_id(RadioGroup::new(["Red", "Green", "Blue"]), first_id)
+            .with_child_id(RadioGroup::new(["Small", "Large"]), second_id);
+
+        let config = WindowConfig::default().with_initial_focus(first_id);
+        let mut harness = TestHarness::create_with_config(widget, config);
+        assert_eq!(harness.window().focus_chain(), &[first_id, second_id]);
+        assert_eq!(harness.window().focus, Some(first_id));
+
+        // Arrows move the selection, not the focus.
+        harness.keyboard_press_key(KbKey::ArrowDown, RawMods::None);
+        assert_eq!(harness.window().focus, Some(first_id));
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::RadioSelected(1), first_id))
+        );
+        harness.keyboard_press_key(KbKey::ArrowRight, RawMods::None);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::RadioSelected(2), first_id))
+        );
+
+        // The selection wraps around at either end.
+        harness.keyboard_press_key(KbKey::ArrowDown, RawMods::None);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::RadioSelected(0), first_id))
+        );
+        harness.keyboard_press_key(KbKey::ArrowUp, RawMods::None);
+        harness.keyboard_press_key(KbKey::Home, RawMods::None);
+        harness.keyboard_press_key(KbKey::End, RawMods::None);
+        let group = harness.get_widget(first_id);
+        assert_eq!(group.downcast::<RadioGroup>().unwrap().selected(), 2);
+        while harness.pop_action().is_some() {}
+
+        // A single Tab leaves the group, whichever option is selected.
+        harness.keyboard_press_key(KbKey::Tab, RawMods::None);
+        assert_eq!(harness.window().focus, Some(second_id));
+        harness.keyboard_press_key(KbKey::Tab, RawMods::Shift);
+        assert_eq!(harness.window().focus, Some(first_id));
+        assert_eq!(harness.pop_action(), None);
+    }
+
+    #[test]
+    fn tab_from_preceding_widget() {
+        let [textbox_id, group_id, after_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child_id(TextBox::new(""), textbox_id)
+            .with_child_id(RadioGroup::new(["Red", "Green", "Blue"]), group_id)
+            .with_child_id(TextBox::new(""), after_id);
+
+        let config = WindowConfig::default().with_initial_focus(textbox_id);
+        let mut harness = TestHarness::create_with_config(widget, config);
+        assert_eq!(harness.window().focus, Some(textbox_id));
+
+        // Tab enters the group, and the next Tab leaves it.
+        harness.keyboard_press_key(KbKey::Tab, RawMods::None);
+        assert_eq!(harness.window().focus, Some(group_id));
+        harness.keyboard_press_key(KbKey::Tab, RawMods::None);
+        assert_eq!(harness.window().focus, Some(after_id));
+
+        // Entering the group doesn't change its selection.
+        let group = harness.get_widget(group_id);
+        assert_eq!(group.downcast::<RadioGroup>().unwrap().selected(), 0);
+        assert_eq!(harness.pop_action(), None);
+    }
+
+    #[test]
+    fn click_selects_option() {
+        let [group_id] = widget_ids();
+        let widget = RadioGroup::new(["One", "Two"]).with_id(group_id);
+
+        let mut harness = TestHarness::create(widget);
+        let label_id = harness.get_widget(group_id).children()[1].state().id;
+        harness.mouse_click_on(label_id);
+
+        assert_eq!(harness.window().focus, Some(group_id));
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::RadioSelected(1), group_id))
+        );
+    }
+}
@@ -0,0 +1,241 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A grip that resizes the window it's in.
+
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+
+use crate::command::CONFIGURE_WINDOW;
+use crate::kurbo::{Line, Vec2};
+use crate::shell::KbKey;
+use crate::widget::WidgetRef;
+use crate::{
+    theme, BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, RenderContext, Size, StatusChange, Widget, WindowConfig,
+};
+
+const HANDLE_SIZE: f64 = 16.0;
+const KEYBOARD_STEP: f64 = 10.0;
+const DEFAULT_MIN_WINDOW_SIZE: Size = Size::new(100.0, 100.0);
+
+/// A grip that resizes the window it's in when dragged.
+///
+/// This is meant for windows without a titlebar or OS-drawn border (see
+/// [`WindowConfig::show_titlebar`]), and is usually placed in the window's
+/// bottom-right corner. Dragging the handle moves the window's bottom-right corner
+/// by the same amount.
+///
+/// When focused, the arrow keys resize the window by steps of 10 points.
+///
+/// The resize is requested with the [`CONFIGURE_WINDOW`] command; the platform
+/// may adjust or ignore it.
+pub struct ResizeHandle {
+    min_window_size: Size,
+    window_size: Size,
+    // Mouse position in window coordinates and window size when the drag started.
+    drag_start: Option<(Point, Size)>,
+}
+
+crate::declare_widget!(ResizeHandleMut, ResizeHandle);
+
+impl ResizeHandle {
+    /// Create a new `ResizeHandle`.
+    pub fn new() -> Self {
+        ResizeHandle {
+            min_window_size: DEFAULT_MIN_WINDOW_SIZE,
+            window_size: Size::ZERO,
+            drag_start: None,
+        }
+    }
+
+    /// Builder-style method to set the size below which the handle won't shrink the window.
+    pub fn with_min_window_size(mut self, size: impl Into<Size>) -> Self {
+        self.min_window_size = size.into();
+        self
+    }
+
+    fn request_window_size(&mut self, ctx: &mut EventCtx, size: Size) {
+        let size = size.clamp(
+            self.min_window_size,
+            Size::new(f64::INFINITY, f64::INFINITY),
+        );
+        if size == self.window_size {
+            return;
+        }
+        trace!(
+            "ResizeHandle {:?} requested window size {}",
+            ctx.widget_id(),
+            size
+        );
+        ctx.submit_command(CONFIGURE_WINDOW.with(WindowConfig::default().window_size(size)));
+    }
+}
+
+impl Default for ResizeHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, 'b> ResizeHandleMut<'a, 'b> {
+    /// Set the size below which the handle won't shrink the window.
+    pub fn set_min_window_size(&mut self, size: impl Into<Size>) {
+        self.1.min_window_size = size.into();
+    }
+}
+
+impl Widget for ResizeHandle {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, _env: &Env) {
+        match event {
+            Event::WindowSize(size) => {
+                self.window_size = *size;
+            }
+            Event::MouseDown(mouse) if !ctx.is_disabled() => {
+                ctx.set_active(true);
+                ctx.request_focus();
+                self.drag_start = Some((mouse.window_pos, self.window_size));
+                ctx.set_handled();
+            }
+            Event::MouseMove(mouse) => {
+                if let (true, Some((start_pos, start_size))) = (ctx.is_active(), self.drag_start) {
+                    let delta = mouse.window_pos - start_pos;
+                    self.request_window_size(ctx, start_size + delta.to_size());
+                    ctx.set_handled();
+                }
+            }
+            Event::MouseUp(_) => {
+                if ctx.is_active() {
+                    ctx.set_active(false);
+                    ctx.request_paint();
+                }
+                self.drag_start = None;
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                let delta = match key.key {
+                    KbKey::ArrowLeft => Vec2::new(-KEYBOARD_STEP, 0.0),
+                    KbKey::ArrowRight => Vec2::new(KEYBOARD_STEP, 0.0),
+                    KbKey::ArrowUp => Vec2::new(0.0, -KEYBOARD_STEP),
+                    KbKey::ArrowDown => Vec2::new(0.0, KEYBOARD_STEP),
+                    _ => return,
+                };
+                self.request_window_size(ctx, self.window_size + delta.to_size());
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _env: &Env) {
+        if let LifeCycle::BuildFocusChain = event {
+            ctx.register_for_focus();
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _env: &Env) -> Size {
+        let size = bc.constrain(Size::new(HANDLE_SIZE, HANDLE_SIZE));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        let color = if (ctx.is_hot() || ctx.is_active()) && !ctx.is_disabled() {
+            env.get(theme::BORDER_LIGHT)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+
+        // Three diagonal lines in the bottom-right corner.
+        let size = ctx.size();
+        let corner = Point::new(size.width, size.height);
+        let extent = size.width.min(size.height);
+        for i in 1..=3 {
+            let offset = extent * i as f64 / 4.0;
+            let line = Line::new(
+                corner - Vec2::new(offset, 0.0),
+                corner - Vec2::new(0.0, offset),
+            );
+            ctx.stroke(line, &color, 1.0);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("ResizeHandle")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::MouseButton;
+    use crate::testing::{Record, Recording, TestHarness, TestWidgetExt as _};
+
+    fn requested_sizes(recording: &Recording) -> Vec<Size> {
+        recording
+            .drain()
+            .into_iter()
+            .filter_map(|record| match record {
+                Record::E(Event::Command(cmd)) => cmd.try_get(CONFIGURE_WINDOW)?.size,
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn drag_requests_resize() {
+        let recording = Recording::default();
+        let widget = ResizeHandle::new().record(&recording);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400.0, 300.0));
+        recording.clear();
+
+        harness.mouse_move((390.0, 290.0));
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_move((420.0, 270.0));
+        harness.mouse_button_release(MouseButton::Left);
+
+        assert_eq!(requested_sizes(&recording), [Size::new(430.0, 280.0)]);
+    }
+
+    #[test]
+    fn drag_follows_every_move() {
+        let recording = Recording::default();
+        let widget = ResizeHandle::new().record(&recording);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400.0, 300.0));
+        recording.clear();
+
+        harness.mouse_drag_with_steps((390.0, 290.0), (430.0, 270.0), MouseButton::Left, 2);
+
+        assert_eq!(
+            requested_sizes(&recording),
+            [Size::new(420.0, 290.0), Size::new(440.0, 280.0)]
+        );
+    }
+
+    #[test]
+    fn drag_respects_min_size() {
+        let recording = Recording::default();
+        let widget = ResizeHandle::new()
+            .with_min_window_size((200.0, 200.0))
+            .record(&recording);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400.0, 300.0));
+        recording.clear();
+
+        harness.mouse_move((390.0, 290.0));
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_move((0.0, 0.0));
+
+        assert_eq!(requested_sizes(&recording), [Size::new(200.0, 200.0)]);
+    }
+}
@@ -0,0 +1,172 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A container which clips its child to a rounded rectangle.
+
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+
+use crate::kurbo::{RoundedRect, Shape as _};
+use crate::widget::{WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Point,
+    RenderContext, Size, StatusChange, Widget,
+};
+
+/// A container which clips its child to a rounded rectangle, eg for cards or avatars.
+///
+/// The corners are clipped with anti-aliasing, and the mouse isn't considered to be over
+/// the child while it's in a clipped corner: the child doesn't become hot or receive
+/// clicks there. A child which is [active](crate::EventCtx::set_active), eg during a
+/// drag, still gets every mouse event.
+///
+/// Usually created with [`WidgetExt::rounded`](crate::widget::WidgetExt::rounded).
+pub struct RoundedClip {
+    child: WidgetPod<Box<dyn Widget>>,
+    radius: f64,
+}
+
+crate::declare_widget!(RoundedClipMut, RoundedClip);
+
+impl RoundedClip {
+    /// Create a new `RoundedClip` around `child`, with corners of the given radius.
+    pub fn new(child: impl Widget, radius: f64) -> Self {
+        RoundedClip {
+            child: WidgetPod::new(child).boxed(),
+            radius,
+        }
+    }
+
+    /// The shape the child is clipped to, in our coordinates.
+    fn clip_shape(&self) -> RoundedRect {
+        self.child.layout_rect().to_rounded_rect(self.radius)
+    }
+}
+
+impl<'a, 'b> RoundedClipMut<'a, 'b> {
+    /// Set the radius of the corners.
+    pub fn set_radius(&mut self, radius: f64) {
+        self.1.radius = radius;
+        self.0.request_paint();
+    }
+
+    /// Return a mutable reference to the child.
+    pub fn child_mut(&mut self) -> WidgetMut<'_, 'b, Box<dyn Widget>> {
+        self.0.get_mut(&mut self.1.child)
+    }
+}
+
+impl Widget for RoundedClip {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        let in_clipped_corner = match event {
+            Event::MouseDown(mouse)
+            | Event::MouseUp(mouse)
+            | Event::MouseMove(mouse)
+            | Event::Wheel(mouse) => {
+                !self.child.has_active() && !self.clip_shape().contains(mouse.pos)
+            }
+            _ => false,
+        };
+
+        if in_clipped_corner {
+            // As far as the child is concerned, the mouse left it.
+            if self.child.is_hot() {
+                self.child.on_event(ctx, &Event::MouseLeave, env);
+            } else {
+                ctx.skip_child(&mut self.child);
+            }
+            return;
+        }
+        self.child.on_event(ctx, event, env);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        self.child.lifecycle(ctx, event, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, env);
+        ctx.place_child(&mut self.child, Point::ORIGIN, env);
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        ctx.clip(self.clip_shape());
+        self.child.paint(ctx, env);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.child.as_dyn()]
+    }
+
+    fn get_child_at_pos(&self, pos: Point) -> Option<WidgetRef<'_, dyn Widget>> {
+        self.clip_shape().contains(pos).then(|| self.child.as_dyn())
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("RoundedClip")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_render_snapshot;
+    use crate::piet::ImageFormat;
+    use crate::shell::MouseButton;
+    use crate::testing::{widget_ids, Record, Recording, TestHarness, TestWidgetExt as _};
+    use crate::widget::{Image, SizedBox, WidgetExt as _};
+    use crate::ImageBuf;
+
+    #[test]
+    fn rounded_image() {
+        let image_data = ImageBuf::from_raw(vec![0x5c, 0xc4, 0xff], ImageFormat::Rgb, 1, 1);
+        let widget = Image::new(image_data).rounded(20.0);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(80.0, 80.0));
+        assert_render_snapshot!(harness, "rounded_image");
+    }
+
+    #[test]
+    fn clicks_in_corner_miss_child() {
+        let [child_id] = widget_ids();
+        let recording = Recording::default();
+        // Not `.rounded()`, which SizedBox shadows with its own method.
+        let widget = RoundedClip::new(
+            SizedBox::empty()
+                .width(100.0)
+                .height(100.0)
+                .record(&recording)
+                .with_id(child_id),
+            30.0,
+        );
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(100.0, 100.0));
+        recording.clear();
+
+        // Inside the layout rect, but outside the top-left corner's arc.
+        harness.mouse_move((2.0, 2.0));
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_button_release(MouseButton::Left);
+        assert!(!harness.get_widget(child_id).state().is_hot);
+        assert!(recording
+            .drain()
+            .into_iter()
+            .all(|record| !matches!(record, Record::E(Event::MouseDown(_)))));
+        assert!(harness
+            .root_widget()
+            .find_widget_at_pos(Point::new(2.0, 2.0))
+            .map_or(true, |widget| widget.state().id != child_id));
+
+        // In the middle of the widget, the child gets the click.
+        harness.mouse_click_on(child_id);
+        assert!(recording
+            .drain()
+            .into_iter()
+            .any(|record| matches!(record, Record::E(Event::MouseDown(_)))));
+    }
+}
@@ -0,0 +1,130 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A container which keeps its child out of the obscured parts of the window.
+
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+
+use crate::widget::{WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    theme, BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, Size, StatusChange, Widget,
+};
+
+/// A container which keeps its child out of the obscured parts of the window, eg
+/// rounded corners or a camera notch.
+///
+/// The child is inset by the window's [`SAFE_AREA_INSETS`](theme::SAFE_AREA_INSETS),
+/// as set by [`WindowRoot::set_safe_area_insets`](crate::WindowRoot::set_safe_area_insets).
+/// The insets are relative to the window's edges, so this is meant to wrap the root
+/// widget of a window, or a widget which fills it.
+pub struct SafeArea {
+    child: WidgetPod<Box<dyn Widget>>,
+}
+
+crate::declare_widget!(SafeAreaMut, SafeArea);
+
+impl SafeArea {
+    /// Create a new `SafeArea` around `child`.
+    pub fn new(child: impl Widget) -> Self {
+        SafeArea {
+            child: WidgetPod::new(child).boxed(),
+        }
+    }
+}
+
+impl<'a, 'b> SafeAreaMut<'a, 'b> {
+    /// Return a mutable reference to the child.
+    pub fn child_mut(&mut self) -> WidgetMut<'_, 'b, Box<dyn Widget>> {
+        self.0.get_mut(&mut self.1.child)
+    }
+}
+
+impl Widget for SafeArea {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        self.child.on_event(ctx, event, env);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        self.child.lifecycle(ctx, event, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let insets = env.get(theme::SAFE_AREA_INSETS);
+        let inset_size = Size::new(insets.x_value(), insets.y_value());
+
+        let child_bc = bc.shrink(inset_size);
+        let child_size = self.child.layout(ctx, &child_bc, env);
+        ctx.place_child(&mut self.child, Point::new(insets.x0, insets.y0), env);
+
+        let size = bc.constrain(child_size + inset_size);
+        ctx.set_baseline_offset(self.child.baseline_offset() + insets.y1);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        self.child.paint(ctx, env);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.child.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("SafeArea")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::testing::{widget_ids, ModularWidget, TestHarness, TestWidgetExt as _};
+    use crate::widget::SizedBox;
+    use crate::{Insets, Rect};
+
+    #[test]
+    fn child_avoids_safe_area() {
+        let [child_id] = widget_ids();
+        let widget = SafeArea::new(SizedBox::empty().expand().with_id(child_id));
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 100.0));
+        assert_eq!(
+            harness.get_widget(child_id).state().window_layout_rect(),
+            Rect::new(0.0, 0.0, 200.0, 100.0)
+        );
+
+        harness.set_safe_area_insets(Insets::new(10.0, 20.0, 5.0, 0.0));
+        assert_eq!(
+            harness.get_widget(child_id).state().window_layout_rect(),
+            Rect::new(10.0, 20.0, 195.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn env_version_is_stable_with_safe_area() {
+        let versions: Rc<RefCell<Vec<u64>>> = Rc::new(RefCell::new(Vec::new()));
+        let widget = ModularWidget::new(versions.clone()).paint_fn(|versions, _ctx, env| {
+            versions.borrow_mut().push(env.version());
+        });
+
+        let mut harness = TestHarness::create(widget);
+        harness.set_safe_area_insets(Insets::new(10.0, 20.0, 5.0, 0.0));
+        harness.render();
+        harness
+            .window_mut()
+            .invalid_mut()
+            .add_rect(Rect::new(0.0, 0.0, 100.0, 100.0));
+        harness.render();
+
+        let versions = versions.borrow();
+        assert!(versions.len() >= 2);
+        assert!(versions.iter().all(|v| *v == versions[0]));
+    }
+}
@@ -83,6 +83,20 @@ impl SizedBox {
         }
     }
 
+    /// Construct container with a named child, and both width and height not set.
+    ///
+    /// See [`WidgetPod::new_with_name`].
+    pub fn new_with_name(child: impl Widget, name: &'static str) -> Self {
+        Self {
+            child: Some(WidgetPod::new_with_name(child, name).boxed()),
+            width: None,
+            height: None,
+            background: None,
+            border: None,
+            corner_radius: RoundedRectRadii::from_single_radius(0.0).into(),
+        }
+    }
+
     /// Construct container without child, and both width and height not set.
     ///
     /// If the widget is unchanged, it will render nothing, which can be useful if you want to draw a
@@ -178,13 +192,17 @@ impl SizedBox {
 
 impl<'a, 'b> SizedBoxMut<'a, 'b> {
     pub fn set_child(&mut self, child: impl Widget) {
-        self.1.child = Some(WidgetPod::new(child).boxed());
+        if let Some(old_child) = self.1.child.replace(WidgetPod::new(child).boxed()) {
+            self.0.remove_child(old_child);
+        }
         self.0.children_changed();
         self.0.request_layout();
     }
 
     pub fn remove_child(&mut self) {
-        self.1.child = None;
+        if let Some(old_child) = self.1.child.take() {
+            self.0.remove_child(old_child);
+        }
         self.0.children_changed();
         self.0.request_layout();
     }
@@ -397,7 +415,7 @@ impl Widget for SizedBox {
 impl BackgroundBrush {
     /// Draw this brush into a provided [`PaintCtx`].
     pub fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
-        let bounds = ctx.size().to_rect();
+        let bounds = ctx.crisp_rect(ctx.size().to_rect());
         match self {
             Self::Color(color) => ctx.fill(bounds, &color.resolve(env)),
             Self::Linear(grad) => ctx.fill(bounds, grad),
@@ -16,9 +16,10 @@ use crate::{
     LifeCycleCtx, PaintCtx, Point, RenderContext, Size, StatusChange, Vec2, Widget,
 };
 
-// TODO - Set color
 /// An animated spinner widget for showing a loading state.
 ///
+/// The spinner is drawn with [`theme::SPINNER_COLOR`] unless another color is set.
+///
 /// To customize the spinner's size, you can place it inside a [`SizedBox`]
 /// that has a fixed width and height.
 ///
@@ -63,7 +64,7 @@ impl Default for Spinner {
     fn default() -> Self {
         Spinner {
             t: 0.0,
-            color: theme::TEXT_COLOR.into(),
+            color: theme::SPINNER_COLOR.into(),
         }
     }
 }
@@ -136,8 +137,9 @@ impl Widget for Spinner {
 mod tests {
     use super::*;
     use crate::assert_render_snapshot;
-    use crate::testing::TestHarness;
-    //use instant::Duration;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::{Flex, Label};
+    use instant::Duration;
 
     #[test]
     fn simple_spinner() {
@@ -146,9 +148,18 @@ mod tests {
         let mut harness = TestHarness::create(spinner);
         assert_render_snapshot!(harness, "spinner_init");
 
-        // TODO - See issue #12
-        //harness.move_timers_forward(Duration::from_millis(700));
-        //assert_render_snapshot!(harness, "spinner_700ms");
+        harness.move_timers_forward(Duration::from_millis(700));
+        assert_render_snapshot!(harness, "spinner_700ms");
+    }
+
+    #[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "openbsd"))]
+    #[test]
+    fn spinner_svg() {
+        let mut harness = TestHarness::create_with_size(Spinner::new(), Size::new(40.0, 40.0));
+        crate::assert_svg_snapshot!(harness, "spinner_init");
+
+        harness.move_timers_forward(Duration::from_millis(700));
+        crate::assert_svg_snapshot!(harness, "spinner_700ms");
     }
 
     #[test]
@@ -176,4 +187,23 @@ mod tests {
         // We don't use assert_eq because we don't want rich assert
         assert!(image_1 == image_2);
     }
+
+    #[test]
+    fn spinner_color_theme_key() {
+        let [spinner_id, label_id] = widget_ids();
+        let widget = Flex::row()
+            .with_child(Spinner::new().with_id(spinner_id))
+            .with_child(Label::new("Loading").with_id(label_id));
+
+        let mut harness = TestHarness::create(widget);
+        let spinner_before = harness.render_widget(spinner_id);
+        let label_before = harness.render_widget(label_id);
+
+        harness.edit_env(|env| env.set(theme::SPINNER_COLOR, Color::PURPLE));
+        let spinner_after = harness.render_widget(spinner_id);
+        let label_after = harness.render_widget(label_id);
+
+        assert!(spinner_before.raw_pixels() != spinner_after.raw_pixels());
+        assert!(label_before.raw_pixels() == label_after.raw_pixels());
+    }
 }
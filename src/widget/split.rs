@@ -249,6 +249,7 @@ impl Split {
             ),
         };
         let splitter_color = self.bar_color(env);
+        let rect = ctx.crisp_rect(rect);
         ctx.fill(rect, &splitter_color);
     }
 
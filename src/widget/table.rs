@@ -0,0 +1,660 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A widget that lays out cells in rows and columns.
+
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+
+use crate::kurbo::{BezPath, Line, Rect};
+use crate::shell::KbKey;
+use crate::widget::{WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    theme, Action, BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, Point, RenderContext, Size, StatusChange, UnitPoint, Widget,
+};
+
+const DEFAULT_COLUMN_SPACING: f64 = 8.0;
+const HEADER_SEPARATOR_WIDTH: f64 = 1.0;
+const SORT_INDICATOR_SIZE: f64 = 6.0;
+
+/// How the width of a [`Table`] column is computed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnWidth {
+    /// The column is as wide as its widest cell.
+    Auto,
+    /// The column has the given width, regardless of its content.
+    Fixed(f64),
+}
+
+/// The parameters of a [`Table`] column.
+#[derive(Debug, Clone, Copy)]
+pub struct TableColumn {
+    width: ColumnWidth,
+    align: UnitPoint,
+}
+
+/// A widget that lays out cells in rows and columns, with an optional header row.
+///
+/// By default, each column is as wide as its widest cell (based on the cells'
+/// [max intrinsic width](Widget::compute_max_intrinsic_width)), and each row is as
+/// tall as its tallest cell. Cells are aligned within their slot according to
+/// their column's alignment.
+///
+/// Rows may have fewer cells than there are columns; the missing cells are left empty.
+///
+/// ## Interaction
+///
+/// Clicking a header cell emits [`Action::SortRequested`], ascending unless the column
+/// was already sorted ascending. The table doesn't reorder its rows itself: the app
+/// is expected to rebuild them in the requested order.
+///
+/// Clicking a row selects it and emits [`Action::RowSelected`]. When the table has
+/// focus, the up and down arrow keys move the selection. Row indices never count the
+/// header.
+pub struct Table {
+    columns: Vec<TableColumn>,
+    has_header: bool,
+    rows: Vec<Vec<WidgetPod<Box<dyn Widget>>>>,
+    column_spacing: f64,
+    sort: Option<(usize, bool)>,
+    selected_row: Option<usize>,
+    // Computed in layout, used in event and paint.
+    // Vertical extent of each row, including the header.
+    row_spans: Vec<(f64, f64)>,
+    // Horizontal extent of each column.
+    column_spans: Vec<(f64, f64)>,
+}
+
+crate::declare_widget!(TableMut, Table);
+
+// --- TableColumn ---
+
+impl TableColumn {
+    /// A column as wide as its widest cell.
+    pub fn auto() -> Self {
+        TableColumn {
+            width: ColumnWidth::Auto,
+            align: UnitPoint::LEFT,
+        }
+    }
+
+    /// A column with the given width.
+    pub fn fixed(width: f64) -> Self {
+        TableColumn {
+            width: ColumnWidth::Fixed(width),
+            align: UnitPoint::LEFT,
+        }
+    }
+
+    /// Builder-style method to set how cells are aligned in this column.
+    ///
+    /// The default is [`UnitPoint::LEFT`].
+    pub fn align(mut self, align: UnitPoint) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Return how the width of this column is computed.
+    pub fn width(&self) -> ColumnWidth {
+        self.width
+    }
+}
+
+impl Default for TableColumn {
+    fn default() -> Self {
+        Self::auto()
+    }
+}
+
+// --- Table ---
+
+impl Table {
+    /// Create a new empty `Table`.
+    pub fn new() -> Self {
+        Table {
+            columns: Vec::new(),
+            has_header: false,
+            rows: Vec::new(),
+            column_spacing: DEFAULT_COLUMN_SPACING,
+            sort: None,
+            selected_row: None,
+            row_spans: Vec::new(),
+            column_spans: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to add a column definition.
+    ///
+    /// Columns are defined in order. Columns without a definition are
+    /// [auto-sized](TableColumn::auto) and left-aligned.
+    pub fn with_column(mut self, column: TableColumn) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Builder-style method to set the header row.
+    ///
+    /// The header is laid out like other rows, above them and separated by a line.
+    pub fn with_header<W: Widget>(mut self, cells: impl IntoIterator<Item = W>) -> Self {
+        let row = make_row(cells);
+        if self.has_header {
+            self.rows[0] = row;
+        } else {
+            self.rows.insert(0, row);
+            self.has_header = true;
+        }
+        self
+    }
+
+    /// Builder-style method to add a row of cells.
+    ///
+    /// To mix cells of different types, pass an iterator of `Box<dyn Widget>`.
+    pub fn with_row<W: Widget>(mut self, cells: impl IntoIterator<Item = W>) -> Self {
+        self.rows.push(make_row(cells));
+        self
+    }
+
+    /// Builder-style method to set the horizontal space between columns.
+    pub fn with_column_spacing(mut self, spacing: f64) -> Self {
+        self.column_spacing = spacing;
+        self
+    }
+
+    /// Return the number of rows, not counting the header.
+    pub fn row_count(&self) -> usize {
+        self.rows.len() - self.has_header as usize
+    }
+
+    /// Return the index of the selected row, if any.
+    pub fn selected_row(&self) -> Option<usize> {
+        self.selected_row
+    }
+
+    /// Return the column the table is sorted by and whether the sort is ascending, if any.
+    ///
+    /// This is only used to display the sort indicator: the table doesn't sort its rows.
+    pub fn sort(&self) -> Option<(usize, bool)> {
+        self.sort
+    }
+
+    fn column_count(&self) -> usize {
+        let max_row_len = self.rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        max_row_len.max(self.columns.len())
+    }
+
+    fn column(&self, index: usize) -> TableColumn {
+        self.columns.get(index).copied().unwrap_or_default()
+    }
+
+    fn all_cells_mut(&mut self) -> impl Iterator<Item = &mut WidgetPod<Box<dyn Widget>>> {
+        self.rows.iter_mut().flatten()
+    }
+
+    /// Return the index of the row at `y`, counting the header.
+    fn row_at(&self, y: f64) -> Option<usize> {
+        self.row_spans
+            .iter()
+            .position(|&(y0, y1)| (y0..y1).contains(&y))
+    }
+
+    /// Return the index of the column at `x`, including the spacing after it.
+    fn column_at(&self, x: f64) -> Option<usize> {
+        self.column_spans
+            .iter()
+            .position(|&(x0, x1)| (x0..x1 + self.column_spacing).contains(&x))
+    }
+
+    fn row_rect(&self, width: f64, row: usize) -> Option<Rect> {
+        let row = row + self.has_header as usize;
+        let (y0, y1) = *self.row_spans.get(row)?;
+        Some(Rect::new(0.0, y0, width, y1))
+    }
+
+    fn select_row(&mut self, ctx: &mut EventCtx, row: usize) {
+        if self.selected_row != Some(row) {
+            self.selected_row = Some(row);
+            ctx.request_paint();
+        }
+        ctx.submit_action(Action::RowSelected(row));
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn make_row<W: Widget>(cells: impl IntoIterator<Item = W>) -> Vec<WidgetPod<Box<dyn Widget>>> {
+    cells
+        .into_iter()
+        .map(|cell| WidgetPod::new(cell).boxed())
+        .collect()
+}
+
+// --- Mutate live Table - WidgetMut ---
+
+impl<'a, 'b> TableMut<'a, 'b> {
+    /// Add a row of cells at the bottom of the table.
+    pub fn add_row<W: Widget>(&mut self, cells: impl IntoIterator<Item = W>) {
+        self.1.rows.push(make_row(cells));
+        self.0.children_changed();
+        self.0.request_layout();
+    }
+
+    /// Remove the row at the given index, not counting the header.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn remove_row(&mut self, index: usize) {
+        let row = self.1.rows.remove(index + self.1.has_header as usize);
+        for cell in row {
+            self.0.remove_child(cell);
+        }
+        self.1.selected_row = match self.1.selected_row {
+            Some(selected) if selected == index => None,
+            Some(selected) if selected > index => Some(selected - 1),
+            selected => selected,
+        };
+        self.0.children_changed();
+        self.0.request_layout();
+    }
+
+    /// Remove all rows, keeping the header.
+    pub fn clear_rows(&mut self) {
+        let header_len = self.1.has_header as usize;
+        for cell in self.1.rows.drain(header_len..).flatten() {
+            self.0.remove_child(cell);
+        }
+        self.1.selected_row = None;
+        self.0.children_changed();
+        self.0.request_layout();
+    }
+
+    /// Set the selected row, without emitting [`Action::RowSelected`].
+    pub fn set_selected_row(&mut self, row: Option<usize>) {
+        self.1.selected_row = row.filter(|&row| row < self.1.row_count());
+        self.0.request_paint();
+    }
+
+    /// Set the sort indicator shown in the header, without emitting [`Action::SortRequested`].
+    ///
+    /// `sort` is the column index and whether the sort is ascending.
+    pub fn set_sort(&mut self, sort: Option<(usize, bool)>) {
+        self.1.sort = sort;
+        self.0.request_paint();
+    }
+
+    /// Set the width of the column at the given index.
+    pub fn set_column_width(&mut self, index: usize, width: ColumnWidth) {
+        if self.1.columns.len() <= index {
+            self.1.columns.resize(index + 1, TableColumn::auto());
+        }
+        self.1.columns[index].width = width;
+        self.0.request_layout();
+    }
+
+    /// Return a mutable reference to the cell at the given row and column, not
+    /// counting the header.
+    pub fn cell_mut(
+        &mut self,
+        row: usize,
+        column: usize,
+    ) -> Option<WidgetMut<'_, 'b, Box<dyn Widget>>> {
+        let row = row + self.1.has_header as usize;
+        let cell = self.1.rows.get_mut(row)?.get_mut(column)?;
+        Some(self.0.get_mut(cell))
+    }
+}
+
+// --- Trait impls ---
+
+impl Widget for Table {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        for cell in self.all_cells_mut() {
+            cell.on_event(ctx, event, env);
+        }
+        if ctx.is_handled() {
+            return;
+        }
+
+        match event {
+            Event::MouseDown(mouse) => {
+                let Some(row) = self.row_at(mouse.pos.y) else {
+                    return;
+                };
+                ctx.request_focus();
+                ctx.set_handled();
+
+                if self.has_header && row == 0 {
+                    let Some(column) = self.column_at(mouse.pos.x) else {
+                        return;
+                    };
+                    let ascending = self.sort != Some((column, true));
+                    self.sort = Some((column, ascending));
+                    ctx.submit_action(Action::SortRequested { column, ascending });
+                    ctx.request_paint();
+                    trace!(
+                        "Table {:?} sort requested on column {}",
+                        ctx.widget_id(),
+                        column
+                    );
+                } else {
+                    self.select_row(ctx, row - self.has_header as usize);
+                }
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                let row_count = self.row_count();
+                if row_count == 0 {
+                    return;
+                }
+                let row = match (&key.key, self.selected_row) {
+                    (KbKey::ArrowUp, Some(selected)) => selected.saturating_sub(1),
+                    (KbKey::ArrowDown, Some(selected)) => (selected + 1).min(row_count - 1),
+                    (KbKey::ArrowUp, None) => row_count - 1,
+                    (KbKey::ArrowDown, None) => 0,
+                    _ => return,
+                };
+                self.select_row(ctx, row);
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        if let LifeCycle::BuildFocusChain = event {
+            ctx.register_for_focus();
+        }
+        for cell in self.all_cells_mut() {
+            cell.lifecycle(ctx, event, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let column_count = self.column_count();
+        let columns: Vec<TableColumn> = (0..column_count).map(|i| self.column(i)).collect();
+
+        let rows = &mut self.rows;
+        let column_widths: Vec<f64> = columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| match column.width {
+                ColumnWidth::Fixed(width) => width,
+                ColumnWidth::Auto => rows
+                    .iter_mut()
+                    .filter_map(|row| row.get_mut(index))
+                    .map(|cell| cell.max_intrinsic_width(ctx, f64::INFINITY, env))
+                    .fold(0.0, f64::max),
+            })
+            .collect();
+
+        let mut y = 0.0;
+        self.row_spans.clear();
+        for (row_index, row) in self.rows.iter_mut().enumerate() {
+            let sizes: Vec<Size> = row
+                .iter_mut()
+                .zip(&column_widths)
+                .map(|(cell, &width)| {
+                    let cell_bc = BoxConstraints::new(Size::ZERO, Size::new(width, f64::INFINITY));
+                    cell.layout(ctx, &cell_bc, env)
+                })
+                .collect();
+            let row_height = sizes.iter().map(|size| size.height).fold(0.0, f64::max);
+
+            let mut x = 0.0;
+            for (column, (cell, size)) in row.iter_mut().zip(sizes).enumerate() {
+                let extra = Size::new(column_widths[column], row_height) - size;
+                let offset = columns[column].align.resolve(Rect::new(
+                    0.0,
+                    0.0,
+                    extra.width.max(0.0),
+                    extra.height.max(0.0),
+                ));
+                ctx.place_child(cell, Point::new(x, y) + offset.to_vec2(), env);
+                x += column_widths[column] + self.column_spacing;
+            }
+            self.row_spans.push((y, y + row_height));
+            y += row_height;
+
+            if row_index == 0 && self.has_header {
+                y += HEADER_SEPARATOR_WIDTH;
+            }
+        }
+
+        let mut x = 0.0;
+        self.column_spans.clear();
+        for width in &column_widths {
+            self.column_spans.push((x, x + width));
+            x += width + self.column_spacing;
+        }
+
+        let spacing = self.column_spacing * column_count.saturating_sub(1) as f64;
+        let width = column_widths.iter().sum::<f64>() + spacing;
+        let size = bc.constrain(Size::new(width, y));
+        trace!(
+            "Computed layout: size={}, column_widths={:?}",
+            size,
+            column_widths
+        );
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        let width = ctx.size().width;
+
+        if let Some(rect) = self.selected_row.and_then(|row| self.row_rect(width, row)) {
+            let color = if ctx.is_focused() {
+                env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR)
+            } else {
+                env.get(theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR)
+            };
+            let rect = ctx.crisp_rect(rect);
+            ctx.fill(rect, &color);
+        }
+
+        if let (true, Some(&(_, header_y1))) = (self.has_header, self.row_spans.first()) {
+            let y = header_y1 + HEADER_SEPARATOR_WIDTH / 2.0;
+            let line = Line::new((0.0, y), (width, y));
+            ctx.stroke(line, &env.get(theme::BORDER_LIGHT), HEADER_SEPARATOR_WIDTH);
+
+            if let Some(&(_, column_x1)) = self
+                .sort
+                .and_then(|(column, _)| self.column_spans.get(column))
+            {
+                // A small triangle in the column spacing, pointing up for ascending order.
+                let ascending = self.sort.map_or(true, |(_, ascending)| ascending);
+                let center = Point::new(column_x1 + self.column_spacing / 2.0, header_y1 / 2.0);
+                let half = SORT_INDICATOR_SIZE / 2.0;
+                let (tip, base) = if ascending {
+                    (-half, half)
+                } else {
+                    (half, -half)
+                };
+                let mut path = BezPath::new();
+                path.move_to((center.x, center.y + tip));
+                path.line_to((center.x + half, center.y + base));
+                path.line_to((center.x - half, center.y + base));
+                path.close_path();
+                ctx.fill(path, &env.get(theme::FOREGROUND_DARK));
+            }
+        }
+
+        for cell in self.all_cells_mut() {
+            cell.paint(ctx, env);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        self.rows
+            .iter()
+            .flatten()
+            .map(|cell| cell.as_dyn())
+            .collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Table")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(format!("{}x{}", self.row_count(), self.column_count()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::RawMods;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::Label;
+    use crate::WidgetId;
+
+    fn rect(harness: &TestHarness, id: WidgetId) -> Rect {
+        harness.get_widget(id).state().window_layout_rect()
+    }
+
+    #[test]
+    fn auto_column_width() {
+        let [short_id, long_id, right_1_id, right_2_id] = widget_ids();
+        let table = Table::new()
+            .with_row([
+                Label::new("a").with_id(short_id),
+                Label::new("b").with_id(right_1_id),
+            ])
+            .with_row([
+                Label::new("a much longer cell").with_id(long_id),
+                Label::new("c").with_id(right_2_id),
+            ]);
+
+        let harness = TestHarness::create(table);
+
+        let long_width = rect(&harness, long_id).width();
+        assert!(rect(&harness, short_id).width() < long_width);
+        assert_eq!(rect(&harness, right_1_id).x0, rect(&harness, right_2_id).x0);
+        assert_eq!(
+            rect(&harness, right_1_id).x0,
+            long_width + DEFAULT_COLUMN_SPACING
+        );
+    }
+
+    #[test]
+    fn fixed_column_width() {
+        let [long_id, right_id] = widget_ids();
+        let table = Table::new()
+            .with_column(TableColumn::fixed(50.0))
+            .with_row([
+                Label::new("a much longer cell").with_id(long_id),
+                Label::new("b").with_id(right_id),
+            ]);
+
+        let harness = TestHarness::create(table);
+
+        assert!(rect(&harness, long_id).width() <= 50.0);
+        assert_eq!(rect(&harness, right_id).x0, 50.0 + DEFAULT_COLUMN_SPACING);
+    }
+
+    #[test]
+    fn header_row() {
+        let [header_id, cell_id] = widget_ids();
+        let table = Table::new()
+            .with_header([Label::new("Name").with_id(header_id)])
+            .with_row([Label::new("value").with_id(cell_id)]);
+
+        let harness = TestHarness::create(table);
+
+        let table = harness.root_widget();
+        assert_eq!(table.downcast::<Table>().unwrap().row_count(), 1);
+        assert_eq!(
+            rect(&harness, cell_id).y0,
+            rect(&harness, header_id).y1 + HEADER_SEPARATOR_WIDTH
+        );
+    }
+
+    #[test]
+    fn click_header_requests_sort() {
+        let [name_id, size_id] = widget_ids();
+        let table = Table::new()
+            .with_header([
+                Label::new("Name").with_id(name_id),
+                Label::new("Size").with_id(size_id),
+            ])
+            .with_row([Label::new("a"), Label::new("1")]);
+
+        let mut harness = TestHarness::create(table);
+        let table_id = harness.root_widget().id();
+
+        harness.mouse_click_on(size_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((
+                Action::SortRequested {
+                    column: 1,
+                    ascending: true
+                },
+                table_id
+            ))
+        );
+
+        harness.mouse_click_on(size_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((
+                Action::SortRequested {
+                    column: 1,
+                    ascending: false
+                },
+                table_id
+            ))
+        );
+    }
+
+    #[test]
+    fn arrow_keys_move_selection() {
+        let [first_id] = widget_ids();
+        let table = Table::new()
+            .with_header([Label::new("Name")])
+            .with_row([Label::new("a").with_id(first_id)])
+            .with_row([Label::new("b")])
+            .with_row([Label::new("c")]);
+
+        let mut harness = TestHarness::create(table);
+        let table_id = harness.root_widget().id();
+
+        harness.mouse_click_on(first_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::RowSelected(0), table_id))
+        );
+
+        harness.keyboard_press_key(KbKey::ArrowDown, RawMods::None);
+        harness.keyboard_press_key(KbKey::ArrowDown, RawMods::None);
+        harness.keyboard_press_key(KbKey::ArrowDown, RawMods::None);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::RowSelected(1), table_id))
+        );
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::RowSelected(2), table_id))
+        );
+        // The selection stays on the last row.
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::RowSelected(2), table_id))
+        );
+
+        harness.keyboard_press_key(KbKey::ArrowUp, RawMods::None);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::RowSelected(1), table_id))
+        );
+        let table = harness.root_widget();
+        assert_eq!(table.downcast::<Table>().unwrap().selected_row(), Some(1));
+    }
+}
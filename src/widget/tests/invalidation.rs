@@ -4,8 +4,11 @@
 
 //! Tests related to propagation of invalid rects.
 
+use smallvec::SmallVec;
+
 use crate::testing::{widget_ids, TestHarness};
-use crate::widget::{Button, Flex};
+use crate::widget::{Button, Flex, WidgetRef};
+use crate::*;
 
 #[test]
 fn invalidate_union() {
@@ -37,4 +40,79 @@ fn invalidate_union() {
     );
 }
 
+const SET_VALUE: Selector<u64> = Selector::new("masonry-test.set-value");
+
+// A widget that requests a repaint every time its value is set, even if it didn't change.
+struct HashedValue(u64);
+
+impl Widget for HashedValue {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, _env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some(value) = cmd.try_get(SET_VALUE) {
+                self.0 = *value;
+                ctx.request_paint();
+            }
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _env: &Env) {}
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _env: &Env) -> Size {
+        bc.constrain(Size::new(50.0, 20.0))
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _env: &Env) {}
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        SmallVec::new()
+    }
+
+    fn content_hash(&self) -> Option<u64> {
+        Some(self.0)
+    }
+}
+
+#[test]
+fn unchanged_content_hash_skips_paint() {
+    let [id] = widget_ids();
+
+    let widget = Flex::column().with_child_id(HashedValue(1), id);
+
+    let mut harness = TestHarness::create(widget);
+    let _ = harness.render();
+    assert!(harness.window().invalid().is_empty());
+
+    harness.submit_command(SET_VALUE.with(1).to(id));
+    assert!(harness.window().invalid().is_empty());
+
+    harness.submit_command(SET_VALUE.with(2).to(id));
+    let rect = harness.get_widget(id).state().layout_rect();
+    assert_eq!(harness.window().invalid().rects(), &[rect]);
+}
+
+#[test]
+fn localized_change_reports_small_damage() {
+    let [id] = widget_ids();
+
+    let widget = Flex::column()
+        .with_child_id(HashedValue(1), id)
+        .with_child(Button::new("hello"))
+        .with_child(Button::new("world"));
+
+    let mut harness = TestHarness::create(widget);
+    let _ = harness.render();
+    let first_frame = harness.window().frame_metrics().clone();
+    assert_eq!(first_frame.damage_ratio(), 1.0);
+
+    harness.submit_command(SET_VALUE.with(2).to(id));
+    let _ = harness.render();
+    let metrics = harness.window().frame_metrics();
+    let rect = harness.get_widget(id).state().layout_rect();
+    assert_eq!(metrics.damaged_area, rect.area());
+    assert!(metrics.damage_ratio() < 0.1);
+    assert!(metrics.painted_widgets < first_frame.painted_widgets);
+}
+
 // TODO: Add a test with scrolling/viewport
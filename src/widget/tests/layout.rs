@@ -6,10 +6,14 @@
 
 #![allow(unused_imports)]
 
-use druid_shell::kurbo::{Insets, Size};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use druid_shell::kurbo::{Insets, Point, Size};
 
 use crate::testing::{widget_ids, ModularWidget, TestHarness, TestWidgetExt};
-use crate::widget::{Flex, SizedBox};
+use crate::widget::{Flex, Label, SizedBox};
+use crate::{BoxConstraints, Widget, WidgetPod};
 
 #[test]
 fn layout_simple() {
@@ -72,6 +76,152 @@ fn layout_insets() {
     assert_eq!(parent_paint_rect.y1, BOX_WIDTH + 20.0);
 }
 
+#[test]
+fn column_sized_to_max_intrinsic_width() {
+    const LONG_TEXT: &str = "a much longer label";
+
+    let [short_id, long_id, natural_id] = widget_ids();
+
+    let children: Vec<WidgetPod<Box<dyn Widget>>> = vec![
+        WidgetPod::new_with_id(Label::new("short"), short_id).boxed(),
+        WidgetPod::new_with_id(Label::new(LONG_TEXT), long_id).boxed(),
+    ];
+    // A column that makes all its children as wide as the widest one.
+    let column = ModularWidget::new(children)
+        .event_fn(|children, ctx, event, env| {
+            for child in children {
+                child.on_event(ctx, event, env);
+            }
+        })
+        .lifecycle_fn(|children, ctx, event, env| {
+            for child in children {
+                child.lifecycle(ctx, event, env);
+            }
+        })
+        .layout_fn(|children, ctx, bc, env| {
+            let width = children
+                .iter_mut()
+                .map(|child| child.max_intrinsic_width(ctx, f64::INFINITY, env))
+                .fold(0.0, f64::max);
+            let child_bc =
+                BoxConstraints::new(Size::new(width, 0.0), Size::new(width, f64::INFINITY));
+
+            let mut y = 0.0;
+            for child in children.iter_mut() {
+                let size = child.layout(ctx, &child_bc, env);
+                ctx.place_child(child, Point::new(0.0, y), env);
+                y += size.height;
+            }
+            bc.constrain(Size::new(width, y))
+        })
+        .children_fn(|children| children.iter().map(|child| child.as_dyn()).collect());
+
+    let harness = TestHarness::create(Flex::column().with_child(column));
+    let natural_harness =
+        TestHarness::create(Flex::column().with_child_id(Label::new(LONG_TEXT), natural_id));
+
+    let short_width = harness.get_widget(short_id).state().layout_rect().width();
+    let long_width = harness.get_widget(long_id).state().layout_rect().width();
+    let natural_width = natural_harness
+        .get_widget(natural_id)
+        .state()
+        .layout_rect()
+        .width();
+
+    assert_eq!(long_width, natural_width);
+    assert_eq!(short_width, long_width);
+}
+
 // TODO - insets + flex
 // TODO - viewport
 // TODO - insets + viewport
+
+#[test]
+fn batched_edits_cause_single_layout() {
+    let layout_count: Rc<Cell<u32>> = Rc::new(0.into());
+
+    let counter = ModularWidget::new(layout_count.clone()).layout_fn(|state, _ctx, _bc, _env| {
+        state.set(state.get() + 1);
+        Size::ZERO
+    });
+    let widget = Flex::column().with_child(counter);
+
+    let mut harness = TestHarness::create(widget);
+    layout_count.set(0);
+
+    harness.edit_root_widget_batched(|harness| {
+        for i in 0..100 {
+            harness.edit_root_widget(|mut flex, _| {
+                let mut flex = flex.downcast::<Flex>().unwrap();
+                flex.add_child(Label::new(format!("Item {i}")));
+            });
+        }
+    });
+    assert_eq!(layout_count.get(), 1);
+
+    harness.edit_root_widget(|mut flex, _| {
+        let mut flex = flex.downcast::<Flex>().unwrap();
+        flex.add_child(Label::new("Unbatched"));
+    });
+    assert_eq!(layout_count.get(), 2);
+}
+
+#[test]
+fn layout_only_never_renders() {
+    let [box_1, box_2] = widget_ids();
+    let widget = Flex::column()
+        .with_child(SizedBox::empty().width(50.0).height(20.0).with_id(box_1))
+        .with_child(SizedBox::empty().width(30.0).height(40.0).with_id(box_2));
+
+    let mut harness = TestHarness::create(widget);
+    harness.set_layout_only(true);
+
+    harness.run_for(instant::Duration::from_millis(100));
+    assert!(harness.try_render().is_err());
+
+    let rect_1 = harness.get_widget(box_1).state().window_layout_rect();
+    let rect_2 = harness.get_widget(box_2).state().window_layout_rect();
+    assert_eq!(rect_1.size(), Size::new(50.0, 20.0));
+    assert_eq!(rect_2.size(), Size::new(30.0, 40.0));
+    assert_eq!(rect_2.y0, rect_1.y1);
+
+    assert_eq!(harness.render_target_count(), 0);
+}
+
+#[test]
+fn resizing_window_fires_size_changed() {
+    type SizeChanges = Rc<RefCell<Vec<(Size, Size)>>>;
+
+    fn size_tracker(changes: &SizeChanges, fixed_size: Option<Size>) -> impl Widget {
+        ModularWidget::new(changes.clone())
+            .layout_fn(move |_changes, _ctx, bc, _env| fixed_size.unwrap_or(bc.max()))
+            .size_changed_fn(|changes, _ctx, old_size, new_size, _env| {
+                changes.borrow_mut().push((old_size, new_size));
+            })
+    }
+
+    let expanding_changes = SizeChanges::default();
+    let fixed_changes = SizeChanges::default();
+    let widget = Flex::column()
+        .with_child(size_tracker(&fixed_changes, Some(Size::new(50.0, 50.0))))
+        .with_flex_child(size_tracker(&expanding_changes, None), 1.0);
+
+    let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 200.0));
+    // The first layout counts as a change from a size of zero.
+    assert_eq!(
+        expanding_changes.take(),
+        [(Size::ZERO, Size::new(200.0, 150.0))]
+    );
+    assert_eq!(fixed_changes.take(), [(Size::ZERO, Size::new(50.0, 50.0))]);
+
+    harness.resize_window(Size::new(300.0, 250.0));
+    assert_eq!(
+        expanding_changes.take(),
+        [(Size::new(200.0, 150.0), Size::new(300.0, 200.0))]
+    );
+    assert!(fixed_changes.take().is_empty());
+
+    // Layouts which don't change the size don't fire the callback.
+    harness.resize_window(Size::new(300.0, 250.0));
+    assert!(expanding_changes.take().is_empty());
+}
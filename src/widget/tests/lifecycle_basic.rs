@@ -5,7 +5,8 @@
 use insta::assert_debug_snapshot;
 
 use crate::testing::{
-    widget_ids, Record, Recording, ReplaceChild, TestHarness, TestWidgetExt as _, REPLACE_CHILD,
+    widget_ids, ModularWidget, Record, Recording, ReplaceChild, TestHarness, TestWidgetExt as _,
+    REPLACE_CHILD,
 };
 use crate::widget::{Flex, Label, SizedBox};
 use crate::*;
@@ -21,6 +22,41 @@ fn app_creation() {
     assert_debug_snapshot!(record);
 }
 
+/// Test that nothing reaches the widgets of an uninitialized harness until it's connected.
+#[test]
+fn uninitialized_harness() {
+    let record = Recording::default();
+    let widget = ModularWidget::new(())
+        .lifecycle_fn(|_, ctx, event, _| {
+            if let LifeCycle::WidgetAdded = event {
+                ctx.request_anim_frame();
+            }
+        })
+        .record(&record);
+
+    let mut harness = TestHarness::create_uninitialized(widget);
+
+    assert!(record.is_empty());
+    assert!(!harness.window().wants_animation_frame());
+
+    harness.connect();
+    let _ = harness.render();
+
+    let records = record.drain();
+    assert!(records
+        .iter()
+        .any(|record| matches!(record, Record::L(LifeCycle::WidgetAdded))));
+    assert!(records.iter().any(|record| matches!(record, Record::Paint)));
+    assert!(harness.window().wants_animation_frame());
+}
+
+#[test]
+#[should_panic(expected = "harness is already connected")]
+fn connect_twice() {
+    let mut harness = TestHarness::create(SizedBox::empty());
+    harness.connect();
+}
+
 /// Test that lifecycle events are sent correctly to a child added during event
 /// handling
 #[test]
@@ -53,6 +89,31 @@ fn adding_child() {
     assert_debug_snapshot!(record_new_child);
 }
 
+/// Test that replacing a child adds the new child and removes the old one exactly once.
+#[test]
+fn replacing_child_lifecycle() {
+    let [old_id, new_id] = widget_ids();
+    let replacer = ReplaceChild::new(Label::new("old").with_id(old_id), move || {
+        Label::new("new").with_id(new_id)
+    });
+
+    let mut harness = TestHarness::create(replacer);
+    harness.set_record_lifecycle_events(true);
+    harness.submit_command(REPLACE_CHILD);
+
+    let count = |id, name| {
+        harness
+            .lifecycle_events(id)
+            .into_iter()
+            .filter(|event| *event == name)
+            .count()
+    };
+    assert_eq!(count(new_id, "WidgetAdded"), 1);
+    assert_eq!(count(new_id, "WidgetRemoved"), 0);
+    assert_eq!(count(old_id, "WidgetAdded"), 0);
+    assert_eq!(count(old_id, "WidgetRemoved"), 1);
+}
+
 #[test]
 fn child_tracking() {
     let [id_1, id_2, id_3, id_4] = widget_ids();
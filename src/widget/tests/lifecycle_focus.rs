@@ -2,11 +2,12 @@
 // "as-is" basis without warranties of any kind. See the LICENSE file for
 // details.
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 use smallvec::smallvec;
 
+use crate::shell::{HotKey, KbKey, RawMods};
 use crate::testing::{
     widget_ids, ModularWidget, ReplaceChild, TestHarness, TestWidgetExt as _, REPLACE_CHILD,
 };
@@ -31,6 +32,15 @@ impl FocusTaker {
                         return;
                     }
                 }
+                if let Event::KeyDown(key_event) = event {
+                    if key_event.key == KbKey::Tab {
+                        if key_event.mods.shift() {
+                            ctx.focus_prev();
+                        } else {
+                            ctx.focus_next();
+                        }
+                    }
+                }
             })
             .status_change_fn(|is_focused, _ctx, event, _env| {
                 if let StatusChange::FocusChanged(focus) = event {
@@ -62,6 +72,22 @@ fn build_focus_chain() {
     assert_eq!(harness.window().focus_chain(), &[id_1, id_2, id_3, id_4]);
 }
 
+#[test]
+fn initial_focus() {
+    let [id_1, id_2] = widget_ids();
+    let second_focus: Rc<Cell<bool>> = Default::default();
+
+    let widget = Flex::column()
+        .with_child_id(FocusTaker::new(), id_1)
+        .with_child_id(FocusTaker::track(second_focus.clone()), id_2);
+
+    let config = WindowConfig::default().with_initial_focus(id_2);
+    let harness = TestHarness::create_with_config(widget, config);
+
+    assert_eq!(harness.window().focus, Some(id_2));
+    assert_eq!(second_focus.get(), true);
+}
+
 /// Check that focus changes trigger on_status_change
 #[test]
 fn focus_status_change() {
@@ -94,6 +120,39 @@ fn focus_status_change() {
     assert_eq!(right_focus.get(), true);
 }
 
+#[test]
+fn harness_focus_next_and_prev() {
+    let [id_1, id_2, id_3] = widget_ids();
+    let first_focus: Rc<Cell<bool>> = Default::default();
+    let last_focus: Rc<Cell<bool>> = Default::default();
+
+    let widget = Flex::column()
+        .with_child_id(FocusTaker::track(first_focus.clone()), id_1)
+        .with_child_id(FocusTaker::new(), id_2)
+        .with_child_id(FocusTaker::track(last_focus.clone()), id_3);
+
+    let config = WindowConfig::default().with_initial_focus(id_1);
+    let mut harness = TestHarness::create_with_config(widget, config);
+
+    harness.focus_next();
+    assert_eq!(harness.window().focus, Some(id_2));
+    assert_eq!(first_focus.get(), false);
+
+    harness.focus_next();
+    assert_eq!(harness.window().focus, Some(id_3));
+    assert_eq!(last_focus.get(), true);
+
+    // Focus wraps around
+    harness.focus_next();
+    assert_eq!(harness.window().focus, Some(id_1));
+    assert_eq!(first_focus.get(), true);
+    assert_eq!(last_focus.get(), false);
+
+    harness.focus_prev();
+    assert_eq!(harness.window().focus, Some(id_3));
+    assert_eq!(last_focus.get(), true);
+}
+
 /// test that the last widget to request focus during an event gets it.
 #[test]
 fn take_focus() {
@@ -233,3 +292,215 @@ fn resign_focus_on_disable() {
     assert_eq!(harness.window().focus_chain(), &[focus_2]);
     assert_eq!(harness.window().focus, None);
 }
+
+fn make_focus_trap(child: impl Widget) -> impl Widget {
+    ModularWidget::new(WidgetPod::new(child))
+        .event_fn(|child, ctx, event, env| {
+            child.on_event(ctx, event, env);
+        })
+        .lifecycle_fn(|child, ctx, event, env| {
+            if let LifeCycle::WidgetAdded = event {
+                ctx.set_focus_trap(true);
+            }
+            child.lifecycle(ctx, event, env);
+        })
+        .layout_fn(|child, ctx, bc, env| {
+            let layout = child.layout(ctx, bc, env);
+            ctx.place_child(child, Point::ZERO, env);
+            layout
+        })
+        .children_fn(|child| smallvec![child.as_dyn()])
+}
+
+#[test]
+fn focus_trap() {
+    let [background_1, trapped_1, trapped_2, background_2] = widget_ids();
+
+    let dialog = Flex::column()
+        .with_child_id(FocusTaker::new(), trapped_1)
+        .with_child_id(FocusTaker::new(), trapped_2);
+    let root = Flex::column()
+        .with_child_id(FocusTaker::new(), background_1)
+        .with_child(make_focus_trap(dialog))
+        .with_child_id(FocusTaker::new(), background_2);
+
+    let mut harness = TestHarness::create(root);
+    assert_eq!(
+        harness.window().focus_chain(),
+        &[background_1, trapped_1, trapped_2, background_2]
+    );
+
+    harness.submit_command(REQUEST_FOCUS.to(trapped_1));
+    harness.keyboard_press_key(KbKey::Tab, RawMods::None);
+    assert_eq!(harness.window().focus, Some(trapped_2));
+
+    // Tabbing past the last widget in the trap wraps around to the first one.
+    harness.keyboard_press_key(KbKey::Tab, RawMods::None);
+    assert_eq!(harness.window().focus, Some(trapped_1));
+
+    harness.keyboard_press_key(KbKey::Tab, RawMods::Shift);
+    assert_eq!(harness.window().focus, Some(trapped_2));
+}
+
+#[test]
+fn accelerator_scoped_to_focus() {
+    const BOLD: Selector = Selector::new("masonry-test.bold");
+
+    fn make_editor(child: impl Widget, bold_count: Rc<Cell<u32>>) -> impl Widget {
+        ModularWidget::new((WidgetPod::new(child), bold_count))
+            .event_fn(|(child, bold_count), ctx, event, env| {
+                if let Event::Command(cmd) = event {
+                    if cmd.is(BOLD) {
+                        bold_count.set(bold_count.get() + 1);
+                        ctx.set_handled();
+                    }
+                }
+                child.on_event(ctx, event, env);
+            })
+            .lifecycle_fn(|(child, _), ctx, event, env| {
+                if let LifeCycle::WidgetAdded = event {
+                    let command = BOLD.to(ctx.widget_id());
+                    ctx.register_accelerator(HotKey::new(RawMods::Ctrl, "b"), command);
+                }
+                child.lifecycle(ctx, event, env);
+            })
+            .layout_fn(|(child, _), ctx, bc, env| {
+                let layout = child.layout(ctx, bc, env);
+                ctx.place_child(child, Point::ZERO, env);
+                layout
+            })
+            .children_fn(|(child, _)| smallvec![child.as_dyn()])
+    }
+
+    let [inside, outside] = widget_ids();
+    let bold_count: Rc<Cell<u32>> = Default::default();
+
+    let editor = make_editor(FocusTaker::new().with_id(inside), bold_count.clone());
+    let root = Flex::column()
+        .with_child(editor)
+        .with_child_id(FocusTaker::new(), outside);
+
+    let mut harness = TestHarness::create(root);
+    let key_b = || KbKey::Character("b".into());
+
+    harness.keyboard_press_key(key_b(), RawMods::Ctrl);
+    assert_eq!(bold_count.get(), 0);
+
+    harness.submit_command(REQUEST_FOCUS.to(outside));
+    harness.keyboard_press_key(key_b(), RawMods::Ctrl);
+    assert_eq!(bold_count.get(), 0);
+
+    harness.submit_command(REQUEST_FOCUS.to(inside));
+    harness.keyboard_press_key(key_b(), RawMods::Ctrl);
+    assert_eq!(bold_count.get(), 1);
+
+    // Without the modifier, the key isn't an accelerator.
+    harness.keyboard_press_key(key_b(), RawMods::None);
+    assert_eq!(bold_count.get(), 1);
+}
+
+#[test]
+fn global_and_focused_accelerators() {
+    const BOLD: Selector = Selector::new("masonry-test.bold");
+    const GLOBAL_BOLD: Selector = Selector::new("masonry-test.global-bold");
+    const UNREGISTER: Selector = Selector::new("masonry-test.unregister");
+
+    let ctrl_b = || HotKey::new(RawMods::Ctrl, "b");
+    let [editor_id, inside, outside] = widget_ids();
+    let received: Rc<RefCell<Vec<&'static str>>> = Default::default();
+
+    let editor = FocusTaker::new().with_id(inside);
+    let editor = ModularWidget::new((WidgetPod::new(editor), received.clone()))
+        .event_fn(move |(child, received), ctx, event, env| {
+            if let Event::Command(cmd) = event {
+                if cmd.is(BOLD) {
+                    received.borrow_mut().push("bold");
+                } else if cmd.is(GLOBAL_BOLD) {
+                    received.borrow_mut().push("global bold");
+                } else if cmd.is(UNREGISTER) {
+                    ctx.unregister_accelerator(&ctrl_b());
+                }
+            }
+            child.on_event(ctx, event, env);
+        })
+        .lifecycle_fn(move |(child, _), ctx, event, env| {
+            if let LifeCycle::WidgetAdded = event {
+                let id = ctx.widget_id();
+                ctx.register_global_accelerator(ctrl_b(), GLOBAL_BOLD.to(id));
+                // Registering the same hotkey again replaces the first command.
+                ctx.register_accelerator(ctrl_b(), GLOBAL_BOLD.to(id));
+                ctx.register_accelerator(ctrl_b(), BOLD.to(id));
+            }
+            child.lifecycle(ctx, event, env);
+        })
+        .layout_fn(|(child, _), ctx, bc, env| {
+            let layout = child.layout(ctx, bc, env);
+            ctx.place_child(child, Point::ZERO, env);
+            layout
+        })
+        .children_fn(|(child, _)| smallvec![child.as_dyn()]);
+    let root = Flex::column()
+        .with_child_id(editor, editor_id)
+        .with_child_id(FocusTaker::new(), outside);
+
+    let mut harness = TestHarness::create(root);
+    let key_b = || KbKey::Character("b".into());
+
+    // With focus elsewhere, only the global accelerator is active.
+    harness.submit_command(REQUEST_FOCUS.to(outside));
+    harness.keyboard_press_key(key_b(), RawMods::Ctrl);
+    assert_eq!(*received.borrow(), ["global bold"]);
+
+    // On the focused path, the focused accelerator wins over the global one.
+    received.borrow_mut().clear();
+    harness.submit_command(REQUEST_FOCUS.to(inside));
+    harness.keyboard_press_key(key_b(), RawMods::Ctrl);
+    assert_eq!(*received.borrow(), ["bold"]);
+
+    // Once unregistered, the hotkey does nothing.
+    received.borrow_mut().clear();
+    harness.submit_command(UNREGISTER.to(editor_id));
+    harness.keyboard_press_key(key_b(), RawMods::Ctrl);
+    assert!(received.borrow().is_empty());
+}
+
+#[test]
+fn nested_focus_traps() {
+    let [outer_1, inner_1, inner_2] = widget_ids();
+
+    let inner = Flex::column()
+        .with_child_id(FocusTaker::new(), inner_1)
+        .with_child_id(FocusTaker::new(), inner_2);
+    let outer = Flex::column()
+        .with_child_id(FocusTaker::new(), outer_1)
+        .with_child(make_focus_trap(inner));
+
+    let mut harness = TestHarness::create(make_focus_trap(outer));
+
+    // The innermost trap wins.
+    harness.submit_command(REQUEST_FOCUS.to(inner_2));
+    harness.keyboard_press_key(KbKey::Tab, RawMods::None);
+    assert_eq!(harness.window().focus, Some(inner_1));
+}
+
+#[test]
+fn sibling_focus_traps() {
+    let [first_1, first_2, last_1, last_2] = widget_ids();
+
+    let first = Flex::column()
+        .with_child_id(FocusTaker::new(), first_1)
+        .with_child_id(FocusTaker::new(), first_2);
+    let last = Flex::column()
+        .with_child_id(FocusTaker::new(), last_1)
+        .with_child_id(FocusTaker::new(), last_2);
+    let root = Flex::column()
+        .with_child(make_focus_trap(first))
+        .with_child(make_focus_trap(last));
+
+    let mut harness = TestHarness::create(root);
+
+    // The last trap in the tree wins.
+    harness.submit_command(REQUEST_FOCUS.to(last_2));
+    harness.keyboard_press_key(KbKey::Tab, RawMods::None);
+    assert_eq!(harness.window().focus, Some(last_1));
+}
@@ -0,0 +1,353 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::kurbo::{Rect, Size};
+use crate::shell::Scale;
+use crate::testing::{
+    temp_dir_for_test, widget_ids, HarnessError, ModularWidget, TestHarness, TestWidgetExt as _,
+};
+use crate::widget::{Flex, Label, SizedBox, WidgetRef};
+use crate::{command, theme, Color, Widget, WidgetId};
+
+#[test]
+fn render_widget_matches_layout_rect() {
+    let [label_id, box_id] = widget_ids();
+    let widget = Flex::column()
+        .with_child(Label::new("Hello").with_id(label_id))
+        .with_child(SizedBox::empty().width(40.0).height(25.0).with_id(box_id));
+
+    let mut harness = TestHarness::create(widget);
+
+    for id in [label_id, box_id] {
+        let rect = harness.get_widget(id).state().window_layout_rect().round();
+        let image = harness.render_widget(id);
+        assert_eq!(image.width(), rect.width() as usize);
+        assert_eq!(image.height(), rect.height() as usize);
+    }
+}
+
+#[test]
+fn env_version_only_changes_on_edit() {
+    let versions: Rc<RefCell<Vec<u64>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let widget = ModularWidget::new(versions.clone()).paint_fn(|state, _ctx, env| {
+        state.borrow_mut().push(env.version());
+    });
+
+    let mut harness = TestHarness::create(widget);
+    harness.render();
+    harness
+        .window_mut()
+        .invalid_mut()
+        .add_rect(Rect::new(0.0, 0.0, 100.0, 100.0));
+    harness.render();
+
+    let first_version = versions.borrow()[0];
+    assert!(versions.borrow().len() >= 2);
+    assert!(versions.borrow().iter().all(|v| *v == first_version));
+
+    harness.edit_env(|env| env.set(theme::WINDOW_BACKGROUND_COLOR, Color::BLACK));
+    harness.render();
+
+    assert_ne!(*versions.borrow().last().unwrap(), first_version);
+}
+
+#[test]
+fn debug_overlay_outlines_every_widget() {
+    fn count_widgets(widget: WidgetRef<'_, dyn Widget>) -> usize {
+        1 + widget
+            .children()
+            .into_iter()
+            .map(count_widgets)
+            .sum::<usize>()
+    }
+
+    let widget = Flex::column()
+        .with_child(Label::new("Hello"))
+        .with_child(SizedBox::empty().width(40.0).height(25.0));
+
+    let mut harness = TestHarness::create(widget);
+    let without_overlay = harness.render();
+
+    harness.submit_command(command::TOGGLE_DEBUG_OVERLAY);
+    let with_overlay = harness.render();
+    // We don't use assert_eq because we don't want rich assert
+    assert!(without_overlay != with_overlay);
+    assert_eq!(
+        harness.window().debug_overlay_outlines(),
+        count_widgets(harness.root_widget())
+    );
+
+    harness.submit_command(command::TOGGLE_DEBUG_OVERLAY);
+    assert!(harness.render() == without_overlay);
+}
+
+#[test]
+fn hit_test_overlay_follows_mouse() {
+    let [top_id, bottom_id] = widget_ids();
+    let widget = Flex::column()
+        .with_child_id(Label::new("Top"), top_id)
+        .with_child_id(Label::new("Bottom"), bottom_id);
+
+    let mut harness = TestHarness::create(widget);
+    harness.submit_command(command::TOGGLE_HIT_TEST_OVERLAY);
+
+    harness.mouse_move_to(top_id);
+    let top_image = harness.render();
+    assert_eq!(harness.window().hit_test_target(), Some(top_id));
+
+    harness.mouse_move_to(bottom_id);
+    let bottom_image = harness.render();
+    assert_eq!(harness.window().hit_test_target(), Some(bottom_id));
+
+    // We don't use assert_eq because we don't want rich assert
+    assert!(top_image != bottom_image);
+}
+
+#[test]
+fn render_at_scale_factor() {
+    let [box_id] = widget_ids();
+    let scale = Rc::new(RefCell::new(None));
+    let widget = ModularWidget::new(scale.clone()).layout_fn(|scale, ctx, bc, _env| {
+        *scale.borrow_mut() = Some(ctx.scale());
+        bc.max()
+    });
+    let widget = Flex::row()
+        .with_child(SizedBox::empty().width(30.0).height(20.0).with_id(box_id))
+        .with_flex_child(widget, 1.0);
+
+    let mut harness =
+        TestHarness::create_with_scale(widget, Size::new(100.0, 50.0), Scale::new(2.0, 2.0));
+
+    assert_eq!(*scale.borrow(), Some(Scale::new(2.0, 2.0)));
+    assert_eq!(harness.render().len(), 200 * 100 * 4);
+
+    // Layout rects are in display points, renders are in pixels.
+    let image = harness.render_widget(box_id);
+    assert_eq!(image.width(), 60);
+    assert_eq!(image.height(), 40);
+}
+
+#[test]
+fn harness_widget_errors() {
+    let [inside_id, outside_id] = widget_ids();
+    let widget = Flex::column()
+        .with_child(
+            SizedBox::empty()
+                .width(40.0)
+                .height(40.0)
+                .with_id(inside_id),
+        )
+        .with_child(
+            SizedBox::empty()
+                .width(40.0)
+                .height(40.0)
+                .with_id(outside_id),
+        );
+    let mut harness = TestHarness::create_with_size(widget, Size::new(50.0, 50.0));
+
+    let missing_id = WidgetId::next();
+    assert!(harness.try_get_widget(missing_id).is_none());
+    assert!(matches!(
+        harness.try_render_widget(missing_id),
+        Err(HarnessError::MissingWidget(id)) if id == missing_id
+    ));
+    assert!(matches!(
+        harness.try_render_widget(outside_id),
+        Err(HarnessError::WidgetOutsideWindow(id)) if id == outside_id
+    ));
+    assert!(harness.try_render_widget(inside_id).is_ok());
+
+    harness.set_layout_only(true);
+    assert!(matches!(
+        harness.try_render(),
+        Err(HarnessError::LayoutOnly)
+    ));
+    assert!(matches!(
+        harness.try_check_render_snapshot(
+            env!("CARGO_MANIFEST_DIR"),
+            file!(),
+            module_path!(),
+            "layout_only",
+        ),
+        Err(HarnessError::LayoutOnly)
+    ));
+}
+
+#[test]
+fn harness_snapshot_errors() {
+    let screenshot_dir = temp_dir_for_test();
+    let check = |color: Color| {
+        let widget = SizedBox::empty().width(20.0).height(20.0).background(color);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(20.0, 20.0));
+        harness.set_screenshot_dir(&screenshot_dir);
+        harness.try_check_render_snapshot(
+            env!("CARGO_MANIFEST_DIR"),
+            file!(),
+            module_path!(),
+            "errors",
+        )
+    };
+
+    let Err(HarnessError::MissingReference {
+        reference_path,
+        new_path,
+    }) = check(Color::RED)
+    else {
+        panic!("expected a missing reference");
+    };
+    std::fs::rename(new_path, &reference_path).unwrap();
+    assert!(check(Color::RED).is_ok());
+
+    let Err(HarnessError::SnapshotMismatch {
+        reference_path: mismatched_path,
+        new_path,
+        diff_path,
+    }) = check(Color::BLUE)
+    else {
+        panic!("expected a snapshot mismatch");
+    };
+    assert_eq!(mismatched_path, reference_path);
+    assert!(new_path.exists());
+    assert!(diff_path.exists());
+}
+
+#[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "openbsd"))]
+#[test]
+fn svg_snapshot_errors() {
+    let screenshot_dir = temp_dir_for_test();
+    let check = |color: Color| {
+        let widget = SizedBox::empty().width(20.0).height(20.0).background(color);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(20.0, 20.0));
+        harness.set_screenshot_dir(&screenshot_dir);
+        harness.try_check_svg_snapshot(
+            env!("CARGO_MANIFEST_DIR"),
+            file!(),
+            module_path!(),
+            "svg_errors",
+        )
+    };
+
+    let Err(HarnessError::MissingReference {
+        reference_path,
+        new_path,
+    }) = check(Color::RED)
+    else {
+        panic!("expected a missing reference");
+    };
+    assert_eq!(reference_path.extension().unwrap(), "svg");
+    std::fs::rename(new_path, &reference_path).unwrap();
+    // The surface ids cairo picks change with every render, but the document doesn't.
+    assert!(check(Color::RED).is_ok());
+
+    let Err(HarnessError::SvgSnapshotMismatch {
+        reference_path: mismatched_path,
+        new_path,
+    }) = check(Color::BLUE)
+    else {
+        panic!("expected a snapshot mismatch");
+    };
+    assert_eq!(mismatched_path, reference_path);
+    let document = std::fs::read_to_string(new_path).unwrap();
+    assert!(document.contains("fill:rgb(0%,0%,100%)"));
+
+    let mut harness = TestHarness::create(SizedBox::empty());
+    harness.set_layout_only(true);
+    assert!(matches!(
+        harness.try_check_svg_snapshot(
+            env!("CARGO_MANIFEST_DIR"),
+            file!(),
+            module_path!(),
+            "svg_layout_only",
+        ),
+        Err(HarnessError::LayoutOnly)
+    ));
+}
+
+#[test]
+fn render_diff_against_reference() {
+    let screenshot_dir = temp_dir_for_test();
+    let harness_with_color = |color: Color| {
+        let widget = SizedBox::empty().width(20.0).height(20.0).background(color);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(20.0, 20.0));
+        harness.set_screenshot_dir(&screenshot_dir);
+        harness
+    };
+
+    // Write a reference image through the snapshot machinery.
+    let Err(HarnessError::MissingReference { new_path, .. }) = harness_with_color(Color::RED)
+        .try_check_render_snapshot(
+            env!("CARGO_MANIFEST_DIR"),
+            file!(),
+            module_path!(),
+            "diff_against",
+        )
+    else {
+        panic!("expected a missing reference");
+    };
+
+    let diff = harness_with_color(Color::RED)
+        .render_diff_against(&new_path)
+        .unwrap();
+    assert!(diff.is_none());
+
+    let diff = harness_with_color(Color::BLUE)
+        .render_diff_against(&new_path)
+        .unwrap()
+        .expect("renders should differ");
+    assert_eq!((diff.width(), diff.height()), (20, 20));
+
+    assert!(matches!(
+        harness_with_color(Color::RED).render_diff_against(screenshot_dir.join("missing.png")),
+        Err(HarnessError::Io(..))
+    ));
+}
+
+#[test]
+fn snapshot_multiple_references() {
+    let screenshot_dir = temp_dir_for_test();
+    let check = |color: Color, name: &str| {
+        let widget = SizedBox::empty().width(20.0).height(20.0).background(color);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(20.0, 20.0));
+        harness.set_screenshot_dir(&screenshot_dir);
+        harness.set_screenshot_name("{test}");
+        harness.try_check_render_snapshot(env!("CARGO_MANIFEST_DIR"), file!(), module_path!(), name)
+    };
+    let render_to = |color: Color, file_name: &str| {
+        let Err(HarnessError::MissingReference { new_path, .. }) = check(color, "scratch") else {
+            panic!("expected a missing reference");
+        };
+        std::fs::rename(new_path, screenshot_dir.join(file_name)).unwrap();
+    };
+
+    // Any of the numbered references can match.
+    render_to(Color::BLUE, "multi.0.png");
+    render_to(Color::RED, "multi.1.png");
+    assert!(check(Color::BLUE, "multi").is_ok());
+    assert!(check(Color::RED, "multi").is_ok());
+    // Mismatches are reported against the first reference.
+    let Err(HarnessError::SnapshotMismatch { reference_path, .. }) = check(Color::GREEN, "multi")
+    else {
+        panic!("expected a snapshot mismatch");
+    };
+    assert_eq!(reference_path, screenshot_dir.join("multi.0.png"));
+
+    // Numbered references specific to the current platform replace the generic ones.
+    let os = std::env::consts::OS;
+    render_to(Color::RED, "platform.png");
+    render_to(Color::BLUE, &format!("platform.{os}.0.png"));
+    let Err(HarnessError::SnapshotMismatch { reference_path, .. }) = check(Color::RED, "platform")
+    else {
+        panic!("expected a snapshot mismatch");
+    };
+    assert_eq!(
+        reference_path,
+        screenshot_dir.join(format!("platform.{os}.0.png"))
+    );
+    render_to(Color::RED, &format!("platform.{os}.1.png"));
+    assert!(check(Color::RED, "platform").is_ok());
+}
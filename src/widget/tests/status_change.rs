@@ -2,7 +2,10 @@
 // "as-is" basis without warranties of any kind. See the LICENSE file for
 // details.
 
-use druid_shell::MouseButton;
+use std::cell::Cell;
+use std::rc::Rc;
+
+use druid_shell::{KbKey, Modifiers, MouseButton, RawMods};
 
 use crate::testing::{
     widget_ids, ModularWidget, Record, Recording, TestHarness, TestWidgetExt as _,
@@ -133,10 +136,35 @@ fn update_hot_on_mouse_leave() {
     assert!(is_hot(&harness, label_id));
 
     label_rec.clear();
-    harness.process_event(Event::Internal(InternalEvent::MouseLeave));
+    harness.mouse_leave();
 
     assert!(!is_hot(&harness, label_id));
     assert_eq!(next_hot_changed(&label_rec), Some(false));
+    assert!(label_rec
+        .drain()
+        .iter()
+        .any(|record| matches!(record, Record::E(Event::MouseLeave))));
+}
+
+#[test]
+fn button_hover_resets_on_mouse_leave() {
+    let [button_id] = widget_ids();
+    let button_rec = Recording::default();
+    let widget = Button::new("Hover me")
+        .record(&button_rec)
+        .with_id(button_id);
+
+    let mut harness = TestHarness::create(widget);
+
+    harness.mouse_move_to(button_id);
+    assert!(harness.get_widget(button_id).state().is_hot());
+    let hovered = harness.render();
+
+    button_rec.clear();
+    harness.mouse_leave();
+    assert!(!harness.get_widget(button_id).state().is_hot());
+    assert_eq!(next_hot_changed(&button_rec), Some(false));
+    assert_ne!(harness.render(), hovered);
 }
 
 #[test]
@@ -306,3 +334,129 @@ fn get_mouse_events_while_active() {
     harness.mouse_move_to(empty_2);
     assert!(next_mouse_event(&button_rec).is_none());
 }
+
+#[test]
+fn initial_mouse_pos_makes_widget_hot() {
+    let [id] = widget_ids();
+    let widget = SizedBox::empty().width(10.0).height(10.0).with_id(id);
+
+    let mut harness = TestHarness::create_uninitialized(widget).with_initial_mouse_pos((5.0, 5.0));
+    assert!(!is_hot(&harness, id));
+
+    harness.connect();
+    assert!(is_hot(&harness, id));
+}
+
+#[test]
+fn secondary_click_sends_right_button() {
+    let [id] = widget_ids();
+    let recording = Recording::default();
+    let widget = SizedBox::empty()
+        .width(10.0)
+        .height(10.0)
+        .record(&recording)
+        .with_id(id);
+
+    let mut harness = TestHarness::create(widget);
+    recording.clear();
+    harness.mouse_click_on_with_button(id, MouseButton::Right);
+
+    let buttons: Vec<_> = recording
+        .drain()
+        .into_iter()
+        .filter_map(|record| match record {
+            Record::E(Event::MouseDown(mouse)) => Some(("down", mouse.button)),
+            Record::E(Event::MouseUp(mouse)) => Some(("up", mouse.button)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        buttons,
+        [("down", MouseButton::Right), ("up", MouseButton::Right)]
+    );
+}
+
+#[test]
+fn drag_across_multiple_roots() {
+    let [source_id, target_id] = widget_ids();
+    let drop_pos: Rc<Cell<Option<Point>>> = Rc::new(Cell::new(None));
+
+    let source = ModularWidget::new(drop_pos.clone())
+        .event_fn(|drop_pos, ctx, event, _| match event {
+            Event::MouseDown(_) => ctx.set_active(true),
+            Event::MouseUp(mouse) if ctx.is_active() => {
+                ctx.set_active(false);
+                drop_pos.set(Some(mouse.window_pos));
+            }
+            _ => {}
+        })
+        .layout_fn(|_, _, bc, _| bc.constrain(Size::new(100.0, 100.0)))
+        .with_id(source_id);
+    let target = Label::new("Drop here").with_id(target_id);
+
+    let mut harness = TestHarness::create_multi(vec![Box::new(source), Box::new(target)]);
+    let source_pos = harness
+        .get_widget(source_id)
+        .state()
+        .window_layout_rect()
+        .center();
+    let target_pos = harness
+        .get_widget(target_id)
+        .state()
+        .window_layout_rect()
+        .center();
+
+    harness.mouse_drag(source_pos, target_pos, MouseButton::Left);
+
+    // The source received the whole drag, and was released over the other root.
+    let drop_pos = drop_pos.get().unwrap();
+    assert!(harness
+        .multi_root(1)
+        .state()
+        .window_layout_rect()
+        .contains(drop_pos));
+    assert!(!harness.get_widget(source_id).state().is_active);
+    assert!(is_hot(&harness, target_id));
+}
+
+#[test]
+fn held_modifiers_apply_to_input() {
+    let [id] = widget_ids();
+    let recording = Recording::default();
+    let widget = ModularWidget::new(())
+        .lifecycle_fn(|_, ctx, event, _| {
+            if let LifeCycle::BuildFocusChain = event {
+                ctx.register_for_focus();
+            }
+        })
+        .record(&recording)
+        .with_id(id);
+    let config = WindowConfig::default().with_initial_focus(id);
+
+    let mut harness =
+        TestHarness::create_with_config(widget, config).with_held_modifiers(Modifiers::SHIFT);
+    recording.clear();
+
+    let mods = |recording: &Recording| {
+        recording
+            .drain()
+            .into_iter()
+            .filter_map(|record| match record {
+                Record::E(Event::MouseDown(mouse)) => Some(mouse.mods),
+                Record::E(Event::KeyDown(key)) => Some(key.mods),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    harness.mouse_click_on(id);
+    assert_eq!(mods(&recording), [Modifiers::SHIFT]);
+
+    // Held modifiers are added to the ones passed explicitly.
+    harness.key_down(KbKey::Character("a".into()), RawMods::Ctrl);
+    assert_eq!(mods(&recording), [Modifiers::SHIFT | Modifiers::CONTROL]);
+
+    harness.set_held_modifiers(Modifiers::empty());
+    harness.key_down(KbKey::Character("a".into()), RawMods::None);
+    assert_eq!(mods(&recording), [Modifiers::empty()]);
+}
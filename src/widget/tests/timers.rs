@@ -7,7 +7,11 @@ use std::rc::Rc;
 
 use instant::Duration;
 
-use crate::testing::{ModularWidget, TestHarness};
+use crate::event_log::EventKind;
+use crate::testing::{
+    widget_ids, ModularWidget, TestHarness, TestWidgetExt as _, HARNESS_FRAME_DURATION,
+};
+use crate::widget::{Flex, Label};
 use crate::*;
 
 #[test]
@@ -39,3 +43,254 @@ fn basic_timer() {
     harness.move_timers_forward(Duration::from_secs(2));
     assert_eq!(timer_handled.get(), true);
 }
+
+#[test]
+fn cancelled_timer_doesnt_fire() {
+    let timers_handled: Rc<Cell<u32>> = Rc::new(0.into());
+
+    let widget = ModularWidget::new((None, timers_handled.clone()))
+        .lifecycle_fn(move |state, ctx, event, _| match event {
+            LifeCycle::WidgetAdded => {
+                let cancelled_token = ctx.request_timer(Duration::from_secs(1));
+                state.0 = Some(ctx.request_timer(Duration::from_secs(2)));
+                ctx.cancel_timer(cancelled_token);
+            }
+            _ => {}
+        })
+        .event_fn(|state, _ctx, event, _| {
+            if let Event::Timer(token) = event {
+                assert_eq!(*token, state.0.unwrap());
+                state.1.set(state.1.get() + 1);
+            }
+        });
+
+    let mut harness = TestHarness::create(widget);
+
+    harness.move_timers_forward(Duration::from_secs(1));
+    assert_eq!(timers_handled.get(), 0);
+
+    harness.move_timers_forward(Duration::from_secs(1));
+    assert_eq!(timers_handled.get(), 1);
+}
+
+#[test]
+fn interval_timer_fires_repeatedly() {
+    let fire_count: Rc<Cell<u32>> = Rc::new(0.into());
+
+    let widget = ModularWidget::new((None, fire_count.clone()))
+        .lifecycle_fn(move |state, ctx, event, _| match event {
+            LifeCycle::WidgetAdded => {
+                state.0 = Some(ctx.request_interval_timer(Duration::from_millis(100)));
+            }
+            _ => {}
+        })
+        .event_fn(|state, ctx, event, _| {
+            if let Event::Timer(token) = event {
+                assert_eq!(*token, state.0.unwrap());
+                state.1.set(state.1.get() + 1);
+                if state.1.get() == 5 {
+                    ctx.cancel_timer(*token);
+                }
+            }
+        });
+
+    let mut harness = TestHarness::create(widget);
+
+    harness.move_timers_forward(Duration::from_millis(50));
+    assert_eq!(fire_count.get(), 0);
+
+    harness.move_timers_forward(Duration::from_millis(300));
+    assert_eq!(fire_count.get(), 3);
+
+    // The widget cancels the timer on the fifth fire.
+    harness.move_timers_forward(Duration::from_secs(1));
+    assert_eq!(fire_count.get(), 5);
+}
+
+#[test]
+fn removed_widget_timer_doesnt_fire() {
+    let timer_handled: Rc<Cell<bool>> = Rc::new(false.into());
+
+    let timer_widget = ModularWidget::new(timer_handled.clone())
+        .lifecycle_fn(move |_state, ctx, event, _| match event {
+            LifeCycle::WidgetAdded => {
+                let _ = ctx.request_timer(Duration::from_secs(1));
+            }
+            _ => {}
+        })
+        .event_fn(|state, _ctx, event, _| {
+            if let Event::Timer(_) = event {
+                state.set(true);
+            }
+        });
+    let widget = Flex::row().with_child(timer_widget);
+
+    let mut harness = TestHarness::create(widget);
+    assert_eq!(harness.window().timers.len(), 1);
+
+    harness.edit_root_widget(|mut flex, _| {
+        let mut flex = flex.downcast::<Flex>().unwrap();
+        flex.remove_child(0);
+    });
+    assert!(harness.window().timers.is_empty());
+
+    harness.move_timers_forward(Duration::from_secs(2));
+    assert_eq!(timer_handled.get(), false);
+}
+
+#[test]
+fn hover_schedules_single_tooltip_timer() {
+    let tooltip_delay = Duration::from_millis(500);
+
+    let widget = ModularWidget::new(())
+        .status_change_fn(move |_state, ctx, event, _| {
+            if let StatusChange::HotChanged(true) = event {
+                let _ = ctx.request_timer(tooltip_delay);
+            }
+        })
+        .layout_fn(|_state, _ctx, _bc, _env| Size::new(50.0, 50.0));
+
+    let mut harness = TestHarness::create(widget);
+    assert!(harness.pending_timers().is_empty());
+
+    harness.mouse_move((25.0, 25.0));
+    harness.move_timers_forward(Duration::from_millis(100));
+    // Moving within the widget doesn't schedule another timer.
+    harness.mouse_move((10.0, 10.0));
+
+    let pending_timers = harness.pending_timers();
+    assert_eq!(pending_timers.len(), 1);
+    assert_eq!(pending_timers[0].1, Duration::from_millis(400));
+}
+
+#[test]
+fn run_for_fires_timer_on_right_frame() {
+    let timer_handled: Rc<Cell<bool>> = Rc::new(false.into());
+
+    let widget = ModularWidget::new((None, timer_handled.clone()))
+        .lifecycle_fn(move |state, ctx, event, _| match event {
+            LifeCycle::WidgetAdded => {
+                state.0 = Some(ctx.request_timer(Duration::from_millis(100)));
+            }
+            _ => {}
+        })
+        .event_fn(|state, _ctx, event, _| {
+            if let Event::Timer(token) = event {
+                if *token == state.0.unwrap() {
+                    state.1.set(true);
+                }
+            }
+        });
+
+    let mut harness = TestHarness::create(widget);
+
+    // 6 frames is 96ms
+    harness.run_for(HARNESS_FRAME_DURATION * 6);
+    assert_eq!(timer_handled.get(), false);
+
+    harness.run_for(HARNESS_FRAME_DURATION);
+    assert_eq!(timer_handled.get(), true);
+}
+
+#[test]
+fn run_for_sends_anim_frames() {
+    let frame_count: Rc<Cell<u32>> = Rc::new(0.into());
+
+    let widget = ModularWidget::new((Duration::ZERO, frame_count.clone()))
+        .lifecycle_fn(move |_state, ctx, event, _| match event {
+            LifeCycle::WidgetAdded => {
+                ctx.request_anim_frame();
+            }
+            _ => {}
+        })
+        .event_fn(|state, ctx, event, _| {
+            if let Event::AnimFrame(interval) = event {
+                state.0 += Duration::from_nanos(*interval);
+                state.1.set(state.1.get() + 1);
+                if state.0 < Duration::from_millis(100) {
+                    ctx.request_anim_frame();
+                }
+            }
+        });
+
+    let mut harness = TestHarness::create(widget);
+    harness.run_for(Duration::from_secs(1));
+
+    // The animation stops on the first frame past 100ms, ie 7 * 16ms = 112ms
+    assert_eq!(frame_count.get(), 7);
+}
+
+#[test]
+fn animate_frame_skips_widgets_without_request() {
+    let [animated_id, label_id] = widget_ids();
+    let animated = ModularWidget::new(()).lifecycle_fn(|_state, ctx, event, _| {
+        if let LifeCycle::WidgetAdded = event {
+            ctx.request_anim_frame();
+        }
+    });
+    let widget = Flex::row()
+        .with_child(animated.with_id(animated_id))
+        .with_child(Label::new("Static").with_id(label_id));
+
+    let mut harness = TestHarness::create(widget);
+    harness.set_record_events(true);
+    harness.animate_frame(Duration::from_millis(16));
+
+    assert!(harness
+        .events_received(animated_id)
+        .contains(&EventKind::Animation));
+    assert!(!harness
+        .events_received(label_id)
+        .contains(&EventKind::Animation));
+
+    // The widget didn't request another frame.
+    harness.animate_frame(Duration::from_millis(16));
+    assert_eq!(
+        harness
+            .events_received(animated_id)
+            .iter()
+            .filter(|kind| **kind == EventKind::Animation)
+            .count(),
+        1
+    );
+}
+
+/// A widget animating a spring from 1.0 to 0.0, until it comes to rest.
+fn spring(damping: f64) -> impl Widget {
+    const STIFFNESS: f64 = 100.0;
+    const REST_THRESHOLD: f64 = 1e-3;
+
+    // Position and velocity
+    ModularWidget::new((1.0_f64, 0.0_f64))
+        .lifecycle_fn(|_state, ctx, event, _| {
+            if let LifeCycle::WidgetAdded = event {
+                ctx.request_anim_frame();
+            }
+        })
+        .event_fn(move |(position, velocity), ctx, event, _| {
+            if let Event::AnimFrame(interval) = event {
+                let dt = Duration::from_nanos(*interval).as_secs_f64();
+                *velocity += (-STIFFNESS * *position - damping * *velocity) * dt;
+                *position += *velocity * dt;
+                if position.abs() > REST_THRESHOLD || velocity.abs() > REST_THRESHOLD {
+                    ctx.request_anim_frame();
+                }
+            }
+        })
+}
+
+#[test]
+fn settled_spring_has_no_pending_animations() {
+    // Critical damping
+    let mut harness = TestHarness::create(spring(20.0));
+    harness.run_for(Duration::from_secs(2));
+    harness.assert_no_pending_animations();
+}
+
+#[test]
+#[should_panic(expected = "animations still running")]
+fn undamped_spring_never_settles() {
+    let mut harness = TestHarness::create(spring(0.0));
+    harness.run_for(Duration::from_secs(2));
+    harness.assert_no_pending_animations();
+}
@@ -0,0 +1,237 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A multi-line text input that grows with its content.
+
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+
+use crate::widget::{TextBox, WidgetMut, WidgetRef};
+use crate::{
+    BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Point,
+    Size, StatusChange, Widget, WidgetPod,
+};
+
+/// A multi-line text input.
+///
+/// Lines are wrapped at the width the `TextArea` is given, and pressing Enter
+/// inserts a newline.
+///
+/// By default, the `TextArea` grows vertically to fit its content, up to its
+/// [maximum height](TextArea::with_max_height). Past that height, its content
+/// can be scrolled.
+pub struct TextArea {
+    inner: WidgetPod<TextBox>,
+    autosize: bool,
+    max_height: f64,
+}
+
+crate::declare_widget!(TextAreaMut, TextArea);
+
+impl TextArea {
+    /// Create a new `TextArea` with the given text.
+    pub fn new(initial_text: impl Into<String>) -> Self {
+        TextArea {
+            inner: WidgetPod::new(TextBox::multiline(initial_text).with_line_wrapping(true)),
+            autosize: true,
+            max_height: f64::INFINITY,
+        }
+    }
+
+    /// Builder-style method to set whether the `TextArea` grows to fit its content.
+    ///
+    /// If `false`, the `TextArea` takes all the height it's given (up to its maximum
+    /// height) regardless of its content. If it's given unbounded height, it
+    /// still fits its content.
+    ///
+    /// The default is `true`.
+    pub fn with_autosize(mut self, autosize: bool) -> Self {
+        self.autosize = autosize;
+        self
+    }
+
+    /// Builder-style method to set the maximum height of the `TextArea`.
+    ///
+    /// The default is no maximum.
+    pub fn with_max_height(mut self, max_height: f64) -> Self {
+        self.max_height = max_height;
+        self
+    }
+
+    /// Return the area's current contents.
+    pub fn text(&self) -> String {
+        self.inner.widget().text()
+    }
+}
+
+impl<'a, 'b> TextAreaMut<'a, 'b> {
+    /// Set the area's contents.
+    pub fn set_text(&mut self, new_text: impl Into<String>) {
+        self.text_box_mut().set_text(new_text);
+    }
+
+    /// Set whether the `TextArea` grows to fit its content.
+    ///
+    /// See [`TextArea::with_autosize`].
+    pub fn set_autosize(&mut self, autosize: bool) {
+        self.1.autosize = autosize;
+        self.0.request_layout();
+    }
+
+    /// Set the maximum height of the `TextArea`.
+    ///
+    /// See [`TextArea::with_max_height`].
+    pub fn set_max_height(&mut self, max_height: f64) {
+        self.1.max_height = max_height;
+        self.0.request_layout();
+    }
+
+    /// Return a [`WidgetMut`] to the inner multi-line [`TextBox`].
+    pub fn text_box_mut(&mut self) -> WidgetMut<'_, 'b, TextBox> {
+        self.0.get_mut(&mut self.1.inner)
+    }
+}
+
+impl Widget for TextArea {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        self.inner.on_event(ctx, event, env);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        self.inner.lifecycle(ctx, event, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let max_height = bc.max().height.min(self.max_height);
+        let min_height = if !self.autosize && max_height.is_finite() {
+            max_height
+        } else {
+            bc.min().height.min(max_height)
+        };
+        let child_bc = BoxConstraints::new(
+            Size::new(bc.min().width, min_height),
+            Size::new(bc.max().width, max_height),
+        );
+
+        // The inner TextBox wraps its text at the max width, and reports the
+        // height of the wrapped text (within the given constraints).
+        let size = self.inner.layout(ctx, &child_bc, env);
+        ctx.place_child(&mut self.inner, Point::ORIGIN, env);
+        ctx.set_baseline_offset(self.inner.baseline_offset());
+        bc.constrain(size)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        self.inner.paint(ctx, env);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.inner.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("TextArea")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::Action;
+    use crate::shell::{KbKey, RawMods};
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::{Flex, SizedBox};
+    use crate::WidgetId;
+
+    const LONG_TEXT: &str = "The quick brown fox jumps over the lazy dog, then does it again.";
+
+    fn text_area_height(harness: &TestHarness, id: WidgetId) -> f64 {
+        harness.get_widget(id).state().layout_rect().height()
+    }
+
+    #[test]
+    fn wrapping_grows_height() {
+        let [area_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child(SizedBox::new(TextArea::new("Hello").with_id(area_id)).width(150.0));
+
+        let mut harness = TestHarness::create(widget);
+        let single_line_height = text_area_height(&harness, area_id);
+
+        harness.mouse_click_on(area_id);
+        harness.keyboard_type_chars(LONG_TEXT);
+        let wrapped_height = text_area_height(&harness, area_id);
+        assert!(wrapped_height > single_line_height);
+
+        // The text is wrapped, not scrolled horizontally.
+        assert_eq!(
+            harness.get_widget(area_id).state().layout_rect().width(),
+            150.0
+        );
+    }
+
+    #[test]
+    fn max_height() {
+        let [area_id, capped_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child(SizedBox::new(TextArea::new(LONG_TEXT).with_id(area_id)).width(150.0))
+            .with_child(
+                SizedBox::new(
+                    TextArea::new(LONG_TEXT)
+                        .with_max_height(20.0)
+                        .with_id(capped_id),
+                )
+                .width(150.0),
+            );
+
+        let harness = TestHarness::create(widget);
+        assert!(text_area_height(&harness, area_id) > 20.0);
+        assert_eq!(text_area_height(&harness, capped_id), 20.0);
+    }
+
+    #[test]
+    fn fixed_height() {
+        let [area_id] = widget_ids();
+        let widget = Flex::column().with_child(
+            TextArea::new("Hello")
+                .with_autosize(false)
+                .with_max_height(100.0)
+                .with_id(area_id),
+        );
+
+        let harness = TestHarness::create(widget);
+        assert_eq!(text_area_height(&harness, area_id), 100.0);
+    }
+
+    #[test]
+    fn enter_inserts_newline() {
+        let [area_id] = widget_ids();
+        let widget = Flex::column().with_child(TextArea::new("").with_id(area_id));
+
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_click_on(area_id);
+        harness.keyboard_type_chars("Hello");
+        let single_line_height = text_area_height(&harness, area_id);
+
+        harness.keyboard_press_key(KbKey::Enter, RawMods::None);
+        harness.keyboard_type_chars("world");
+
+        let area = harness.get_widget(area_id);
+        assert_eq!(area.downcast::<TextArea>().unwrap().text(), "Hello\nworld");
+        assert!(text_area_height(&harness, area_id) > single_line_height);
+
+        while let Some((action, _)) = harness.pop_action() {
+            assert!(
+                !matches!(action, Action::TextEntered(_)),
+                "Enter should not submit a TextArea"
+            );
+        }
+    }
+}
@@ -5,23 +5,24 @@
 //! A textbox widget.
 
 use std::sync::Arc;
-use std::time::Duration;
 
 use smallvec::{smallvec, SmallVec};
 use tracing::{trace_span, Span};
 
 use crate::action::Action;
 use crate::kurbo::Insets;
-use crate::piet::{RenderContext as _, TextLayout as _};
-use crate::shell::{HotKey, KeyEvent, SysMods, TimerToken};
-use crate::text::{ImeInvalidation, Selection, TextAlignment, TextComponent, TextLayout};
+use crate::piet::RenderContext as _;
+use crate::shell::{HotKey, KeyEvent, SysMods};
+use crate::text::{
+    CaretBlink, ImeInvalidation, Selection, TextAlignment, TextComponent, TextLayout,
+    TextLayoutInfo,
+};
 use crate::widget::{Portal, WidgetMut, WidgetRef};
 use crate::{
     theme, ArcStr, BoxConstraints, Command, Env, Event, EventCtx, LayoutCtx, LifeCycle,
     LifeCycleCtx, PaintCtx, Point, Rect, Size, StatusChange, Vec2, Widget, WidgetPod,
 };
 
-const CURSOR_BLINK_DURATION: Duration = Duration::from_millis(500);
 const MAC_OR_LINUX: bool = cfg!(any(target_os = "macos", target_os = "linux"));
 
 // TODO - Implement formatters (TextBox where the text represents a value of some other type).
@@ -46,13 +47,16 @@ pub struct TextBox {
     /// on the click position; if focus happens automatically (e.g. on tab)
     /// then we select our entire contents.
     was_focused_from_click: bool,
-    cursor_on: bool,
-    cursor_timer: TimerToken,
+    caret: CaretBlink,
     /// if `true` (the default), this textbox will attempt to change focus on tab.
     ///
     /// You can override this in a controller if you want to customize tab
     /// behaviour.
     pub handles_tab_notifications: bool,
+    /// if `true`, the text is obscured and can't be copied.
+    password: bool,
+    /// if `true`, the text of a password field is shown in clear.
+    password_revealed: bool,
     // TODO
     #[allow(dead_code)]
     text_pos: Point,
@@ -78,35 +82,44 @@ impl TextBox {
             placeholder_layout,
             multiline: false,
             was_focused_from_click: false,
-            cursor_on: false,
-            cursor_timer: TimerToken::INVALID,
+            caret: CaretBlink::new(),
             handles_tab_notifications: true,
+            password: false,
+            password_revealed: false,
             text_pos: Point::ZERO,
         }
     }
 
     /// Create a new multi-line `TextBox`.
+    ///
+    /// Pressing Enter inserts a newline, and never emits [`Action::TextEntered`].
     pub fn multiline(initial_text: impl Into<String>) -> Self {
         let mut this = TextBox::new(initial_text);
-        //TODO
-        //this.inner.set_enabled_scrollbars(crate::scroll_component::ScrollbarsEnabled::Both);
-        //this.text_mut().borrow_mut().set_accepts_newlines(true);
-        //this.inner.set_horizontal_scroll_enabled(false);
+        this.inner
+            .as_ref()
+            .child()
+            .borrow_mut()
+            .send_notification_on_return = false;
+        this.inner
+            .as_ref()
+            .child()
+            .borrow_mut()
+            .set_accepts_newlines(true);
         this.multiline = true;
         this
     }
 
-    // TODO
-    #[cfg(FALSE)]
     /// If `true` (and this is a [`multiline`] text box) lines will be wrapped
     /// at the maximum layout width.
     ///
-    /// If `false`, lines will not be wrapped, and horizontal scrolling will
-    /// be enabled.
+    /// If `false` (the default), lines will not be wrapped, and horizontal scrolling
+    /// will be enabled.
     ///
     /// [`multiline`]: TextBox::multiline
     pub fn with_line_wrapping(mut self, wrap_lines: bool) -> Self {
-        self.inner.set_horizontal_scroll_enabled(!wrap_lines);
+        self.inner
+            .widget_mut()
+            .set_constrain_horizontal_raw(wrap_lines);
         self
     }
 }
@@ -264,13 +277,14 @@ impl TextBox {
 
 impl TextBox {
     /// Builder-style method to set the `TextBox`'s placeholder text.
+    ///
+    /// The placeholder is a hint displayed in [`PLACEHOLDER_COLOR`](theme::PLACEHOLDER_COLOR)
+    /// while the `TextBox` is empty and doesn't have focus.
     pub fn with_placeholder(mut self, placeholder: impl Into<ArcStr>) -> Self {
         self.set_placeholder(placeholder);
         self
     }
 
-    // TODO
-    /// Set the `TextBox`'s placeholder text.
     fn set_placeholder(&mut self, placeholder: impl Into<ArcStr>) {
         self.placeholder_text = placeholder.into();
         self.placeholder_layout
@@ -278,6 +292,29 @@ impl TextBox {
     }
 }
 
+impl TextBox {
+    /// Builder-style method to make this `TextBox` a password field.
+    ///
+    /// A password field displays a bullet in place of each character, and its
+    /// text can't be copied to the clipboard. [`text`](Self::text) and
+    /// [`Action::TextChanged`] still give access to the actual text.
+    pub fn with_password(mut self, password: bool) -> Self {
+        self.password = password;
+        self.update_obscured();
+        self
+    }
+
+    fn update_obscured(&self) {
+        let obscured = self.password && !self.password_revealed;
+        self.inner
+            .as_ref()
+            .child()
+            .borrow_mut()
+            .layout
+            .set_obscured(obscured);
+    }
+}
+
 impl TextBox {
     // TODO - Return &str
     /// Return the box's current contents.
@@ -293,20 +330,44 @@ impl TextBox {
             .to_string()
     }
 
-    fn text_len(&self) -> usize {
-        self.inner.as_ref().child().borrow().layout.text_len()
+    /// Return the selected range of text.
+    pub fn selection(&self) -> Selection {
+        self.inner.as_ref().child().borrow().selection()
+    }
+
+    /// Replace the selected text with `text` and put the caret after it, eg when pasting.
+    fn replace_selection(&mut self, ctx: &mut EventCtx, text: &str) {
+        let selection = self.inner.as_ref().child().borrow().selection();
+        let mut new_text = self.text();
+        new_text.replace_range(selection.range(), text);
+
+        ctx.get_mut(&mut self.inner)
+            .child_mut()
+            .set_text(new_text.clone());
+        let caret = Selection::caret(selection.min() + text.len());
+        if let Some(inval) = self
+            .inner
+            .as_ref()
+            .child()
+            .borrow_mut()
+            .set_selection(caret)
+        {
+            ctx.invalidate_text_input(inval);
+        }
+        self.scroll_to_selection_after_layout = true;
+        ctx.submit_action(Action::TextChanged(new_text));
+        ctx.request_paint();
     }
 
-    fn reset_cursor_blink(&mut self, token: TimerToken) {
-        self.cursor_on = true;
-        self.cursor_timer = token;
+    fn text_len(&self) -> usize {
+        self.inner.as_ref().child().borrow().layout.text_len()
     }
 
     fn should_draw_cursor(&self) -> bool {
         if cfg!(target_os = "macos") && self.inner.as_ref().child().can_read() {
-            self.cursor_on && self.inner.as_ref().child().borrow().selection().is_caret()
+            self.caret.is_visible() && self.inner.as_ref().child().borrow().selection().is_caret()
         } else {
-            self.cursor_on
+            self.caret.is_visible()
         }
     }
 }
@@ -319,6 +380,50 @@ impl<'a, 'b> TextBoxMut<'a, 'b> {
     pub fn set_text(&mut self, new_text: impl Into<String>) {
         self.inner_mut().child_mut().set_text(new_text.into());
     }
+
+    /// Set the selected range of text.
+    ///
+    /// The selection must be within the text, on character boundaries.
+    pub fn set_selection(&mut self, selection: Selection) {
+        let inval = self
+            .1
+            .inner
+            .as_ref()
+            .child()
+            .borrow_mut()
+            .set_selection(selection);
+        if let Some(inval) = inval {
+            self.0.invalidate_text_input(inval);
+        }
+        self.0.request_paint();
+    }
+
+    /// Set the `TextBox`'s placeholder text.
+    ///
+    /// See [`TextBox::with_placeholder`].
+    pub fn set_placeholder(&mut self, placeholder: impl Into<ArcStr>) {
+        self.1.set_placeholder(placeholder);
+        self.0.request_layout();
+    }
+
+    /// Set whether this `TextBox` is a password field.
+    ///
+    /// See [`TextBox::with_password`].
+    pub fn set_password(&mut self, password: bool) {
+        self.1.password = password;
+        self.1.update_obscured();
+        self.0.request_layout();
+    }
+
+    /// Show or hide the text of a password field.
+    ///
+    /// This is meant to back a "show password" toggle. The text still can't be
+    /// copied while it's shown.
+    pub fn set_password_revealed(&mut self, revealed: bool) {
+        self.1.password_revealed = revealed;
+        self.1.update_obscured();
+        self.0.request_layout();
+    }
 }
 
 impl TextBox {
@@ -327,7 +432,7 @@ impl TextBox {
         let child = self.inner.as_ref();
         let child = child.child();
         let text = child.borrow();
-        let layout = text.layout.layout().unwrap();
+        let layout = &text.layout;
 
         let hit = layout.hit_test_text_position(text.selection().active);
         let line = layout.line_metric(hit.line).unwrap();
@@ -443,26 +548,18 @@ impl Widget for TextBox {
                     if !mouse.focus {
                         ctx.request_focus();
                         self.was_focused_from_click = true;
-                        self.reset_cursor_blink(ctx.request_timer(CURSOR_BLINK_DURATION));
+                        self.caret.reset(ctx, env);
                     } else {
                         ctx.set_handled();
                     }
                 }
             }
-            Event::Timer(id) => {
-                if !ctx.is_disabled() {
-                    if *id == self.cursor_timer && ctx.has_focus() {
-                        self.cursor_on = !self.cursor_on;
-                        ctx.request_paint();
-                        self.cursor_timer = ctx.request_timer(CURSOR_BLINK_DURATION);
-                    }
-                } else if self.cursor_on {
-                    self.cursor_on = false;
-                    ctx.request_paint();
-                }
+            Event::Timer(token) => {
+                self.caret.on_timer(ctx, *token);
             }
             Event::ImeStateChange => {
-                self.reset_cursor_blink(ctx.request_timer(CURSOR_BLINK_DURATION));
+                // Typing makes the caret solid.
+                self.caret.reset(ctx, env);
                 // TODO - external_text_change.is_some()
             }
             Event::Command(ref cmd)
@@ -470,7 +567,14 @@ impl Widget for TextBox {
                     && ctx.is_focused()
                     && cmd.is(crate::command::COPY) =>
             {
-                self.inner.as_ref().child().borrow().set_clipboard();
+                if !self.password {
+                    let mut clipboard = ctx.clipboard();
+                    self.inner
+                        .as_ref()
+                        .child()
+                        .borrow()
+                        .set_clipboard(&mut clipboard);
+                }
                 ctx.set_handled();
             }
             Event::Command(cmd)
@@ -502,27 +606,25 @@ impl Widget for TextBox {
                 }
                 ctx.set_handled();
             }
-            Event::Paste(ref item) if self.inner.as_ref().child().can_write() => {
-                if let Some(string) = item.get_string() {
-                    let _text = if self.multiline {
+            Event::Paste(ref clipboard) if self.inner.as_ref().child().can_write() => {
+                if let Some(string) = clipboard.get_string() {
+                    let text = if self.multiline {
                         &string
                     } else {
                         string.lines().next().unwrap_or("")
                     };
-                    // TODO
-                    #[cfg(FALSE)]
                     if !text.is_empty() {
-                        let inval = self.text_mut().borrow_mut().insert_text(data, text);
-                        ctx.invalidate_text_input(inval);
+                        self.replace_selection(ctx, text);
                     }
                 }
+                ctx.set_handled();
             }
             _ => (),
         }
         self.inner.on_event(ctx, event, env)
     }
 
-    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange, _env: &Env) {
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange, env: &Env) {
         match event {
             StatusChange::FocusChanged(true) => {
                 // TODO
@@ -537,7 +639,7 @@ impl Widget for TextBox {
                     let mut child = ctx.get_mut(&mut self.inner);
                     child.child_mut().set_focused(true);
                 }
-                self.reset_cursor_blink(ctx.request_timer(CURSOR_BLINK_DURATION));
+                self.caret.focus_changed(ctx, true, env);
                 self.was_focused_from_click = false;
                 ctx.request_paint();
             }
@@ -563,7 +665,7 @@ impl Widget for TextBox {
                     }
                 }
 
-                self.cursor_timer = TimerToken::INVALID;
+                self.caret.focus_changed(ctx, false, env);
                 self.was_focused_from_click = false;
                 ctx.request_paint();
             }
@@ -645,7 +747,7 @@ impl Widget for TextBox {
 
         ctx.fill(clip_rect, &background_color);
 
-        if self.text_len() != 0 {
+        if self.text_len() != 0 || is_focused {
             self.inner.paint(ctx, env);
         } else {
             ctx.skip_child(&mut self.inner);
@@ -709,6 +811,20 @@ impl Widget for TextBox {
     fn make_trace_span(&self) -> Span {
         trace_span!("TextBox")
     }
+
+    fn text_layout_info(&self) -> Option<TextLayoutInfo> {
+        let portal = self.inner.as_ref();
+        let component = portal.child();
+        if !component.can_read() {
+            return None;
+        }
+        // The text is drawn by the component, which is scrolled inside the portal.
+        let session = component.borrow();
+        let origin = self.inner.layout_rect().origin()
+            + component.state().layout_rect().origin().to_vec2()
+            + Vec2::new(session.alignment_offset(), 0.0);
+        Some(TextLayoutInfo::new(&session.layout, origin))
+    }
 }
 
 fn x_offset_for_extra_width(alignment: TextAlignment, extra_width: f64) -> f64 {
@@ -726,7 +842,10 @@ mod tests {
     use super::*;
     use crate::action::Action;
     use crate::assert_render_snapshot;
+    use crate::event_log::EventKind;
+    use crate::shell::{KbKey, RawMods};
     use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::Flex;
 
     #[test]
     fn simple_textbox() {
@@ -743,6 +862,7 @@ mod tests {
         harness.mouse_click_on(textbox_id);
         assert_eq!(harness.focused_widget().unwrap().id(), textbox_id);
         assert_eq!(harness.pop_action(), None);
+        harness.set_text_selection(textbox_id, Selection::new(0, 5));
 
         harness.keyboard_type_chars("abc");
         assert_eq!(
@@ -769,6 +889,249 @@ mod tests {
         );
     }
 
+    #[test]
+    fn textbox_selection() {
+        let [textbox_id] = widget_ids();
+        let textbox = TextBox::new("Hello world").with_id(textbox_id);
+
+        let mut harness = TestHarness::create(textbox);
+        harness.mouse_click_on(textbox_id);
+
+        harness.set_text_selection(textbox_id, Selection::new(0, 5));
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::new(0, 5))
+        );
+
+        harness.set_text_selection(textbox_id, Selection::caret(11));
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::caret(11))
+        );
+
+        harness.keyboard_type_chars("!");
+        assert_eq!(
+            harness
+                .get_widget(textbox_id)
+                .downcast::<TextBox>()
+                .unwrap()
+                .text(),
+            "Hello world!"
+        );
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::caret(12))
+        );
+    }
+
+    #[test]
+    fn ime_composition() {
+        let [textbox_id] = widget_ids();
+        let textbox = TextBox::new("a").with_id(textbox_id);
+
+        let mut harness = TestHarness::create(textbox);
+        harness.mouse_click_on(textbox_id);
+        harness.set_text_selection(textbox_id, Selection::caret(1));
+
+        let mut ime = harness.ime_session();
+        ime.set_composing_region("k").set_composing_region("か");
+        assert_eq!(ime.composing_region(), Some(1..4));
+        ime.set_composing_region("かん");
+        assert_eq!(ime.composing_region(), Some(1..7));
+        ime.commit("漢");
+        assert_eq!(ime.composing_region(), None);
+
+        let textbox = harness.get_widget(textbox_id);
+        assert_eq!(textbox.downcast::<TextBox>().unwrap().text(), "a漢");
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::caret(4))
+        );
+    }
+
+    #[test]
+    fn textbox_word_and_line_navigation() {
+        let [textbox_id] = widget_ids();
+        // Two ideographs (3 bytes each), then "cafe" with a combining accent (2 bytes).
+        let textbox = TextBox::new("漢字 and cafe\u{301}").with_id(textbox_id);
+
+        let mut harness = TestHarness::create(textbox);
+        harness.mouse_click_on(textbox_id);
+        harness.set_text_selection(textbox_id, Selection::caret(0));
+
+        harness.keyboard_press_key(KbKey::ArrowRight, RawMods::Ctrl);
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::caret(3))
+        );
+        harness.keyboard_press_key(KbKey::ArrowRight, RawMods::Ctrl);
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::caret(6))
+        );
+        harness.keyboard_press_key(KbKey::ArrowRight, RawMods::Ctrl);
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::caret(10))
+        );
+
+        harness.keyboard_press_key(KbKey::End, RawMods::None);
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::caret(17))
+        );
+
+        harness.keyboard_press_key(KbKey::ArrowLeft, RawMods::CtrlShift);
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::new(17, 11))
+        );
+
+        harness.keyboard_press_key(KbKey::Home, RawMods::None);
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::caret(0))
+        );
+    }
+
+    #[test]
+    fn textbox_bidi_navigation() {
+        let [textbox_id] = widget_ids();
+        // Hebrew letters are 2 bytes long. This is displayed as "ab גבא".
+        let textbox = TextBox::new("ab \u{5d0}\u{5d1}\u{5d2}").with_id(textbox_id);
+
+        let mut harness = TestHarness::create(textbox);
+        harness.mouse_click_on(textbox_id);
+        harness.set_text_selection(textbox_id, Selection::caret(2));
+
+        // Entering the RTL run from the left puts the caret at the end of the run,
+        // then moving right walks back towards its start.
+        harness.keyboard_press_key(KbKey::ArrowRight, RawMods::None);
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::caret(9))
+        );
+        harness.keyboard_press_key(KbKey::ArrowRight, RawMods::None);
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::caret(7))
+        );
+
+        harness.keyboard_press_key(KbKey::ArrowLeft, RawMods::Shift);
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::new(7, 9))
+        );
+
+        // Offset 9 is displayed to the left of offset 7, so that's where the
+        // selection collapses.
+        harness.keyboard_press_key(KbKey::ArrowLeft, RawMods::None);
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::caret(9))
+        );
+
+        harness.keyboard_press_key(KbKey::ArrowLeft, RawMods::Shift);
+        harness.keyboard_press_key(KbKey::ArrowRight, RawMods::None);
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::caret(9))
+        );
+    }
+
+    #[test]
+    fn password_textbox() {
+        let textbox = TextBox::new("hunter2").with_password(true);
+        let mut harness = TestHarness::create_with_size(textbox, Size::new(150.0, 40.0));
+        assert_render_snapshot!(harness, "password");
+    }
+
+    #[test]
+    fn password_textbox_revealed() {
+        let textbox = TextBox::new("hunter2").with_password(true);
+        let mut harness = TestHarness::create_with_size(textbox, Size::new(150.0, 40.0));
+        harness.edit_root_widget(|mut textbox, _| {
+            let mut textbox = textbox.downcast::<TextBox>().unwrap();
+            textbox.set_password_revealed(true);
+        });
+        assert_render_snapshot!(harness, "password_revealed");
+    }
+
+    #[test]
+    fn password_textbox_no_copy() {
+        let [textbox_id] = widget_ids();
+        let textbox = TextBox::new("hunter2")
+            .with_password(true)
+            .with_id(textbox_id);
+
+        let mut harness = TestHarness::create(textbox);
+        harness.mouse_click_on(textbox_id);
+        harness.set_text_selection(textbox_id, Selection::new(0, 7));
+
+        harness.submit_command(crate::command::COPY.to(textbox_id));
+        assert_eq!(harness.clipboard().get_string(), None);
+
+        assert_eq!(
+            harness
+                .get_widget(textbox_id)
+                .downcast::<TextBox>()
+                .unwrap()
+                .text(),
+            "hunter2"
+        );
+    }
+
+    #[test]
+    fn copy_and_paste() {
+        let [textbox_id] = widget_ids();
+        let textbox = TextBox::new("Hello world").with_id(textbox_id);
+        let paste_mods = if cfg!(target_os = "macos") {
+            RawMods::Meta
+        } else {
+            RawMods::Ctrl
+        };
+
+        let mut harness = TestHarness::create(textbox);
+        harness.mouse_click_on(textbox_id);
+        harness.set_text_selection(textbox_id, Selection::new(0, 5));
+        harness.submit_command(crate::command::COPY.to(textbox_id));
+        assert_eq!(harness.clipboard().get_string().as_deref(), Some("Hello"));
+
+        // Pasting replaces the selection.
+        harness.set_clipboard_contents("Goodbye\nand more");
+        harness.set_text_selection(textbox_id, Selection::new(6, 11));
+        harness.keyboard_press_key(KbKey::Character("v".into()), paste_mods);
+
+        // A single-line TextBox only pastes the first line.
+        let text = "Hello Goodbye".to_string();
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TextChanged(text.clone()), textbox_id))
+        );
+        let textbox = harness.get_widget(textbox_id);
+        assert_eq!(textbox.downcast::<TextBox>().unwrap().text(), text);
+    }
+
+    #[test]
+    fn textbox_text_layout() {
+        let [textbox_id] = widget_ids();
+        let textbox = TextBox::multiline("Hello\nWorld").with_id(textbox_id);
+
+        let harness = TestHarness::create(textbox);
+        let layout = harness.text_layout(textbox_id);
+        assert_eq!(layout.line_count(), 2);
+
+        let first_line = layout.line_rects()[0];
+        let second_line = layout.line_rects()[1];
+        assert!(first_line.y1 <= second_line.y0);
+
+        // The caret after the newline is at the start of the second line.
+        let caret = layout.caret_position("Hello\n".len()).unwrap();
+        assert_eq!(caret.p0.x, second_line.x0);
+        assert_eq!(caret.p0.y, second_line.y0);
+        assert_eq!(layout.caret_position(100), None);
+    }
+
     #[test]
     fn simple_textbox_placeholder() {
         let textbox = TextBox::new("").with_placeholder("placeholder text");
@@ -779,6 +1142,153 @@ mod tests {
         assert_render_snapshot!(harness, "placeholder");
     }
 
+    #[test]
+    fn placeholder_hidden_on_focus() {
+        let [textbox_id] = widget_ids();
+
+        let image_1 = {
+            let textbox = TextBox::new("")
+                .with_placeholder("placeholder text")
+                .with_id(textbox_id);
+            let mut harness = TestHarness::create_with_size(textbox, Size::new(150.0, 40.0));
+            harness.mouse_click_on(textbox_id);
+            harness.render()
+        };
+
+        let image_2 = {
+            let textbox = TextBox::new("").with_id(textbox_id);
+            let mut harness = TestHarness::create_with_size(textbox, Size::new(150.0, 40.0));
+            harness.mouse_click_on(textbox_id);
+            harness.render()
+        };
+
+        // We don't use assert_eq because we don't want rich assert
+        assert!(image_1 == image_2);
+    }
+
+    #[test]
+    fn placeholder_hidden_with_text() {
+        let image_1 = {
+            let textbox = TextBox::new("").with_placeholder("placeholder text");
+            let mut harness = TestHarness::create_with_size(textbox, Size::new(150.0, 40.0));
+            harness.edit_root_widget(|mut textbox, _| {
+                let mut textbox = textbox.downcast::<TextBox>().unwrap();
+                textbox.set_text("Hello");
+            });
+            harness.render()
+        };
+
+        let image_2 = {
+            let textbox = TextBox::new("Hello");
+            let mut harness = TestHarness::create_with_size(textbox, Size::new(150.0, 40.0));
+            harness.render()
+        };
+
+        // We don't use assert_eq because we don't want rich assert
+        assert!(image_1 == image_2);
+    }
+
+    #[test]
+    fn paste_replaces_selection() {
+        let [textbox_id] = widget_ids();
+        let textbox = TextBox::multiline("Hello world").with_id(textbox_id);
+
+        let mut harness = TestHarness::create(textbox);
+        harness.mouse_click_on(textbox_id);
+
+        harness.set_text_selection(textbox_id, Selection::new(6, 11));
+        harness.paste("wörld\nand ünïcode");
+        assert_eq!(
+            harness
+                .get_widget(textbox_id)
+                .downcast::<TextBox>()
+                .unwrap()
+                .text(),
+            "Hello wörld\nand ünïcode"
+        );
+        assert_eq!(
+            harness.text_selection(textbox_id),
+            Some(Selection::caret(26))
+        );
+    }
+
+    #[test]
+    fn multi_click_selection() {
+        let [word_id, line_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child(TextBox::new("Supercalifragilistic").with_id(word_id))
+            .with_child(TextBox::new("Hello world").with_id(line_id));
+
+        let mut harness = TestHarness::create(widget);
+
+        harness.mouse_click_on(word_id);
+        assert!(harness.text_selection(word_id).unwrap().is_caret());
+
+        harness.mouse_double_click_on(word_id);
+        assert_eq!(harness.text_selection(word_id), Some(Selection::new(0, 20)));
+
+        harness.mouse_triple_click_on(line_id);
+        assert_eq!(harness.text_selection(line_id), Some(Selection::new(0, 11)));
+    }
+
+    #[test]
+    fn typing_with_modifiers() {
+        let [textbox_id] = widget_ids();
+        let textbox = TextBox::new("").with_id(textbox_id);
+
+        let mut harness = TestHarness::create(textbox);
+        harness.mouse_click_on(textbox_id);
+        harness.keyboard_type_chars_with_mods("AB", RawMods::Shift);
+        // Ctrl+C is a shortcut, not text.
+        harness.keyboard_type_chars_with_mods("c", RawMods::Ctrl);
+
+        let textbox = harness.get_widget(textbox_id);
+        assert_eq!(textbox.downcast::<TextBox>().unwrap().text(), "AB");
+
+        harness.set_record_events(true);
+        harness.key_down(KbKey::Shift, RawMods::Shift);
+        harness.key_up(KbKey::Shift, RawMods::None);
+        assert_eq!(
+            harness.events_received(textbox_id),
+            [EventKind::Keyboard, EventKind::Keyboard]
+        );
+    }
+
+    #[test]
+    fn caret_blinks_and_pauses_while_typing() {
+        use std::time::Duration;
+
+        fn caret_visible(harness: &TestHarness, id: crate::WidgetId) -> bool {
+            let textbox = harness.get_widget(id);
+            textbox.downcast::<TextBox>().unwrap().caret.is_visible()
+        }
+
+        let [textbox_id] = widget_ids();
+        let textbox = TextBox::new("Hello").with_id(textbox_id);
+        let mut harness = TestHarness::create(textbox);
+        harness.edit_env(|env| env.set(theme::CURSOR_BLINK_INTERVAL, 300u64));
+        let interval = Duration::from_millis(300);
+        assert!(!caret_visible(&harness, textbox_id));
+
+        harness.mouse_click_on(textbox_id);
+        assert!(caret_visible(&harness, textbox_id));
+
+        harness.move_timers_forward(interval);
+        assert!(!caret_visible(&harness, textbox_id));
+        harness.move_timers_forward(interval);
+        assert!(caret_visible(&harness, textbox_id));
+        harness.move_timers_forward(interval);
+        assert!(!caret_visible(&harness, textbox_id));
+
+        // Typing makes the caret solid until a full interval has passed.
+        harness.keyboard_type_chars("a");
+        assert!(caret_visible(&harness, textbox_id));
+        harness.move_timers_forward(interval / 2);
+        assert!(caret_visible(&harness, textbox_id));
+        harness.move_timers_forward(interval / 2);
+        assert!(!caret_visible(&harness, textbox_id));
+    }
+
     // TODO - styled textbox
 
     #[test]
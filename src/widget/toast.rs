@@ -0,0 +1,487 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! Transient notifications shown over the rest of the UI.
+
+use std::time::Duration;
+
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+
+use crate::contexts::impl_context_method;
+use crate::shell::TimerToken;
+use crate::widget::{Button, Label, LineBreaking, WidgetRef};
+use crate::{
+    theme, ArcStr, BoxConstraints, Command, Env, Event, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, RenderContext, Selector, Size, StatusChange, Widget, WidgetCtx,
+    WidgetPod,
+};
+
+// The space between toasts, and between toasts and the edge of the host.
+const TOAST_MARGIN: f64 = 8.0;
+const TOAST_PADDING: f64 = 8.0;
+const TOAST_MAX_WIDTH: f64 = 320.0;
+const TOAST_CORNER_RADIUS: f64 = 4.0;
+// How long a toast takes to slide in.
+const SLIDE_IN_DURATION: Duration = Duration::from_millis(150);
+
+/// How important a [`Toast`] is.
+///
+/// This determines the toast's background color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    /// A neutral message, eg confirming that an operation succeeded.
+    Info,
+    /// Something the user should be aware of, but that didn't stop an operation.
+    Warning,
+    /// An operation failed.
+    Error,
+}
+
+/// A transient message, shown by a [`ToastHost`].
+///
+/// Toasts are usually shown with [`EventCtx::show_toast`]. Toasts with action
+/// buttons are shown by submitting a [`ToastHost::SHOW_TOAST`] command.
+#[derive(Debug, Clone)]
+pub struct Toast {
+    message: ArcStr,
+    level: ToastLevel,
+    duration: Duration,
+    actions: Vec<(ArcStr, Command)>,
+}
+
+impl Toast {
+    /// Create a toast that is dismissed after the given duration.
+    pub fn new(message: impl Into<ArcStr>, level: ToastLevel, duration: Duration) -> Self {
+        Toast {
+            message: message.into(),
+            level,
+            duration,
+            actions: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to add an action button to the toast.
+    ///
+    /// Pressing the button submits the given command and dismisses the toast.
+    pub fn with_action(mut self, label: impl Into<ArcStr>, command: impl Into<Command>) -> Self {
+        self.actions.push((label.into(), command.into()));
+        self
+    }
+}
+
+impl_context_method!(
+    WidgetCtx<'_, '_>,
+    EventCtx<'_, '_>,
+    LifeCycleCtx<'_, '_>,
+    LayoutCtx<'_, '_>,
+    {
+        /// Show a transient message in the window's [`ToastHost`].
+        ///
+        /// The message is dismissed after `duration`. To show a message with
+        /// action buttons, submit a [`ToastHost::SHOW_TOAST`] command instead.
+        pub fn show_toast(
+            &mut self,
+            message: impl Into<ArcStr>,
+            level: ToastLevel,
+            duration: Duration,
+        ) {
+            self.submit_command(ToastHost::SHOW_TOAST.with(Toast::new(message, level, duration)));
+        }
+    }
+);
+
+/// A widget that shows [`Toast`]s over its child.
+///
+/// Toasts are stacked in the bottom-right corner, newest at the bottom. Each
+/// one slides in when shown, and is dismissed once its duration has elapsed.
+///
+/// A `ToastHost` is usually the root widget of a window, so that toasts
+/// submitted from anywhere in the window reach it.
+pub struct ToastHost {
+    child: WidgetPod<Box<dyn Widget>>,
+    toasts: Vec<ActiveToast>,
+}
+
+struct ActiveToast {
+    view: WidgetPod<ToastView>,
+    timer: TimerToken,
+    /// How far the toast has slid in, from 0 to 1.
+    slide_in: f64,
+}
+
+/// The widget displaying a single toast.
+struct ToastView {
+    level: ToastLevel,
+    message: WidgetPod<Label>,
+    buttons: Vec<(WidgetPod<Button>, Command)>,
+    dismissed: bool,
+}
+
+crate::declare_widget!(ToastHostMut, ToastHost);
+
+impl ToastHost {
+    /// Show a [`Toast`].
+    ///
+    /// This command is handled by the first [`ToastHost`] it reaches.
+    pub const SHOW_TOAST: Selector<Toast> = Selector::new("masonry-builtin.show-toast");
+
+    /// Create a new `ToastHost` wrapping the given widget.
+    pub fn new(child: impl Widget) -> Self {
+        ToastHost {
+            child: WidgetPod::new(child).boxed(),
+            toasts: Vec::new(),
+        }
+    }
+
+    fn show(&mut self, ctx: &mut EventCtx, toast: &Toast) {
+        trace!(
+            "ToastHost {:?} showing toast {:?}",
+            ctx.widget_id(),
+            toast.message
+        );
+        let view = ToastView {
+            level: toast.level,
+            message: WidgetPod::new(
+                Label::new(toast.message.clone()).with_line_break_mode(LineBreaking::WordWrap),
+            ),
+            buttons: toast
+                .actions
+                .iter()
+                .map(|(label, command)| {
+                    (WidgetPod::new(Button::new(label.clone())), command.clone())
+                })
+                .collect(),
+            dismissed: false,
+        };
+        self.toasts.push(ActiveToast {
+            view: WidgetPod::new(view),
+            timer: ctx.request_timer(toast.duration),
+            slide_in: 0.0,
+        });
+        ctx.children_changed();
+        ctx.request_anim_frame();
+    }
+
+    /// Mark every child as visited, for events the host handles itself.
+    fn skip_children(&mut self, ctx: &mut EventCtx) {
+        ctx.skip_child(&mut self.child);
+        for toast in &mut self.toasts {
+            ctx.skip_child(&mut toast.view);
+        }
+    }
+}
+
+impl Widget for ToastHost {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(Self::SHOW_TOAST) => {
+                let toast = cmd.try_get(Self::SHOW_TOAST).unwrap();
+                self.skip_children(ctx);
+                self.show(ctx, toast);
+                ctx.set_handled();
+                return;
+            }
+            Event::Timer(token) => {
+                if let Some(idx) = self.toasts.iter().position(|toast| toast.timer == *token) {
+                    trace!("ToastHost {:?} dismissing toast {}", ctx.widget_id(), idx);
+                    let toast = self.toasts.remove(idx);
+                    self.skip_children(ctx);
+                    ctx.remove_child(toast.view);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::AnimFrame(interval) => {
+                let progress = *interval as f64 / SLIDE_IN_DURATION.as_nanos() as f64;
+                let mut sliding = false;
+                for toast in &mut self.toasts {
+                    if toast.slide_in < 1.0 {
+                        toast.slide_in = (toast.slide_in + progress).min(1.0);
+                        sliding |= toast.slide_in < 1.0;
+                        ctx.request_layout();
+                    }
+                }
+                if sliding {
+                    ctx.request_anim_frame();
+                }
+            }
+            _ => (),
+        }
+
+        self.child.on_event(ctx, event, env);
+        for toast in &mut self.toasts {
+            toast.view.on_event(ctx, event, env);
+        }
+
+        let (dismissed, toasts): (Vec<_>, Vec<_>) = std::mem::take(&mut self.toasts)
+            .into_iter()
+            .partition(|toast| toast.view.widget().dismissed);
+        self.toasts = toasts;
+        for toast in dismissed {
+            ctx.cancel_timer(toast.timer);
+            ctx.remove_child(toast.view);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        self.child.lifecycle(ctx, event, env);
+        for toast in &mut self.toasts {
+            toast.view.lifecycle(ctx, event, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, env);
+        ctx.place_child(&mut self.child, Point::ORIGIN, env);
+
+        let max_toast_width = TOAST_MAX_WIDTH
+            .min(size.width - 2.0 * TOAST_MARGIN)
+            .max(0.0);
+        let toast_bc = BoxConstraints::new(Size::ZERO, Size::new(max_toast_width, f64::INFINITY));
+
+        // The newest toast is at the bottom, and older ones are stacked above it.
+        let mut y = size.height - TOAST_MARGIN;
+        for toast in self.toasts.iter_mut().rev() {
+            let toast_size = toast.view.layout(ctx, &toast_bc, env);
+            y -= toast_size.height;
+            let slide_offset = (1.0 - toast.slide_in) * (toast_size.width + TOAST_MARGIN);
+            let x = size.width - TOAST_MARGIN - toast_size.width + slide_offset;
+            ctx.place_child(&mut toast.view, Point::new(x, y), env);
+            y -= TOAST_MARGIN;
+        }
+
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        self.child.paint(ctx, env);
+        for toast in &mut self.toasts {
+            toast.view.paint(ctx, env);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        let mut children = SmallVec::new();
+        children.push(self.child.as_dyn());
+        for toast in &self.toasts {
+            children.push(toast.view.as_dyn());
+        }
+        children
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("ToastHost")
+    }
+}
+
+impl Widget for ToastView {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        let was_active: SmallVec<[bool; 4]> = self
+            .buttons
+            .iter()
+            .map(|(button, _)| button.is_active())
+            .collect();
+
+        self.message.on_event(ctx, event, env);
+        for (button, _) in &mut self.buttons {
+            button.on_event(ctx, event, env);
+        }
+
+        if let Event::MouseUp(_) = event {
+            for ((button, command), was_active) in self.buttons.iter().zip(was_active) {
+                if was_active && button.is_hot() && !ctx.is_disabled() {
+                    ctx.submit_command(command.clone());
+                    self.dismissed = true;
+                }
+            }
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        self.message.lifecycle(ctx, event, env);
+        for (button, _) in &mut self.buttons {
+            button.lifecycle(ctx, event, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let content_bc = bc
+            .shrink((2.0 * TOAST_PADDING, 2.0 * TOAST_PADDING))
+            .loosen();
+
+        let buttons_bc = BoxConstraints::new(
+            Size::ZERO,
+            Size::new(f64::INFINITY, content_bc.max().height),
+        );
+        let button_sizes: SmallVec<[Size; 4]> = self
+            .buttons
+            .iter_mut()
+            .map(|(button, _)| button.layout(ctx, &buttons_bc, env))
+            .collect();
+        let buttons_width: f64 = button_sizes
+            .iter()
+            .map(|size| size.width + TOAST_PADDING)
+            .sum();
+
+        let message_bc = BoxConstraints::new(
+            Size::ZERO,
+            Size::new(
+                (content_bc.max().width - buttons_width).max(0.0),
+                content_bc.max().height,
+            ),
+        );
+        let message_size = self.message.layout(ctx, &message_bc, env);
+
+        let content_height = button_sizes
+            .iter()
+            .map(|size| size.height)
+            .fold(message_size.height, f64::max);
+        let size = bc.constrain(Size::new(
+            message_size.width + buttons_width + 2.0 * TOAST_PADDING,
+            content_height + 2.0 * TOAST_PADDING,
+        ));
+
+        let message_y = (size.height - message_size.height) / 2.0;
+        ctx.place_child(&mut self.message, Point::new(TOAST_PADDING, message_y), env);
+        let mut x = TOAST_PADDING + message_size.width + TOAST_PADDING;
+        for ((button, _), button_size) in self.buttons.iter_mut().zip(button_sizes) {
+            let button_y = (size.height - button_size.height) / 2.0;
+            ctx.place_child(button, Point::new(x, button_y), env);
+            x += button_size.width + TOAST_PADDING;
+        }
+
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        let color = match self.level {
+            ToastLevel::Info => env.get(theme::TOAST_INFO_COLOR),
+            ToastLevel::Warning => env.get(theme::TOAST_WARNING_COLOR),
+            ToastLevel::Error => env.get(theme::TOAST_ERROR_COLOR),
+        };
+        let background = ctx.size().to_rounded_rect(TOAST_CORNER_RADIUS);
+        ctx.fill(background, &color);
+
+        self.message.paint(ctx, env);
+        for (button, _) in &mut self.buttons {
+            button.paint(ctx, env);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        let mut children = SmallVec::new();
+        children.push(self.message.as_dyn());
+        for (button, _) in &self.buttons {
+            children.push(button.as_dyn());
+        }
+        children
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("ToastView")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.message.widget().text().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        widget_ids, ModularWidget, Record, Recording, TestHarness, TestWidgetExt as _,
+    };
+    use crate::widget::Flex;
+
+    const UNDO_DELETE: Selector = Selector::new("masonry-test.undo-delete");
+
+    fn toast_count(harness: &TestHarness) -> usize {
+        // The first child is the host's content.
+        harness.root_widget().children().len() - 1
+    }
+
+    #[test]
+    fn toast_auto_dismiss() {
+        let [content_id] = widget_ids();
+        let content = ModularWidget::new(()).event_fn(|_, ctx, event, _| {
+            if let Event::MouseDown(_) = event {
+                ctx.show_toast("Saved", ToastLevel::Info, Duration::from_secs(3));
+            }
+        });
+        let mut harness = TestHarness::create(ToastHost::new(content.with_id(content_id)));
+        assert_eq!(toast_count(&harness), 0);
+
+        harness.mouse_click_on(content_id);
+        assert_eq!(toast_count(&harness), 1);
+
+        harness.move_timers_forward(Duration::from_millis(2900));
+        assert_eq!(toast_count(&harness), 1);
+
+        harness.move_timers_forward(Duration::from_millis(200));
+        assert_eq!(toast_count(&harness), 0);
+    }
+
+    #[test]
+    fn toasts_stack() {
+        let mut harness = TestHarness::create(ToastHost::new(Flex::column()));
+        harness.submit_command(ToastHost::SHOW_TOAST.with(Toast::new(
+            "First",
+            ToastLevel::Info,
+            Duration::from_secs(1),
+        )));
+        harness.submit_command(ToastHost::SHOW_TOAST.with(Toast::new(
+            "Second",
+            ToastLevel::Error,
+            Duration::from_secs(2),
+        )));
+        harness.run_for(SLIDE_IN_DURATION);
+
+        let root = harness.root_widget();
+        let first_rect = root.children()[1].state().layout_rect();
+        let second_rect = root.children()[2].state().layout_rect();
+        assert!(first_rect.y1 < second_rect.y0);
+        assert_eq!(first_rect.x1, second_rect.x1);
+
+        harness.move_timers_forward(Duration::from_millis(1500));
+        assert_eq!(toast_count(&harness), 1);
+        let root = harness.root_widget();
+        assert_eq!(root.children()[1].get_debug_text(), Some("Second".into()));
+    }
+
+    #[test]
+    fn toast_action() {
+        let [target_id] = widget_ids();
+        let recording = Recording::default();
+        let content =
+            Flex::column().with_child(Label::new("target").record(&recording).with_id(target_id));
+        let mut harness = TestHarness::create(ToastHost::new(content));
+        harness.submit_command(
+            ToastHost::SHOW_TOAST.with(
+                Toast::new("File deleted", ToastLevel::Warning, Duration::from_secs(5))
+                    .with_action("Undo", UNDO_DELETE.to(target_id)),
+            ),
+        );
+        harness.run_for(SLIDE_IN_DURATION);
+
+        let button_id = harness.root_widget().children()[1].children()[1].id();
+        recording.clear();
+        harness.mouse_click_on(button_id);
+
+        assert_eq!(toast_count(&harness), 0);
+        let undo_commands = recording
+            .drain()
+            .into_iter()
+            .filter(
+                |record| matches!(record, Record::E(Event::Command(cmd)) if cmd.is(UNDO_DELETE)),
+            )
+            .count();
+        assert_eq!(undo_commands, 1);
+    }
+}
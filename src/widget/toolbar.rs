@@ -0,0 +1,622 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A row of commands that collapses into a menu when space runs out.
+
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+
+use crate::kurbo::{Insets, Rect};
+use crate::widget::{Icon, Label, Menu, WidgetRef};
+use crate::{
+    theme, ArcStr, BoxConstraints, Command, Env, Event, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, RenderContext, Size, StatusChange, Widget, WidgetPod,
+};
+
+const ITEM_PADDING: Insets = Insets::uniform_xy(6.0, 4.0);
+const OVERFLOW_TITLE: &str = "⋯";
+
+/// A row of commands, shown as text or icons.
+///
+/// Clicking an item submits its [`Command`]. When the items don't fit in the available
+/// width, the ones at the end are moved to an overflow [`Menu`], opened with a "⋯" button
+/// at the end of the row. The menu lists the items by title.
+///
+/// The open menu is laid out below the row, and grows the toolbar's height, so the toolbar
+/// should be placed somewhere it can expand vertically, eg at the top of a [`Flex`] column.
+///
+/// [`Flex`]: crate::widget::Flex
+pub struct Toolbar {
+    items: Vec<ToolbarItem>,
+    overflow_button: WidgetPod<Label>,
+    overflow_button_rect: Rect,
+    /// Built from the overflowing items when the menu is opened, and removed when it's closed.
+    overflow_menu: Option<WidgetPod<Menu>>,
+    /// Set when the items in the row change while the menu is open. The menu is then closed
+    /// on the next event, since children can't be removed during layout.
+    overflow_outdated: bool,
+    /// The number of items that fit in the row. The other items are in the overflow menu.
+    visible_count: usize,
+    hovered: Option<ItemIdx>,
+    pressed: Option<ItemIdx>,
+}
+
+struct ToolbarItem {
+    title: ArcStr,
+    command: Command,
+    widget: WidgetPod<Box<dyn Widget>>,
+    /// The clickable area of the item, set during layout.
+    rect: Rect,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ItemIdx {
+    Item(usize),
+    OverflowButton,
+}
+
+crate::declare_widget!(ToolbarMut, Toolbar);
+
+impl Toolbar {
+    /// Create a new `Toolbar` with no items.
+    pub fn new() -> Self {
+        Toolbar {
+            items: Vec::new(),
+            overflow_button: WidgetPod::new(Label::new(OVERFLOW_TITLE)),
+            overflow_button_rect: Rect::ZERO,
+            overflow_menu: None,
+            overflow_outdated: false,
+            visible_count: 0,
+            hovered: None,
+            pressed: None,
+        }
+    }
+
+    /// Builder-style method to add an item showing its title.
+    pub fn with_item(mut self, title: impl Into<ArcStr>, command: impl Into<Command>) -> Self {
+        let title = title.into();
+        let label = Label::new(title.clone());
+        self.items
+            .push(ToolbarItem::new(title, command.into(), Box::new(label)));
+        self.visible_count = self.items.len();
+        self
+    }
+
+    /// Builder-style method to add an item showing an icon.
+    ///
+    /// The title is used in the overflow menu.
+    pub fn with_icon_item(
+        mut self,
+        icon: Icon,
+        title: impl Into<ArcStr>,
+        command: impl Into<Command>,
+    ) -> Self {
+        self.items.push(ToolbarItem::new(
+            title.into(),
+            command.into(),
+            Box::new(icon),
+        ));
+        self.visible_count = self.items.len();
+        self
+    }
+
+    /// Return the number of items shown in the row.
+    pub fn visible_count(&self) -> usize {
+        self.visible_count
+    }
+
+    /// Return the number of items moved to the overflow menu.
+    pub fn overflow_count(&self) -> usize {
+        self.items.len() - self.visible_count
+    }
+
+    /// Return `true` if the overflow menu is open.
+    pub fn is_overflow_open(&self) -> bool {
+        self.overflow_menu.is_some()
+    }
+
+    /// Return the area of the "⋯" button, in the toolbar's coordinate space.
+    ///
+    /// This is empty if all items fit in the row.
+    pub fn overflow_button_rect(&self) -> Rect {
+        self.overflow_button_rect
+    }
+
+    /// Return the overflow menu, if it's open.
+    pub fn overflow_menu(&self) -> Option<WidgetRef<'_, Menu>> {
+        self.overflow_menu.as_ref().map(|menu| menu.as_ref())
+    }
+
+    fn has_overflow(&self) -> bool {
+        self.visible_count < self.items.len()
+    }
+
+    fn build_overflow_menu(&self) -> Menu {
+        let mut menu = Menu::new();
+        for item in &self.items[self.visible_count..] {
+            menu = menu.with_entry(item.title.clone(), item.command.clone());
+        }
+        menu
+    }
+
+    fn item_at(&self, pos: Point) -> Option<ItemIdx> {
+        if self.has_overflow() && self.overflow_button_rect.contains(pos) {
+            return Some(ItemIdx::OverflowButton);
+        }
+        self.items[..self.visible_count]
+            .iter()
+            .position(|item| item.rect.contains(pos))
+            .map(ItemIdx::Item)
+    }
+
+    fn item_rect(&self, idx: ItemIdx) -> Rect {
+        match idx {
+            ItemIdx::Item(idx) => self.items[idx].rect,
+            ItemIdx::OverflowButton => self.overflow_button_rect,
+        }
+    }
+
+    fn set_overflow_open(&mut self, ctx: &mut EventCtx, open: bool) {
+        if let Some(old_menu) = self.overflow_menu.take() {
+            // Don't leave focus on a menu that's no longer in the tree.
+            if !open && old_menu.has_focus() {
+                ctx.resign_focus();
+            }
+            ctx.remove_child(old_menu);
+        }
+        if open {
+            let menu = WidgetPod::new(self.build_overflow_menu());
+            // Give the menu focus, so that it can be used with the keyboard.
+            ctx.set_focus(menu.id());
+            self.overflow_menu = Some(menu);
+        }
+        self.overflow_outdated = false;
+        ctx.children_changed();
+    }
+}
+
+impl ToolbarItem {
+    fn new(title: ArcStr, command: Command, widget: Box<dyn Widget>) -> Self {
+        ToolbarItem {
+            title,
+            command,
+            widget: WidgetPod::new(widget),
+            rect: Rect::ZERO,
+        }
+    }
+}
+
+impl Default for Toolbar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToolbarMut<'_, '_> {
+    /// Add an item showing its title at the end of the toolbar.
+    pub fn add_item(&mut self, title: impl Into<ArcStr>, command: impl Into<Command>) {
+        let title = title.into();
+        let label = Label::new(title.clone());
+        self.1
+            .items
+            .push(ToolbarItem::new(title, command.into(), Box::new(label)));
+        self.0.children_changed();
+    }
+
+    /// Open or close the overflow menu.
+    ///
+    /// This does nothing if all items fit in the row. If the menu had focus, closing it
+    /// resigns focus.
+    pub fn set_overflow_open(&mut self, open: bool) {
+        let open = open && self.1.has_overflow();
+        if let Some(old_menu) = self.1.overflow_menu.take() {
+            self.0.remove_child(old_menu);
+        }
+        if open {
+            self.1.overflow_menu = Some(WidgetPod::new(self.1.build_overflow_menu()));
+        }
+        self.1.overflow_outdated = false;
+        self.0.children_changed();
+    }
+}
+
+impl Widget for Toolbar {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        let propagate_to_hidden = event.should_propagate_to_hidden();
+        for (i, item) in self.items.iter_mut().enumerate() {
+            if i < self.visible_count || propagate_to_hidden {
+                item.widget.on_event(ctx, event, env);
+            } else {
+                ctx.skip_child(&mut item.widget);
+            }
+        }
+        if self.visible_count < self.items.len() || propagate_to_hidden {
+            self.overflow_button.on_event(ctx, event, env);
+        } else {
+            ctx.skip_child(&mut self.overflow_button);
+        }
+        if self.overflow_outdated {
+            self.set_overflow_open(ctx, false);
+        }
+        if let Some(menu) = &mut self.overflow_menu {
+            menu.on_event(ctx, event, env);
+        }
+
+        match event {
+            Event::MouseMove(mouse) => {
+                let hovered = self.item_at(mouse.pos);
+                if hovered != self.hovered {
+                    self.hovered = hovered;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseDown(mouse) => {
+                self.pressed = self.item_at(mouse.pos);
+                if self.pressed.is_some() {
+                    ctx.set_active(true);
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(mouse) => {
+                if let Some(pressed) = self.pressed.take() {
+                    if ctx.is_active() && self.item_rect(pressed).contains(mouse.pos) {
+                        match pressed {
+                            ItemIdx::Item(idx) => {
+                                let item = &self.items[idx];
+                                trace!("Toolbar {:?} activated {}", ctx.widget_id(), item.title);
+                                ctx.submit_command(item.command.clone());
+                            }
+                            ItemIdx::OverflowButton => {
+                                self.set_overflow_open(ctx, self.overflow_menu.is_none());
+                            }
+                        }
+                    }
+                    ctx.request_paint();
+                }
+                ctx.set_active(false);
+            }
+            Event::Notification(notification)
+                if notification.is(Menu::ENTRY_ACTIVATED) || notification.is(Menu::DISMISSED) =>
+            {
+                self.set_overflow_open(ctx, false);
+                ctx.set_handled();
+            }
+            Event::MouseLeave if self.hovered.take().is_some() => {
+                ctx.request_paint();
+            }
+            _ => {}
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        let propagate_to_hidden = event.should_propagate_to_hidden();
+        for (i, item) in self.items.iter_mut().enumerate() {
+            if i < self.visible_count || propagate_to_hidden {
+                item.widget.lifecycle(ctx, event, env);
+            } else {
+                ctx.skip_child(&mut item.widget);
+            }
+        }
+        if self.visible_count < self.items.len() || propagate_to_hidden {
+            self.overflow_button.lifecycle(ctx, event, env);
+        } else {
+            ctx.skip_child(&mut self.overflow_button);
+        }
+        if let Some(menu) = &mut self.overflow_menu {
+            menu.lifecycle(ctx, event, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let child_bc = BoxConstraints::new(
+            Size::ZERO,
+            Size::new(
+                f64::INFINITY,
+                (bc.max().height - ITEM_PADDING.y_value()).max(0.0),
+            ),
+        );
+        let padded = |size: Size| {
+            Size::new(
+                size.width + ITEM_PADDING.x_value(),
+                size.height + ITEM_PADDING.y_value(),
+            )
+        };
+
+        let item_sizes: Vec<Size> = self
+            .items
+            .iter_mut()
+            .map(|item| padded(item.widget.layout(ctx, &child_bc, env)))
+            .collect();
+        let button_size = padded(self.overflow_button.layout(ctx, &child_bc, env));
+
+        // Keep as many items as possible in the row, leaving room for the overflow button
+        // if some items don't fit.
+        let total_width: f64 = item_sizes.iter().map(|size| size.width).sum();
+        let visible_count = if total_width <= bc.max().width {
+            self.items.len()
+        } else {
+            let available_width = bc.max().width - button_size.width;
+            let mut x = 0.0;
+            item_sizes
+                .iter()
+                .take_while(|size| {
+                    x += size.width;
+                    x <= available_width
+                })
+                .count()
+        };
+        if visible_count != self.visible_count {
+            trace!(
+                "Toolbar shows {} items out of {}",
+                visible_count,
+                self.items.len()
+            );
+            self.visible_count = visible_count;
+            // The menu's entries are out of date.
+            self.overflow_outdated = self.overflow_menu.is_some();
+        }
+        let has_overflow = self.has_overflow();
+
+        let row_height = item_sizes[..visible_count]
+            .iter()
+            .chain(has_overflow.then_some(&button_size))
+            .map(|size| size.height)
+            .fold(bc.min().height, f64::max);
+
+        let mut x = 0.0;
+        for (i, (item, item_size)) in self.items.iter_mut().zip(item_sizes).enumerate() {
+            if i < visible_count {
+                let y = (row_height - item_size.height) / 2.0;
+                item.rect = Rect::from_origin_size((x, y), item_size);
+                ctx.place_child(
+                    &mut item.widget,
+                    Point::new(x + ITEM_PADDING.x0, y + ITEM_PADDING.y0),
+                    env,
+                );
+                x += item_size.width;
+            } else {
+                // Hidden items are skipped by the other passes.
+                item.rect = Rect::ZERO;
+                ctx.place_child(&mut item.widget, Point::ORIGIN, env);
+            }
+        }
+
+        let width = if has_overflow {
+            let button_x = bc.max().width - button_size.width;
+            let y = (row_height - button_size.height) / 2.0;
+            self.overflow_button_rect = Rect::from_origin_size((button_x, y), button_size);
+            ctx.place_child(
+                &mut self.overflow_button,
+                Point::new(button_x + ITEM_PADDING.x0, y + ITEM_PADDING.y0),
+                env,
+            );
+            bc.max().width
+        } else {
+            self.overflow_button_rect = Rect::ZERO;
+            ctx.place_child(&mut self.overflow_button, Point::ORIGIN, env);
+            x
+        };
+
+        let mut height = row_height;
+        if let Some(menu) = &mut self.overflow_menu {
+            let menu_bc = BoxConstraints::new(Size::ZERO, Size::new(width, f64::INFINITY));
+            let menu_size = menu.layout(ctx, &menu_bc, env);
+            let menu_origin = Point::new(width - menu_size.width, row_height);
+            ctx.place_child(menu, menu_origin, env);
+            height += menu_size.height;
+        }
+
+        let size = bc.constrain(Size::new(width, height));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        let highlighted = self.pressed.filter(|_| ctx.is_active()).or(self.hovered);
+        if let Some(idx) = highlighted {
+            let rect = self
+                .item_rect(idx)
+                .to_rounded_rect(env.get(theme::BUTTON_BORDER_RADIUS));
+            let color = if self.pressed.is_some() {
+                env.get(theme::BACKGROUND_DARK)
+            } else {
+                env.get(theme::BACKGROUND_LIGHT)
+            };
+            ctx.fill(rect, &color);
+        }
+
+        for (i, item) in self.items.iter_mut().enumerate() {
+            if i < self.visible_count {
+                item.widget.paint(ctx, env);
+            } else {
+                ctx.skip_child(&mut item.widget);
+            }
+        }
+        if self.visible_count < self.items.len() {
+            self.overflow_button.paint(ctx, env);
+        } else {
+            ctx.skip_child(&mut self.overflow_button);
+        }
+        if let Some(menu) = &mut self.overflow_menu {
+            menu.paint(ctx, env);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        let mut children: SmallVec<_> =
+            self.items.iter().map(|item| item.widget.as_dyn()).collect();
+        children.push(self.overflow_button.as_dyn());
+        if let Some(menu) = &self.overflow_menu {
+            children.push(menu.as_dyn());
+        }
+        children
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Toolbar")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(format!(
+            "{} items, {} in overflow",
+            self.items.len(),
+            self.overflow_count()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::{KbKey, MouseButton, RawMods};
+    use crate::testing::{widget_ids, Record, Recording, TestHarness, TestWidgetExt as _};
+    use crate::widget::{Flex, SizedBox};
+    use crate::{Selector, WidgetId};
+
+    const CUT: Selector = Selector::new("masonry-test.cut");
+    const COPY: Selector = Selector::new("masonry-test.copy");
+    const PASTE: Selector = Selector::new("masonry-test.paste");
+    const UNDO: Selector = Selector::new("masonry-test.undo");
+    const REDO: Selector = Selector::new("masonry-test.redo");
+
+    // A 24x24 square, so that items have a known width.
+    fn square_icon() -> Icon {
+        Icon::from_svg_path("M4 4h16v16h-16z")
+            .unwrap()
+            .with_view_box(Rect::new(0.0, 0.0, 24.0, 24.0))
+    }
+
+    fn toolbar(target_id: WidgetId) -> Toolbar {
+        Toolbar::new()
+            .with_icon_item(square_icon(), "Cut", CUT.to(target_id))
+            .with_icon_item(square_icon(), "Copy", COPY.to(target_id))
+            .with_icon_item(square_icon(), "Paste", PASTE.to(target_id))
+            .with_icon_item(square_icon(), "Undo", UNDO.to(target_id))
+            .with_icon_item(square_icon(), "Redo", REDO.to(target_id))
+    }
+
+    fn received_commands(recording: &Recording) -> Vec<Command> {
+        recording
+            .drain()
+            .into_iter()
+            .filter_map(|record| match record {
+                Record::E(Event::Command(command)) => Some(command),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn click(harness: &mut TestHarness, pos: Point) {
+        harness.mouse_move(pos);
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_button_release(MouseButton::Left);
+    }
+
+    #[test]
+    fn all_items_fit() {
+        let [toolbar_id, target_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child(toolbar(target_id).with_id(toolbar_id))
+            .with_child(Label::new("target").with_id(target_id));
+
+        let harness = TestHarness::create(widget);
+        let toolbar = harness.get_widget(toolbar_id);
+        let toolbar = toolbar.downcast::<Toolbar>().unwrap();
+
+        assert_eq!(toolbar.visible_count(), 5);
+        assert_eq!(toolbar.overflow_count(), 0);
+        assert!(toolbar.overflow_button_rect().is_empty());
+    }
+
+    #[test]
+    fn narrow_toolbar_overflows() {
+        let [toolbar_id, target_id] = widget_ids();
+        let recording = Recording::default();
+
+        // Each item is 36px wide, so with the overflow button, only two items fit.
+        let widget = Flex::column()
+            .with_child(SizedBox::new(toolbar(target_id).with_id(toolbar_id)).width(120.0))
+            .with_child(Label::new("target").record(&recording).with_id(target_id));
+
+        let mut harness = TestHarness::create(widget);
+        let (button_center, toolbar_origin) = {
+            let toolbar = harness.get_widget(toolbar_id);
+            let toolbar = toolbar.downcast::<Toolbar>().unwrap();
+            assert_eq!(toolbar.visible_count(), 2);
+            assert_eq!(toolbar.overflow_count(), 3);
+            assert!(toolbar.overflow_menu().is_none());
+            (
+                toolbar.overflow_button_rect().center(),
+                toolbar.state().window_origin(),
+            )
+        };
+
+        // Open the overflow menu, which lists the items that didn't fit.
+        click(&mut harness, toolbar_origin + button_center.to_vec2());
+        let menu_entry_center = {
+            let toolbar = harness.get_widget(toolbar_id);
+            let toolbar = toolbar.downcast::<Toolbar>().unwrap();
+            assert!(toolbar.is_overflow_open());
+            let menu = toolbar.overflow_menu().unwrap();
+            let titles: Vec<_> = (0..menu.len())
+                .map(|idx| menu.title(idx).unwrap().to_string())
+                .collect();
+            assert_eq!(titles, ["Paste", "Undo", "Redo"]);
+            menu.state().window_origin() + menu.entry_rect(1).unwrap().center().to_vec2()
+        };
+
+        // Clicking a menu entry submits its command and closes the menu.
+        recording.clear();
+        click(&mut harness, menu_entry_center);
+        let commands = received_commands(&recording);
+        assert_eq!(commands.len(), 1);
+        assert!(commands[0].is(UNDO));
+
+        let toolbar = harness.get_widget(toolbar_id);
+        let toolbar = toolbar.downcast::<Toolbar>().unwrap();
+        assert!(!toolbar.is_overflow_open());
+    }
+
+    #[test]
+    fn closing_menu_gives_up_focus() {
+        let [target_id] = widget_ids();
+        let mut harness =
+            TestHarness::create_with_size(toolbar(target_id), Size::new(120.0, 200.0));
+        let button_center = {
+            let toolbar = harness.root_widget();
+            let toolbar = toolbar.downcast::<Toolbar>().unwrap();
+            toolbar.overflow_button_rect().center()
+        };
+        let menu_id = |harness: &TestHarness| {
+            let toolbar = harness.root_widget();
+            let toolbar = toolbar.downcast::<Toolbar>().unwrap();
+            toolbar.overflow_menu().map(|menu| menu.id())
+        };
+
+        // Opening the menu focuses it, and closing it with the button gives up focus.
+        click(&mut harness, button_center);
+        assert!(menu_id(&harness).is_some());
+        assert_eq!(harness.window().focus, menu_id(&harness));
+        click(&mut harness, button_center);
+        assert_eq!(menu_id(&harness), None);
+        assert_eq!(harness.window().focus, None);
+
+        // Same when closing the menu with Escape.
+        click(&mut harness, button_center);
+        harness.keyboard_press_key(KbKey::Escape, RawMods::None);
+        assert_eq!(menu_id(&harness), None);
+        assert_eq!(harness.window().focus, None);
+
+        // Same when closing the menu from code.
+        click(&mut harness, button_center);
+        assert_eq!(harness.window().focus, menu_id(&harness));
+        harness.edit_root_widget(|mut toolbar, _| {
+            let mut toolbar = toolbar.downcast::<Toolbar>().unwrap();
+            toolbar.set_overflow_open(false);
+        });
+        assert_eq!(harness.window().focus, None);
+    }
+}
@@ -0,0 +1,155 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A widget that can hide its child while keeping its space.
+
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+
+use crate::widget::{WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Point,
+    Size, StatusChange, Widget,
+};
+
+/// A widget that can hide its child while keeping its space.
+///
+/// A hidden child is still laid out, so hiding or showing it doesn't move its
+/// siblings, but it isn't painted, doesn't receive user input and can't get focus.
+/// It still receives the events that [propagate to hidden widgets](Event::should_propagate_to_hidden),
+/// eg timers and commands.
+///
+/// To remove the child from the layout entirely, use [`When`](crate::widget::When).
+pub struct Visibility {
+    visible: bool,
+    child: WidgetPod<Box<dyn Widget>>,
+}
+
+crate::declare_widget!(VisibilityMut, Visibility);
+
+impl Visibility {
+    /// Create a new `Visibility` wrapping `child`, shown if `visible` is true.
+    pub fn new(visible: bool, child: impl Widget) -> Self {
+        Visibility {
+            visible,
+            child: WidgetPod::new(child).boxed(),
+        }
+    }
+
+    /// Return `true` if the child is currently shown.
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+}
+
+impl<'a, 'b> VisibilityMut<'a, 'b> {
+    /// Show or hide the child.
+    pub fn set_visible(&mut self, visible: bool) {
+        if self.1.visible == visible {
+            return;
+        }
+        self.1.visible = visible;
+        // Hidden widgets are excluded from the focus chain.
+        self.0.children_changed();
+        self.0.request_paint();
+    }
+
+    /// Return a mutable reference to the child, even if it's hidden.
+    pub fn child_mut(&mut self) -> WidgetMut<'_, 'b, Box<dyn Widget>> {
+        self.0.get_mut(&mut self.1.child)
+    }
+}
+
+impl Widget for Visibility {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        if self.visible || event.should_propagate_to_hidden() {
+            self.child.on_event(ctx, event, env);
+        } else {
+            ctx.skip_child(&mut self.child);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        if self.visible || event.should_propagate_to_hidden() {
+            self.child.lifecycle(ctx, event, env);
+        } else {
+            ctx.skip_child(&mut self.child);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, env);
+        ctx.place_child(&mut self.child, Point::ORIGIN, env);
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        if self.visible {
+            self.child.paint(ctx, env);
+        } else {
+            ctx.skip_child(&mut self.child);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.child.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Visibility")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(if self.visible { "visible" } else { "hidden" }.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, Record, Recording, TestHarness, TestWidgetExt as _};
+    use crate::widget::{Flex, Label, WidgetExt as _};
+
+    fn painted(recording: &Recording) -> bool {
+        recording
+            .drain()
+            .into_iter()
+            .any(|record| matches!(record, Record::Paint))
+    }
+
+    #[test]
+    fn hidden_child_keeps_its_space() {
+        let [sibling_id] = widget_ids();
+        let recording = Recording::default();
+        let widget = Flex::column()
+            .with_child(Label::new("hello").record(&recording).visible(true))
+            .with_child(Label::new("world").with_id(sibling_id));
+
+        let mut harness = TestHarness::create(widget);
+        let sibling_rect = harness.get_widget(sibling_id).state().layout_rect();
+
+        recording.clear();
+        harness.render();
+        assert!(painted(&recording));
+
+        harness.edit_root_widget(|mut flex, _| {
+            let mut flex = flex.downcast::<Flex>().unwrap();
+            let mut visibility = flex.child_mut(0).unwrap();
+            let mut visibility = visibility.downcast::<Visibility>().unwrap();
+            visibility.set_visible(false);
+        });
+
+        assert_eq!(
+            harness.get_widget(sibling_id).state().layout_rect(),
+            sibling_rect
+        );
+
+        recording.clear();
+        harness.render();
+        assert!(!painted(&recording));
+    }
+}
@@ -36,13 +36,43 @@ impl WebImage {
     }
 }
 
+/// Download and decode the image at `url`, logging any error.
+pub(crate) fn load_image(url: &str) -> Option<ImageBuf> {
+    let response = match reqwest::blocking::get(url) {
+        Ok(response) => response,
+        Err(err) => {
+            error!("Cannot load image at '{}': {}", url, err);
+            return None;
+        }
+    };
+    let body = match response.bytes() {
+        Ok(body) => body,
+        Err(err) => {
+            error!("Cannot load image at '{}': {}", url, err);
+            return None;
+        }
+    };
+    let image_buf = match ImageBuf::from_data(&body) {
+        Ok(image_buf) => image_buf,
+        Err(err) => {
+            error!("Cannot parse image at '{}': {}", url, err);
+            return None;
+        }
+    };
+    Some(image_buf)
+}
+
 // --- TRAIT IMPLS ---
 
 impl Widget for WebImage {
     fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
         match event {
             Event::PromiseResult(result) => {
-                if let Some(image_buf) = result.try_get(self.image_promise) {
+                if let Some(image_buf) = result.try_get_result(self.image_promise) {
+                    let image_buf = image_buf.unwrap_or_else(|err| {
+                        error!("Cannot load image at '{}': {}", self.url, err);
+                        ImageBuf::empty()
+                    });
                     self.inner = Some(WidgetPod::new(
                         Image::new(image_buf).fill_mode(FillStrat::Contain),
                     ));
@@ -63,37 +93,15 @@ impl Widget for WebImage {
     fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
-        fn load_image(url: &str) -> Option<ImageBuf> {
-            let response = match reqwest::blocking::get(url) {
-                Ok(response) => response,
-                Err(err) => {
-                    error!("Cannot load image at '{}': {}", url, err);
-                    return None;
-                }
-            };
-            let body = match response.bytes() {
-                Ok(body) => body,
-                Err(err) => {
-                    error!("Cannot load image at '{}': {}", url, err);
-                    return None;
-                }
-            };
-            let image_buf = match ImageBuf::from_data(&body) {
-                Ok(image_buf) => image_buf,
-                Err(err) => {
-                    error!("Cannot parse image at '{}': {}", url, err);
-                    return None;
-                }
-            };
-            Some(image_buf)
-        }
-
         match event {
             LifeCycle::WidgetAdded => {
                 let url = self.url.clone();
                 self.image_promise = ctx
                     .compute_in_background(move |_| load_image(&url).unwrap_or(ImageBuf::empty()));
             }
+            LifeCycle::WidgetRemoved if self.inner.is_none() => {
+                ctx.cancel_promise(self.image_promise);
+            }
             _ => {}
         }
 
@@ -136,3 +144,71 @@ impl Widget for WebImage {
         trace_span!("WebImage")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_log::EventKind;
+    use crate::piet::ImageFormat;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::Flex;
+
+    #[test]
+    fn placeholder_skipped_after_load() {
+        let [image_id] = widget_ids();
+        let widget = WebImage::new(String::new()).with_id(image_id);
+
+        let mut harness = TestHarness::create(widget);
+        let placeholder_id = harness.get_widget(image_id).children()[0].id();
+
+        harness.set_record_events(true);
+        harness.mouse_move_to(image_id);
+        assert!(harness
+            .events_received(placeholder_id)
+            .contains(&EventKind::Mouse));
+
+        let web_image = harness.get_widget(image_id);
+        let promise_token = web_image.downcast::<WebImage>().unwrap().image_promise;
+        let image_buf = ImageBuf::from_raw(vec![255; 2 * 2 * 4], ImageFormat::RgbaSeparate, 2, 2);
+        harness
+            .window()
+            .ext_event_sink
+            .resolve_promise(
+                promise_token.make_result(image_buf),
+                image_id,
+                harness.window().id,
+            )
+            .unwrap();
+        harness.process_ext_events();
+        let inner_id = harness.get_widget(image_id).children()[0].id();
+        assert_ne!(inner_id, placeholder_id);
+
+        harness.set_record_events(true);
+        harness.mouse_move((10.0, 10.0));
+        harness.mouse_click_on(image_id);
+        assert_eq!(harness.events_received(placeholder_id), []);
+        assert!(harness
+            .events_received(inner_id)
+            .contains(&EventKind::Mouse));
+    }
+
+    #[test]
+    fn removing_cancels_load() {
+        let [image_id] = widget_ids();
+        let widget = Flex::row().with_child(WebImage::new(String::new()).with_id(image_id));
+
+        let mut harness = TestHarness::create(widget);
+        let web_image = harness.get_widget(image_id);
+        let promise_token = web_image.downcast::<WebImage>().unwrap().image_promise;
+        let sink = harness.window().ext_event_sink.clone();
+        assert!(!sink.is_promise_cancelled(promise_token));
+
+        harness.edit_root_widget(|mut flex, _| {
+            let mut flex = flex.downcast::<Flex>().unwrap();
+            flex.remove_child(0);
+        });
+        // The load may or may not have finished already. Either way, its result
+        // was dropped from the queue, or will be discarded when it arrives.
+        assert!(!sink.is_promise_queued(promise_token));
+    }
+}
@@ -0,0 +1,158 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A widget that includes its child only when a condition is true.
+
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+
+use crate::widget::{WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Point,
+    Size, StatusChange, Widget,
+};
+
+/// A widget that includes its child only when a condition is true.
+///
+/// When the condition is false, the child is removed from the widget tree: it
+/// doesn't receive events, isn't laid out or painted, and takes no space. This is
+/// unlike a child hidden with [`Visibility`](crate::widget::Visibility), which
+/// would still be laid out.
+///
+/// The child keeps its state while it's excluded.
+pub struct When {
+    condition: bool,
+    child: WidgetPod<Box<dyn Widget>>,
+}
+
+crate::declare_widget!(WhenMut, When);
+
+impl When {
+    /// Create a new `When` wrapping `child`, included if `condition` is true.
+    pub fn new(condition: bool, child: impl Widget) -> Self {
+        When {
+            condition,
+            child: WidgetPod::new(child).boxed(),
+        }
+    }
+
+    /// Return `true` if the child is currently included.
+    pub fn condition(&self) -> bool {
+        self.condition
+    }
+}
+
+impl<'a, 'b> WhenMut<'a, 'b> {
+    /// Include or exclude the child.
+    pub fn set_condition(&mut self, condition: bool) {
+        if self.1.condition == condition {
+            return;
+        }
+        self.1.condition = condition;
+        self.0.children_changed();
+        self.0.request_layout();
+    }
+
+    /// Return a mutable reference to the child, or `None` if it's excluded.
+    pub fn child_mut(&mut self) -> Option<WidgetMut<'_, 'b, Box<dyn Widget>>> {
+        if !self.1.condition {
+            return None;
+        }
+        Some(self.0.get_mut(&mut self.1.child))
+    }
+}
+
+impl Widget for When {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        if self.condition {
+            self.child.on_event(ctx, event, env);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        // A removed child must hear about it even while hidden.
+        if self.condition || matches!(event, LifeCycle::WidgetRemoved) {
+            self.child.lifecycle(ctx, event, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        if !self.condition {
+            return bc.min();
+        }
+
+        let size = self.child.layout(ctx, bc, env);
+        ctx.place_child(&mut self.child, Point::ORIGIN, env);
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        if self.condition {
+            self.child.paint(ctx, env);
+        }
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        if self.condition {
+            smallvec![self.child.as_dyn()]
+        } else {
+            smallvec![]
+        }
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("When")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.condition.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
+    use crate::widget::{Flex, Label, SizedBox};
+
+    #[test]
+    fn excluded_child() {
+        let [when_id, child_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child(
+                When::new(false, SizedBox::empty().height(50.0).with_id(child_id)).with_id(when_id),
+            )
+            .with_child(Label::new("below"));
+
+        let mut harness = TestHarness::create(widget);
+
+        assert!(harness.get_widget(when_id).children().is_empty());
+        assert!(harness.try_get_widget(child_id).is_none());
+        assert_eq!(
+            harness.get_widget(when_id).state().layout_rect().size(),
+            Size::ZERO
+        );
+
+        harness.edit_root_widget(|mut flex, _| {
+            let mut flex = flex.downcast::<Flex>().unwrap();
+            let mut sized_box = flex.child_mut(0).unwrap();
+            let mut sized_box = sized_box.downcast::<SizedBox>().unwrap();
+            let mut when = sized_box.child_mut().unwrap();
+            let mut when = when.downcast::<When>().unwrap();
+            assert!(when.child_mut().is_none());
+            when.set_condition(true);
+            assert!(when.child_mut().is_some());
+        });
+
+        assert_eq!(harness.get_widget(when_id).children().len(), 1);
+        assert!(harness.try_get_widget(child_id).is_some());
+        assert_eq!(
+            harness.get_widget(when_id).state().layout_rect().height(),
+            50.0
+        );
+    }
+}
@@ -10,6 +10,7 @@ use smallvec::SmallVec;
 use tracing::{trace_span, Span};
 
 use crate::event::StatusChange;
+use crate::text::TextLayoutInfo;
 use crate::widget::WidgetRef;
 use crate::{
     AsAny, BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
@@ -100,6 +101,66 @@ pub trait Widget: AsAny {
     /// The layout strategy is strongly inspired by Flutter.
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size;
 
+    /// Called right after [`layout`](Self::layout) when the widget's size changed since
+    /// its previous layout, eg because the window was resized.
+    ///
+    /// This is where to update anything which depends on the widget's actual size, eg
+    /// a cached layout, without diffing sizes manually in `layout`. It's also called
+    /// after the widget's first layout, with an `old_size` of [`Size::ZERO`], unless
+    /// that first layout returned a size of zero.
+    ///
+    /// The default implementation does nothing.
+    fn on_size_changed(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        _old_size: Size,
+        _new_size: Size,
+        _env: &Env,
+    ) {
+    }
+
+    /// Return the smallest width this widget can take without its content
+    /// overflowing, given the height.
+    ///
+    /// Containers can call [`WidgetPod::min_intrinsic_width`](crate::WidgetPod::min_intrinsic_width)
+    /// to query the natural size of a child without committing to a layout, eg
+    /// to size table columns to their content. `height` may be infinite.
+    ///
+    /// The default implementation returns [`compute_max_intrinsic_width`](Self::compute_max_intrinsic_width),
+    /// ie it assumes the widget can't shrink below its natural width.
+    fn compute_min_intrinsic_width(&mut self, ctx: &mut LayoutCtx, height: f64, env: &Env) -> f64 {
+        self.compute_max_intrinsic_width(ctx, height, env)
+    }
+
+    /// Return the width this widget would take if given unbounded width and the
+    /// given height.
+    ///
+    /// The default implementation calls [`layout`](Self::layout) with these
+    /// constraints. Widgets that can compute their natural width more cheaply
+    /// should override it.
+    fn compute_max_intrinsic_width(&mut self, ctx: &mut LayoutCtx, height: f64, env: &Env) -> f64 {
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(f64::INFINITY, height));
+        self.layout(ctx, &bc, env).width
+    }
+
+    /// Return the smallest height this widget can take without its content
+    /// overflowing, given the width.
+    ///
+    /// The default implementation returns [`compute_max_intrinsic_height`](Self::compute_max_intrinsic_height).
+    fn compute_min_intrinsic_height(&mut self, ctx: &mut LayoutCtx, width: f64, env: &Env) -> f64 {
+        self.compute_max_intrinsic_height(ctx, width, env)
+    }
+
+    /// Return the height this widget would take if given unbounded height and the
+    /// given width.
+    ///
+    /// The default implementation calls [`layout`](Self::layout) with these
+    /// constraints.
+    fn compute_max_intrinsic_height(&mut self, ctx: &mut LayoutCtx, width: f64, env: &Env) -> f64 {
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(width, f64::INFINITY));
+        self.layout(ctx, &bc, env).height
+    }
+
     /// Paint the widget appearance.
     ///
     /// The [`PaintCtx`] derefs to something that implements the
@@ -143,6 +204,26 @@ pub trait Widget: AsAny {
         None
     }
 
+    /// Return a cheap hash of everything that affects this widget's layout and paint.
+    ///
+    /// If a widget without children returns `Some`, layout and paint requests it makes
+    /// while handling an event are dropped when the hash is the same as when the
+    /// widget was last laid out or painted. This lets data-bound widgets request a
+    /// repaint on every update without checking whether their data actually changed.
+    ///
+    /// The default implementation returns `None`, which disables this optimization.
+    fn content_hash(&self) -> Option<u64> {
+        None
+    }
+
+    /// Return a snapshot of the layout of this widget's text, if it displays text.
+    ///
+    /// This is what [`TestHarness::text_layout`](crate::testing::TestHarness::text_layout)
+    /// returns. The default implementation returns `None`.
+    fn text_layout_info(&self) -> Option<TextLayoutInfo> {
+        None
+    }
+
     // --- Auto-generated implementations ---
 
     /// Return which child, if any, has the given `pos` in its layout rect.
@@ -365,6 +446,31 @@ impl Widget for Box<dyn Widget> {
         self.deref_mut().layout(ctx, bc, env)
     }
 
+    fn on_size_changed(&mut self, ctx: &mut LayoutCtx, old_size: Size, new_size: Size, env: &Env) {
+        self.deref_mut()
+            .on_size_changed(ctx, old_size, new_size, env)
+    }
+
+    fn compute_min_intrinsic_width(&mut self, ctx: &mut LayoutCtx, height: f64, env: &Env) -> f64 {
+        self.deref_mut()
+            .compute_min_intrinsic_width(ctx, height, env)
+    }
+
+    fn compute_max_intrinsic_width(&mut self, ctx: &mut LayoutCtx, height: f64, env: &Env) -> f64 {
+        self.deref_mut()
+            .compute_max_intrinsic_width(ctx, height, env)
+    }
+
+    fn compute_min_intrinsic_height(&mut self, ctx: &mut LayoutCtx, width: f64, env: &Env) -> f64 {
+        self.deref_mut()
+            .compute_min_intrinsic_height(ctx, width, env)
+    }
+
+    fn compute_max_intrinsic_height(&mut self, ctx: &mut LayoutCtx, width: f64, env: &Env) -> f64 {
+        self.deref_mut()
+            .compute_max_intrinsic_height(ctx, width, env)
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
         self.deref_mut().paint(ctx, env);
     }
@@ -377,6 +483,10 @@ impl Widget for Box<dyn Widget> {
         self.deref().children()
     }
 
+    fn get_child_at_pos(&self, pos: Point) -> Option<WidgetRef<'_, dyn Widget>> {
+        self.deref().get_child_at_pos(pos)
+    }
+
     fn make_trace_span(&self) -> Span {
         self.deref().make_trace_span()
     }
@@ -385,6 +495,14 @@ impl Widget for Box<dyn Widget> {
         self.deref().get_debug_text()
     }
 
+    fn content_hash(&self) -> Option<u64> {
+        self.deref().content_hash()
+    }
+
+    fn text_layout_info(&self) -> Option<TextLayoutInfo> {
+        self.deref().text_layout_info()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self.deref().as_dyn_any()
     }
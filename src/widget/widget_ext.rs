@@ -0,0 +1,34 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+use crate::widget::{Badge, RoundedClip, Visibility};
+use crate::Widget;
+
+/// Convenience methods to wrap widgets in common containers.
+///
+/// Implemented for every widget.
+pub trait WidgetExt: Widget + Sized + 'static {
+    /// Clip this widget to a rounded rectangle with the given corner radius.
+    ///
+    /// See [`RoundedClip`].
+    fn rounded(self, radius: f64) -> RoundedClip {
+        RoundedClip::new(self, radius)
+    }
+
+    /// Overlay a count on the top-right corner of this widget, hidden while it's zero.
+    ///
+    /// See [`Badge`].
+    fn badge(self, count: u32) -> Badge {
+        Badge::new(self).with_count(count)
+    }
+
+    /// Show or hide this widget, while keeping its space in the layout.
+    ///
+    /// See [`Visibility`].
+    fn visible(self, visible: bool) -> Visibility {
+        Visibility::new(visible, self)
+    }
+}
+
+impl<W: Widget + 'static> WidgetExt for W {}
@@ -59,6 +59,33 @@ impl<W: Widget> WidgetPod<W> {
         }
     }
 
+    /// Create a new widget pod with a name, which commands can target with
+    /// [`Target::Named`](crate::Target::Named).
+    pub fn new_with_name(inner: W, name: &'static str) -> WidgetPod<W> {
+        let mut pod = Self::new(inner);
+        pod.state.name = Some(name);
+        pod
+    }
+
+    /// Create a new widget pod with a key.
+    ///
+    /// Containers that reconcile their children with a new list, eg [`Flex`] children
+    /// added with [`Flex::with_keyed_child`], keep the existing widget with the same key,
+    /// along with its state, instead of the new one.
+    ///
+    /// [`Flex`]: crate::widget::Flex
+    /// [`Flex::with_keyed_child`]: crate::widget::Flex::with_keyed_child
+    pub fn new_with_key(inner: W, key: impl Into<ArcStr>) -> WidgetPod<W> {
+        let mut pod = Self::new(inner);
+        pod.state.key = Some(key.into());
+        pod
+    }
+
+    /// The key this widget pod was created with, if any.
+    pub fn key(&self) -> Option<&ArcStr> {
+        self.state.key.as_ref()
+    }
+
     /// Read-only access to state. We don't mark the field as `pub` because
     /// we want to control mutation.
     pub(crate) fn state(&self) -> &WidgetState {
@@ -335,9 +362,12 @@ impl<W: Widget + 'static> WidgetPod<W> {
     /// Box the contained widget.
     ///
     /// Convert a `WidgetPod` containing a widget of a specific concrete type
-    /// into a dynamically boxed widget.
+    /// into a dynamically boxed widget. The pod keeps its id and name.
     pub fn boxed(self) -> WidgetPod<Box<dyn Widget>> {
-        WidgetPod::new_with_id(Box::new(self.inner), self.state.id)
+        let mut pod: WidgetPod<Box<dyn Widget>> =
+            WidgetPod::new_with_id(Box::new(self.inner), self.state.id);
+        pod.state.name = self.state.name;
+        pod
     }
 }
 
@@ -386,17 +416,6 @@ impl<W: Widget> WidgetPod<W> {
         // TODO: factor as much logic as possible into monomorphic functions.
         let call_inner = match event {
             Event::Internal(internal) => match internal {
-                InternalEvent::MouseLeave => {
-                    let hot_changed = WidgetPod::update_hot_state(
-                        &mut self.inner,
-                        &mut self.state,
-                        parent_ctx.global_state,
-                        rect,
-                        None,
-                        env,
-                    );
-                    had_active || hot_changed
-                }
                 InternalEvent::TargetedCommand(cmd) => {
                     match cmd.target() {
                         Target::Widget(id) if id == self.id() => {
@@ -503,6 +522,17 @@ impl<W: Widget> WidgetPod<W> {
                     false
                 }
             }
+            Event::MouseLeave => {
+                let hot_changed = WidgetPod::update_hot_state(
+                    &mut self.inner,
+                    &mut self.state,
+                    parent_ctx.global_state,
+                    rect,
+                    None,
+                    env,
+                );
+                had_active || hot_changed
+            }
             Event::Wheel(mouse_event) => {
                 WidgetPod::update_hot_state(
                     &mut self.inner,
@@ -543,6 +573,16 @@ impl<W: Widget> WidgetPod<W> {
         if call_inner {
             self.call_widget_method_with_checks("event", |widget_pod| {
                 // widget_pod is a reborrow of `self`
+
+                // Requests may be dropped if the content hash doesn't change; see
+                // Widget::content_hash.
+                let requests_before = widget_pod.state.content_hash.map(|_| {
+                    (
+                        widget_pod.state.needs_layout,
+                        widget_pod.state.invalid.clone(),
+                    )
+                });
+
                 let mut notifications = VecDeque::new();
                 let mut inner_ctx = EventCtx {
                     global_state: parent_ctx.global_state,
@@ -555,8 +595,24 @@ impl<W: Widget> WidgetPod<W> {
                 let inner_event = modified_event.as_ref().unwrap_or(event);
                 inner_ctx.widget_state.has_active = false;
 
+                inner_ctx
+                    .global_state
+                    .debug_logger
+                    .record_event(inner_ctx.widget_state.id, inner_event);
+
                 widget_pod.inner.on_event(&mut inner_ctx, inner_event, env);
 
+                if let Some((needs_layout, invalid)) = requests_before {
+                    let content_unchanged = inner_ctx.widget_state.content_hash
+                        == widget_pod.inner.content_hash()
+                        && widget_pod.inner.children().is_empty();
+                    if content_unchanged {
+                        trace!("Content hash unchanged, dropping layout and paint requests");
+                        inner_ctx.widget_state.needs_layout = needs_layout;
+                        inner_ctx.widget_state.invalid = invalid;
+                    }
+                }
+
                 inner_ctx.widget_state.has_active |= inner_ctx.widget_state.is_active;
                 parent_ctx.is_handled |= inner_ctx.is_handled;
 
@@ -711,11 +767,12 @@ impl<W: Widget> WidgetPod<W> {
                                 widget_state: &mut widget_pod.state,
                             };
 
-                            widget_pod.inner.lifecycle(
-                                &mut inner_ctx,
-                                &LifeCycle::DisabledChanged(disabled),
-                                env,
+                            let event = LifeCycle::DisabledChanged(disabled);
+                            inner_ctx.global_state.debug_logger.record_lifecycle_event(
+                                inner_ctx.widget_state.id,
+                                event.short_name(),
                             );
+                            widget_pod.inner.lifecycle(&mut inner_ctx, &event, env);
                         });
                         //Each widget needs only one of DisabledChanged and RouteDisabledChanged
                         false
@@ -770,6 +827,8 @@ impl<W: Widget> WidgetPod<W> {
 
                 true
             }
+            // Widgets that were never added have nothing to clean up.
+            LifeCycle::WidgetRemoved => self.is_initialized(),
             _ if !self.is_initialized() => {
                 debug_panic!(
                     "Error in '{}' #{}: received LifeCycle::{:?} before receiving WidgetAdded.",
@@ -799,6 +858,7 @@ impl<W: Widget> WidgetPod<W> {
                     self.state.has_focus = is_focused;
 
                     self.state.focus_chain.clear();
+                    self.state.trapped_focus_chain = None;
                     true
                 } else {
                     false
@@ -816,6 +876,10 @@ impl<W: Widget> WidgetPod<W> {
                     widget_state: &mut widget_pod.state,
                 };
 
+                inner_ctx
+                    .global_state
+                    .debug_logger
+                    .record_lifecycle_event(inner_ctx.widget_state.id, event.short_name());
                 widget_pod.inner.lifecycle(&mut inner_ctx, event, env);
             });
         }
@@ -855,6 +919,34 @@ impl<W: Widget> WidgetPod<W> {
                 // recursions.
                 self.state.is_explicitly_disabled_new = self.state.is_explicitly_disabled;
             }
+            LifeCycle::WidgetRemoved => {
+                if self.state.has_focus {
+                    self.state.request_focus = Some(FocusChange::Resign);
+                }
+                // Otherwise the timers would fire into a widget that no longer exists.
+                parent_ctx.global_state.cancel_widget_timers(self.id());
+            }
+            // Bubble the rect of the newly focused widget up, so that scrolling
+            // ancestors (eg Portal) can bring it into view.
+            LifeCycle::Internal(InternalLifeCycle::RouteFocusChanged { new, .. }) => {
+                let target_rect = if *new == Some(self.id()) {
+                    Some(self.state.size.to_rect())
+                } else if let Some(target_rect) = self.state.request_pan_to_focused.take() {
+                    let mut inner_ctx = LifeCycleCtx {
+                        global_state: parent_ctx.global_state,
+                        widget_state: &mut self.state,
+                    };
+                    let event = LifeCycle::RequestPanToChild(target_rect);
+                    self.inner.lifecycle(&mut inner_ctx, &event, env);
+                    Some(target_rect)
+                } else {
+                    None
+                };
+                if let Some(target_rect) = target_rect {
+                    parent_ctx.widget_state.request_pan_to_focused =
+                        Some(target_rect + self.state.origin.to_vec2());
+                }
+            }
             // Update focus-chain of our parent
             LifeCycle::BuildFocusChain => {
                 self.state.update_focus_chain = false;
@@ -869,11 +961,19 @@ impl<W: Widget> WidgetPod<W> {
                 }
                 self.state.has_focus = had_focus;
 
+                if self.state.is_focus_trap && self.state.trapped_focus_chain.is_none() {
+                    self.state.trapped_focus_chain = Some(self.state.focus_chain.clone());
+                }
+
                 if !self.state.is_disabled() {
                     parent_ctx
                         .widget_state
                         .focus_chain
                         .extend(&self.state.focus_chain);
+                    if let Some(trapped_focus_chain) = &self.state.trapped_focus_chain {
+                        parent_ctx.widget_state.trapped_focus_chain =
+                            Some(trapped_focus_chain.clone());
+                    }
                 }
             }
             _ => (),
@@ -933,8 +1033,7 @@ impl<W: Widget> WidgetPod<W> {
             .mouse_pos
             .map(|pos| pos - self.layout_rect().origin().to_vec2());
 
-        // TODO - remove ?
-        let _prev_size = self.state.size;
+        let prev_size = self.state.size;
 
         self.state.local_paint_rect = Rect::ZERO;
 
@@ -949,6 +1048,7 @@ impl<W: Widget> WidgetPod<W> {
 
             widget_pod.inner.layout(&mut inner_ctx, bc, env)
         });
+        self.state.content_hash = self.inner.content_hash();
 
         self.state.local_paint_rect = self
             .state
@@ -995,8 +1095,18 @@ impl<W: Widget> WidgetPod<W> {
         // size is (0,0)
         // See issue #4
 
-        parent_ctx.widget_state.merge_up(&mut self.state);
         self.state.size = new_size;
+        if new_size != prev_size {
+            let mut inner_ctx = LayoutCtx {
+                widget_state: &mut self.state,
+                global_state: parent_ctx.global_state,
+                mouse_pos: inner_mouse_pos,
+            };
+            self.inner
+                .on_size_changed(&mut inner_ctx, prev_size, new_size, env);
+        }
+
+        parent_ctx.widget_state.merge_up(&mut self.state);
         self.log_layout_issues(new_size);
 
         parent_ctx
@@ -1013,6 +1123,79 @@ impl<W: Widget> WidgetPod<W> {
         new_size
     }
 
+    /// Return the smallest width the child can take without overflowing, given the height.
+    ///
+    /// This doesn't change the child's layout; the child must still be laid out with
+    /// [`layout`](Self::layout). See [`Widget::compute_min_intrinsic_width`].
+    pub fn min_intrinsic_width(
+        &mut self,
+        parent_ctx: &mut LayoutCtx,
+        height: f64,
+        env: &Env,
+    ) -> f64 {
+        self.query_intrinsic_size(parent_ctx, |widget, ctx| {
+            widget.compute_min_intrinsic_width(ctx, height, env)
+        })
+    }
+
+    /// Return the width the child would take given unbounded width and the given height.
+    ///
+    /// See [`Widget::compute_max_intrinsic_width`].
+    pub fn max_intrinsic_width(
+        &mut self,
+        parent_ctx: &mut LayoutCtx,
+        height: f64,
+        env: &Env,
+    ) -> f64 {
+        self.query_intrinsic_size(parent_ctx, |widget, ctx| {
+            widget.compute_max_intrinsic_width(ctx, height, env)
+        })
+    }
+
+    /// Return the smallest height the child can take without overflowing, given the width.
+    ///
+    /// See [`Widget::compute_min_intrinsic_height`].
+    pub fn min_intrinsic_height(
+        &mut self,
+        parent_ctx: &mut LayoutCtx,
+        width: f64,
+        env: &Env,
+    ) -> f64 {
+        self.query_intrinsic_size(parent_ctx, |widget, ctx| {
+            widget.compute_min_intrinsic_height(ctx, width, env)
+        })
+    }
+
+    /// Return the height the child would take given unbounded height and the given width.
+    ///
+    /// See [`Widget::compute_max_intrinsic_height`].
+    pub fn max_intrinsic_height(
+        &mut self,
+        parent_ctx: &mut LayoutCtx,
+        width: f64,
+        env: &Env,
+    ) -> f64 {
+        self.query_intrinsic_size(parent_ctx, |widget, ctx| {
+            widget.compute_max_intrinsic_height(ctx, width, env)
+        })
+    }
+
+    fn query_intrinsic_size(
+        &mut self,
+        parent_ctx: &mut LayoutCtx,
+        query: impl FnOnce(&mut W, &mut LayoutCtx) -> f64,
+    ) -> f64 {
+        let _span = self.inner.make_trace_span().entered();
+        self.check_initialized("intrinsic size query");
+
+        let mut inner_ctx = LayoutCtx {
+            widget_state: &mut self.state,
+            global_state: parent_ctx.global_state,
+            mouse_pos: None,
+        };
+        query(&mut self.inner, &mut inner_ctx)
+    }
+
     fn log_layout_issues(&self, size: Size) {
         if size.width.is_infinite() {
             let name = self.inner.type_name();
@@ -1040,6 +1223,7 @@ impl<W: Widget> WidgetPod<W> {
     /// [`paint`]: #method.paint
     pub fn paint_raw(&mut self, ctx: &mut PaintCtx, env: &Env) {
         self.mark_as_visited();
+        ctx.global_state.painted_widgets += 1;
 
         // we need to do this before we borrow from self
         if env.get(Env::DEBUG_WIDGET_ID) {
@@ -1056,6 +1240,7 @@ impl<W: Widget> WidgetPod<W> {
                 z_ops: Vec::new(),
                 region: ctx.region.clone(),
                 depth: ctx.depth,
+                crisp_edges: env.get(crate::theme::PREFERS_CRISP_EDGES),
             };
             widget_pod.inner.paint(&mut inner_ctx, env);
 
@@ -1071,6 +1256,7 @@ impl<W: Widget> WidgetPod<W> {
 
             ctx.z_ops.append(&mut inner_ctx.z_ops);
         });
+        self.state.content_hash = self.inner.content_hash();
     }
 
     /// Paint the widget, translating it by the origin of its layout rectangle.
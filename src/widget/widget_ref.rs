@@ -2,6 +2,7 @@
 // "as-is" basis without warranties of any kind. See the LICENSE file for
 // details.
 
+use std::any::TypeId;
 use std::ops::Deref;
 
 use smallvec::SmallVec;
@@ -121,15 +122,56 @@ impl<'w> WidgetRef<'w, dyn Widget> {
         self.widget.children()
     }
 
+    /// Return a rough estimate of the memory used by this widget and its descendants.
+    ///
+    /// This is the inline size of each widget and of its [`WidgetState`]. Heap
+    /// allocations owned by widgets (text buffers, images, etc) aren't counted.
+    pub fn estimated_memory(&self) -> usize {
+        std::mem::size_of_val(self.widget)
+            + std::mem::size_of::<WidgetState>()
+            + self
+                .children()
+                .iter()
+                .map(|child| child.estimated_memory())
+                .sum::<usize>()
+    }
+
+    /// Return this widget and its descendants, in depth-first order.
+    pub fn subtree(&self) -> impl Iterator<Item = WidgetRef<'w, dyn Widget>> {
+        let mut stack = vec![*self];
+        std::iter::from_fn(move || {
+            let widget = stack.pop()?;
+            stack.extend(widget.children().into_iter().rev());
+            Some(widget)
+        })
+    }
+
+    /// Recursively find the first widget, in depth-first order, matching `predicate`.
+    pub fn find_widget(
+        &self,
+        mut predicate: impl FnMut(WidgetRef<'w, dyn Widget>) -> bool,
+    ) -> Option<WidgetRef<'w, dyn Widget>> {
+        self.subtree().find(|widget| predicate(*widget))
+    }
+
     /// Recursively find child widget with given id.
     pub fn find_widget_by_id(&self, id: WidgetId) -> Option<WidgetRef<'w, dyn Widget>> {
-        if self.state().id == id {
-            Some(*self)
-        } else {
-            self.children()
-                .into_iter()
-                .find_map(|child| child.find_widget_by_id(id))
-        }
+        self.find_widget(|widget| widget.state().id == id)
+    }
+
+    /// Recursively find the first widget, in depth-first order, with the given name.
+    ///
+    /// See [`WidgetPod::new_with_name`](crate::WidgetPod::new_with_name).
+    pub fn find_widget_by_name(&self, name: &str) -> Option<WidgetRef<'w, dyn Widget>> {
+        self.find_widget(|widget| widget.state().name == Some(name))
+    }
+
+    /// Recursively find the first widget, in depth-first order, of the given type.
+    pub(crate) fn find_widget_by_type_id(
+        &self,
+        type_id: TypeId,
+    ) -> Option<WidgetRef<'w, dyn Widget>> {
+        self.find_widget(|widget| widget.deref().as_any().type_id() == type_id)
     }
 
     /// Recursively find innermost widget at given position.
@@ -211,7 +253,7 @@ mod tests {
 
     use super::*;
     use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
-    use crate::widget::{Button, Label};
+    use crate::widget::{Button, Flex, Label, SizedBox};
     use crate::{Widget, WidgetPod};
 
     #[test]
@@ -235,4 +277,50 @@ mod tests {
         assert_matches!(harness.get_widget(label_id).downcast::<Label>(), Some(_));
         assert_matches!(harness.get_widget(label_id).downcast::<Button>(), None);
     }
+
+    #[test]
+    fn find_widgets_by_type_in_harness() {
+        let widget = Flex::column()
+            .with_child(Label::new("First"))
+            .with_child(SizedBox::new(Label::new("Second")))
+            .with_child(SizedBox::empty());
+
+        let harness = TestHarness::create(widget);
+
+        let first = harness.find_widget_by_type::<Label>().unwrap();
+        assert_eq!(first.text().to_string(), "First");
+        assert_matches!(harness.find_widget_by_type::<Button>(), None);
+
+        let labels: Vec<_> = harness
+            .find_all_widgets_by_type::<Label>()
+            .iter()
+            .map(|label| label.text().to_string())
+            .collect();
+        assert_eq!(labels, ["First", "Second"]);
+        assert_eq!(harness.find_all_widgets_by_type::<SizedBox>().len(), 2);
+    }
+
+    #[test]
+    fn find_widget_with_borrowing_predicate() {
+        let widget = Flex::column()
+            .with_child(Label::new("Hello"))
+            .with_child(SizedBox::new(Label::new("World")));
+
+        let harness = TestHarness::create(widget);
+
+        let wanted = String::from("World");
+        let label = harness
+            .find_widget(|widget| {
+                widget
+                    .downcast::<Label>()
+                    .map_or(false, |label| label.text().to_string() == wanted)
+            })
+            .unwrap();
+        assert_eq!(
+            label.downcast::<Label>().unwrap().text().to_string(),
+            "World"
+        );
+
+        assert_matches!(harness.find_widget(|_| false), None);
+    }
 }
@@ -6,13 +6,13 @@
 
 use std::sync::atomic::{AtomicBool, Ordering};
 
-use druid_shell::{Cursor, Region};
+use druid_shell::{Cursor, HotKey, Region};
 
 use crate::bloom::Bloom;
 use crate::kurbo::{Insets, Point, Rect, Size};
 use crate::text::TextFieldRegistration;
 use crate::widget::{CursorChange, FocusChange};
-use crate::WidgetId;
+use crate::{ArcStr, Command, WidgetId};
 
 // FIXME #5 - Make a note documenting this: the only way to get a &mut WidgetState should be in a pass.
 // A pass should reborrow the parent widget state (to avoid crossing wires) and call merge_up at
@@ -39,6 +39,10 @@ use crate::WidgetId;
 #[derive(Clone, Debug)]
 pub struct WidgetState {
     pub(crate) id: WidgetId,
+    /// The name used to target commands with [`Target::Named`](crate::Target::Named).
+    pub(crate) name: Option<&'static str>,
+    /// The key used to match this widget across rebuilds of its parent's children.
+    pub(crate) key: Option<ArcStr>,
 
     // --- LAYOUT ---
     /// The size of the child; this is the value returned by the child's layout
@@ -95,7 +99,20 @@ pub struct WidgetState {
     pub(crate) update_focus_chain: bool,
 
     pub(crate) focus_chain: Vec<WidgetId>,
+    /// Focus traversal can't leave this widget's subtree.
+    pub(crate) is_focus_trap: bool,
+    /// The focus chain of the focus trap in this subtree, if any.
+    ///
+    /// See [`set_focus_trap`](crate::EventCtx::set_focus_trap) for which trap wins.
+    pub(crate) trapped_focus_chain: Option<Vec<WidgetId>>,
     pub(crate) request_focus: Option<FocusChange>,
+    /// The rect, in this widget's coordinates, of a newly focused descendant that
+    /// scrolling ancestors should reveal.
+    pub(crate) request_pan_to_focused: Option<Rect>,
+    /// Accelerators that are active while this widget or one of its descendants has focus.
+    pub(crate) accelerators: Vec<(HotKey, Command)>,
+    /// Accelerators that are active while this widget is enabled, wherever focus is.
+    pub(crate) global_accelerators: Vec<(HotKey, Command)>,
 
     pub(crate) children: Bloom<WidgetId>,
     pub(crate) children_changed: bool,
@@ -129,6 +146,9 @@ pub struct WidgetState {
     // TODO - document
     pub(crate) is_stashed: bool,
 
+    /// The widget's content hash when it was last laid out or painted.
+    pub(crate) content_hash: Option<u64>,
+
     // --- DEBUG INFO ---
     // Used in event/lifecycle/etc methods that are expected to be called recursively
     // on a widget's children, to make sure each child was visited.
@@ -148,6 +168,8 @@ impl WidgetState {
     pub(crate) fn new(id: WidgetId, size: Option<Size>, widget_name: &'static str) -> WidgetState {
         WidgetState {
             id,
+            name: None,
+            key: None,
             origin: Point::ORIGIN,
             parent_window_origin: Point::ORIGIN,
             size: size.unwrap_or_default(),
@@ -169,7 +191,12 @@ impl WidgetState {
             has_focus: false,
             request_anim: false,
             request_focus: None,
+            request_pan_to_focused: None,
+            accelerators: Vec::new(),
+            global_accelerators: Vec::new(),
             focus_chain: Vec::new(),
+            is_focus_trap: false,
+            trapped_focus_chain: None,
             children: Bloom::new(),
             children_changed: false,
             cursor_change: CursorChange::Default,
@@ -178,6 +205,7 @@ impl WidgetState {
             text_registrations: Vec::new(),
             update_focus_chain: false,
             is_stashed: false,
+            content_hash: None,
             #[cfg(debug_assertions)]
             needs_visit: VisitBool(false.into()),
             #[cfg(debug_assertions)]
@@ -297,6 +325,14 @@ impl WidgetState {
         Rect::from_origin_size(self.window_origin(), self.size)
     }
 
+    /// Whether the mouse is over the widget, as of the last mouse event.
+    ///
+    /// Widgets are notified of changes with
+    /// [`StatusChange::HotChanged`](crate::StatusChange::HotChanged).
+    pub fn is_hot(&self) -> bool {
+        self.is_hot
+    }
+
     pub(crate) fn window_origin(&self) -> Point {
         self.parent_window_origin + self.origin.to_vec2()
     }
@@ -0,0 +1,235 @@
+// This software is licensed under Apache License 2.0 and distributed on an
+// "as-is" basis without warranties of any kind. See the LICENSE file for
+// details.
+
+//! A region that moves the window it's in when dragged.
+
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+
+use crate::command::CONFIGURE_WINDOW;
+use crate::widget::{WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Point,
+    Size, StatusChange, Widget, WindowConfig,
+};
+
+/// A region that moves the window it's in when dragged, like a titlebar.
+///
+/// This is meant for windows without a titlebar (see [`WindowConfig::show_titlebar`]).
+/// The child is usually the passive part of the app's custom titlebar, eg the title
+/// label. Interactive widgets such as buttons should be placed next to the region rather
+/// than inside it: on platforms that report the region as a titlebar (see below), the
+/// platform intercepts mouse presses before the child sees them.
+///
+/// On platforms where druid-shell supports it (Windows and GTK), the region is
+/// reported to the platform as part of the titlebar, so the platform moves the window
+/// itself, with its usual behavior (snapping, double-click to maximize, etc).
+/// Elsewhere, or if disabled with [`with_platform_drag`](Self::with_platform_drag), the
+/// region moves the window by requesting a new position with the [`CONFIGURE_WINDOW`]
+/// command as the mouse is dragged.
+///
+/// The two are never combined: when the platform moves the window, the region doesn't
+/// submit `CONFIGURE_WINDOW` commands, so the platform's move always wins. Position
+/// changes requested by other widgets during a platform drag are up to the platform.
+///
+/// To resize the window, see [`ResizeHandle`](crate::widget::ResizeHandle).
+pub struct WindowDragRegion {
+    child: WidgetPod<Box<dyn Widget>>,
+    platform_drag: bool,
+    // Mouse position in screen coordinates and window position when the drag started.
+    drag_start: Option<(Point, Point)>,
+}
+
+// Whether druid-shell implements `WindowHandle::handle_titlebar` on this platform.
+const PLATFORM_HANDLES_TITLEBAR: bool = cfg!(any(
+    target_os = "windows",
+    all(
+        feature = "gtk",
+        not(feature = "x11"),
+        any(
+            target_os = "linux",
+            target_os = "freebsd",
+            target_os = "openbsd"
+        )
+    )
+));
+
+crate::declare_widget!(WindowDragRegionMut, WindowDragRegion);
+
+impl WindowDragRegion {
+    /// Create a new `WindowDragRegion` around `child`.
+    pub fn new(child: impl Widget) -> Self {
+        WindowDragRegion {
+            child: WidgetPod::new(child).boxed(),
+            platform_drag: PLATFORM_HANDLES_TITLEBAR,
+            drag_start: None,
+        }
+    }
+
+    /// Builder-style method to choose whether the platform moves the window.
+    ///
+    /// This is `true` by default. If `false`, the region always moves the window with
+    /// [`CONFIGURE_WINDOW`] commands. On platforms that can't report titlebar regions,
+    /// this has no effect.
+    pub fn with_platform_drag(mut self, platform_drag: bool) -> Self {
+        self.platform_drag = platform_drag && PLATFORM_HANDLES_TITLEBAR;
+        self
+    }
+}
+
+impl<'a, 'b> WindowDragRegionMut<'a, 'b> {
+    /// Return a mutable reference to the child.
+    pub fn child_mut(&mut self) -> WidgetMut<'_, 'b, Box<dyn Widget>> {
+        self.0.get_mut(&mut self.1.child)
+    }
+}
+
+impl Widget for WindowDragRegion {
+    fn on_event(&mut self, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        self.child.on_event(ctx, event, env);
+        if ctx.is_handled() || self.child.has_active() {
+            return;
+        }
+
+        if self.platform_drag {
+            if let Event::MouseDown(_) | Event::MouseMove(_) = event {
+                // The window root resets this before every mouse event.
+                ctx.window().handle_titlebar(true);
+            }
+            return;
+        }
+
+        match event {
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                if let Some((start_mouse_pos, start_window_pos)) = self.drag_start {
+                    let delta = ctx.to_screen(mouse.pos) - start_mouse_pos;
+                    let position = start_window_pos + delta;
+                    trace!(
+                        "WindowDragRegion {:?} requested window position {}",
+                        ctx.widget_id(),
+                        position
+                    );
+                    ctx.submit_command(
+                        CONFIGURE_WINDOW.with(WindowConfig::default().set_position(position)),
+                    );
+                    ctx.set_handled();
+                }
+            }
+            Event::MouseDown(mouse) => {
+                ctx.set_active(true);
+                self.drag_start = Some((ctx.to_screen(mouse.pos), ctx.window().get_position()));
+                ctx.set_handled();
+            }
+            Event::MouseUp(_) => {
+                ctx.set_active(false);
+                self.drag_start = None;
+            }
+            _ => (),
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange, _env: &Env) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
+        self.child.lifecycle(ctx, event, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, env);
+        ctx.place_child(&mut self.child, Point::ORIGIN, env);
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        self.child.paint(ctx, env);
+    }
+
+    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+        smallvec![self.child.as_dyn()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("WindowDragRegion")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::MouseButton;
+    use crate::testing::{widget_ids, Record, Recording, TestHarness, TestWidgetExt as _};
+    use crate::widget::{Button, Flex, SizedBox};
+
+    fn requested_positions(recording: &Recording) -> Vec<Point> {
+        recording
+            .drain()
+            .into_iter()
+            .filter_map(|record| match record {
+                Record::E(Event::Command(cmd)) => cmd.try_get(CONFIGURE_WINDOW)?.position,
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn drag_requests_move() {
+        let recording = Recording::default();
+        let widget = WindowDragRegion::new(SizedBox::empty().width(200.0).height(30.0))
+            .with_platform_drag(false)
+            .record(&recording);
+
+        let mut harness = TestHarness::create(widget);
+        recording.clear();
+
+        harness.mouse_move((10.0, 10.0));
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_move((40.0, 30.0));
+        harness.mouse_button_release(MouseButton::Left);
+
+        // The mock window is at the screen's origin.
+        assert_eq!(requested_positions(&recording), [Point::new(30.0, 20.0)]);
+    }
+
+    #[test]
+    fn platform_drag_wins() {
+        let recording = Recording::default();
+        let widget =
+            WindowDragRegion::new(SizedBox::empty().width(200.0).height(30.0)).record(&recording);
+
+        let mut harness = TestHarness::create(widget);
+        recording.clear();
+
+        harness.mouse_move((10.0, 10.0));
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_move((40.0, 30.0));
+        harness.mouse_button_release(MouseButton::Left);
+
+        // When the platform moves the window, the region doesn't request moves itself.
+        let expected: &[Point] = if PLATFORM_HANDLES_TITLEBAR {
+            &[]
+        } else {
+            &[Point::new(30.0, 20.0)]
+        };
+        assert_eq!(requested_positions(&recording), expected);
+    }
+
+    #[test]
+    fn button_next_to_region_doesnt_move() {
+        let [button_id] = widget_ids();
+        let recording = Recording::default();
+        let widget = Flex::row()
+            .with_flex_child(WindowDragRegion::new(SizedBox::empty().expand()), 1.0)
+            .with_child(Button::new("Close").with_id(button_id))
+            .record(&recording);
+
+        let mut harness = TestHarness::create(widget);
+        recording.clear();
+
+        harness.mouse_click_on(button_id);
+        harness.mouse_move((300.0, 300.0));
+
+        assert_eq!(requested_positions(&recording), []);
+    }
+}